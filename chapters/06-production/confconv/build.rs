@@ -0,0 +1,12 @@
+//! 构建脚本：启用 grpc 功能时从 proto/confconv.proto 生成服务代码
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/confconv.proto");
+        // 使用随依赖分发的 protoc，构建环境不需要另外安装
+        let protoc = protoc_bin_vendored::protoc_bin_path().expect("找不到随附的 protoc");
+        std::env::set_var("PROTOC", protoc);
+        tonic_prost_build::compile_protos("proto/confconv.proto").expect("生成 gRPC 代码失败");
+    }
+}
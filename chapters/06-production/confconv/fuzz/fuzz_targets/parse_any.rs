@@ -0,0 +1,15 @@
+//! 自动识别格式的解析，以及解析结果到所有格式的转换
+
+#![no_main]
+
+use confconv::codec;
+use confconv::format::Format;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((value, _)) = codec::parse_untrusted(data, None) {
+        for format in [Format::Json, Format::Yaml, Format::Toml] {
+            let _ = codec::serialize(&value, format, false);
+        }
+    }
+});
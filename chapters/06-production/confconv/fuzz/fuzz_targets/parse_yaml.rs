@@ -0,0 +1,13 @@
+//! Yaml 解析：任意字节都不能导致 panic，解析成功的值要能再序列化回 Yaml
+
+#![no_main]
+
+use confconv::codec;
+use confconv::format::Format;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok((value, _)) = codec::parse_untrusted(data, Some(Format::Yaml)) {
+        let _ = codec::serialize(&value, Format::Yaml, true);
+    }
+});
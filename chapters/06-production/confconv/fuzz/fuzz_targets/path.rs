@@ -0,0 +1,17 @@
+//! 路径表达式和通配模式的解析：解析成功的路径显示后再解析应当得到同一条路径
+
+#![no_main]
+
+use confconv::path::{Path, Pattern};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(text) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(path) = Path::parse(text) {
+        let shown = path.to_string();
+        assert_eq!(Path::parse(&shown).ok(), Some(path), "{:?}", shown);
+    }
+    let _ = Pattern::parse(text);
+});
@@ -0,0 +1,234 @@
+//! 配置的大小预算
+//!
+//! 下游系统对配置有硬性限制：Kubernetes ConfigMap 不能超过 1MB，环境变量块的总长度有上限，
+//! 有些服务只接受有限层数的嵌套。这些限制往往到部署时才暴露，check 命令在 CI 中按这里的规则
+//! 提前拒绝超出限制的文件。
+//!
+//! 限制可以在配置文件中按文件模式设置，命令行参数对所有文件生效并覆盖配置中的值：
+//!
+//! ```toml
+//! [[budget]]
+//! # 写法见 [`FilePattern`]
+//! pattern = "k8s/**/*.yaml"
+//! max_size = "1MB"
+//!
+//! [[budget]]
+//! pattern = "*.env.json"
+//! max_keys = 200
+//! max_depth = 1
+//! ```
+
+use crate::config::BudgetRule;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::{Error, Result};
+use crate::t;
+use crate::units;
+use crate::walk::FilePattern;
+use serde_json::Value;
+
+/// 一组限制，None 表示不限制
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Limits {
+    /// 文件的字节数（压缩文件按解压后的内容计算）
+    pub max_size: Option<u64>,
+    /// 所有对象中键的总数
+    pub max_keys: Option<usize>,
+    /// 对象和数组的最大嵌套层数
+    pub max_depth: Option<usize>,
+}
+
+impl Limits {
+    /// 用 `other` 中设置了的限制覆盖自己的
+    fn overlay(&mut self, other: &Limits) {
+        self.max_size = other.max_size.or(self.max_size);
+        self.max_keys = other.max_keys.or(self.max_keys);
+        self.max_depth = other.max_depth.or(self.max_depth);
+    }
+}
+
+/// 按文件选用限制
+#[derive(Debug, Default)]
+pub struct Budget {
+    /// 配置中的规则，按定义顺序叠加
+    rules: Vec<(FilePattern, Limits)>,
+    /// 命令行参数
+    overrides: Limits,
+}
+
+impl Budget {
+    /// 编译配置中的规则；`source` 是定义规则的配置文件，用于错误信息
+    pub fn new(rules: &[BudgetRule], overrides: Limits, source: &str) -> Result<Budget> {
+        let invalid = |message: String| Error::Config {
+            path: source.to_string(),
+            message,
+        };
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let pattern = FilePattern::new(&rule.pattern)
+                .map_err(|e| invalid(t!("[[budget]] 的 pattern 无效: {}", e)))?;
+            let max_size = match &rule.max_size {
+                Some(text) => Some(parse_size(text).map_err(invalid)?),
+                None => None,
+            };
+            let limits = Limits {
+                max_size,
+                max_keys: rule.max_keys,
+                max_depth: rule.max_depth,
+            };
+            compiled.push((pattern, limits));
+        }
+        Ok(Budget {
+            rules: compiled,
+            overrides,
+        })
+    }
+
+    /// 适用于 `path` 的限制：依次叠加匹配的规则，最后叠加命令行参数
+    pub fn limits(&self, path: &str) -> Limits {
+        let mut limits = Limits::default();
+        for (pattern, rule) in &self.rules {
+            if pattern.matches(path) {
+                limits.overlay(rule);
+            }
+        }
+        limits.overlay(&self.overrides);
+        limits
+    }
+}
+
+/// 文件实际占用的大小
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Usage {
+    /// 字节数
+    pub size: u64,
+    /// 所有对象中键的总数
+    pub keys: usize,
+    /// 对象和数组的嵌套层数，顶层是标量时为 0
+    pub depth: usize,
+}
+
+impl Usage {
+    /// 统计文件内容和解析后的值
+    pub fn measure(content: &[u8], value: &Value) -> Usage {
+        fn walk(value: &Value, depth: usize, usage: &mut Usage) {
+            let children: Box<dyn Iterator<Item = &Value>> = match value {
+                Value::Object(map) => {
+                    usage.keys += map.len();
+                    Box::new(map.values())
+                }
+                Value::Array(items) => Box::new(items.iter()),
+                _ => return,
+            };
+            usage.depth = usage.depth.max(depth + 1);
+            for child in children {
+                walk(child, depth + 1, usage);
+            }
+        }
+        let mut usage = Usage {
+            size: content.len() as u64,
+            keys: 0,
+            depth: 0,
+        };
+        walk(value, 0, &mut usage);
+        usage
+    }
+}
+
+/// 超出的每项限制报告一个错误
+pub fn check(usage: &Usage, limits: &Limits) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut exceeded = |code: &'static str, message: String| {
+        diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            code,
+            location: None,
+            message,
+        });
+    };
+    if let Some(max) = limits.max_size.filter(|max| usage.size > *max) {
+        exceeded(
+            "max-size",
+            t!(
+                "文件有 {} 字节，超过了 {} 字节（{}）的限制",
+                usage.size,
+                max,
+                units::humanize_size(max)
+            ),
+        );
+    }
+    if let Some(max) = limits.max_keys.filter(|max| usage.keys > *max) {
+        exceeded(
+            "max-keys",
+            t!("共有 {} 个键，超过了 {} 个的限制", usage.keys, max),
+        );
+    }
+    if let Some(max) = limits.max_depth.filter(|max| usage.depth > *max) {
+        exceeded(
+            "max-depth",
+            t!("嵌套了 {} 层，超过了 {} 层的限制", usage.depth, max),
+        );
+    }
+    diagnostics
+}
+
+/// 解析字节数限制，如 `64KB`、`1MiB`、`1048576`
+pub fn parse_size(text: &str) -> std::result::Result<u64, String> {
+    units::parse_size(text).ok_or_else(|| t!("无效的字节数: {}（例如 64KB、1MiB）", text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(pattern: &str, max_size: Option<&str>, max_keys: Option<usize>) -> BudgetRule {
+        BudgetRule {
+            pattern: pattern.to_string(),
+            max_size: max_size.map(str::to_string),
+            max_keys,
+            max_depth: None,
+        }
+    }
+
+    #[test]
+    fn later_rules_and_arguments_override_earlier_ones() {
+        let rules = [
+            rule("*.yaml", Some("1MB"), Some(500)),
+            rule("k8s/**/*.yaml", Some("64KB"), None),
+        ];
+        let overrides = Limits {
+            max_depth: Some(8),
+            ..Limits::default()
+        };
+        let budget = Budget::new(&rules, overrides, "config.toml").unwrap();
+        assert_eq!(
+            budget.limits("k8s/prod/app.yaml"),
+            Limits {
+                max_size: Some(64_000),
+                max_keys: Some(500),
+                max_depth: Some(8),
+            }
+        );
+        assert_eq!(budget.limits("app.json"), overrides);
+    }
+
+    #[test]
+    fn reports_each_exceeded_limit() {
+        let value: Value = serde_json::from_str(r#"{"a": {"b": [1, 2]}, "c": 3}"#).unwrap();
+        let usage = Usage::measure(b"12345", &value);
+        assert_eq!(
+            usage,
+            Usage {
+                size: 5,
+                keys: 3,
+                depth: 3,
+            }
+        );
+        let limits = Limits {
+            max_size: Some(5),
+            max_keys: Some(2),
+            max_depth: Some(2),
+        };
+        let codes: Vec<&str> = check(&usage, &limits).iter().map(|d| d.code).collect();
+        assert_eq!(codes, ["max-keys", "max-depth"]);
+    }
+}
@@ -0,0 +1,203 @@
+//! 解析结果缓存
+//!
+//! 监视模式每次有文件修改都会重新转换所有输入，gRPC 服务也常常反复收到相同的文档，
+//! 而解析通常是转换中最慢的一步。这里按内容的 SHA-256（连同程序版本、格式和方言设置）缓存解析结果：
+//! 内存中保留最近使用的若干份，打开磁盘缓存时还会写到缓存目录下的 `parse/` 中，
+//! 重新启动后仍然有效。磁盘上同样最多保留 `entries` 份，超出时删除最早写入的；
+//! 缓存文件只有所有者可以读写。
+//!
+//! 缓存默认关闭，只由长时间运行的 watch 和 serve 打开，容量和磁盘缓存在配置文件中设置：
+//!
+//! ```toml
+//! [cache]
+//! entries = 256
+//! disk = true
+//! ```
+//!
+//! 解析失败的结果不缓存；插件格式的解析交给外部程序，也不缓存。
+//! 磁盘缓存中的文件可以随时删除
+
+use crate::config::CacheConfig;
+use crate::dialect;
+use crate::dirs;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::log;
+use crate::stamp::sha256_hex;
+use indexmap::IndexMap;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::time::SystemTime;
+
+/// 打开了的缓存
+static CACHE: OnceLock<Mutex<Cache>> = OnceLock::new();
+
+/// 最近使用的解析结果
+struct Cache {
+    /// 按使用时间排列，最近使用的在最后
+    entries: IndexMap<String, Value>,
+    capacity: usize,
+    /// 磁盘缓存目录，None 表示只缓存在内存中
+    dir: Option<PathBuf>,
+}
+
+impl Cache {
+    fn get(&mut self, key: &str) -> Option<Value> {
+        if let Some(value) = self.entries.shift_remove(key) {
+            self.entries.insert(key.to_string(), value.clone());
+            return Some(value);
+        }
+        let path = self.dir.as_ref()?.join(format!("{}.json", key));
+        let value: Value = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+        self.insert(key, value.clone());
+        Some(value)
+    }
+
+    fn insert(&mut self, key: &str, value: Value) {
+        self.entries.insert(key.to_string(), value);
+        while self.entries.len() > self.capacity {
+            self.entries.shift_remove_index(0);
+        }
+    }
+
+    /// 写入磁盘缓存；失败时只是下次需要重新解析
+    fn store(&self, key: &str, value: &Value) {
+        let Some(dir) = &self.dir else {
+            return;
+        };
+        let written = fs::create_dir_all(dir)
+            .and_then(|_| {
+                let path = dir.join(format!("{}.json", key));
+                fsutil::write_private(&path, &serde_json::to_vec(value).unwrap_or_default())
+            })
+            .and_then(|_| self.evict(dir));
+        if let Err(e) = written {
+            log!(Debug, "无法写入解析缓存 {}: {}", dir.display(), e);
+        }
+    }
+
+    /// 磁盘缓存超过容量时删除最早写入的文件
+    fn evict(&self, dir: &Path) -> io::Result<()> {
+        let mut files: Vec<(SystemTime, PathBuf)> = fs::read_dir(dir)?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let path = entry.path();
+                let modified = entry.metadata().ok()?.modified().ok()?;
+                (path.extension()? == "json").then_some((modified, path))
+            })
+            .collect();
+        if files.len() <= self.capacity {
+            return Ok(());
+        }
+        files.sort();
+        for (_, path) in &files[..files.len() - self.capacity] {
+            fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// 打开缓存，只有第一次调用有效；`entries` 为 0 时不缓存
+pub fn init(config: &CacheConfig) {
+    if config.entries == 0 {
+        return;
+    }
+    let dir = config
+        .disk
+        .then(dirs::cache_dir)
+        .flatten()
+        .map(|dir| dir.join("parse"));
+    let _ = CACHE.set(Mutex::new(Cache {
+        entries: IndexMap::new(),
+        capacity: config.entries,
+        dir,
+    }));
+}
+
+/// 取出 `input` 按 `format` 解析的结果，没有缓存时调用 `parse` 并缓存成功的结果
+pub fn parse_cached(
+    input: &[u8],
+    format: Format,
+    parse: impl FnOnce() -> Result<Value>,
+) -> Result<Value> {
+    let Some(cache) = CACHE.get().filter(|_| !matches!(format, Format::Plugin(_))) else {
+        return parse();
+    };
+    // 方言设置和程序版本都会影响解析结果，也作为键的一部分
+    let mut keyed = format!(
+        "{}\n{}\n{:?}\n",
+        env!("CARGO_PKG_VERSION"),
+        format.name(),
+        dialect::current()
+    )
+    .into_bytes();
+    keyed.extend_from_slice(input);
+    let key = sha256_hex(&keyed);
+
+    // 解析时不持有锁，多个请求可以同时解析
+    let cached = cache.lock().ok().and_then(|mut cache| cache.get(&key));
+    if let Some(value) = cached {
+        log!(Debug, "使用缓存的解析结果（{} 字节）", input.len());
+        return Ok(value);
+    }
+    let value = parse()?;
+    if let Ok(mut cache) = cache.lock() {
+        cache.store(&key, &value);
+        cache.insert(&key, value.clone());
+    }
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let mut cache = Cache {
+            entries: IndexMap::new(),
+            capacity: 2,
+            dir: None,
+        };
+        cache.insert("a", Value::from(1));
+        cache.insert("b", Value::from(2));
+        assert_eq!(cache.get("a"), Some(Value::from(1)));
+        cache.insert("c", Value::from(3));
+        assert_eq!(cache.get("b"), None);
+        assert_eq!(cache.get("a"), Some(Value::from(1)));
+        assert_eq!(cache.get("c"), Some(Value::from(3)));
+    }
+
+    #[test]
+    fn disk_cache_is_private_and_bounded() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = Cache {
+            entries: IndexMap::new(),
+            capacity: 2,
+            dir: Some(dir.path().join("parse")),
+        };
+        for key in ["a", "b", "c"] {
+            cache.store(key, &Value::from(key));
+        }
+        let parse = dir.path().join("parse");
+        let mut names: Vec<_> = fs::read_dir(&parse)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["b.json", "c.json"]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = fs::metadata(parse.join("c.json"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+    }
+}
@@ -1,7 +1,27 @@
 //! CLI 定义模块
 
-use clap::{Parser, Subcommand};
+use crate::budget;
+use crate::codec::{NullPolicy, Quote};
+use crate::color::ColorChoice;
+use crate::commands::{EnvFormat, KvFlavor, Shell, ValidateFormat};
+use crate::config::TransformConfig;
+use crate::custom_tags::CustomTags;
+use crate::diagnostic;
+use crate::dialect::{NanPolicy, TomlVersion, YamlSpec};
 use crate::format::Format;
+use crate::i18n::Lang;
+use crate::ini::IniDialect;
+use crate::lint::KeyCase;
+use crate::merge::{Assignment, MergeKey};
+use crate::path::Pattern;
+use crate::report::{self, ReportFormat};
+use crate::schema::Level;
+use crate::toml_arrays::ArrayRule;
+use crate::transform::{Coerce, Rename, SortMode, Transforms};
+use crate::yaml_tags::YamlTags;
+use clap::{ArgAction, Args, Parser, Subcommand};
+use regex::Regex;
+use std::path::PathBuf;
 
 /// 配置文件格式转换工具
 ///
@@ -11,14 +31,81 @@ use crate::format::Format;
 #[command(author, version, about, long_about = None)]
 #[command(arg_required_else_help = true)]
 pub struct Cli {
-    /// 显示详细信息
-    #[arg(short, long, global = true, conflicts_with = "quiet")]
-    pub verbose: bool,
+    /// 显示详细信息（-v 处理过程，-vv 调试信息，-vvv 跟踪中间表示）
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    pub verbose: u8,
 
-    /// 安静模式
+    /// 安静模式：只输出错误和命令要求的数据
     #[arg(short, long, global = true, conflicts_with = "verbose")]
     pub quiet: bool,
 
+    /// 界面语言（默认按 LC_ALL、LC_MESSAGES、LANG 环境变量选择）
+    #[arg(long, global = true, value_name = "LANG")]
+    pub lang: Option<Lang>,
+
+    /// TOML 规范版本，决定解析时接受和输出时使用的语法
+    #[arg(long, global = true, value_name = "VERSION", default_value = "1.0")]
+    pub toml_version: TomlVersion,
+
+    /// YAML 规范版本，决定没有引号的标量（yes、on、0777 等）如何解释
+    #[arg(long, global = true, value_name = "VERSION", default_value = "1.2")]
+    pub yaml_spec: YamlSpec,
+
+    /// YAML 中 !!timestamp 和 !!binary 标签的处理方式
+    #[arg(long, global = true, value_name = "MODE", default_value = "string")]
+    pub yaml_tags: YamlTags,
+
+    /// YAML 中自定义标签（!Ref、!Sub 等）的处理方式
+    #[arg(long, global = true, value_name = "MODE", default_value = "error")]
+    pub custom_tags: CustomTags,
+
+    /// INI 方言：git、npmrc，或者 separator=、comments=、repeated=、subsections= 设置，用逗号分隔
+    #[arg(long, global = true, value_name = "SPEC", value_parser = IniDialect::parse, default_value = "default")]
+    pub ini_dialect: IniDialect,
+
+    /// 保留无穷大和 NaN：JSON 输入接受 NaN、Infinity 字面量，JSON 输出按 MODE 写出（默认 literal）
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "literal"
+    )]
+    pub json_allow_nan: Option<NanPolicy>,
+
+    /// 把警告视为错误：规则名，或者 warnings 表示所有警告（可多次指定）
+    #[arg(long, global = true, value_name = "CODE", value_parser = diagnostic::parse_code)]
+    pub deny: Vec<String>,
+
+    /// 保持为警告，用来排除 --deny warnings 中的规则（可多次指定）
+    #[arg(long, global = true, value_name = "CODE", value_parser = diagnostic::parse_code)]
+    pub warn: Vec<String>,
+
+    /// 不报告这些警告（可多次指定）
+    #[arg(long, global = true, value_name = "CODE", value_parser = diagnostic::parse_code)]
+    pub allow: Vec<String>,
+
+    /// 同时把诊断信息输出为 CI 的注解，显示在 PR 的代码行上
+    #[arg(long, global = true, value_name = "CI")]
+    pub annotate: Option<diagnostic::Annotate>,
+
+    /// 何时给诊断信息和差异着色
+    #[arg(long, global = true, value_name = "WHEN", default_value = "auto")]
+    pub color: ColorChoice,
+
+    /// 折行和截断使用的宽度（默认检测终端，0 表示不折行也不截断）
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    pub term_width: Option<usize>,
+
+    /// 不使用分页器（默认在输出到终端时使用 $PAGER 或 less）
+    #[arg(long, global = true)]
+    pub no_pager: bool,
+
+    /// 使用配置文件中 [profile.NAME] 段的参数作为默认值
+    #[arg(long, global = true, value_name = "NAME", env = "CONFCONV_PROFILE")]
+    pub profile: Option<String>,
+
     #[command(subcommand)]
     pub command: Commands,
 }
@@ -30,45 +117,244 @@ pub enum Commands {
     /// 示例：
     ///   confconv convert config.json --to yaml
     ///   cat config.json | confconv convert --from json --to yaml
+    ///   confconv convert a.toml b.toml --to json --to yaml --out-dir dist
+    ///   confconv convert deploy.yaml --filter 'jq .spec' --to toml
+    ///   confconv convert --map migrate.yaml
+    ///   confconv convert s3://configs/app.yaml -t json -o gs://dist/app.json
+    ///   confconv convert app.yaml -t json -o app.json --watch --exec 'pkill -HUP devserver'
+    ///   confconv convert generated.json -t yaml --order-file layout.txt
+    ///   confconv convert legacy.ini -t yaml --coerce server.port=integer
     #[command(alias = "c")]
     Convert {
         /// 输入文件路径（使用 - 表示标准输入）
         #[arg(default_value = "-")]
-        input: String,
+        inputs: Vec<String>,
 
         /// 输出文件路径
-        #[arg(short, long)]
+        #[arg(short, long, conflicts_with = "out_dir")]
         output: Option<String>,
 
+        /// 输出目录（每个输入、每种目标格式各生成一个文件）
+        #[arg(long)]
+        out_dir: Option<String>,
+
+        /// 按规则文件批量转换（如 configs/*.yaml -> dist/{stem}.json），代替输入文件和输出参数
+        #[arg(long, value_name = "RULES", conflicts_with_all = ["inputs", "output", "out_dir", "to"])]
+        map: Option<String>,
+
         /// 源格式（从标准输入读取时必需）
         #[arg(short, long)]
         from: Option<Format>,
 
-        /// 目标格式
-        #[arg(short = 't', long = "to")]
-        to: Format,
+        /// 目标格式（可重复指定多个）
+        #[arg(short = 't', long = "to", required_unless_present = "map")]
+        to: Vec<Format>,
 
         /// 美化输出
         #[arg(short, long)]
         pretty: bool,
+
+        /// YAML 输出的行宽：超长字符串折叠换行，短数组写成 [a, b] 形式
+        #[arg(long, value_parser = clap::value_parser!(u16).range(20..))]
+        width: Option<u16>,
+
+        /// JSON 输出的缩进空格数（1-8），指定时即为美化输出
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=8))]
+        indent: Option<u8>,
+
+        /// YAML 字符串值的引号写法
+        #[arg(long, value_name = "STYLE", default_value = "auto")]
+        quote: Quote,
+
+        /// null 的处理方式
+        #[arg(long, value_name = "POLICY", default_value = "keep")]
+        nulls: NullPolicy,
+
+        /// TOML 中对象数组的写法：tables（[[...]] 段）、inline 或 auto（一行放得下时内联），可以用 PATH= 只指定某些数组（可多次指定）
+        #[arg(long, value_name = "[PATH=]STYLE", value_parser = ArrayRule::parse)]
+        toml_arrays: Vec<ArrayRule>,
+
+        /// 在输出末尾追加 sha256 完整性标记注释（JSON 不支持），之后可用 verify 检查
+        #[arg(long)]
+        stamp: bool,
+
+        /// 转换为 JSON 时把 YAML、TOML 的注释存入 "_comment_<键>" 字段，转换为 YAML、TOML 时把这些字段写回注释
+        #[arg(long)]
+        comments_as_keys: bool,
+
+        /// 标准输出是终端时也输出二进制数据
+        #[arg(long)]
+        force: bool,
+
+        /// 以十六进制转储显示输出（只用于标准输出）
+        #[arg(long, conflicts_with_all = ["output", "out_dir", "map", "base64"])]
+        hex: bool,
+
+        /// 以 base64 显示输出（只用于标准输出）
+        #[arg(long, conflicts_with_all = ["output", "out_dir", "map"])]
+        base64: bool,
+
+        /// 监视输入文件，修改后重新转换（按 Ctrl-C 退出）
+        #[arg(long, conflicts_with = "map")]
+        watch: bool,
+
+        /// 监视模式下每次转换成功后执行的命令（如重启开发服务器）
+        #[arg(long, value_name = "CMD", requires = "watch")]
+        exec: Option<String>,
+
+        /// 监视模式下每次转换前清屏
+        #[arg(long, requires = "watch")]
+        clear: bool,
+
+        /// 尽量转换：跳过出错的文件继续转换其余文件，多文档 YAML 转换为文档的数组（出错的文档写成 null），最后汇总报告所有问题
+        #[arg(long)]
+        best_effort: bool,
+
+        #[command(flatten)]
+        transform: TransformArgs,
     },
 
     /// 验证配置文件语法
+    ///
+    /// 示例：
+    ///   confconv validate config.toml
+    ///   confconv validate configs/
+    ///   tail -f events.jsonl | confconv validate --stdin-stream --format jsonl
+    ///   confconv validate configs/ --report-format junit > validate.xml
     #[command(alias = "v")]
     Validate {
-        /// 配置文件路径
-        file: String,
+        /// 配置文件或目录路径（目录会被递归遍历）
+        #[arg(
+            required_unless_present = "stdin_stream",
+            conflicts_with = "stdin_stream"
+        )]
+        files: Vec<String>,
+
+        /// 指定格式（jsonl 表示每行一个 JSON 值）
+        #[arg(short, long, required_if_eq("stdin_stream", "true"))]
+        format: Option<ValidateFormat>,
+
+        /// 持续读取标准输入，每条记录（JSON Lines 的一行或 YAML 的一个文档）到达时立即验证并输出结果
+        #[arg(long)]
+        stdin_stream: bool,
+
+        /// 报告的格式（-f/--format 指定的是输入的格式）
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            default_value = "text",
+            conflicts_with = "stdin_stream",
+            value_parser = report::parser(&[ReportFormat::Text, ReportFormat::Junit])
+        )]
+        report_format: ReportFormat,
+
+        #[command(flatten)]
+        walk: WalkArgs,
+    },
+
+    /// 检查配置文件中容易引起混淆的写法
+    ///
+    /// 检查 YAML 的锚点和别名（没有被引用或重复定义的锚点、未定义或在定义之前使用的别名、被引用次数过多的锚点、循环引用），以及所有格式中重复的键、重复的子树、取值相同的相似键、不符合命名规范的键名；指定 --sorted-keys、--indent 时还检查键的顺序和 JSON 的缩进。--fix 修正其中有确定修正方式的问题。有错误时以非零状态退出
+    ///
+    /// 示例：
+    ///   confconv lint values.yaml
+    ///   confconv lint charts/ --max-alias-fanout 5
+    ///   confconv lint values.yaml --min-duplicate-size 20
+    ///   confconv lint config.toml --key-case snake --fix > fixed.toml
+    ///   confconv lint configs/ --sorted-keys --indent 4 --fix --write
+    ///   confconv lint configs/ --report-format html > lint.html
+    ///   confconv lint configs/ --report-format junit > lint.xml
+    Lint {
+        /// 配置文件或目录路径（目录会被递归遍历）
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// 指定格式
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 同一锚点被别名引用超过这个次数时给出警告
+        #[arg(long, value_name = "N", default_value = "10")]
+        max_alias_fanout: usize,
+
+        /// 至少包含 N 个值的相同子树出现在多处时给出警告
+        #[arg(long, value_name = "N", default_value = "8")]
+        min_duplicate_size: usize,
+
+        /// 要求键名使用的大小写风格（覆盖配置文件中的 [[naming]]）
+        #[arg(long, value_name = "CASE", conflicts_with = "key_pattern")]
+        key_case: Option<KeyCase>,
+
+        /// 要求键名匹配的正则表达式（覆盖配置文件中的 [[naming]]）
+        #[arg(long, value_name = "REGEX")]
+        key_pattern: Option<Regex>,
+
+        /// 要求对象的键按顺序排列（默认按字符逐个比较）
+        #[arg(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "lexical")]
+        sorted_keys: Option<SortMode>,
+
+        /// 要求 JSON 的缩进是 N 个空格的整数倍，修正时按 N 个空格缩进
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=8))]
+        indent: Option<u8>,
+
+        /// 修正有确定修正方式的问题（键名大小写、重复的键、键的顺序、缩进），修正后的文件输出到标准输出（只能指定一个文件）。只重新生成被修正的对象，其中有注释、锚点或流式集合时报错
+        #[arg(long)]
+        fix: bool,
+
+        /// 与 --fix 一起使用时原地修改文件，可以同时修正多个文件
+        #[arg(short = 'w', long, requires = "fix")]
+        write: bool,
+
+        /// 报告的格式（-f/--format 指定的是输入的格式）
+        #[arg(long, value_name = "FORMAT", default_value = "text")]
+        report_format: ReportFormat,
+
+        #[command(flatten)]
+        walk: WalkArgs,
+    },
+
+    /// 检查配置文件是否超出大小预算
+    ///
+    /// 限制文件的字节数、键的总数和嵌套层数，超出时以非零状态退出，用于在 CI 中提前发现下游系统（如 1MB 的 ConfigMap）无法接受的配置。配置文件中可以用 [[budget]] 按文件模式设置限制，命令行参数对所有文件生效并覆盖配置
+    ///
+    /// 示例：
+    ///   confconv check config.yaml --max-size 64KB --max-keys 500 --max-depth 8
+    ///   confconv check k8s/ --max-size 1MB
+    Check {
+        /// 配置文件或目录路径（目录会被递归遍历）
+        #[arg(required = true)]
+        files: Vec<String>,
 
         /// 指定格式
         #[arg(short, long)]
         format: Option<Format>,
+
+        /// 文件字节数上限（如 64KB、1MiB）
+        #[arg(long, value_name = "SIZE", value_parser = budget::parse_size)]
+        max_size: Option<u64>,
+
+        /// 所有对象中键的总数上限
+        #[arg(long, value_name = "N")]
+        max_keys: Option<usize>,
+
+        /// 对象和数组的嵌套层数上限
+        #[arg(long, value_name = "N")]
+        max_depth: Option<usize>,
+
+        #[command(flatten)]
+        walk: WalkArgs,
     },
 
     /// 格式化配置文件
+    ///
+    /// 示例：
+    ///   confconv format config.yaml -w
+    ///   confconv format shared.yaml --only server.tls -w
     #[command(alias = "fmt")]
     Format {
-        /// 配置文件路径
-        file: String,
+        /// 配置文件或目录路径（目录会被递归遍历）
+        #[arg(required = true)]
+        files: Vec<String>,
 
         /// 缩进空格数（1-8）
         #[arg(
@@ -79,8 +365,1239 @@ pub enum Commands {
         )]
         indent: u8,
 
+        /// 原地修改文件（保留文件权限）
+        #[arg(short = 'w', long)]
+        write: bool,
+
+        /// 原地修改时保留文件的修改时间
+        #[arg(long, requires = "write")]
+        preserve_mtime: bool,
+
+        /// YAML 输出的行宽：超长字符串折叠换行，短数组写成 [a, b] 形式
+        #[arg(long, value_parser = clap::value_parser!(u16).range(20..))]
+        width: Option<u16>,
+
+        /// TOML 中对象数组的写法：tables（[[...]] 段）、inline 或 auto（一行放得下时内联），可以用 PATH= 只指定某些数组（可多次指定）
+        #[arg(long, value_name = "[PATH=]STYLE", value_parser = ArrayRule::parse)]
+        toml_arrays: Vec<ArrayRule>,
+
+        /// 只格式化路径上的子树（如 server.tls），文件的其余部分保持不变，值变换也只作用于这个子树
+        #[arg(long, value_name = "PATH")]
+        only: Option<String>,
+
+        #[command(flatten)]
+        transform: TransformArgs,
+
+        #[command(flatten)]
+        walk: WalkArgs,
+    },
+
+    /// 把多个配置文件合并为一个文档
+    ///
+    /// 示例：
+    ///   confconv join db.yaml cache.yaml --nest-by-filename -t toml
+    ///   confconv join a.yaml b.yaml -o all.yaml
+    Join {
+        /// 输入文件路径
+        #[arg(required = true)]
+        inputs: Vec<String>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// 目标格式（默认使用第一个输入文件的格式）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 以文件名（不含扩展名）为键嵌套各文件内容，否则拼接为多文档/数组
+        #[arg(short, long)]
+        nest_by_filename: bool,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+    },
+
+    /// 从任意文本（如日志）中提取内嵌的配置对象
+    ///
+    /// 示例：
+    ///   confconv extract --format json app.log
+    ///   grep payload app.log | confconv extract --to yaml
+    Extract {
+        /// 输入文件路径（使用 - 表示标准输入）
+        #[arg(default_value = "-")]
+        input: String,
+
+        /// 要提取的内嵌格式
+        #[arg(short, long, default_value = "json")]
+        format: Format,
+
+        /// 转换为目标格式（默认每个对象输出为一行 JSON）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+    },
+
+    /// 把配置转义为 JSON 字符串字面量
+    ///
+    /// 示例：
+    ///   confconv escape config.yaml --as json
+    ///   APP_CONFIG=$(confconv escape config.json --as json)
+    Escape {
+        /// 输入文件路径（使用 - 表示标准输入）
+        #[arg(default_value = "-")]
+        input: String,
+
+        /// 源格式（配合 --as 使用）
+        #[arg(short, long)]
+        from: Option<Format>,
+
+        /// 转义前先转换为该格式（不指定则原样转义输入文本）
+        #[arg(long = "as")]
+        as_format: Option<Format>,
+    },
+
+    /// 把 JSON 字符串字面量还原为结构化配置
+    ///
+    /// 示例：
+    ///   echo "$APP_CONFIG" | confconv unescape --to yaml
+    Unescape {
+        /// 输入文件路径（使用 - 表示标准输入）
+        #[arg(default_value = "-")]
+        input: String,
+
+        /// 内嵌内容的格式（不指定则自动识别）
+        #[arg(short, long)]
+        from: Option<Format>,
+
+        /// 目标格式（默认与内嵌内容的格式相同）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+    },
+
+    /// 把路径上的子树移动到新路径
+    ///
+    /// 示例：
+    ///   confconv move config.yaml server.port server.http.port -w
+    #[command(alias = "mv", alias = "rename")]
+    Move {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 原路径（点号分隔）
+        from: String,
+
+        /// 新路径（点号分隔，缺少的父级会自动创建）
+        to: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 新路径已存在时覆盖
+        #[arg(long)]
+        force: bool,
+
         /// 原地修改文件
         #[arg(short = 'w', long)]
         write: bool,
     },
+
+    /// 读取路径上的值
+    ///
+    /// 示例：
+    ///   confconv get config.yaml servers[0].host --raw
+    ///   confconv get config.toml database --output json
+    Get {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 路径表达式（如 servers[0].host）
+        path: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 输出裸标量（字符串不带引号），便于在 shell 中使用
+        #[arg(short, long, conflicts_with_all = ["json", "output"])]
+        raw: bool,
+
+        /// 始终输出合法的 JSON
+        #[arg(long, conflicts_with = "output")]
+        json: bool,
+
+        /// 以指定格式输出复杂值（默认使用源文件的格式）
+        #[arg(short, long)]
+        output: Option<Format>,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+    },
+
+    /// 编辑配置文件
+    ///
+    /// 不指定 --ops 时在 $EDITOR 中打开文件，保存后验证并写回
+    ///
+    /// 示例：
+    ///   confconv edit config.yaml --ops migrate.yaml -w
+    ///   confconv edit Cargo.toml --as yaml
+    Edit {
+        /// 配置文件路径
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 操作文件：按顺序执行的 set/unset/move/rename 操作列表
+        #[arg(long, value_name = "FILE")]
+        ops: Option<String>,
+
+        /// 在编辑器中以该格式编辑，保存后转换回原格式
+        #[arg(long = "as", conflicts_with = "ops")]
+        as_format: Option<Format>,
+
+        /// 原地修改文件（配合 --ops 使用，交互式编辑总是写回）
+        #[arg(short = 'w', long, requires = "ops")]
+        write: bool,
+    },
+
+    /// 撤销最近一次对文件的原地修改（--write、edit 等）
+    ///
+    /// 修改前的内容记录在状态目录的 history 中，每撤销一次回退一个版本
+    ///
+    /// 示例：
+    ///   confconv undo config.yaml
+    ///   confconv undo config.yaml --list
+    Undo {
+        /// 配置文件路径
+        file: String,
+
+        /// 列出修改记录（从新到旧），不恢复
+        #[arg(long)]
+        list: bool,
+
+        /// 文件在那次修改之后又被改过时仍然恢复
+        #[arg(long, conflicts_with = "list")]
+        force: bool,
+    },
+
+    /// 把配置导出为环境变量
+    ///
+    /// 示例：
+    ///   confconv env config.yaml > .env
+    ///   eval "$(confconv env config.yaml --output shell --prefix APP_ --export)"
+    Env {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 输出形式
+        #[arg(short, long, value_enum, default_value = "dotenv")]
+        output: EnvFormat,
+
+        /// 变量名前缀
+        #[arg(long, default_value = "")]
+        prefix: String,
+
+        /// 嵌套键之间的连接符
+        #[arg(long, default_value = "_")]
+        separator: String,
+
+        /// 每行前加 export
+        #[arg(long)]
+        export: bool,
+    },
+
+    /// 把配置展开为命令行参数
+    ///
+    /// 示例：
+    ///   confconv args config.yaml
+    ///   confconv args config.yaml --template '-D{key}={value}'
+    ///   eval "mytool $(confconv args config.yaml --quote)"
+    Args {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 参数模板，{key} 为路径，{value} 为值
+        #[arg(
+            short,
+            long,
+            default_value = "--{key}={value}",
+            allow_hyphen_values = true
+        )]
+        template: String,
+
+        /// 嵌套键之间的连接符
+        #[arg(long, default_value = ".")]
+        separator: String,
+
+        /// 用单引号转义每个参数并输出为一行
+        #[arg(long)]
+        quote: bool,
+    },
+
+    /// 比较两个配置文件的内容
+    ///
+    /// 示例：
+    ///   confconv diff old.yaml new.yaml
+    ///   confconv diff config.json config.toml --ignore-order
+    ///   confconv diff a.yaml b.yaml --ignore-case --numeric-tolerance 0.001
+    ///   confconv diff old.yaml new.yaml --only spec --ignore 'spec.template.metadata'
+    ///   confconv diff old.yaml new.yaml --report-format html > diff.html
+    Diff {
+        /// 旧文件路径（使用 - 表示标准输入）
+        left: String,
+
+        /// 新文件路径
+        right: String,
+
+        /// 指定两边的格式（不指定则各自从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 数组不考虑元素顺序
+        #[arg(long)]
+        ignore_order: bool,
+
+        /// 对象的键不区分大小写
+        #[arg(long)]
+        ignore_case: bool,
+
+        /// 数字之差不超过该值时视为相等
+        #[arg(long, value_name = "EPS")]
+        numeric_tolerance: Option<f64>,
+
+        /// 只比较匹配的路径，可重复，支持 * 和 **（如 spec、items[*].name）
+        #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+        only: Vec<Pattern>,
+
+        /// 不比较匹配的路径，可重复，支持 * 和 **（如 metadata、**.password）
+        #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+        ignore: Vec<Pattern>,
+
+        /// 报告的格式（-f/--format 指定的是输入的格式）
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            default_value = "text",
+            value_parser = report::parser(&[ReportFormat::Text, ReportFormat::Html])
+        )]
+        report_format: ReportFormat,
+    },
+
+    /// 检查线上配置相对基准配置的漂移
+    ///
+    /// 每个文件分别与基准比较：~ 值与基准不同，- 缺少基准中的键，+ 基准中没有的键。退出码：0 没有漂移，1 发现漂移，2 无法完成检查
+    ///
+    /// 示例：
+    ///   confconv drift --golden golden.yaml deployed.json --ignore 'metadata.*'
+    ///   confconv drift --golden golden.yaml exports/*.json --allow-extra --json
+    Drift {
+        /// 审定的基准配置
+        #[arg(long, value_name = "FILE")]
+        golden: String,
+
+        /// 要检查的配置文件，可以有多个
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// 数组不考虑元素顺序
+        #[arg(long)]
+        ignore_order: bool,
+
+        /// 只比较匹配的路径，可重复，支持 * 和 **（如 spec、items[*].name）
+        #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+        only: Vec<Pattern>,
+
+        /// 不比较匹配的路径，可重复，支持 * 和 **（如 metadata、**.password）
+        #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+        ignore: Vec<Pattern>,
+
+        /// 不报告基准中没有的键
+        #[arg(long)]
+        allow_extra: bool,
+
+        /// 输出 JSON 格式的报告
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 列出配置中有、但应用不认识的键，用于清理废弃的设置
+    ///
+    /// 一个键不认识时不再列出它下面的键。发现未使用的键时以非零状态退出
+    ///
+    /// 示例：
+    ///   confconv unused config.yaml --known-keys keys.txt
+    ///   confconv unused config.toml --schema schema.json
+    Unused {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 键列表文件：每行一个路径，支持 * 和 **，# 开头的行是注释；server.port 同时认识 server
+        #[arg(long, value_name = "FILE", required_unless_present = "schema")]
+        known_keys: Option<String>,
+
+        /// JSON Schema 文件，properties、patternProperties 和 additionalProperties 中的键视为认识
+        #[arg(long, value_name = "FILE", conflicts_with = "known_keys")]
+        schema: Option<String>,
+
+        /// 源格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        from: Option<Format>,
+    },
+
+    /// 按顺序深度合并多个配置文件，后面的覆盖前面的
+    ///
+    /// 示例：
+    ///   confconv merge base.yaml prod.yaml -o config.yaml
+    ///   confconv merge base.yaml overlays/*.yaml --strict
+    ///   confconv merge base.yaml prod.yaml --interactive -o config.yaml
+    ///   confconv merge deployment.yaml patch.yaml --merge-key '**.containers=name'
+    Merge {
+        /// 输入文件路径，按顺序叠加
+        #[arg(required = true)]
+        inputs: Vec<String>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// 目标格式（默认使用第一个输入文件的格式）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 不允许后面的文件覆盖前面已定义的值，报告所有冲突并以非零状态退出
+        #[arg(long)]
+        strict: bool,
+
+        /// 逐个询问冲突保留哪一边（l 左边、r 右边、e 在编辑器中修改、q 放弃），写出解决后的结果
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// 对象数组按合并键逐个元素合并，而不是整体覆盖（[PATH=]KEY，可重复），如 '**.containers=name'
+        #[arg(long = "merge-key", value_name = "[PATH=]KEY", value_parser = MergeKey::parse)]
+        merge_keys: Vec<MergeKey>,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+
+        /// 在输出末尾追加 sha256 完整性标记注释（JSON 不支持），之后可用 verify 检查
+        #[arg(long)]
+        stamp: bool,
+    },
+
+    /// 按 base + overlays/<环境> + local 的约定叠加配置，输出最终生效的配置
+    ///
+    /// 覆盖文件在基础配置旁的 overlays 目录中，本机覆盖文件 local.* 与基础配置放在一起、不存在时跳过；文件可以是任意支持的格式
+    ///
+    /// 示例：
+    ///   confconv resolve --base base.yaml --env prod
+    ///   confconv resolve --base config/base.toml --env staging --no-local -t json -o effective.json
+    ///   confconv resolve --base base.yaml --env prod --set replicas=3
+    Resolve {
+        /// 基础配置文件
+        #[arg(long, value_name = "FILE")]
+        base: String,
+
+        /// 环境名，叠加 overlays/<环境>.* 中的覆盖
+        #[arg(long, value_name = "NAME")]
+        env: Option<String>,
+
+        /// 覆盖文件所在目录（默认为基础配置旁的 overlays）
+        #[arg(long, value_name = "DIR")]
+        overlays: Option<String>,
+
+        /// 本机覆盖文件（默认为基础配置旁的 local.*）
+        #[arg(long, value_name = "FILE")]
+        local: Option<String>,
+
+        /// 不叠加本机覆盖文件
+        #[arg(long, conflicts_with = "local")]
+        no_local: bool,
+
+        /// 覆盖单个值（path=value），可重复，在所有文件之后应用
+        #[arg(long = "set", value_name = "PATH=VALUE", value_parser = Assignment::parse)]
+        sets: Vec<Assignment>,
+
+        /// 对象数组按合并键逐个元素合并，而不是整体覆盖（[PATH=]KEY，可重复），如 '**.containers=name'
+        #[arg(long = "merge-key", value_name = "[PATH=]KEY", value_parser = MergeKey::parse)]
+        merge_keys: Vec<MergeKey>,
+
+        /// 目标格式（默认使用基础配置的格式）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+
+        /// 在输出末尾追加 sha256 完整性标记注释（JSON 不支持），之后可用 verify 检查
+        #[arg(long)]
+        stamp: bool,
+    },
+
+    /// 用配置文件中的变量渲染 Tera 模板
+    ///
+    /// 示例：
+    ///   confconv template config.yaml.tera --vars prod.json -o config.yaml
+    ///   confconv template app.toml.j2 --vars vars.yaml --validate
+    ///   confconv template config.yaml.tera --vars vars.json --to json -p
+    ///   confconv template config.yaml.tera --vars base.yaml --vars prod.yaml --set replicas=3
+    Template {
+        /// 模板文件路径（使用 - 表示标准输入）
+        template: String,
+
+        /// 变量文件路径，可重复，按顺序深度合并，顶层的键即模板中可用的变量
+        #[arg(long)]
+        vars: Vec<String>,
+
+        /// 覆盖单个变量（path=value），可重复，在所有变量文件之后应用
+        #[arg(long = "set", value_name = "PATH=VALUE", value_parser = Assignment::parse)]
+        sets: Vec<Assignment>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// 渲染结果的格式（不指定则从模板或输出文件的扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 把渲染结果转换为目标格式
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 检查渲染结果的语法，有错误时不输出
+        #[arg(long)]
+        validate: bool,
+
+        /// 美化输出（仅在转换时生效）
+        #[arg(short, long)]
+        pretty: bool,
+    },
+
+    /// 按断言文件检查配置中的值
+    ///
+    /// 示例：
+    ///   confconv test config.yaml --spec tests.yaml
+    ///   confconv -q test config.json --spec prod.tests.yaml
+    ///   confconv test config.yaml --spec tests.yaml --report-format junit > test.xml
+    Test {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 断言文件路径
+        #[arg(short, long)]
+        spec: String,
+
+        /// 指定配置文件的格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 报告的格式
+        #[arg(
+            long,
+            value_name = "FORMAT",
+            default_value = "text",
+            value_parser = report::parser(&[ReportFormat::Text, ReportFormat::Junit])
+        )]
+        report_format: ReportFormat,
+    },
+
+    /// 把配置的规范形式保存为快照，或检查配置是否偏离快照
+    ///
+    /// 快照保存在配置文件所在目录的 __confconv_snapshots__/ 下
+    ///
+    /// 示例：
+    ///   confconv snapshot config.yaml --update
+    ///   confconv snapshot config/*.yaml --check
+    Snapshot {
+        /// 配置文件路径
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// 写入（或覆盖）快照
+        #[arg(long, conflicts_with = "check")]
+        update: bool,
+
+        /// 与快照比较，不一致时以非零状态退出（默认行为）
+        #[arg(long)]
+        check: bool,
+    },
+
+    /// JSON Schema 相关操作
+    Schema {
+        #[command(subcommand)]
+        command: SchemaCommand,
+    },
+
+    /// 按 JSON Schema 的默认值补全配置
+    Defaults {
+        #[command(subcommand)]
+        command: DefaultsCommand,
+    },
+
+    /// Kubernetes ConfigMap/Secret 打包和解包
+    K8s {
+        #[command(subcommand)]
+        command: K8sCommand,
+    },
+
+    /// 与 Consul/etcd 键值存储的导出格式互相转换
+    Kv {
+        #[command(subcommand)]
+        command: KvCommand,
+    },
+
+    /// 导出到 AWS Parameter Store 或 Secrets Manager
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+
+    /// 把配置中的值替换为假值，保留结构和类型，便于在问题报告中分享
+    ///
+    /// 示例：
+    ///   confconv anonymize config.yaml > repro.yaml
+    ///   confconv anonymize config.json --keep 'features.*' --seed 42
+    Anonymize {
+        /// 输入文件路径（使用 - 表示标准输入）
+        #[arg(default_value = "-")]
+        input: String,
+
+        /// 源格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        from: Option<Format>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// 保留原值的路径，可重复，支持 * 和 **
+        #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+        keep: Vec<Pattern>,
+
+        /// 随机种子，指定时可以重现同样的假值（默认每次运行随机选择）
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// 按 JSON Schema 生成示例配置
+    ///
+    /// 示例：
+    ///   confconv example schema.json --to yaml -o config.example.yaml
+    ///   confconv example schema.json --to toml
+    Example {
+        /// Schema 文件路径（使用 - 表示标准输入）
+        schema: String,
+
+        /// Schema 文件的格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        from: Option<Format>,
+
+        /// 目标格式
+        #[arg(short = 't', long = "to", default_value = "yaml")]
+        to: Format,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+    },
+
+    /// 生成配置的 Markdown 参考文档
+    ///
+    /// 每个键一行，列出类型、默认值和说明。说明取自 Schema 的 description 或配置文件中键上的注释
+    ///
+    /// 示例：
+    ///   confconv docs config.yaml --schema schema.json -o CONFIG.md
+    ///   confconv docs config.toml --title "服务配置"
+    Docs {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// JSON Schema 文件，提供类型、默认值、说明和可选值
+        #[arg(long, value_name = "FILE")]
+        schema: Option<String>,
+
+        /// 源格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        from: Option<Format>,
+
+        /// 文档标题（默认为“<文件> 配置参考”）
+        #[arg(long)]
+        title: Option<String>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 检查文件中哪些内容无法无损转换为其他格式
+    ///
+    /// 示例：
+    ///   confconv doctor config.yaml
+    ///   confconv doctor Cargo.toml
+    Doctor {
+        /// 文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+    },
+
+    /// 检查生成的文件是否被手工修改过，或校验分离签名
+    ///
+    /// 示例：
+    ///   confconv convert app.json -t yaml -o app.yaml --stamp
+    ///   confconv verify app.yaml
+    ///   confconv verify config.yaml --sig config.yaml.sig --key key.pub.pem
+    Verify {
+        /// 文件路径
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// 校验分离签名（由 sign 生成）而不是完整性标记
+        #[arg(long)]
+        sig: Option<String>,
+
+        /// 可信的公钥（PKCS#8 PEM），不指定时使用用户配置 [signature] 段的 key，都没有时报错
+        #[arg(long, requires = "sig")]
+        key: Option<String>,
+    },
+
+    /// 对配置的规范形式做 Ed25519 签名，重新排版或转换格式后签名仍然有效
+    ///
+    /// 示例：
+    ///   openssl genpkey -algorithm ed25519 -out key.pem
+    ///   confconv sign config.yaml --key key.pem
+    Sign {
+        /// 配置文件路径
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 私钥文件（PKCS#8 PEM）
+        #[arg(short, long)]
+        key: String,
+
+        /// 签名文件路径（默认为 <文件>.sig）
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 输出版本、支持的格式、压缩方式和可用的命令
+    ///
+    /// 示例：
+    ///   confconv info
+    ///   confconv info --json
+    Info {
+        /// 输出机器可读的 JSON（包括每个命令的参数）
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// 配置文件相关操作
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// 输出 shell 补全脚本，补全子命令、选项和配置文件中的键路径
+    ///
+    /// 示例：
+    ///   source <(confconv completions bash)
+    ///   source <(confconv completions zsh)
+    ///   confconv completions fish | source
+    Completions {
+        /// shell 类型
+        shell: Shell,
+    },
+
+    /// 补全脚本使用的内部命令：补全最后一个词，每行输出一个候选
+    #[command(name = "__complete", hide = true)]
+    Complete {
+        /// 命令行中子命令及之后的词，最后一个是正在补全的词
+        #[arg(allow_hyphen_values = true, trailing_var_arg = true)]
+        words: Vec<String>,
+    },
+
+    /// 启动 gRPC 服务，提供 Convert、Validate、Diff 接口（协议见 proto/confconv.proto）
+    ///
+    /// 示例：
+    ///   confconv serve
+    ///   confconv serve --listen 0.0.0.0:50051
+    #[cfg(feature = "grpc")]
+    Serve {
+        /// 监听地址
+        #[arg(long, default_value = "127.0.0.1:50051")]
+        listen: std::net::SocketAddr,
+    },
+}
+
+/// schema 的子命令
+#[derive(Subcommand)]
+pub enum SchemaCommand {
+    /// 从样例配置推断 JSON Schema
+    ///
+    /// 示例：
+    ///   confconv schema infer config.yaml
+    ///   confconv schema infer samples/*.yaml --level strict -o schema.json
+    Infer {
+        /// 样例文件路径，可以有多个
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// 推断的严格程度
+        #[arg(short, long, value_enum, default_value = "normal")]
+        level: Level,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+/// defaults 的子命令
+#[derive(Subcommand)]
+pub enum DefaultsCommand {
+    /// 用 Schema 中的 default 补全缺少的键，输出完整的生效配置
+    ///
+    /// 示例：
+    ///   confconv defaults apply config.yaml --schema schema.json
+    ///   confconv defaults apply config.toml --schema schema.json -t json -o effective.json
+    Apply {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// JSON Schema 文件
+        #[arg(long, value_name = "FILE")]
+        schema: String,
+
+        /// 源格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        from: Option<Format>,
+
+        /// 目标格式（不指定则与源格式相同）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 删去值等于默认值的键，只留下有意修改的部分
+    ///
+    /// 默认值取自 Schema 中的 default，或参考配置文件中同一路径的值
+    ///
+    /// 示例：
+    ///   confconv defaults prune config.yaml --schema schema.json
+    ///   confconv defaults prune config.yaml --reference defaults.yaml -o config.yaml
+    Prune {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// JSON Schema 文件
+        #[arg(long, value_name = "FILE", required_unless_present = "reference")]
+        schema: Option<String>,
+
+        /// 参考配置文件，其中的值视为默认值
+        #[arg(long, value_name = "FILE", conflicts_with = "schema")]
+        reference: Option<String>,
+
+        /// 源格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        from: Option<Format>,
+
+        /// 目标格式（不指定则与源格式相同）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+/// config 的子命令
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// 输出用户配置、项目配置、缓存目录和状态目录的位置
+    ///
+    /// 示例：
+    ///   confconv config path
+    ///   confconv config path --json
+    Path {
+        /// 输出机器可读的 JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// k8s 的子命令
+#[derive(Subcommand)]
+pub enum K8sCommand {
+    /// 把配置文件打包为 ConfigMap（或 Secret）清单，文件内容放在 data 下
+    ///
+    /// 示例：
+    ///   confconv k8s wrap config.yaml --name app-config
+    ///   confconv k8s wrap app.toml logging.yaml --name app --namespace prod -o configmap.yaml
+    ///   confconv k8s wrap .env --name app-env --secret
+    Wrap {
+        /// 配置文件路径，每个文件成为 data 中的一项（使用 - 表示标准输入）
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// 资源名称
+        #[arg(long)]
+        name: String,
+
+        /// 命名空间
+        #[arg(long)]
+        namespace: Option<String>,
+
+        /// data 中的键（默认为文件名，只有一个文件时可以指定）
+        #[arg(long)]
+        key: Option<String>,
+
+        /// 生成 Secret（内容以 base64 存放）而不是 ConfigMap
+        #[arg(long)]
+        secret: bool,
+
+        /// 清单的格式
+        #[arg(short = 't', long = "to", default_value = "yaml")]
+        to: Format,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 从 ConfigMap/Secret 清单中取出内嵌的配置，可同时转换格式
+    ///
+    /// 示例：
+    ///   confconv k8s unwrap configmap.yaml
+    ///   kubectl get cm app-config -o yaml | confconv k8s unwrap --key app.toml --to json
+    Unwrap {
+        /// 清单文件路径（使用 - 表示标准输入）
+        #[arg(default_value = "-")]
+        manifest: String,
+
+        /// 要取出的 data 键（只有一项时可以省略）
+        #[arg(long)]
+        key: Option<String>,
+
+        /// 转换为目标格式（默认原样输出）
+        #[arg(short = 't', long = "to")]
+        to: Option<Format>,
+
+        /// 美化输出（仅在转换时生效）
+        #[arg(short, long)]
+        pretty: bool,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+/// kv 的子命令
+#[derive(Subcommand)]
+pub enum KvCommand {
+    /// 把配置展开为键值对，输出 consul kv import 或 etcd 可用的 JSON 数组
+    ///
+    /// 示例：
+    ///   confconv kv export config.yaml --prefix app/prod/ > kv.json && consul kv import @kv.json
+    ///   confconv kv export config.toml --flavor etcd --prefix /app/
+    Export {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 导出格式
+        #[arg(long, value_enum, default_value = "consul")]
+        flavor: KvFlavor,
+
+        /// 所有键的前缀
+        #[arg(long, default_value = "")]
+        prefix: String,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// 把键值存储的导出还原为嵌套配置
+    ///
+    /// 示例：
+    ///   consul kv export app/prod/ | confconv kv import --prefix app/prod/ -t yaml
+    ///   etcdctl get --prefix /app/ -w json | confconv kv import --flavor etcd --prefix /app/ -t toml
+    Import {
+        /// 导出文件路径（使用 - 表示标准输入）
+        #[arg(default_value = "-")]
+        file: String,
+
+        /// 导出格式
+        #[arg(long, value_enum, default_value = "consul")]
+        flavor: KvFlavor,
+
+        /// 只导入该前缀下的键，并从路径中去掉前缀
+        #[arg(long, default_value = "")]
+        prefix: String,
+
+        /// 所有值都作为字符串（默认按 YAML 标量解释数字、布尔和 null）
+        #[arg(long)]
+        strings: bool,
+
+        /// 目标格式
+        #[arg(short = 't', long = "to", default_value = "yaml")]
+        to: Format,
+
+        /// 美化输出
+        #[arg(short, long)]
+        pretty: bool,
+
+        /// 输出文件路径
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+}
+
+/// export 的子命令
+#[derive(Subcommand)]
+pub enum ExportCommand {
+    /// 把配置展开为 SSM Parameter Store 参数，输出 aws ssm put-parameter 命令或直接执行
+    ///
+    /// 示例：
+    ///   confconv export ssm config.yaml --prefix /app/prod/
+    ///   confconv export ssm config.yaml --prefix /app/prod/ --secure '**.password' --apply
+    Ssm {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 参数名前缀
+        #[arg(long, default_value = "/")]
+        prefix: String,
+
+        /// 使用 SecureString 类型的路径（支持通配），可重复
+        #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+        secure: Vec<Pattern>,
+
+        /// 直接执行（需要已安装并配置 AWS CLI），而不是输出命令
+        #[arg(long)]
+        apply: bool,
+    },
+
+    /// 把整个配置作为 Secrets Manager 密钥的 JSON 值，输出 aws secretsmanager 命令或直接执行
+    ///
+    /// 示例：
+    ///   confconv export secretsmanager config.yaml --secret-id app/prod
+    #[command(name = "secretsmanager")]
+    SecretsManager {
+        /// 配置文件路径（使用 - 表示标准输入）
+        file: String,
+
+        /// 指定格式（不指定则从扩展名推断）
+        #[arg(short, long)]
+        format: Option<Format>,
+
+        /// 密钥的名称或 ARN
+        #[arg(long)]
+        secret_id: String,
+
+        /// 直接执行（需要已安装并配置 AWS CLI），而不是输出命令
+        #[arg(long)]
+        apply: bool,
+    },
+}
+
+/// 值变换参数
+#[derive(Args)]
+pub struct TransformArgs {
+    /// 只保留路径（及其子树），其余的键都删除，支持通配，可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    select: Vec<Pattern>,
+
+    /// 删除路径，支持通配，可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    exclude: Vec<Pattern>,
+
+    /// 重命名键（如 server.addr=address），可重复
+    #[arg(long, value_name = "PATH=KEY", value_parser = Rename::parse)]
+    rename: Vec<Rename>,
+
+    /// 把路径上的标量转换为 string、number、integer 或 boolean（如 'ports.*=integer'），可重复
+    #[arg(long, value_name = "PATH=TYPE", value_parser = Coerce::parse)]
+    coerce: Vec<Coerce>,
+
+    /// 把路径上的值替换为 ***（在脚本和外部命令之后执行），支持通配，可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    redact: Vec<Pattern>,
+
+    /// 按规则文件对路径上的值脱敏、计算摘要或加密（默认使用配置文件 [transform] 段的 rules）
+    #[arg(long, value_name = "FILE")]
+    rules: Option<PathBuf>,
+
+    /// 优先排在最前的键（逗号分隔），其余的键排序后排在后面
+    #[arg(long, value_delimiter = ',', value_name = "KEYS")]
+    key_order: Option<Vec<String>>,
+
+    /// 对所有对象的键排序
+    #[arg(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "lexical")]
+    sort: Option<SortMode>,
+
+    /// 按排序文件中路径的先后排列键（每行一个路径，支持通配），没有列出的键排在后面
+    #[arg(long, value_name = "FILE")]
+    order_file: Option<PathBuf>,
+
+    /// 对元素全是标量的数组排序（使用 --sort 指定的排序方式）
+    #[arg(long)]
+    sort_arrays: bool,
+
+    /// 解码路径上的 base64 值（如 Secret 的 data.*），内容是文档时展开为结构，可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    decode_base64: Vec<Pattern>,
+
+    /// 输出前把路径上的值编码为 base64（结构先序列化为 JSON），可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    encode_base64: Vec<Pattern>,
+
+    /// 把内嵌 JSON/YAML 文档的字符串展开为结构，不指定路径时处理所有字符串，可重复
+    #[arg(
+        long,
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = "**",
+        require_equals = true,
+        value_parser = Pattern::parse
+    )]
+    parse_embedded: Vec<Pattern>,
+
+    /// 输出前把路径上的结构重新序列化为 JSON 字符串，可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    embed: Vec<Pattern>,
+
+    /// 把 vault:路径#字段 形式的字符串替换为 HashiCorp Vault 中的值（使用 VAULT_ADDR、VAULT_TOKEN）
+    #[arg(long)]
+    resolve_vault: bool,
+
+    /// 把日期时间字符串和 TOML 日期时间规范化为 RFC 3339
+    #[arg(long)]
+    normalize_dates: bool,
+
+    /// 规范化日期时间时把带偏移的时间转换到 UTC
+    #[arg(long, requires = "normalize_dates")]
+    utc: bool,
+
+    /// 把路径上的时长（30s、1.5h、1h30m）换算为秒，可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    durations: Vec<Pattern>,
+
+    /// 把路径上的大小（512Mi、2GB）换算为字节，可重复
+    #[arg(long, value_name = "PATH", value_parser = Pattern::parse)]
+    sizes: Vec<Pattern>,
+
+    /// 反过来把 --durations/--sizes 路径上的值换算为带单位的写法（1h30m、512Mi）
+    #[arg(long)]
+    humanize: bool,
+
+    /// 运行 WASM 变换模块（在沙箱中执行，导出 transform），可重复，排在配置文件中的模块之后
+    #[arg(long, value_name = "MODULE")]
+    wasm: Vec<PathBuf>,
+
+    /// 运行 Rhai 变换脚本，脚本中读写的变量 value 即整个文档，可重复
+    #[arg(long, value_name = "FILE")]
+    script: Vec<PathBuf>,
+
+    /// 把中间 JSON 交给外部命令处理（如 'jq .spec'），用其输出的 JSON 继续转换，可重复
+    #[arg(long, value_name = "COMMAND")]
+    filter: Vec<String>,
+}
+
+impl TransformArgs {
+    /// 转换为值变换配置，配置文件中的 WASM 模块排在命令行指定的之前
+    pub fn transforms(self, config: &TransformConfig) -> Transforms {
+        Transforms {
+            select: self.select,
+            exclude: self.exclude,
+            rename: self.rename,
+            coerce: self.coerce,
+            redact: self.redact,
+            rules: self.rules.or_else(|| config.rules.clone()),
+            encrypt: config.encrypt.clone(),
+            key_order: self.key_order,
+            sort: self.sort,
+            order_file: self.order_file,
+            sort_arrays: self.sort_arrays,
+            decode_base64: self.decode_base64,
+            encode_base64: self.encode_base64,
+            parse_embedded: self.parse_embedded,
+            embed: self.embed,
+            resolve_vault: self.resolve_vault,
+            normalize_dates: self.normalize_dates,
+            utc: self.utc,
+            durations: self.durations,
+            sizes: self.sizes,
+            humanize: self.humanize,
+            wasm: config.wasm.iter().cloned().chain(self.wasm).collect(),
+            scripts: self.script,
+            filters: self.filter,
+        }
+    }
+}
+
+/// 目录遍历参数
+#[derive(Args)]
+pub struct WalkArgs {
+    /// 遍历目录时遵守 .gitignore（默认开启）
+    #[arg(long, overrides_with = "no_respect_gitignore")]
+    respect_gitignore: bool,
+
+    /// 遍历目录时不遵守 .gitignore
+    #[arg(long, overrides_with = "respect_gitignore")]
+    no_respect_gitignore: bool,
+}
+
+impl WalkArgs {
+    /// 是否遵守 .gitignore
+    pub fn respect_gitignore(&self) -> bool {
+        !self.no_respect_gitignore
+    }
 }
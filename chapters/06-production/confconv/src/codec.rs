@@ -0,0 +1,690 @@
+//! 编解码：在文本和统一的中间表示之间转换
+//!
+//! 所有格式都先解析为 `serde_json::Value`，再从它序列化为目标格式，
+//! 这样 N 种格式只需要 N 个解析器和 N 个序列化器
+
+use crate::cache;
+use crate::compress::{self, Compression};
+use crate::custom_tags::{self, CustomTags};
+use crate::cycle;
+use crate::dialect::{self, TomlVersion, YamlSpec};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::i18n::tr;
+use crate::ini;
+use crate::log;
+use crate::nonfinite::{self, Lossless};
+use crate::path::{Path, Segment};
+use crate::remote;
+use crate::stamp;
+use crate::t;
+use crate::toml_arrays::{self, ArrayRule};
+use crate::yaml;
+use crate::yaml11;
+use crate::yaml_tags;
+use clap::ValueEnum;
+use std::fs;
+use std::io::{self, Read};
+use std::time::Instant;
+
+/// 读取输入内容并确定源格式
+pub fn read_input(input: &str, from: Option<Format>) -> Result<(String, Format)> {
+    let from = input_format(input, from)?;
+    Ok((read_text(input)?, from))
+}
+
+/// 确定输入的源格式：标准输入必须指定，文件可以从扩展名推断
+fn input_format(input: &str, from: Option<Format>) -> Result<Format> {
+    if input == "-" {
+        from.ok_or_else(|| Error::Convert {
+            message: t!("从标准输入读取时必须指定 --from 参数"),
+        })
+    } else {
+        from.or_else(|| Format::from_path(input))
+            .ok_or_else(|| Error::UnknownFormat {
+                path: input.to_string(),
+            })
+    }
+}
+
+/// 读取输入的原始字节并确定源格式，用于可能是二进制的格式（如插件格式）
+pub fn read_input_bytes(input: &str, from: Option<Format>) -> Result<(Vec<u8>, Format)> {
+    let from = input_format(input, from)?;
+    Ok((read_bytes(input)?, from))
+}
+
+/// 读取输入并解析为中间表示，返回值和源格式
+pub fn read_value(input: &str, from: Option<Format>) -> Result<(serde_json::Value, Format)> {
+    let (content, format) = read_input_bytes(input, from)?;
+    Ok((parse_bytes(&content, format)?, format))
+}
+
+/// 读取整个输入文本（`-` 表示标准输入），压缩文件会先解压
+pub fn read_text(input: &str) -> Result<String> {
+    String::from_utf8(read_bytes(input)?).map_err(|e| Error::FileRead {
+        path: if input == "-" { "stdin" } else { input }.to_string(),
+        source: io::Error::new(io::ErrorKind::InvalidData, e.to_string()),
+    })
+}
+
+/// 读取整个输入的原始字节（`-` 表示标准输入），压缩文件会先解压
+pub fn read_bytes(input: &str) -> Result<Vec<u8>> {
+    let path = if input == "-" { "stdin" } else { input };
+    let read_error = |e| Error::FileRead {
+        path: path.to_string(),
+        source: e,
+    };
+    let bytes = if input == "-" {
+        let mut content = Vec::new();
+        io::stdin().read_to_end(&mut content).map_err(read_error)?;
+        content
+    } else if remote::is_remote(input) {
+        remote::read(input)?
+    } else {
+        fs::read(input).map_err(read_error)?
+    };
+    log!(Debug, "读取: {}（{} 字节）", path, bytes.len());
+    match Compression::from_path(input) {
+        Some(compression) => compression
+            .decompress(&bytes, compress::MAX_SIZE)
+            .map_err(read_error),
+        None => Ok(bytes),
+    }
+}
+
+/// 把文本解析为中间表示
+pub fn parse(input: &str, format: Format) -> Result<serde_json::Value> {
+    let start = Instant::now();
+    let value = match format {
+        Format::Json => {
+            // --json-allow-nan 时先把 NaN、Infinity 字面量换成标记对象
+            let input = if nonfinite::enabled() {
+                nonfinite::read_json_literals(input)
+            } else {
+                input.into()
+            };
+            serde_json::from_str(&input).map_err(|e| Error::Parse {
+                format: "JSON",
+                source: e.to_string(),
+            })?
+        }
+        Format::Yaml => {
+            let parsed = match dialect::current().yaml {
+                YamlSpec::V1_1 => yaml11::from_str(input),
+                YamlSpec::V1_2 => match custom_tags::find(input) {
+                    // serde_yml 读不了自定义标签，按 --custom-tags 处理
+                    Some(first) => custom_tags::parse(input, first),
+                    None if nonfinite::enabled() => serde_yml::from_str::<Lossless>(input)
+                        .map(|value| value.0)
+                        .map_err(|e| Error::Parse {
+                            format: "YAML",
+                            source: e.to_string(),
+                        }),
+                    None => serde_yml::from_str(input).map_err(|e| Error::Parse {
+                        format: "YAML",
+                        source: e.to_string(),
+                    }),
+                },
+            };
+            // 自引用的别名会让解析器报告难以理解的错误，改为给出环的路径
+            let mut value = parsed.map_err(|e| match cycle::yaml_alias_cycle(input) {
+                Some(cycle) => Error::Parse {
+                    format: "YAML",
+                    source: cycle.to_string(),
+                },
+                None => e,
+            })?;
+            yaml_tags::apply(input, &mut value, dialect::current().yaml_tags)?;
+            value
+        }
+        Format::Toml => {
+            let parse_error = |e: &dyn std::fmt::Display| Error::Parse {
+                format: "TOML",
+                source: e.to_string(),
+            };
+            // --json-allow-nan 时把 inf 和 nan 换成标记对象，否则转换后为 null
+            match dialect::current().toml {
+                TomlVersion::V1_0 => {
+                    let mut toml_value: toml::Value =
+                        toml::from_str(input).map_err(|e| parse_error(&e))?;
+                    if nonfinite::enabled() {
+                        nonfinite::toml10::mark(&mut toml_value);
+                    }
+                    serde_json::to_value(toml_value)
+                }
+                TomlVersion::V1_1 => {
+                    let mut toml_value: toml11::Value =
+                        toml11::from_str(input).map_err(|e| parse_error(&e))?;
+                    if nonfinite::enabled() {
+                        nonfinite::toml11::mark(&mut toml_value);
+                    }
+                    serde_json::to_value(toml_value)
+                }
+            }
+            .map_err(|e| Error::Convert {
+                message: e.to_string(),
+            })?
+        }
+        Format::Ini => ini::parse(input)?,
+        Format::Plugin(plugin) => plugin.parse(input.as_bytes())?,
+    };
+
+    log!(
+        Debug,
+        "按 {} 解析用时 {}",
+        format.name(),
+        format!("{:?}", start.elapsed())
+    );
+    log!(Trace, "解析结果: {}", value);
+    Ok(value)
+}
+
+/// 把原始字节解析为中间表示：插件格式直接交给插件，其他格式要求是 UTF-8 文本
+pub fn parse_bytes(input: &[u8], format: Format) -> Result<serde_json::Value> {
+    if let Format::Plugin(plugin) = format {
+        return plugin.parse(input);
+    }
+    let text = std::str::from_utf8(input).map_err(|e| Error::Parse {
+        format: format.name(),
+        source: t!("不是有效的 UTF-8: {}", e),
+    })?;
+    cache::parse_cached(input, format, || parse(text, format))
+}
+
+/// 分别解析 YAML 流中的每个文档，某个文档出错不影响其他文档
+///
+/// 按 [`YamlDocuments`] 切分，所以语法错误之后的文档仍然会被解析和报告
+pub fn parse_yaml_documents(input: &str) -> Vec<Result<serde_json::Value>> {
+    let mut splitter = YamlDocuments::default();
+    let mut documents: Vec<(usize, String)> = input
+        .lines()
+        .filter_map(|line| splitter.push(line))
+        .collect();
+    documents.extend(splitter.finish());
+    documents
+        .into_iter()
+        .map(|(start, text)| {
+            // 前面补上空行，错误信息中的行号与原文一致
+            parse(&format!("{}{}", "\n".repeat(start - 1), text), Format::Yaml)
+        })
+        .collect()
+}
+
+/// 逐行把 YAML 流切分为文档，供整个文件和 `validate --stdin-stream` 共用
+///
+/// 行首的 `---` 和 `...` 总是文档的边界（YAML 不允许文档内容在行首写出它们，
+/// 块标量中也一样），所以不需要解析就能切分，一个文档的语法错误不影响之后的文档。
+/// 只有注释和指令（`%YAML`）的部分并入下一个文档
+#[derive(Default)]
+pub struct YamlDocuments {
+    document: String,
+    /// 当前文档第一行的行号（从 1 开始）
+    start: usize,
+    /// 已经读入的行数
+    lines: usize,
+}
+
+impl YamlDocuments {
+    /// 读入一行（不含换行符），一个文档结束时返回它的起始行号和内容
+    pub fn push(&mut self, line: &str) -> Option<(usize, String)> {
+        self.lines += 1;
+        let mut done = None;
+        if marker(line, "---") || line.starts_with('%') {
+            done = self.take();
+        }
+        if self.document.is_empty() {
+            self.start = self.lines;
+        }
+        self.document.push_str(line);
+        self.document.push('\n');
+        if marker(line, "...") {
+            done = self.take();
+            self.document.clear();
+        }
+        done
+    }
+
+    /// 输入结束，返回最后一个文档
+    pub fn finish(&mut self) -> Option<(usize, String)> {
+        self.take()
+    }
+
+    /// 取出有内容的当前文档
+    fn take(&mut self) -> Option<(usize, String)> {
+        has_content(&self.document).then(|| (self.start, std::mem::take(&mut self.document)))
+    }
+}
+
+/// 行是否是文档标记 `marker`：后面只能是空白或行尾
+fn marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with([' ', '\t']))
+}
+
+/// 文档中除空行、注释、指令和文档标记外是否还有内容
+fn has_content(document: &str) -> bool {
+    document.lines().any(|line| {
+        let line = ["---", "..."]
+            .into_iter()
+            .find(|m| marker(line, m))
+            .map_or(line, |m| &line[m.len()..])
+            .trim();
+        !line.is_empty() && !line.starts_with('#') && !line.starts_with('%')
+    })
+}
+
+/// [`check`] 最多报告的错误数
+const MAX_ERRORS: usize = 50;
+
+/// 检查语法并尽量找出所有错误，没有错误时返回空列表
+///
+/// 解析器遇到第一个错误就会停止，这里跳过出错的部分继续检查：
+/// - YAML：逐个文档解析，每个文档报告一个错误
+/// - TOML：按行组织，清空出错的行后重新解析，最多报告 [`MAX_ERRORS`] 个错误
+/// - JSON 和插件格式：只能报告第一个错误
+///
+/// 跳过出错的部分后，之后的错误可能是由它引起的
+pub fn check(input: &str, format: Format) -> Vec<Error> {
+    let errors = match format {
+        Format::Yaml => parse_yaml_documents(input)
+            .into_iter()
+            .filter_map(Result::err)
+            .collect(),
+        Format::Toml => check_toml(input),
+        _ => Vec::new(),
+    };
+    if !errors.is_empty() {
+        return errors;
+    }
+    // 整体解析还可能有逐个检查发现不了的错误（如 YAML 含有多个文档）
+    parse(input, format).err().into_iter().collect()
+}
+
+/// 逐个找出 TOML 的语法错误
+fn check_toml(input: &str) -> Vec<Error> {
+    let mut lines: Vec<String> = input.split_inclusive('\n').map(str::to_string).collect();
+    let mut errors = Vec::new();
+    while errors.len() < MAX_ERRORS {
+        let text = lines.concat();
+        let failure = match dialect::current().toml {
+            TomlVersion::V1_0 => toml::from_str::<toml::Value>(&text)
+                .err()
+                .map(|e| (e.to_string(), e.span())),
+            TomlVersion::V1_1 => toml11::from_str::<toml11::Value>(&text)
+                .err()
+                .map(|e| (e.to_string(), e.span())),
+        };
+        let Some((message, span)) = failure else {
+            break;
+        };
+        errors.push(Error::Parse {
+            format: "TOML",
+            source: message.trim_end().to_string(),
+        });
+        // 清空出错的行（保留换行，行号不变）；定位不到或者该行已经是空行时无法继续
+        let Some(line) = span.map(|span| text[..span.start].matches('\n').count()) else {
+            break;
+        };
+        match lines.get_mut(line) {
+            Some(content) if !content.trim().is_empty() => {
+                *content = if content.ends_with('\n') { "\n" } else { "" }.to_string();
+            }
+            _ => break,
+        }
+    }
+    errors
+}
+
+/// 在不知道格式时依次尝试 JSON、TOML、YAML 解析
+///
+/// YAML 几乎能接受任何文本（普通字符串也是合法的 YAML 标量），所以放在最后
+pub fn parse_any(input: &str) -> Result<(serde_json::Value, Format)> {
+    for format in [Format::Json, Format::Toml] {
+        if let Ok(value) = parse(input, format) {
+            return Ok((value, format));
+        }
+    }
+    parse(input, Format::Yaml).map(|value| (value, Format::Yaml))
+}
+
+/// 解析不可信输入时的资源限制
+#[derive(Clone, Copy, Debug)]
+pub struct Limits {
+    /// 输入的最大字节数
+    pub max_bytes: usize,
+    /// 最大嵌套深度
+    pub max_depth: usize,
+    /// 解析结果中值（包括容器和标量）的最大数量，防止 YAML 别名展开等放大攻击
+    pub max_nodes: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Limits {
+            max_bytes: 16 * 1024 * 1024,
+            max_depth: 64,
+            max_nodes: 1_000_000,
+        }
+    }
+}
+
+/// 以默认的资源限制解析不可信的输入
+///
+/// `format` 为 None 时自动识别格式。与 [`parse`] 不同，这里：
+/// - 接受任意字节，非 UTF-8 输入返回错误
+/// - 解析前检查大小和括号嵌套深度，解析后检查深度和值的数量
+/// - 解析器内部的 panic 会被捕获并转换为错误
+pub fn parse_untrusted(
+    input: &[u8],
+    format: Option<Format>,
+) -> Result<(serde_json::Value, Format)> {
+    parse_untrusted_with(input, format, &Limits::default())
+}
+
+/// 以指定的资源限制解析不可信的输入
+pub fn parse_untrusted_with(
+    input: &[u8],
+    format: Option<Format>,
+    limits: &Limits,
+) -> Result<(serde_json::Value, Format)> {
+    if input.len() > limits.max_bytes {
+        return Err(Error::LimitExceeded {
+            limit: "输入字节数",
+            max: limits.max_bytes,
+        });
+    }
+    let text = std::str::from_utf8(input).map_err(|e| Error::Parse {
+        format: format.map_or("输入", |f| f.name()),
+        source: t!("不是有效的 UTF-8: {}", e),
+    })?;
+    if bracket_depth(text) > limits.max_depth {
+        return Err(Error::LimitExceeded {
+            limit: "嵌套深度",
+            max: limits.max_depth,
+        });
+    }
+
+    let parsed = std::panic::catch_unwind(|| match format {
+        Some(format) => {
+            cache::parse_cached(input, format, || parse(text, format)).map(|value| (value, format))
+        }
+        None => parse_any(text),
+    })
+    .map_err(|_| Error::Parse {
+        format: format.map_or("输入", |f| f.name()),
+        source: t!("解析器内部错误"),
+    })??;
+
+    check_limits(&parsed.0, limits)?;
+    Ok(parsed)
+}
+
+/// 粗略估计括号嵌套深度（不区分字符串内外），用于在解析前拒绝过深的输入
+fn bracket_depth(text: &str) -> usize {
+    let (mut depth, mut max) = (0usize, 0usize);
+    for byte in text.bytes() {
+        match byte {
+            b'[' | b'{' => {
+                depth += 1;
+                max = max.max(depth);
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max
+}
+
+/// 检查解析结果的深度和值的数量，使用显式栈避免深层结构导致栈溢出
+fn check_limits(value: &serde_json::Value, limits: &Limits) -> Result<()> {
+    let mut stack = vec![(value, 1usize)];
+    let mut nodes = 0usize;
+    while let Some((value, depth)) = stack.pop() {
+        nodes += 1;
+        if nodes > limits.max_nodes {
+            return Err(Error::LimitExceeded {
+                limit: "值的数量",
+                max: limits.max_nodes,
+            });
+        }
+        if depth > limits.max_depth {
+            return Err(Error::LimitExceeded {
+                limit: "嵌套深度",
+                max: limits.max_depth,
+            });
+        }
+        match value {
+            serde_json::Value::Array(items) => {
+                stack.extend(items.iter().map(|item| (item, depth + 1)));
+            }
+            serde_json::Value::Object(map) => {
+                stack.extend(map.values().map(|item| (item, depth + 1)));
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// 字符串值的引号写法（目前只影响 YAML）
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Quote {
+    /// 只在需要时加引号
+    #[value(help = tr("只在需要时加引号"))]
+    #[default]
+    Auto,
+    /// 所有字符串值都用双引号
+    #[value(help = tr("所有字符串值都用双引号"))]
+    Double,
+}
+
+/// null 的处理方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum NullPolicy {
+    /// 保持为 null（TOML 没有 null，会报错）
+    #[value(help = tr("保持为 null（TOML 没有 null，会报错）"))]
+    #[default]
+    Keep,
+    /// 删除值为 null 的键和数组元素
+    #[value(help = tr("删除值为 null 的键和数组元素"))]
+    Omit,
+    /// 遇到 null 时报错并给出路径
+    #[value(help = tr("遇到 null 时报错并给出路径"))]
+    Error,
+}
+
+/// 输出选项，命令行的输出参数和库的调用方都通过它控制序列化
+#[derive(Clone, Debug, Default)]
+pub struct OutputOptions {
+    /// 美化输出（JSON、TOML）
+    pub pretty: bool,
+    /// JSON 美化输出的缩进空格数，不指定时为 2
+    pub indent: Option<u8>,
+    /// YAML 行宽，不指定时使用 serde_yml 的默认排版
+    pub width: Option<usize>,
+    /// 字符串值的引号写法
+    pub quote: Quote,
+    /// null 的处理方式
+    pub nulls: NullPolicy,
+    /// 在末尾追加完整性标记（JSON 不支持，会被忽略）
+    pub stamp: bool,
+    /// TOML 中对象数组的写法，见 [`toml_arrays`]
+    pub toml_arrays: Vec<ArrayRule>,
+}
+
+/// 把中间表示序列化为目标格式的文本
+pub fn serialize(value: &serde_json::Value, format: Format, pretty: bool) -> Result<String> {
+    serialize_with(
+        value,
+        format,
+        &OutputOptions {
+            pretty,
+            ..OutputOptions::default()
+        },
+    )
+}
+
+/// 按指定的输出风格序列化为原始字节，插件格式的输出可以是二进制数据
+pub fn serialize_bytes(
+    value: &serde_json::Value,
+    format: Format,
+    style: &OutputOptions,
+) -> Result<Vec<u8>> {
+    match format {
+        Format::Plugin(plugin) => plugin.serialize(value),
+        _ => serialize_with(value, format, style).map(String::into_bytes),
+    }
+}
+
+/// 按指定的输出风格序列化
+pub fn serialize_with(
+    value: &serde_json::Value,
+    format: Format,
+    style: &OutputOptions,
+) -> Result<String> {
+    log!(Trace, "按 {} 序列化: {}", format.name(), value);
+    let omitted;
+    let value = match style.nulls {
+        NullPolicy::Keep => value,
+        NullPolicy::Omit => {
+            omitted = omit_nulls(value.clone());
+            &omitted
+        }
+        NullPolicy::Error => {
+            if let Some(path) = find_null(value, &Path::default()) {
+                return Err(Error::Convert {
+                    message: t!("{} 的值为 null", path),
+                });
+            }
+            value
+        }
+    };
+    if format != Format::Yaml {
+        custom_tags::check_target(value, format.name())?;
+    }
+    let pretty = style.pretty;
+    let output = match format {
+        Format::Json => {
+            let indent = pretty.then(|| style.indent.unwrap_or(2));
+            match dialect::current().json_nan {
+                Some(policy) => nonfinite::to_json(value, policy, indent),
+                None => to_json(value, indent),
+            }
+            .map_err(|e| Error::Convert {
+                message: e.to_string(),
+            })?
+        }
+        Format::Yaml => {
+            // keep 模式下标签对象还原为标签
+            let tags =
+                custom_tags::mode() == CustomTags::Keep && custom_tags::first(value).is_some();
+            match (style.width, style.quote, tags) {
+                (None, Quote::Auto, false) => serde_yml::to_string(&nonfinite::Native(value))
+                    .map_err(|e| Error::Convert {
+                        message: e.to_string(),
+                    })?,
+                (width, quote, tags) => {
+                    yaml::to_string(value, &yaml::Layout { width, quote, tags })?
+                }
+            }
+        }
+        Format::Toml => {
+            let convert_error = |e: &dyn std::fmt::Display| Error::Convert {
+                message: e.to_string(),
+            };
+            match dialect::current().toml {
+                TomlVersion::V1_0 => {
+                    let json_str = serde_json::to_string(&dialect::pad_toml_seconds(value))
+                        .map_err(|e| convert_error(&e))?;
+                    let mut toml_value: toml::Value =
+                        serde_json::from_str(&json_str).map_err(|e| convert_error(&e))?;
+                    nonfinite::toml10::restore(&mut toml_value);
+                    let output = if pretty {
+                        toml::to_string_pretty(&toml_value)
+                    } else {
+                        toml::to_string(&toml_value)
+                    }
+                    .map_err(|e| convert_error(&e))?;
+                    toml_arrays::apply(&output, &ArrayRule::resolve(&style.toml_arrays, value))
+                }
+                TomlVersion::V1_1 => {
+                    let json_str = serde_json::to_string(value).map_err(|e| convert_error(&e))?;
+                    let mut toml_value: toml11::Value =
+                        serde_json::from_str(&json_str).map_err(|e| convert_error(&e))?;
+                    nonfinite::toml11::restore(&mut toml_value);
+                    let output = if pretty {
+                        toml11::to_string_pretty(&toml_value)
+                    } else {
+                        toml11::to_string(&toml_value)
+                    }
+                    .map_err(|e| convert_error(&e))?;
+                    dialect::toml_short_escapes(&output)
+                }
+            }
+        }
+        Format::Ini => ini::to_string(value)?,
+        Format::Plugin(plugin) => {
+            String::from_utf8(plugin.serialize(value)?).map_err(|_| Error::Convert {
+                message: t!(
+                    "{} 的输出是二进制数据，只能用 convert 命令输出",
+                    plugin.name
+                ),
+            })?
+        }
+    };
+
+    if style.stamp && stamp::supports(format) {
+        return Ok(stamp::apply(&output));
+    }
+    Ok(output)
+}
+
+/// 序列化为 JSON，`indent` 为美化输出的缩进空格数，None 表示紧凑输出
+pub fn to_json(value: &impl serde::Serialize, indent: Option<u8>) -> serde_json::Result<String> {
+    let Some(indent) = indent else {
+        return serde_json::to_string(value);
+    };
+    let mut buf = Vec::new();
+    let indent = " ".repeat(indent as usize);
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+    let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
+    value.serialize(&mut ser)?;
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// 删除值为 null 的键和数组元素
+fn omit_nulls(value: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+    match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .filter(|(_, child)| !child.is_null())
+                .map(|(key, child)| (key, omit_nulls(child)))
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .into_iter()
+                .filter(|item| !item.is_null())
+                .map(omit_nulls)
+                .collect(),
+        ),
+        other => other,
+    }
+}
+
+/// 第一个 null 值的路径
+fn find_null(value: &serde_json::Value, path: &Path) -> Option<Path> {
+    use serde_json::Value;
+    match value {
+        Value::Null => Some(path.clone()),
+        Value::Object(map) => map
+            .iter()
+            .find_map(|(key, child)| find_null(child, &path.join(Segment::Key(key.clone())))),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .find_map(|(i, item)| find_null(item, &path.join(Segment::Index(i as i64)))),
+        _ => None,
+    }
+}
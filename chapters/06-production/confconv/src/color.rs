@@ -0,0 +1,93 @@
+//! 终端颜色
+//!
+//! 由全局参数 `--color` 决定诊断信息和差异是否着色：
+//! - `auto`（默认）：输出到终端时着色；设置了 `NO_COLOR` 时不着色，
+//!   设置了 `CLICOLOR_FORCE`（不为 `0`）时即使不是终端也着色
+//! - `always`、`never`：总是或从不着色，不看环境变量
+//!
+//! 标准输出和标准错误分别判断，所以 `confconv diff a b | less` 时差异不着色而错误信息仍然着色
+
+use crate::i18n::tr;
+use crate::pager;
+use clap::ValueEnum;
+use std::env;
+use std::sync::OnceLock;
+
+/// 何时着色
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum ColorChoice {
+    /// 输出到终端时着色
+    #[default]
+    #[value(help = tr("输出到终端时着色，遵循 NO_COLOR 和 CLICOLOR_FORCE"))]
+    Auto,
+    /// 总是着色
+    #[value(help = tr("总是着色"))]
+    Always,
+    /// 从不着色
+    #[value(help = tr("从不着色"))]
+    Never,
+}
+
+/// 输出流
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stream {
+    /// 标准输出
+    Stdout,
+    /// 标准错误
+    Stderr,
+}
+
+/// 颜色
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Cyan,
+}
+
+impl Color {
+    /// ANSI 前景色代码
+    fn code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+static CHOICE: OnceLock<ColorChoice> = OnceLock::new();
+
+/// 设置本次运行的着色方式，只有第一次调用有效
+pub fn init(choice: ColorChoice) {
+    let _ = CHOICE.set(choice);
+}
+
+/// 输出到 `stream` 的内容是否着色
+pub fn enabled(stream: Stream) -> bool {
+    match CHOICE.get().copied().unwrap_or_default() {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => {
+            if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+                return false;
+            }
+            if env::var_os("CLICOLOR_FORCE").is_some_and(|value| !value.is_empty() && value != "0")
+            {
+                return true;
+            }
+            pager::is_terminal(stream)
+        }
+    }
+}
+
+/// 给文本着色，`bold` 同时加粗；不着色时原样返回
+pub fn paint(stream: Stream, color: Color, bold: bool, text: &str) -> String {
+    if !enabled(stream) {
+        return text.to_string();
+    }
+    let bold = if bold { "1;" } else { "" };
+    format!("\x1b[{}{}m{}\x1b[0m", bold, color.code(), text)
+}
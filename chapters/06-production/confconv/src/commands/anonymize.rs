@@ -0,0 +1,275 @@
+//! anonymize 命令实现
+
+use crate::codec;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::path::{self, Path, Pattern, Segment};
+use crate::stamp;
+use crate::t;
+use serde_json::{Number, Value};
+use std::hash::{BuildHasher, RandomState};
+use std::time::SystemTime;
+
+/// 执行匿名化命令
+///
+/// 保留结构、键和类型，把字符串和数字替换为看起来合理的假值：
+/// - 邮箱、URL、IP、主机名、UUID 替换为同类的假值
+/// - 其他字符串逐字符替换，保留大小写、数字和标点的位置
+/// - 整数保留符号和位数，小数保留数量级
+///
+/// 布尔值和 null 不含敏感信息，保持不变。同一个 `seed` 下相同的原值总是得到相同的假值，
+/// 因此配置内部的引用关系不会被破坏。没有指定 `seed` 时每次运行随机选择，
+/// 避免别人用已知的种子从假值反推常见的原值
+pub fn run(
+    input: &str,
+    from: Option<Format>,
+    output: Option<&str>,
+    keep: &[Pattern],
+    seed: Option<u64>,
+    verbose: bool,
+) -> Result<()> {
+    let (mut value, format) = codec::read_value(input, from)?;
+    if verbose {
+        eprintln!("{}", t!("源格式: {}", format.name()));
+    }
+
+    let seed = seed.unwrap_or_else(random_seed);
+    let keep = path::resolve_patterns(keep, &value);
+    anonymize(&mut value, &Path::default(), &keep, seed);
+    let content = codec::serialize(&value, format, true)?;
+
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// 每次运行不同的种子：RandomState 的密钥来自操作系统的随机数
+fn random_seed() -> u64 {
+    RandomState::new().hash_one(SystemTime::now())
+}
+
+fn anonymize(value: &mut Value, path: &Path, keep: &[Pattern], seed: u64) {
+    if keep.iter().any(|p| p.covers(path)) {
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                anonymize(child, &path.join(Segment::Key(key.clone())), keep, seed);
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter_mut().enumerate() {
+                anonymize(child, &path.join(Segment::Index(i as i64)), keep, seed);
+            }
+        }
+        Value::String(s) => *s = fake_string(s, &mut Rng::new(seed, s)),
+        Value::Number(n) => {
+            let mut rng = Rng::new(seed, &n.to_string());
+            if let Some(fake) = fake_number(n, &mut rng) {
+                *n = fake;
+            }
+        }
+        Value::Bool(_) | Value::Null => {}
+    }
+}
+
+/// 按字符串的样子生成假值
+fn fake_string(s: &str, rng: &mut Rng) -> String {
+    if let Some((_, domain)) = s.split_once('@').filter(|(user, domain)| {
+        !user.is_empty() && domain.contains('.') && !s.contains(char::is_whitespace)
+    }) {
+        let tld = domain.rsplit('.').next().unwrap_or("com");
+        return format!("user{}@example.{}", rng.below(10000), tld);
+    }
+    if let Some((scheme, rest)) = s.split_once("://") {
+        let tail = rest.find('/').map(|i| &rest[i..]).unwrap_or("");
+        return format!(
+            "{}://host-{}.example.com{}",
+            scheme,
+            rng.below(1000),
+            mask(tail, rng)
+        );
+    }
+    if is_ipv4(s) {
+        return format!(
+            "10.{}.{}.{}",
+            rng.below(256),
+            rng.below(256),
+            1 + rng.below(254)
+        );
+    }
+    if is_uuid(s) {
+        return s
+            .chars()
+            .map(|c| match c {
+                '-' => '-',
+                _ => char::from_digit(rng.below(16) as u32, 16).unwrap_or('0'),
+            })
+            .collect();
+    }
+    if is_hostname(s) {
+        return format!("host-{}.example.com", rng.below(1000));
+    }
+    mask(s, rng)
+}
+
+/// 逐字符替换：字母换字母（保留大小写），数字换数字，其他字符保留
+fn mask(s: &str, rng: &mut Rng) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_lowercase() {
+                (b'a' + rng.below(26) as u8) as char
+            } else if c.is_ascii_uppercase() {
+                (b'A' + rng.below(26) as u8) as char
+            } else if c.is_ascii_digit() {
+                (b'0' + rng.below(10) as u8) as char
+            } else if c.is_alphanumeric() {
+                'x'
+            } else {
+                c
+            }
+        })
+        .collect()
+}
+
+/// 整数保留符号和位数，小数在原值的 0.5 到 1.5 倍之间取值
+fn fake_number(n: &Number, rng: &mut Rng) -> Option<Number> {
+    if let Some(i) = n.as_i64() {
+        let fake = same_digits(i.unsigned_abs(), i64::MAX as u64, rng) as i64;
+        return Some(Number::from(if i < 0 { -fake } else { fake }));
+    }
+    if let Some(u) = n.as_u64() {
+        return Some(Number::from(same_digits(u, u64::MAX, rng)));
+    }
+    let f = n.as_f64()?;
+    let factor = 0.5 + rng.below(1000) as f64 / 1000.0;
+    Number::from_f64((f * factor * 100.0).round() / 100.0)
+}
+
+/// 与 `n` 位数相同、不超过 `max` 的数
+fn same_digits(n: u64, max: u64, rng: &mut Rng) -> u64 {
+    let digits = n.to_string().len() as u32;
+    let low = if digits == 1 {
+        0
+    } else {
+        10u64.pow(digits - 1)
+    };
+    let high = 10u64.saturating_pow(digits).min(max);
+    low + rng.below(high - low)
+}
+
+fn is_ipv4(s: &str) -> bool {
+    let parts: Vec<&str> = s.split('.').collect();
+    parts.len() == 4 && parts.iter().all(|p| p.parse::<u8>().is_ok())
+}
+
+fn is_uuid(s: &str) -> bool {
+    let lengths: Vec<usize> = s.split('-').map(str::len).collect();
+    lengths == [8, 4, 4, 4, 12] && s.chars().all(|c| c == '-' || c.is_ascii_hexdigit())
+}
+
+/// 至少两段、由字母数字和连字符组成、最后一段是字母的域名
+fn is_hostname(s: &str) -> bool {
+    let labels: Vec<&str> = s.split('.').collect();
+    labels.len() >= 2
+        && labels
+            .iter()
+            .all(|l| !l.is_empty() && l.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+        && labels
+            .last()
+            .is_some_and(|tld| tld.chars().all(|c| c.is_ascii_alphabetic()))
+}
+
+/// 由种子和原值决定的确定性随机数（splitmix64）
+///
+/// 初始状态取 SHA-256 的前 8 个字节：`DefaultHasher` 的算法不保证在 Rust 版本之间不变，
+/// 同一个种子在不同版本编译的程序中要得到相同的假值
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64, text: &str) -> Self {
+        let hex = stamp::sha256_hex(format!("{}:{}", seed, text).as_bytes());
+        Rng(u64::from_str_radix(&hex[..16], 16).unwrap_or_default())
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// `[0, n)` 之间的数
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n.max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keeps_shape_and_types_deterministically() {
+        let original = json!({
+            "email": "alice@corp.io",
+            "url": "https://db.internal/v1/items",
+            "ip": "192.168.1.20",
+            "id": "550e8400-e29b-41d4-a716-446655440000",
+            "name": "Alice-01",
+            "port": 5432,
+            "offset": -17,
+            "big": 18446744073709551615u64,
+            "ratio": 0.25,
+            "on": true,
+            "none": null,
+            "list": [{"token": "ab-CD"}]
+        });
+        let run = |seed| {
+            let mut value = original.clone();
+            anonymize(
+                &mut value,
+                &Path::default(),
+                &[Pattern::parse("list[*].token").unwrap()],
+                seed,
+            );
+            value
+        };
+        let fake = run(7);
+        assert_eq!(fake, run(7));
+        assert_ne!(fake, run(8));
+
+        assert!(fake["email"].as_str().unwrap().ends_with("@example.io"));
+        let url = fake["url"].as_str().unwrap();
+        assert!(url.starts_with("https://host-"));
+        assert_eq!(
+            url.split('/').map(str::len).skip(3).collect::<Vec<_>>(),
+            [2, 5]
+        );
+        assert!(is_ipv4(fake["ip"].as_str().unwrap()));
+        assert!(is_uuid(fake["id"].as_str().unwrap()));
+        let name = fake["name"].as_str().unwrap();
+        assert_eq!(name.len(), 8);
+        assert_eq!(&name[5..6], "-");
+        assert!(name[..1].chars().all(|c| c.is_ascii_uppercase()));
+        assert_eq!(fake["port"].as_i64().unwrap().to_string().len(), 4);
+        assert!((-99..=-10).contains(&fake["offset"].as_i64().unwrap()));
+        assert!(fake["big"].as_u64().unwrap() >= 10u64.pow(19));
+        assert!(fake["ratio"].is_f64());
+        assert_eq!(fake["on"], json!(true));
+        assert_eq!(fake["none"], json!(null));
+        assert_eq!(fake["list"], json!([{"token": "ab-CD"}]));
+    }
+}
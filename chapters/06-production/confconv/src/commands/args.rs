@@ -0,0 +1,60 @@
+//! args 命令实现
+
+use super::env::shell_quote;
+use crate::codec;
+use crate::error::Result;
+use crate::flatten;
+use crate::format::Format;
+
+/// 执行参数生成命令
+///
+/// 把配置展开为命令行参数，模板中的 `{key}` 替换为用 `separator`
+/// 连接的路径，`{value}` 替换为值。默认每行一个参数，
+/// `quote` 时用单引号转义后拼成一行，便于在 shell 中展开
+pub fn run(
+    file: &str,
+    format: Option<Format>,
+    template: &str,
+    separator: &str,
+    quote: bool,
+) -> Result<()> {
+    let (value, _) = codec::read_value(file, format)?;
+
+    let args: Vec<String> = flatten::flatten(&value)
+        .iter()
+        .map(|entry| render(template, &entry.path.join(separator), &entry.text()))
+        .collect();
+
+    if quote {
+        let quoted: Vec<String> = args.iter().map(|arg| shell_quote(arg)).collect();
+        println!("{}", quoted.join(" "));
+    } else {
+        for arg in &args {
+            println!("{}", arg);
+        }
+    }
+
+    Ok(())
+}
+
+/// 一次扫描替换占位符，值里出现的 `{key}` 不会被再次替换
+fn render(template: &str, key: &str, value: &str) -> String {
+    let mut out = String::with_capacity(template.len() + key.len() + value.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        if let Some(after) = tail.strip_prefix("{key}") {
+            out.push_str(key);
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("{value}") {
+            out.push_str(value);
+            rest = after;
+        } else {
+            out.push('{');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    out
+}
@@ -0,0 +1,80 @@
+//! check 命令实现
+
+use crate::budget::{self, Budget, Usage};
+use crate::codec;
+use crate::color::Stream;
+use crate::diagnostic::{self, render};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::t;
+
+/// 执行大小预算检查
+///
+/// 逐个检查所有文件，输出超出的每项限制；有文件超出限制或无法读取时返回错误
+pub fn run(
+    files: &[String],
+    format: Option<Format>,
+    budget: &Budget,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    let mut failed = 0;
+    for file in files {
+        let usage = match measure(file, format) {
+            Ok(usage) => usage,
+            Err(e) => {
+                eprintln!("✗ {}: {}", file, e);
+                failed += 1;
+                continue;
+            }
+        };
+        let diagnostics = budget::check(&usage, &budget.limits(file));
+        if diagnostics.is_empty() {
+            if verbose {
+                eprintln!(
+                    "{}",
+                    t!(
+                        "✓ {}: {} 字节，{} 个键，{} 层",
+                        file,
+                        usage.size,
+                        usage.keys,
+                        usage.depth
+                    )
+                );
+            }
+            continue;
+        }
+        for diagnostic in &diagnostics {
+            println!("{}", render(file, diagnostic, Stream::Stdout));
+            diagnostic::annotate(file, diagnostic);
+        }
+        failed += 1;
+    }
+
+    if !quiet {
+        eprintln!(
+            "{}",
+            t!("检查了 {} 个文件：{} 个超出限制", files.len(), failed)
+        );
+    }
+    if failed > 0 {
+        return Err(Error::BudgetExceeded {
+            failed,
+            total: files.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 读取并解析文件，统计大小
+fn measure(file: &str, format: Option<Format>) -> Result<Usage> {
+    let format =
+        format
+            .or_else(|| Format::from_path(file))
+            .ok_or_else(|| Error::UnknownFormat {
+                path: file.to_string(),
+            })?;
+    let content = codec::read_bytes(file)?;
+    let value = codec::parse_bytes(&content, format)?;
+    Ok(Usage::measure(&content, &value))
+}
@@ -0,0 +1,375 @@
+//! 命令行补全
+//!
+//! `completions` 命令输出 shell 的补全脚本，脚本在每次按 Tab 时调用隐藏的 `__complete` 命令，
+//! 由它按命令定义补全子命令、选项和选项的取值。路径参数（`get` 的路径、`move` 的原路径和新路径、
+//! `--only` 等取值为 PATH 的选项）会读取命令行中的配置文件，补全其中实际存在的键路径：
+//!
+//! ```text
+//! confconv __complete -- get config.yaml data.
+//! data.host    "localhost"
+//! data.port    8080
+//! ```
+//!
+//! 每行一个候选，候选和说明用制表符分隔；没有候选时 shell 退回到文件名补全
+
+use crate::cli::Cli;
+use crate::codec;
+use crate::error::Result;
+use crate::format::Format;
+use crate::i18n;
+use crate::path::{self, Path, Segment};
+use crate::remote;
+use crate::term;
+use clap::builder::PossibleValue;
+use clap::{Arg, Command, CommandFactory, ValueEnum};
+use serde_json::Value;
+
+/// 补全说明中值的最大宽度
+const MAX_DESCRIPTION: usize = 40;
+
+/// 支持补全的 shell
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// 输出补全脚本
+pub fn completions(shell: Shell) -> Result<()> {
+    let script = match shell {
+        Shell::Bash => BASH,
+        Shell::Zsh => ZSH,
+        Shell::Fish => FISH,
+    };
+    print!("{}", script);
+    Ok(())
+}
+
+/// 补全最后一个词（可以为空），输出所有候选
+pub fn run(words: &[String]) -> Result<()> {
+    let command = i18n::localize(Cli::command());
+    for candidate in complete(&command, words) {
+        println!("{}", candidate);
+    }
+    Ok(())
+}
+
+/// 已经读过的命令行
+struct Line<'a> {
+    /// 当前所在的子命令
+    command: &'a Command,
+    /// 已经出现的位置参数
+    positionals: Vec<&'a str>,
+    /// `--format` 的值，用来读取配置文件
+    format: Option<&'a str>,
+}
+
+/// 补全最后一个词，返回 `候选\t说明` 形式的候选
+fn complete(root: &Command, words: &[String]) -> Vec<String> {
+    let Some((current, before)) = words.split_last() else {
+        return subcommands(root, "");
+    };
+    let mut line = Line {
+        command: root,
+        positionals: Vec::new(),
+        format: None,
+    };
+    let mut expecting: Option<&Arg> = None;
+    let mut options_done = false;
+    for word in before {
+        if let Some(arg) = expecting.take() {
+            if arg.get_id() == "format" {
+                line.format = Some(word);
+            }
+            continue;
+        }
+        if word == "--" && !options_done {
+            options_done = true;
+            continue;
+        }
+        if !options_done && word.starts_with('-') && word != "-" {
+            let (name, value) = match word.split_once('=') {
+                Some((name, value)) => (name, Some(value)),
+                None => (word.as_str(), None),
+            };
+            match find_option(root, line.command, name) {
+                Some(arg) if value.is_none() && takes_value(arg) => expecting = Some(arg),
+                Some(arg) if arg.get_id() == "format" => line.format = value,
+                _ => {}
+            }
+            continue;
+        }
+        if line.positionals.is_empty() {
+            if let Some(sub) = find_subcommand(line.command, word) {
+                line.command = sub;
+                continue;
+            }
+        }
+        line.positionals.push(word);
+    }
+
+    if let Some(arg) = expecting {
+        return values(arg, &line, current);
+    }
+    if let Some((name, value)) = current
+        .split_once('=')
+        .filter(|(name, _)| name.starts_with("--"))
+    {
+        return match find_option(root, line.command, name) {
+            Some(arg) => values(arg, &line, value)
+                .into_iter()
+                .map(|candidate| format!("{}={}", name, candidate))
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+    if current.starts_with('-') && !options_done {
+        return options(root, line.command, current);
+    }
+    if line.positionals.is_empty() && line.command.has_subcommands() {
+        return subcommands(line.command, current);
+    }
+    // 多值的位置参数会接收之后所有的位置参数
+    let positional = line
+        .command
+        .get_positionals()
+        .nth(line.positionals.len())
+        .or_else(|| line.command.get_positionals().last());
+    match positional {
+        Some(arg) => values(arg, &line, current),
+        None => Vec::new(),
+    }
+}
+
+/// 参数是否取值（`--json-allow-nan` 这种只能用 `=` 写出的可选取值除外）
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_action().takes_values() && !arg.is_require_equals_set()
+}
+
+/// 查找选项：当前子命令的选项，或者全局选项
+fn find_option<'a>(root: &'a Command, command: &'a Command, name: &str) -> Option<&'a Arg> {
+    let matches = |arg: &&Arg| match name.strip_prefix("--") {
+        Some(long) => arg.get_long() == Some(long),
+        None => {
+            let mut short = name.chars().skip(1);
+            arg.get_short().is_some() && arg.get_short() == short.next() && short.next().is_none()
+        }
+    };
+    let found = command.get_arguments().find(matches);
+    found.or_else(|| {
+        root.get_arguments()
+            .filter(|arg| arg.is_global_set())
+            .find(matches)
+    })
+}
+
+/// 按名称或别名查找子命令
+fn find_subcommand<'a>(command: &'a Command, name: &str) -> Option<&'a Command> {
+    command
+        .get_subcommands()
+        .find(|sub| sub.get_name() == name || sub.get_all_aliases().any(|alias| alias == name))
+}
+
+/// 子命令的候选
+fn subcommands(command: &Command, current: &str) -> Vec<String> {
+    command
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set() && sub.get_name().starts_with(current))
+        .map(|sub| candidate(sub.get_name(), sub.get_about().map(ToString::to_string)))
+        .collect()
+}
+
+/// 长选项的候选，包括全局选项
+fn options(root: &Command, command: &Command, current: &str) -> Vec<String> {
+    let globals = root.get_arguments().filter(|arg| arg.is_global_set());
+    let local = command.get_arguments().filter(|arg| !arg.is_global_set());
+    local
+        .chain(globals)
+        .filter(|arg| !arg.is_hide_set())
+        .filter_map(|arg| {
+            let long = format!("--{}", arg.get_long()?);
+            long.starts_with(current)
+                .then(|| candidate(&long, arg.get_help().map(ToString::to_string)))
+        })
+        .chain(
+            ["--help"]
+                .into_iter()
+                .filter(|help| help.starts_with(current) && current.starts_with("--"))
+                .map(str::to_string),
+        )
+        .collect()
+}
+
+/// 参数取值的候选：可选的取值、配置文件中的键路径，其他参数没有候选（由 shell 补全文件名）
+fn values(arg: &Arg, line: &Line, current: &str) -> Vec<String> {
+    let possible: Vec<PossibleValue> = arg.get_possible_values();
+    if !possible.is_empty() {
+        return possible
+            .iter()
+            .filter(|value| !value.is_hide_set() && value.get_name().starts_with(current))
+            .map(|value| candidate(value.get_name(), value.get_help().map(ToString::to_string)))
+            .collect();
+    }
+    let is_path = arg
+        .get_value_names()
+        .is_some_and(|names| names.iter().any(|name| name == "PATH"))
+        || (arg.is_positional() && matches!(arg.get_id().as_str(), "path" | "from" | "to"));
+    match line.positionals.first() {
+        Some(file) if is_path => key_paths(file, line.format, current),
+        _ => Vec::new(),
+    }
+}
+
+/// 配置文件中以 `current` 开头的键路径，只列出下一级
+fn key_paths(file: &str, format: Option<&str>, current: &str) -> Vec<String> {
+    // 不读取标准输入和远程文件：补全应该立即返回
+    if file == "-" || remote::is_remote(file) {
+        return Vec::new();
+    }
+    let format = format.and_then(|name| Format::from_str(name, true).ok());
+    let Ok((value, _)) = codec::read_value(file, format) else {
+        return Vec::new();
+    };
+    let parent = match current.rfind(['.', '[']) {
+        Some(end) => &current[..end],
+        None => "",
+    };
+    let Ok(parent) = Path::parse(parent) else {
+        return Vec::new();
+    };
+    let children: Vec<(Path, &Value)> = match path::get(&value, &parent) {
+        Some(Value::Object(map)) => map
+            .iter()
+            .map(|(key, child)| (parent.join(Segment::Key(key.clone())), child))
+            .collect(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (parent.join(Segment::Index(index as i64)), child))
+            .collect(),
+        _ => Vec::new(),
+    };
+    children
+        .into_iter()
+        .map(|(path, child)| (path.to_string(), child))
+        .filter(|(path, _)| path.starts_with(current))
+        .map(|(path, child)| candidate(&path, Some(summary(child))))
+        .collect()
+}
+
+/// 值的简短说明：容器显示大小，标量显示截断后的值
+fn summary(value: &Value) -> String {
+    match value {
+        Value::Object(map) => format!("{{…}} ({})", map.len()),
+        Value::Array(items) => format!("[…] ({})", items.len()),
+        scalar => term::truncate(&scalar.to_string(), MAX_DESCRIPTION),
+    }
+}
+
+/// `候选\t说明`，说明只保留第一行
+fn candidate(value: &str, description: Option<String>) -> String {
+    match description.as_deref().and_then(|text| text.lines().next()) {
+        Some(description) if !description.is_empty() => format!("{}\t{}", value, description),
+        _ => value.to_string(),
+    }
+}
+
+/// bash 补全脚本：没有候选时按默认方式补全文件名
+const BASH: &str = r#"# confconv 的 bash 补全，加载方式：source <(confconv completions bash)
+_confconv() {
+    local IFS=$'\n'
+    COMPREPLY=($(confconv __complete -- "${COMP_WORDS[@]:1:COMP_CWORD}" 2>/dev/null | cut -f1))
+}
+complete -o default -o nosort -F _confconv confconv
+"#;
+
+/// zsh 补全脚本
+const ZSH: &str = r#"#compdef confconv
+# confconv 的 zsh 补全，加载方式：source <(confconv completions zsh)
+_confconv() {
+    local -a lines values descriptions
+    lines=("${(@f)$(confconv __complete -- "${(@)words[2,CURRENT]}" 2>/dev/null)}")
+    lines=(${lines:#})
+    if (( ${#lines} == 0 )); then
+        _files
+        return
+    fi
+    local line
+    for line in $lines; do
+        values+=("${line%%$'\t'*}")
+        if [[ $line == *$'\t'* ]]; then
+            descriptions+=("${line%%$'\t'*} -- ${line#*$'\t'}")
+        else
+            descriptions+=("${line}")
+        fi
+    done
+    compadd -l -d descriptions -Q -- $values
+}
+compdef _confconv confconv
+"#;
+
+/// fish 补全脚本：fish 直接支持制表符分隔的说明
+const FISH: &str = r#"# confconv 的 fish 补全，加载方式：confconv completions fish | source
+function __confconv_complete
+    set -l tokens (commandline -opc) (commandline -ct)
+    set -e tokens[1]
+    confconv __complete -- $tokens 2>/dev/null
+end
+complete -c confconv -f -n 'test -n "$(__confconv_complete)"' -a '(__confconv_complete)'
+complete -c confconv -F -n 'test -z "$(__confconv_complete)"'
+"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn words(line: &str) -> Vec<String> {
+        line.split(' ').map(str::to_string).collect()
+    }
+
+    fn names(candidates: Vec<String>) -> Vec<String> {
+        candidates
+            .into_iter()
+            .map(|c| c.split('\t').next().unwrap_or_default().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn completes_subcommands_options_and_values() {
+        let command = Cli::command();
+        assert!(names(complete(&command, &words("conv"))).contains(&"convert".to_string()));
+        assert!(
+            names(complete(&command, &words("convert --pre"))).contains(&"--pretty".to_string())
+        );
+        assert!(names(complete(&command, &words("convert a.json --to y")))
+            .contains(&"yaml".to_string()));
+        assert!(names(complete(&command, &words("--yaml-spec=1")))
+            .contains(&"--yaml-spec=1.1".to_string()));
+    }
+
+    #[test]
+    fn completes_key_paths_from_the_file() {
+        let file =
+            std::env::temp_dir().join(format!("confconv-complete-{}.json", std::process::id()));
+        std::fs::write(
+            &file,
+            r#"{"data": {"host": "h", "port": 1, "a.b": 2}, "list": [1, 2]}"#,
+        )
+        .unwrap();
+        let file = file.display().to_string();
+        let complete = |current: &str| {
+            let mut line = vec!["get".to_string(), file.clone()];
+            line.push(current.to_string());
+            names(complete(&Cli::command(), &line))
+        };
+        assert_eq!(complete("d"), vec!["data"]);
+        assert_eq!(
+            complete("data."),
+            vec!["data.host", "data.port", "data.\"a.b\""]
+        );
+        assert_eq!(complete("data.p"), vec!["data.port"]);
+        assert_eq!(complete("list["), vec!["list[0]", "list[1]"]);
+        std::fs::remove_file(&file).unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+//! config path 命令实现
+
+use crate::config::Config;
+use crate::dirs;
+use crate::error::{Error, Result};
+use crate::t;
+use serde_json::json;
+use std::env;
+use std::path::PathBuf;
+
+/// 输出用户配置、项目配置、缓存目录和状态目录的位置
+///
+/// `json` 为 true 时输出 JSON，找不到的位置为 null
+pub fn run(json: bool) -> Result<()> {
+    let user = Config::path();
+    let project = env::current_dir()
+        .ok()
+        .and_then(|dir| Config::discover(&dir));
+    let cache = dirs::cache_dir();
+    let state = dirs::state_dir();
+
+    if json {
+        let locations = json!({
+            "user": user,
+            "user_exists": user.as_ref().is_some_and(|path| path.is_file()),
+            "project": project,
+            "cache": cache,
+            "state": state,
+        });
+        let output = serde_json::to_string_pretty(&locations).map_err(|e| Error::Convert {
+            message: e.to_string(),
+        })?;
+        println!("{}", output);
+        return Ok(());
+    }
+
+    let show = |path: &Option<PathBuf>| match path {
+        Some(path) => path.display().to_string(),
+        None => t!("无法确定"),
+    };
+    let user = match &user {
+        Some(path) if !path.is_file() => t!("{}（不存在）", path.display()),
+        _ => show(&user),
+    };
+    let project = match &project {
+        Some(path) => path.display().to_string(),
+        None => t!("未找到"),
+    };
+    println!("{}", t!("用户配置: {}", user));
+    println!("{}", t!("项目配置: {}", project));
+    println!("{}", t!("缓存目录: {}", show(&cache)));
+    println!("{}", t!("状态目录: {}", show(&state)));
+    Ok(())
+}
@@ -1,128 +1,396 @@
 //! convert 命令实现
 
+use crate::codec::{self, OutputOptions};
+use crate::comments;
+use crate::diagnostic;
 use crate::error::{Error, Result};
 use crate::format::Format;
+use crate::fsutil;
+use crate::hooks::{self, Hook};
+use crate::lossy;
+use crate::mapping;
+use crate::output::{self, StdoutOptions};
+use crate::t;
+use crate::term;
+use crate::transform::{Transform, Transforms};
+use crate::value::{self, ConfValue};
+use crate::verbosity;
+use serde_json::Value;
+use std::collections::HashMap;
 use std::fs;
-use std::io::{self, Read};
+use std::path::Path;
+
+/// 转换结果的输出位置
+pub enum Destination<'a> {
+    /// 标准输出
+    Stdout(StdoutOptions),
+    /// 单个输出文件
+    File(&'a str),
+    /// 输出目录：每个输入、每种目标格式各生成一个 `<文件名>.<扩展名>`
+    Dir(&'a str),
+}
+
+impl<'a> Destination<'a> {
+    /// 由 `--output` 和 `--out-dir` 参数确定输出位置，都没有指定时按 `stdout` 写到标准输出
+    pub fn new(output: Option<&'a str>, out_dir: Option<&'a str>, stdout: StdoutOptions) -> Self {
+        match (output, out_dir) {
+            (_, Some(dir)) => Destination::Dir(dir),
+            (Some(file), None) => Destination::File(file),
+            (None, None) => Destination::Stdout(stdout),
+        }
+    }
+}
+
+/// 读取输入和处理错误的选项
+#[derive(Clone, Copy, Debug, Default)]
+pub struct InputOptions {
+    /// 源格式，不指定时从扩展名推断
+    pub from: Option<Format>,
+    /// 尽量转换：跳过出错的文件和 YAML 文档继续转换其余部分，最后汇总报告所有问题
+    pub best_effort: bool,
+    /// 把注释存入 `_comment_<键>` 字段，或者把这些字段写回注释，见 [`comments`]
+    pub comments_as_keys: bool,
+    /// 显示详细信息
+    pub verbose: bool,
+}
 
 /// 执行转换命令
+///
+/// 多个输入或多个目标格式时必须输出到目录；所有输出在全部转换完成后一起写入，
+/// 任何一个失败时都不写入（尽量转换时跳过的除外）
 pub fn run(
-    input: &str,
-    output: Option<&str>,
-    from: Option<Format>,
-    to: Format,
-    pretty: bool,
-    verbose: bool,
+    inputs: &[String],
+    destination: &Destination,
+    to: &[Format],
+    style: &OutputOptions,
+    transforms: &Transforms,
+    options: &InputOptions,
 ) -> Result<()> {
-    // 读取输入
-    let (content, from_format) = if input == "-" {
-        // 从标准输入读取
-        let from = from.ok_or_else(|| Error::Convert {
-            message: "从标准输入读取时必须指定 --from 参数".to_string(),
-        })?;
-        let mut content = String::new();
-        io::stdin()
-            .read_to_string(&mut content)
-            .map_err(|e| Error::FileRead {
-                path: "stdin".to_string(),
+    let verbose = options.verbose;
+    match destination {
+        Destination::Dir(dir) => {
+            fs::create_dir_all(dir).map_err(|e| Error::FileWrite {
+                path: dir.to_string(),
                 source: e,
             })?;
-        (content, from)
-    } else {
-        // 从文件读取
-        let from = from
-            .or_else(|| Format::from_extension(input))
-            .ok_or_else(|| Error::UnknownFormat {
-                path: input.to_string(),
-            })?;
-        let content = fs::read_to_string(input).map_err(|e| Error::FileRead {
-            path: input.to_string(),
-            source: e,
-        })?;
-        (content, from)
-    };
+            check_outputs(dir, inputs, to)?;
+        }
+        _ if inputs.len() > 1 || to.len() > 1 => {
+            return Err(Error::Convert {
+                message: t!("多个输入文件或多个目标格式时必须指定 --out-dir 参数"),
+            });
+        }
+        _ => {}
+    }
 
-    if verbose {
-        eprintln!("源格式: {}", from_format.name());
-        eprintln!("目标格式: {}", to.name());
+    if style.stamp && to.contains(&Format::Json) && !verbosity::quiet() {
+        eprintln!(
+            "{}",
+            t!("警告: JSON 不支持注释，JSON 输出不会追加完整性标记")
+        );
     }
 
-    // 执行转换
-    let result = convert(&content, from_format, to, pretty)?;
+    // 所有输出一起写入，post_convert 钩子在写入之后触发
+    let mut written = Vec::new();
+    let problems = fsutil::transaction(|| {
+        let mut problems = Problems::new(options.best_effort);
+        for input in inputs {
+            let value = read(input, to, options, transforms, &mut problems);
+            let Some(value) = problems.check(input, value)? else {
+                continue;
+            };
 
-    // 输出结果
-    match output {
-        Some(path) => {
-            fs::write(path, &result).map_err(|e| Error::FileWrite {
-                path: path.to_string(),
-                source: e,
-            })?;
-            if verbose {
-                eprintln!("已写入: {}", path);
+            for &target in to {
+                if verbose {
+                    eprintln!("{}", t!("目标格式: {}", target.name()));
+                }
+                let path = write(&value, input, target, destination, style, verbose);
+                if let Some(path) = problems.check(input, path)? {
+                    written.push((input.clone(), path, target));
+                }
             }
         }
-        None => print!("{}", result),
+        Ok(problems)
+    })?;
+
+    for (input, path, target) in &written {
+        run_post_convert(input, path, *target)?;
+    }
+    problems.finish()
+}
+
+/// 读取、解析并变换一个输入，指定了 `--comments-as-keys` 时保留注释
+///
+/// 结果是 [`ConfValue`]：没有值变换时超出精度的数字、自定义标签等原样带到输出，
+/// 有值变换时经过 `serde_json::Value` 变换，注释按路径放回
+fn read(
+    input: &str,
+    targets: &[Format],
+    options: &InputOptions,
+    transforms: &Transforms,
+    problems: &mut Problems,
+) -> Result<ConfValue> {
+    let (content, from_format) = codec::read_input_bytes(input, options.from)?;
+
+    if options.verbose {
+        eprintln!("{}", t!("源格式: {}", from_format.name()));
+    }
+
+    let text = std::str::from_utf8(&content).ok();
+    let mut value = match text {
+        _ if options.best_effort && from_format == Format::Yaml => {
+            parse_documents(&content, input, problems)?.into()
+        }
+        Some(text) if !matches!(from_format, Format::Plugin(_)) => value::parse(text, from_format)?,
+        _ => codec::parse_bytes(&content, from_format)?.into(),
+    };
+    let pipeline = transforms.pipeline();
+    if let Some(text) = text {
+        let mut diagnostics = lossy::check(text, from_format);
+        // 没有值变换时，JSON 中超出精度的数字按原文写到 JSON 输出中
+        if from_format == Format::Json
+            && pipeline.is_empty()
+            && targets.iter().all(|&target| target == Format::Json)
+        {
+            diagnostics.retain(|d| d.code != "number-precision");
+        }
+        diagnostic::report(input, diagnostics)?;
+    }
+    if !options.comments_as_keys {
+        value.clear_comments();
+    } else if !comments::supports(from_format) {
+        value.take_comment_fields();
+    } else if let Some(text) = text.filter(|_| value.comments().is_empty()) {
+        // 由 codec 解析的值还没有注释
+        value.attach_comments(comments::collect(text, from_format, &mut Value::Null));
     }
 
-    Ok(())
+    if !pipeline.is_empty() {
+        let comments = value.comments();
+        let mut json = value.to_json();
+        pipeline.apply(&mut json)?;
+        value = json.into();
+        value.attach_comments(comments);
+    }
+    Ok(value)
 }
 
-/// 内部转换函数
-fn convert(input: &str, from: Format, to: Format, pretty: bool) -> Result<String> {
-    // 解析为 JSON Value
-    let value: serde_json::Value = match from {
-        Format::Json => serde_json::from_str(input).map_err(|e| Error::Parse {
-            format: "JSON",
-            source: e.to_string(),
-        })?,
-        Format::Yaml => serde_yml::from_str(input).map_err(|e| Error::Parse {
-            format: "YAML",
-            source: e.to_string(),
-        })?,
-        Format::Toml => {
-            let toml_value: toml::Value = toml::from_str(input).map_err(|e| Error::Parse {
-                format: "TOML",
-                source: e.to_string(),
-            })?;
-            serde_json::to_value(toml_value).map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })?
+/// 尽量转换时分别解析 YAML 的每个文档：多个文档时结果是文档的数组，出错的文档写成 null
+fn parse_documents(content: &[u8], input: &str, problems: &mut Problems) -> Result<Value> {
+    let text = std::str::from_utf8(content).map_err(|e| Error::Parse {
+        format: Format::Yaml.name(),
+        source: t!("不是有效的 UTF-8: {}", e),
+    })?;
+    let mut documents = codec::parse_yaml_documents(text);
+    if documents.len() <= 1 {
+        return documents.pop().unwrap_or(Ok(Value::Null));
+    }
+    let mut values = Vec::with_capacity(documents.len());
+    for (i, document) in documents.into_iter().enumerate() {
+        let context = t!("{} 的第 {} 个文档", input, i + 1);
+        values.push(problems.check(&context, document)?.unwrap_or(Value::Null));
+    }
+    Ok(Value::Array(values))
+}
+
+/// 把一个结果序列化为目标格式并输出，返回输出的路径（标准输出为 `-`）
+fn write(
+    value: &ConfValue,
+    input: &str,
+    target: Format,
+    destination: &Destination,
+    style: &OutputOptions,
+    verbose: bool,
+) -> Result<String> {
+    diagnostic::report(
+        input,
+        lossy::check_target(&value.to_json(), target, style.nulls),
+    )?;
+    let result = value::serialize_bytes(value, target, style)?;
+
+    let path = match destination {
+        Destination::Stdout(options) => {
+            output::write_stdout(&result, options)?;
+            return Ok("-".to_string());
         }
+        Destination::File(file) => file.to_string(),
+        Destination::Dir(dir) => output_path(dir, input, target),
     };
+    fsutil::write(&path, &result)?;
+    if verbose {
+        eprintln!("{}", t!("已写入: {}", path));
+    }
+    Ok(path)
+}
+
+/// 尽量转换时收集的问题
+struct Problems {
+    best_effort: bool,
+    messages: Vec<String>,
+}
+
+impl Problems {
+    fn new(best_effort: bool) -> Self {
+        Problems {
+            best_effort,
+            messages: Vec::new(),
+        }
+    }
 
-    // 序列化为目标格式
-    let output = match to {
-        Format::Json => {
-            if pretty {
-                serde_json::to_string_pretty(&value)
-            } else {
-                serde_json::to_string(&value)
+    /// 尽量转换时记下错误并返回 None，否则直接返回错误
+    fn check<T>(&mut self, context: &str, result: Result<T>) -> Result<Option<T>> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if self.best_effort => {
+                self.messages.push(format!("{}: {}", context, e));
+                Ok(None)
             }
-            .map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })?
+            Err(e) => Err(e),
         }
-        Format::Yaml => serde_yml::to_string(&value).map_err(|e| Error::Convert {
-            message: e.to_string(),
-        })?,
-        Format::Toml => {
-            let json_str = serde_json::to_string(&value).map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })?;
-            let toml_value: toml::Value =
-                serde_json::from_str(&json_str).map_err(|e| Error::Convert {
-                    message: e.to_string(),
-                })?;
-            if pretty {
-                toml::to_string_pretty(&toml_value)
-            } else {
-                toml::to_string(&toml_value)
+    }
+
+    /// 汇总报告所有问题
+    fn finish(self) -> Result<()> {
+        if self.messages.is_empty() {
+            return Ok(());
+        }
+        for message in &self.messages {
+            term::eprint_wrapped(&format!("✗ {}", message));
+        }
+        Err(Error::BestEffort {
+            problems: self.messages.len(),
+        })
+    }
+}
+
+/// 按规则文件批量转换，规则见 [`mapping`]
+///
+/// 先找出所有要做的转换并检查输出是否冲突，再依次执行，缺少的输出目录会自动创建。
+/// 所有输出在全部转换完成后一起写入
+pub fn run_map(
+    rules: &str,
+    style: &OutputOptions,
+    transforms: &Transforms,
+    options: &InputOptions,
+) -> Result<()> {
+    let (value, _) = codec::read_value(rules, None)?;
+    let rules_list = mapping::parse_rules(value)?;
+    let root = Path::new(rules)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let jobs = mapping::plan(&rules_list, root)?;
+
+    let mut written = Vec::new();
+    let problems = fsutil::transaction(|| {
+        let mut problems = Problems::new(options.best_effort);
+        for job in &jobs {
+            let value = read(
+                &job.input,
+                &[job.format],
+                options,
+                transforms,
+                &mut problems,
+            );
+            let Some(value) = problems.check(&job.input, value)? else {
+                continue;
+            };
+            let result = write_job(job, &value, style, options.verbose);
+            if problems.check(&job.input, result)?.is_some() {
+                written.push(job);
             }
-            .map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })?
         }
-    };
+        Ok(problems)
+    })?;
+
+    for job in written {
+        run_post_convert(&job.input, &job.output, job.format)?;
+    }
+    problems.finish()
+}
+
+/// 输出规则文件中的一项转换，缺少的输出目录会自动创建
+fn write_job(
+    job: &mapping::Job,
+    value: &ConfValue,
+    style: &OutputOptions,
+    verbose: bool,
+) -> Result<()> {
+    diagnostic::report(
+        &job.input,
+        lossy::check_target(&value.to_json(), job.format, style.nulls),
+    )?;
+    let result = value::serialize_bytes(value, job.format, style)?;
 
-    Ok(output)
+    if let Some(dir) = Path::new(&job.output).parent() {
+        fs::create_dir_all(dir).map_err(|e| Error::FileWrite {
+            path: dir.display().to_string(),
+            source: e,
+        })?;
+    }
+    fsutil::write(&job.output, &result)?;
+    if verbose {
+        eprintln!("{}", t!("已写入: {} -> {}", job.input, job.output));
+    }
+    Ok(())
+}
+
+/// 触发 post_convert 钩子
+fn run_post_convert(input: &str, output: &str, target: Format) -> Result<()> {
+    hooks::run(
+        Hook::PostConvert,
+        &[
+            ("CONFCONV_FILE", output),
+            ("CONFCONV_INPUT", input),
+            ("CONFCONV_FORMAT", target.extension()),
+        ],
+    )
+}
+
+/// 输出目录模式下，在写入任何文件之前检查输出：文件名相同的输入会写到同一个输出，
+/// 输出目录就是输入所在的目录且格式不变时会覆盖输入
+fn check_outputs(out_dir: &str, inputs: &[String], to: &[Format]) -> Result<()> {
+    let mut outputs: HashMap<String, &str> = HashMap::new();
+    for input in inputs {
+        for &target in to {
+            let output = output_path(out_dir, input, target);
+            if let Some(other) = outputs.insert(output.clone(), input) {
+                if other != input {
+                    return Err(Error::Convert {
+                        message: t!("{} 和 {} 都会写入 {}", other, input, output),
+                    });
+                }
+            }
+            if input != "-" && same_file(input, &output) {
+                return Err(Error::Convert {
+                    message: t!("{} 的输出会覆盖输入文件", input),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 两个路径是否指向同一个已经存在的文件
+fn same_file(a: &str, b: &str) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// 输出目录模式下的输出文件路径：`<out_dir>/<输入文件名去掉扩展名>.<目标扩展名>`
+fn output_path(out_dir: &str, input: &str, to: Format) -> String {
+    let stem = if input == "-" {
+        "stdin".to_string()
+    } else {
+        Path::new(input)
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| input.to_string())
+    };
+    Path::new(out_dir)
+        .join(format!("{}.{}", stem, to.extension()))
+        .to_string_lossy()
+        .into_owned()
 }
@@ -0,0 +1,116 @@
+//! defaults 命令实现
+
+use crate::codec;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::path::{Path, Segment};
+use crate::schema;
+use crate::t;
+use serde_json::{Map, Value};
+
+/// 执行 defaults apply 命令
+///
+/// 用 Schema 中的 `default` 补全配置中缺少的键，得到完整的生效配置，便于检查
+pub fn apply(
+    file: &str,
+    schema_file: &str,
+    from: Option<Format>,
+    to: Option<Format>,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    let (mut value, format) = codec::read_value(file, from)?;
+    let (schema, _) = codec::read_value(schema_file, None)?;
+
+    let filled = schema::apply_defaults(&mut value, &schema);
+    if verbose {
+        for path in &filled {
+            eprintln!("{}", t!("已补全: {}", path));
+        }
+    }
+    write(&value, to.unwrap_or(format), output, verbose)
+}
+
+/// 默认值的来源
+pub enum Defaults<'a> {
+    /// JSON Schema 中的 `default`
+    Schema(&'a str),
+    /// 参考配置文件中同一路径的值
+    Reference(&'a str),
+}
+
+/// 执行 defaults prune 命令
+///
+/// 删去值等于默认值的键，只留下有意修改的部分
+pub fn prune(
+    file: &str,
+    defaults: Defaults,
+    from: Option<Format>,
+    to: Option<Format>,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    let (mut value, format) = codec::read_value(file, from)?;
+    let pruned = match defaults {
+        Defaults::Schema(schema_file) => {
+            let (schema, _) = codec::read_value(schema_file, None)?;
+            schema::prune_defaults(&mut value, &schema)
+        }
+        Defaults::Reference(reference_file) => {
+            let (reference, _) = codec::read_value(reference_file, None)?;
+            let mut pruned = Vec::new();
+            prune_reference(&mut value, &reference, &Path::default(), &mut pruned);
+            pruned
+        }
+    };
+    if verbose {
+        for path in &pruned {
+            eprintln!("{}", t!("已删去: {}", path));
+        }
+    }
+    write(&value, to.unwrap_or(format), output, verbose)
+}
+
+/// 删去与参考配置中同一路径的值相等的键；对象逐层比较，数组整体比较
+fn prune_reference(value: &mut Value, reference: &Value, path: &Path, pruned: &mut Vec<Path>) {
+    let (Value::Object(map), Value::Object(reference)) = (value, reference) else {
+        return;
+    };
+    let keys: Vec<String> = map.keys().cloned().collect();
+    for key in keys {
+        let Some(expected) = reference.get(&key) else {
+            continue;
+        };
+        let path = path.join(Segment::Key(key.clone()));
+        let child = &mut map[&key];
+        if child == expected {
+            map.shift_remove(&key);
+            pruned.push(path);
+            continue;
+        }
+        let before = pruned.len();
+        prune_reference(child, expected, &path, pruned);
+        if pruned.len() > before && child.as_object().is_some_and(Map::is_empty) {
+            map.shift_remove(&key);
+            pruned.truncate(before);
+            pruned.push(path);
+        }
+    }
+}
+
+fn write(value: &Value, format: Format, output: Option<&str>, verbose: bool) -> Result<()> {
+    let content = codec::serialize(value, format, true)?;
+
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,130 @@
+//! diff 命令实现
+
+use crate::codec;
+use crate::color::{self, Color, Stream};
+use crate::diff::{self, Change, Options};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::path::Path;
+use crate::report::{self, ReportFormat};
+use crate::t;
+use crate::term;
+use serde_json::Value;
+
+/// 执行比较命令
+///
+/// 两个文件可以是不同的格式，比较的是解析后的内容。
+/// 每处差异输出一行：`+` 新增、`-` 删除、`~` 修改，有差异时返回错误（退出码 1）。
+/// `report` 为 [`ReportFormat::Html`] 时改为输出 HTML 报告
+pub fn run(
+    left: &str,
+    right: &str,
+    format: Option<Format>,
+    options: &Options,
+    report: ReportFormat,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    let (old, old_format) = codec::read_value(left, format)?;
+    let (new, new_format) = codec::read_value(right, format)?;
+
+    if verbose {
+        eprintln!(
+            "{}",
+            t!(
+                "比较 {} ({}) 与 {} ({})",
+                left,
+                old_format.name(),
+                right,
+                new_format.name()
+            )
+        );
+    }
+
+    let changes = diff::diff(&old, &new, options);
+    // 命令行只接受 text 和 html
+    if report != ReportFormat::Text {
+        print!("{}", report::diff_html(left, right, &changes));
+    } else if !quiet {
+        for change in &changes {
+            println!("{}", colored(change));
+        }
+    }
+
+    if changes.is_empty() {
+        if verbose {
+            eprintln!("{}", t!("没有差异"));
+        }
+        Ok(())
+    } else {
+        Err(Error::Differences {
+            count: changes.len(),
+        })
+    }
+}
+
+/// 一处差异的单行描述
+pub(crate) fn describe(change: &Change) -> String {
+    describe_within(change, None)
+}
+
+/// 单行描述，指定宽度时截断过长的值使整行不超过这个宽度（路径总是完整显示）
+fn describe_within(change: &Change, width: Option<usize>) -> String {
+    // 值至少保留这么多列，路径很长时整行会超出宽度
+    const MIN_VALUE: usize = 12;
+    let fit = |value: &Value, prefix: &str, share: usize| {
+        let text = compact(value);
+        match width {
+            Some(width) => {
+                let room = width.saturating_sub(term::display_width(prefix)) / share;
+                term::truncate(&text, room.max(MIN_VALUE))
+            }
+            None => text,
+        }
+    };
+    match change {
+        Change::Added { path, value } => {
+            let prefix = format!("+ {}: ", label(path));
+            format!("{}{}", prefix, fit(value, &prefix, 1))
+        }
+        Change::Removed { path, value } => {
+            let prefix = format!("- {}: ", label(path));
+            format!("{}{}", prefix, fit(value, &prefix, 1))
+        }
+        Change::Changed { path, old, new } => {
+            // 旧值和新值平分剩余的宽度
+            let prefix = format!("~ {}:  -> ", label(path));
+            format!(
+                "~ {}: {} -> {}",
+                label(path),
+                fit(old, &prefix, 2),
+                fit(new, &prefix, 2)
+            )
+        }
+    }
+}
+
+/// 输出到标准输出的描述：新增为绿色，删除为红色，修改为黄色；终端中过长的值会被截断
+pub(crate) fn colored(change: &Change) -> String {
+    let color = match change {
+        Change::Added { .. } => Color::Green,
+        Change::Removed { .. } => Color::Red,
+        Change::Changed { .. } => Color::Yellow,
+    };
+    let line = describe_within(change, term::width(Stream::Stdout));
+    color::paint(Stream::Stdout, color, false, &line)
+}
+
+/// 路径的显示文本，根路径显示为 `.`
+fn label(path: &Path) -> String {
+    if path.is_root() {
+        ".".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// 值的单行 JSON 文本
+fn compact(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
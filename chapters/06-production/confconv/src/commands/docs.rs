@@ -0,0 +1,316 @@
+//! docs 命令实现
+//!
+//! 为配置生成 Markdown 参考文档：每个键一行，列出类型、默认值和说明。
+//! 键来自配置文件和 Schema 的 `properties`（数组元素写成 `[*]`）；
+//! 类型、默认值和说明优先取 Schema 中的 `type`、`default`、`description`，
+//! Schema 中没有时按配置文件中的值和键上的注释（见 [`comments`](crate::comments)）填写
+
+use crate::codec;
+use crate::comments;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::path::{Path, Segment};
+use crate::t;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// `$ref` 展开的最大深度
+const MAX_REF_DEPTH: usize = 16;
+
+/// 文档中的一行
+#[derive(Default)]
+struct Row {
+    /// 类型
+    kind: Option<String>,
+    /// 默认值
+    default: Option<Value>,
+    /// 说明
+    description: Option<String>,
+    /// 可选值
+    choices: Vec<Value>,
+    /// Schema 中列为必填
+    required: bool,
+}
+
+/// 按出现顺序排列的行
+#[derive(Default)]
+struct Rows {
+    order: Vec<String>,
+    rows: HashMap<String, Row>,
+}
+
+impl Rows {
+    fn entry(&mut self, key: String) -> &mut Row {
+        if !self.rows.contains_key(&key) {
+            self.order.push(key.clone());
+        }
+        self.rows.entry(key).or_default()
+    }
+}
+
+/// 执行文档生成命令
+pub fn run(
+    file: &str,
+    schema_file: Option<&str>,
+    from: Option<Format>,
+    title: Option<&str>,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    let (content, format) = codec::read_input_bytes(file, from)?;
+    let mut value = codec::parse_bytes(&content, format)?;
+    let comments = match std::str::from_utf8(&content) {
+        Ok(text) => comments::collect(text, format, &mut value),
+        Err(_) => Vec::new(),
+    };
+
+    let mut rows = Rows::default();
+    from_value(&value, &Path::default(), &mut rows);
+    for (path, text) in comments {
+        let row = rows.entry(display(&path));
+        row.description.get_or_insert(text);
+    }
+    if let Some(schema_file) = schema_file {
+        let (schema, _) = codec::read_value(schema_file, None)?;
+        if verbose {
+            eprintln!("Schema: {}", schema_file);
+        }
+        from_schema(&schema, &schema, "", 0, &mut rows);
+    }
+
+    let title = title
+        .map(str::to_string)
+        .unwrap_or_else(|| t!("{} 配置参考", file));
+    let markdown = render(&title, &rows);
+    match output {
+        Some(path) => {
+            fsutil::write(path, &markdown)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", markdown),
+    }
+    Ok(())
+}
+
+/// 文档中的键：数组下标写成 `[*]`
+fn display(path: &Path) -> String {
+    let mut text = String::new();
+    for segment in path.segments() {
+        match segment {
+            Segment::Key(key) => {
+                if !text.is_empty() {
+                    text.push('.');
+                }
+                text.push_str(&Path::default().join(Segment::Key(key.clone())).to_string());
+            }
+            _ => text.push_str("[*]"),
+        }
+    }
+    text
+}
+
+/// 把 Schema 中的键接到 `parent` 后面
+fn child(parent: &str, key: &str) -> String {
+    let key = display(&Path::default().join(Segment::Key(key.to_string())));
+    if parent.is_empty() {
+        key
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// 配置文件中的键，类型和默认值按其中的值填写
+fn from_value(value: &Value, path: &Path, rows: &mut Rows) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let path = path.join(Segment::Key(key.clone()));
+                let row = rows.entry(display(&path));
+                row.kind.get_or_insert_with(|| kind(child).to_string());
+                if !child.is_object() && !child.is_array() {
+                    row.default.get_or_insert_with(|| child.clone());
+                }
+                from_value(child, &path, rows);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                from_value(item, &path.join(Segment::Index(index as i64)), rows);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 值的 JSON Schema 类型名
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_f64() => "number",
+        Value::Number(_) => "integer",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Schema 中的键；Schema 中的信息覆盖按配置文件推断的类型和默认值
+fn from_schema(schema: &Value, root: &Value, path: &str, depth: usize, rows: &mut Rows) {
+    let Value::Object(node) = schema else {
+        return;
+    };
+    if let Some(reference) = node.get("$ref").and_then(Value::as_str) {
+        let target = reference
+            .strip_prefix('#')
+            .and_then(|pointer| root.pointer(pointer));
+        if let Some(target) = target.filter(|_| depth < MAX_REF_DEPTH) {
+            from_schema(target, root, path, depth + 1, rows);
+        }
+        return;
+    }
+    for key in ["allOf", "oneOf", "anyOf"] {
+        if let Some(Value::Array(parts)) = node.get(key) {
+            for part in parts {
+                from_schema(part, root, path, depth, rows);
+            }
+        }
+    }
+    if let Some(Value::Object(properties)) = node.get("properties") {
+        let required: Vec<&str> = node
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|keys| keys.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        for (key, property) in properties {
+            let key_path = child(path, key);
+            describe(
+                rows.entry(key_path.clone()),
+                property,
+                root,
+                required.contains(&key.as_str()),
+            );
+            from_schema(property, root, &key_path, depth, rows);
+        }
+    }
+    if let Some(items) = node.get("items") {
+        from_schema(items, root, &format!("{}[*]", path), depth, rows);
+    }
+}
+
+/// 用属性的 Schema 填写一行
+fn describe(row: &mut Row, property: &Value, root: &Value, required: bool) {
+    let mut node = property;
+    for _ in 0..MAX_REF_DEPTH {
+        match node
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| root.pointer(reference.strip_prefix('#')?))
+        {
+            Some(target) => node = target,
+            None => break,
+        }
+    }
+    // 属性本身的说明优先于引用目标的说明
+    let get = |key: &str| property.get(key).or_else(|| node.get(key));
+    match get("type") {
+        Some(Value::String(kind)) => row.kind = Some(kind.clone()),
+        Some(Value::Array(kinds)) => {
+            let kinds: Vec<&str> = kinds.iter().filter_map(Value::as_str).collect();
+            row.kind = Some(kinds.join(" | "));
+        }
+        _ => {}
+    }
+    if let Some(default) = get("default") {
+        row.default = Some(default.clone());
+    }
+    if let Some(Value::String(text)) = get("description").or_else(|| get("title")) {
+        row.description = Some(text.clone());
+    }
+    if let Some(Value::Array(choices)) = get("enum") {
+        row.choices = choices.clone();
+    }
+    row.required |= required;
+}
+
+/// 生成 Markdown
+fn render(title: &str, rows: &Rows) -> String {
+    let mut markdown = format!("# {}\n\n", title);
+    let _ = writeln!(
+        markdown,
+        "| {} | {} | {} | {} |",
+        t!("键"),
+        t!("类型"),
+        t!("默认值"),
+        t!("说明")
+    );
+    markdown.push_str("| --- | --- | --- | --- |\n");
+    for key in &rows.order {
+        let row = &rows.rows[key];
+        let mut description = row.description.clone().unwrap_or_default();
+        if row.required {
+            description = format!("{} {}", t!("（必填）"), description);
+        }
+        if !row.choices.is_empty() {
+            let choices: Vec<String> = row.choices.iter().map(code).collect();
+            description = format!("{} {}", description, t!("可选值: {}", choices.join(", ")));
+        }
+        let _ = writeln!(
+            markdown,
+            "| `{}` | {} | {} | {} |",
+            key.replace('`', "\\`"),
+            cell(row.kind.as_deref().unwrap_or_default()),
+            row.default.as_ref().map(code).unwrap_or_default(),
+            cell(description.trim()),
+        );
+    }
+    markdown
+}
+
+/// 值写成行内代码
+fn code(value: &Value) -> String {
+    let text = match value {
+        Value::Object(map) if map.is_empty() => "{}".to_string(),
+        _ => Value::to_string(value),
+    };
+    format!("`{}`", cell(&text))
+}
+
+/// 表格单元格：转义竖线，换行写成 `<br>`
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', "<br>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn schema_overrides_inferred_rows() {
+        let mut rows = Rows::default();
+        from_value(
+            &json!({"port": 8080, "servers": [{"host": "a"}]}),
+            &Path::default(),
+            &mut rows,
+        );
+        let schema = json!({
+            "required": ["port"],
+            "properties": {
+                "port": {"type": "integer", "default": 80, "description": "监听端口"},
+                "mode": {"$ref": "#/$defs/mode"}
+            },
+            "$defs": {"mode": {"enum": ["a", "b"], "title": "运行模式"}}
+        });
+        from_schema(&schema, &schema, "", 0, &mut rows);
+        assert_eq!(rows.order, ["port", "servers", "servers[*].host", "mode"]);
+
+        let markdown = render("T", &rows);
+        assert!(markdown.contains("| `port` | integer | `80` | （必填） 监听端口 |"));
+        assert!(markdown.contains("| `mode` |  |  | 运行模式 可选值: `\"a\"`, `\"b\"` |"));
+    }
+}
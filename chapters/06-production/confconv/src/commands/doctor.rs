@@ -0,0 +1,172 @@
+//! doctor 命令实现
+
+use crate::codec;
+use crate::date::{self, TOML_DATETIME_KEY};
+use crate::diff::{self, Options};
+use crate::error::Result;
+use crate::format::Format;
+use crate::path::{Path, Segment};
+use crate::t;
+use serde_json::Value;
+
+/// 执行诊断命令
+///
+/// 逐个目标格式实际转换一次再解析回来，与原值比较，
+/// 列出无法转换或转换后会改变的路径；注释、锚点等解析时就会丢失的写法单独列出
+pub fn run(file: &str, format: Option<Format>, verbose: bool) -> Result<()> {
+    let (content, source) = codec::read_input(file, format)?;
+    let value = codec::parse(&content, source)?;
+
+    println!("{} ({})", file, source.name());
+
+    let notes = source_notes(&content, source, &value);
+    if !notes.is_empty() {
+        println!("{}", t!("  转换为任何格式都会："));
+        for note in &notes {
+            println!("    - {}", note);
+        }
+    }
+
+    for target in [Format::Json, Format::Yaml, Format::Toml, Format::Ini] {
+        if target == source {
+            continue;
+        }
+        let problems = target_problems(&value, target);
+        if problems.is_empty() {
+            println!("{}", t!("  ✓ {}: 可以无损转换", target.name()));
+        } else {
+            println!("  ✗ {}:", target.name());
+            for problem in &problems {
+                println!("    - {}", problem);
+            }
+        }
+    }
+
+    if verbose {
+        eprintln!("{}", t!("诊断完成"));
+    }
+    Ok(())
+}
+
+/// 源文件中解析后就已经丢失的信息
+fn source_notes(content: &str, source: Format, value: &Value) -> Vec<String> {
+    let mut notes = Vec::new();
+    if source == Format::Json {
+        return notes;
+    }
+
+    let comments = content
+        .lines()
+        .filter(|line| line.trim_start().starts_with('#'))
+        .count();
+    if comments > 0 {
+        notes.push(t!("丢失 {} 行注释", comments));
+    }
+
+    if source == Format::Yaml {
+        let tokens: Vec<&str> = content
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(|line| line.split_whitespace())
+            .collect();
+        let has = |prefix: char| {
+            tokens.iter().any(|t| {
+                t.strip_prefix(prefix)
+                    .is_some_and(|name| name.starts_with(|c: char| c.is_alphanumeric() || c == '_'))
+            })
+        };
+        if has('&') || has('*') {
+            notes.push(t!("展开锚点和别名（&name / *name），重复的内容会被复制"));
+        }
+        if tokens.iter().any(|t| t.starts_with('!')) {
+            notes.push(t!("丢失类型标签（!tag）"));
+        }
+    }
+
+    let datetimes = find_paths(value, &Path::default(), &|v| {
+        date::toml_datetime(v).is_some()
+    });
+    if !datetimes.is_empty() {
+        notes.push(t!(
+            "把日期时间变成 {{\"{}\": ...}} 对象（可用 --normalize-dates 转为字符串）: {}",
+            TOML_DATETIME_KEY,
+            list(&datetimes)
+        ));
+    }
+
+    notes
+}
+
+/// 转换为目标格式时的问题
+fn target_problems(value: &Value, target: Format) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if target == Format::Toml {
+        if !value.is_object() {
+            problems.push(t!("TOML 的顶层必须是表（对象）"));
+        }
+        let nulls = find_paths(value, &Path::default(), &Value::is_null);
+        if !nulls.is_empty() {
+            problems.push(t!("TOML 没有 null: {}", list(&nulls)));
+        }
+        if !problems.is_empty() {
+            return problems;
+        }
+    }
+
+    let text = match codec::serialize(value, target, true) {
+        Ok(text) => text,
+        Err(e) => return vec![e.to_string()],
+    };
+    let back = match codec::parse(&text, target) {
+        Ok(back) => back,
+        Err(e) => return vec![t!("转换结果无法解析: {}", e)],
+    };
+    for change in diff::diff(value, &back, &Options::default()) {
+        problems.push(t!("值会改变: {}", super::diff::describe(&change)));
+    }
+    problems
+}
+
+/// 找出所有满足条件的路径
+fn find_paths(value: &Value, path: &Path, predicate: &dyn Fn(&Value) -> bool) -> Vec<Path> {
+    if predicate(value) {
+        return vec![path.clone()];
+    }
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .flat_map(|(key, child)| {
+                find_paths(child, &path.join(Segment::Key(key.clone())), predicate)
+            })
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .flat_map(|(i, child)| {
+                find_paths(child, &path.join(Segment::Index(i as i64)), predicate)
+            })
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 路径列表，过长时只显示前几个
+fn list(paths: &[Path]) -> String {
+    const SHOWN: usize = 5;
+    let mut names: Vec<String> = paths
+        .iter()
+        .take(SHOWN)
+        .map(|p| {
+            if p.is_root() {
+                ".".to_string()
+            } else {
+                p.to_string()
+            }
+        })
+        .collect();
+    if paths.len() > SHOWN {
+        names.push(t!("等 {} 处", paths.len()));
+    }
+    names.join(", ")
+}
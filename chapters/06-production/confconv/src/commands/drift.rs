@@ -0,0 +1,96 @@
+//! drift 命令实现
+//!
+//! 把线上导出的配置与审定的基准配置比较，用于定期的合规检查。退出码：
+//! - 0：没有漂移
+//! - 1：发现漂移
+//! - 2：无法完成检查（文件无法读取或解析等）
+
+use super::diff::colored;
+use crate::codec;
+use crate::diff::{self, Change, Options};
+use crate::error::{Error, Result};
+use crate::t;
+use serde_json::{json, Value};
+
+/// 执行漂移检测命令
+///
+/// 每个文件分别与基准比较：`~` 值与基准不同，`-` 缺少基准中的键，`+` 基准中没有的键。
+/// `allow_extra` 时不报告基准中没有的键，适合服务端会补充字段的导出配置
+pub fn run(
+    golden: &str,
+    files: &[String],
+    options: &Options,
+    allow_extra: bool,
+    json: bool,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    // 读取失败与发现漂移使用不同的退出码
+    let read = |file: &str| {
+        codec::read_value(file, None).map_err(|e| Error::DriftCheck {
+            source: Box::new(e),
+        })
+    };
+    let (expected, _) = read(golden)?;
+    let mut report = Vec::new();
+    let mut count = 0;
+    let mut drifted = 0;
+    for file in files {
+        let (actual, format) = read(file)?;
+        if verbose {
+            eprintln!("{}", t!("检查 {} ({})", file, format.name()));
+        }
+        let changes: Vec<Change> = diff::diff(&expected, &actual, options)
+            .into_iter()
+            .filter(|change| !(allow_extra && matches!(change, Change::Added { .. })))
+            .collect();
+        if changes.is_empty() {
+            continue;
+        }
+        count += changes.len();
+        drifted += 1;
+        if json {
+            report.extend(changes.iter().map(|change| entry(file, change)));
+        } else if !quiet {
+            if files.len() > 1 {
+                println!("{}:", file);
+            }
+            for change in &changes {
+                println!("{}", colored(change));
+            }
+        }
+    }
+
+    if json && !quiet {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&report).unwrap_or_default()
+        );
+    }
+    if count == 0 {
+        if verbose {
+            eprintln!("{}", t!("没有漂移"));
+        }
+        return Ok(());
+    }
+    Err(Error::Drift {
+        count,
+        files: drifted,
+    })
+}
+
+/// JSON 报告中的一项
+fn entry(file: &str, change: &Change) -> Value {
+    let (kind, path, golden, live) = match change {
+        Change::Changed { path, old, new } => ("changed", path, Some(*old), Some(*new)),
+        Change::Removed { path, value } => ("missing", path, Some(*value), None),
+        Change::Added { path, value } => ("unexpected", path, None, Some(*value)),
+    };
+    json!({
+        "file": file,
+        "path": path.to_string(),
+        "kind": kind,
+        "golden": golden,
+        "live": live,
+    })
+}
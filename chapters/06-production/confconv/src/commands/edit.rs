@@ -0,0 +1,205 @@
+//! edit 命令实现
+//!
+//! 两种编辑方式：
+//! - `--ops`：按操作文件批量编辑
+//! - 交互式：转换为 `--as` 指定的格式后用 $EDITOR 打开，保存后再转换回原格式写回
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::ops;
+use crate::t;
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::process::Command;
+use tempfile::NamedTempFile;
+
+/// 执行编辑命令
+pub fn run(
+    file: &str,
+    format: Option<Format>,
+    ops_file: Option<&str>,
+    as_format: Option<Format>,
+    write: bool,
+    verbose: bool,
+) -> Result<()> {
+    match ops_file {
+        Some(ops_file) => edit_with_ops(file, format, ops_file, write, verbose),
+        None => edit_interactive(file, format, as_format, verbose),
+    }
+}
+
+/// 按顺序应用操作文件中的所有操作，任何一个失败都不会写入文件
+fn edit_with_ops(
+    file: &str,
+    format: Option<Format>,
+    ops_file: &str,
+    write: bool,
+    verbose: bool,
+) -> Result<()> {
+    let (ops_value, _) = codec::read_value(ops_file, None)?;
+    let operations = ops::parse_operations(ops_value)?;
+    let (mut value, format) = codec::read_value(file, format)?;
+
+    ops::apply_all(&mut value, &operations)?;
+
+    if verbose {
+        eprintln!("{}", t!("已应用 {} 个操作", operations.len()));
+    }
+
+    let result = codec::serialize(&value, format, true)?;
+    if write {
+        fsutil::write_in_place(file, &result, false)?;
+        if verbose {
+            eprintln!("{}", t!("已更新: {}", file));
+        }
+    } else {
+        print!("{}", result);
+    }
+
+    Ok(())
+}
+
+/// 在编辑器中以另一种格式编辑文件
+///
+/// 保存后的内容必须能解析并转换回原格式才会写回，否则保留临时文件并报错
+fn edit_interactive(
+    file: &str,
+    format: Option<Format>,
+    as_format: Option<Format>,
+    verbose: bool,
+) -> Result<()> {
+    let (value, format) = codec::read_value(file, format)?;
+    let edit_format = as_format.unwrap_or(format);
+    let original = codec::serialize(&value, edit_format, true)?;
+
+    let stem = std::path::Path::new(file)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "config".to_string());
+    let temp = temp_file(&format!("confconv-edit-{}-", stem), edit_format, &original)?;
+    let tmp = temp.path();
+
+    if verbose {
+        eprintln!(
+            "{}",
+            t!("以 {} 格式编辑: {}", edit_format.name(), tmp.display())
+        );
+    }
+
+    open_editor(tmp)?;
+
+    let edited = fs::read_to_string(tmp).map_err(|e| Error::FileRead {
+        path: tmp.display().to_string(),
+        source: e,
+    })?;
+
+    if edited == original {
+        if verbose {
+            eprintln!("{}", t!("内容未修改: {}", file));
+        }
+        return Ok(());
+    }
+
+    // 先完整验证，失败时保留临时文件，避免丢失编辑内容
+    let result = match codec::parse(&edited, edit_format)
+        .and_then(|value| codec::serialize(&value, format, true))
+    {
+        Ok(result) => result,
+        Err(e) => {
+            let kept = tmp.to_path_buf();
+            let _ = temp.keep();
+            return Err(Error::Convert {
+                message: t!("{}\n编辑内容已保留在: {}", e, kept.display()),
+            });
+        }
+    };
+
+    fsutil::write_in_place(file, &result, false)?;
+
+    if verbose {
+        eprintln!("{}", t!("已更新: {}", file));
+    }
+
+    Ok(())
+}
+
+/// 创建写好 `content` 的临时文件，扩展名决定编辑器的语法高亮
+///
+/// 文件名随机、以独占方式创建，权限只有自己可读写：其他用户既不能预先放一个同名的符号链接，
+/// 也读不到正在编辑的配置。返回值被丢弃时删除文件
+pub(crate) fn temp_file(prefix: &str, format: Format, content: &str) -> Result<NamedTempFile> {
+    let to_error = |e| Error::FileWrite {
+        path: env::temp_dir().join(prefix).display().to_string(),
+        source: e,
+    };
+    let mut file = tempfile::Builder::new()
+        .prefix(prefix)
+        .suffix(&format!(".{}", format.extension()))
+        .tempfile()
+        .map_err(to_error)?;
+    file.write_all(content.as_bytes()).map_err(to_error)?;
+    Ok(file)
+}
+
+/// 打开编辑器并等待其退出
+///
+/// 依次使用 $VISUAL、$EDITOR，都未设置时使用 vi；
+/// 变量中可以带参数，如 `code --wait`
+pub(crate) fn open_editor(path: &std::path::Path) -> Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .ok()
+        .filter(|e| !e.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+
+    let mut parts = editor.split_whitespace();
+    let program = parts.next().unwrap_or("vi");
+    let status = Command::new(program)
+        .args(parts)
+        .arg(path)
+        .status()
+        .map_err(|e| Error::ExternalCommand {
+            command: editor.clone(),
+            message: e.to_string(),
+        })?;
+
+    if !status.success() {
+        return Err(Error::ExternalCommand {
+            command: editor,
+            message: t!("编辑器异常退出（{}），文件未修改", status),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failing_ops_do_not_write_the_file() {
+        let dir = env::temp_dir().join(format!("confconv-edit-ops-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("app.json");
+        let ops = dir.join("ops.yaml");
+        let original = "{\"a\": 1, \"b\": 2}";
+        fs::write(&file, original).unwrap();
+        fs::write(
+            &ops,
+            "- op: set\n  path: a\n  value: 9\n- op: unset\n  path: c\n",
+        )
+        .unwrap();
+
+        let file_name = file.to_str().unwrap();
+        let result = edit_with_ops(file_name, None, ops.to_str().unwrap(), true, false);
+        assert!(matches!(result, Err(Error::Operation { index: 2, .. })));
+        assert_eq!(fs::read_to_string(&file).unwrap(), original);
+        // 没有留下写入用的临时文件
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,148 @@
+//! env 命令实现
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::flatten;
+use crate::format::Format;
+use crate::i18n::tr;
+use crate::path::Path;
+use crate::t;
+use clap::ValueEnum;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 环境变量的输出形式
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum EnvFormat {
+    /// .env 文件格式（KEY=value，必要时加双引号）
+    #[value(help = tr(".env 文件格式（KEY=value，必要时加双引号）"))]
+    Dotenv,
+    /// 可直接 eval 的 shell 赋值语句（KEY='value'）
+    #[value(help = tr("可直接 eval 的 shell 赋值语句（KEY='value'）"))]
+    Shell,
+}
+
+/// 执行环境变量导出命令
+///
+/// 嵌套的键用 `separator` 连接并转为大写，例如 `db.host` → `DB_HOST`。
+/// 不同的路径得到同一个变量名时（如 `a.b` 和 `a_b`）报错，不输出任何内容
+pub fn run(
+    file: &str,
+    format: Option<Format>,
+    output: EnvFormat,
+    prefix: &str,
+    separator: &str,
+    export: bool,
+) -> Result<()> {
+    let (value, _) = codec::read_value(file, format)?;
+    for line in assignments(&value, output, prefix, separator)? {
+        if export {
+            println!("export {}", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    Ok(())
+}
+
+/// 每个叶子对应的赋值语句，变量名冲突时报错
+fn assignments(
+    value: &Value,
+    output: EnvFormat,
+    prefix: &str,
+    separator: &str,
+) -> Result<Vec<String>> {
+    let mut sources: HashMap<String, Path> = HashMap::new();
+    let mut lines = Vec::new();
+    for entry in flatten::flatten(value) {
+        let name = env_name(prefix, &entry.path, separator);
+        if let Some(first) = sources.get(&name) {
+            return Err(Error::Convert {
+                message: t!("{} 和 {} 都对应环境变量 {}", first, entry.location, name),
+            });
+        }
+        sources.insert(name.clone(), entry.location.clone());
+        let text = entry.text();
+        lines.push(match output {
+            EnvFormat::Dotenv => format!("{}={}", name, dotenv_quote(&text)),
+            EnvFormat::Shell => format!("{}={}", name, shell_quote(&text)),
+        });
+    }
+    Ok(lines)
+}
+
+/// 生成合法的环境变量名：非字母数字替换为 `_`，全部大写，不以数字开头
+fn env_name(prefix: &str, path: &[String], separator: &str) -> String {
+    let joined = path.join(separator);
+    let mut name: String = format!("{}{}", prefix, joined)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    name
+}
+
+/// .env 的值：只含安全字符时原样输出，否则用双引号并转义
+fn dotenv_quote(value: &str) -> String {
+    let safe = value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-.,:/@+%".contains(c));
+    if safe {
+        return value.to_string();
+    }
+    let mut quoted = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '$' => quoted.push_str("\\$"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// shell 的值：总是使用单引号，单引号内不做任何展开；值中的 `'` 写成 `'\''`
+pub(crate) fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names_are_sanitized_and_uppercased() {
+        let path = |p: &[&str]| p.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(env_name("APP_", &path(&["db", "host"]), "_"), "APP_DB_HOST");
+        assert_eq!(env_name("", &path(&["a.b"]), "_"), "A_B");
+        assert_eq!(env_name("", &path(&["a", "b"]), "_"), "A_B");
+        assert_eq!(env_name("", &path(&["1st"]), "_"), "_1ST");
+    }
+
+    #[test]
+    fn colliding_names_report_both_paths() {
+        let value = serde_json::json!({"a": {"b": 1}, "a_b": 2});
+        let Err(Error::Convert { message }) = assignments(&value, EnvFormat::Dotenv, "", "_")
+        else {
+            panic!("应报告变量名冲突");
+        };
+        assert!(
+            message.contains("a.b") && message.contains("a_b"),
+            "{}",
+            message
+        );
+        assert!(message.contains("A_B"), "{}", message);
+    }
+}
@@ -0,0 +1,40 @@
+//! escape 命令实现
+//!
+//! 把整个配置转义为一个 JSON 字符串字面量，便于嵌入到另一个 JSON
+//! 的字符串值或环境变量中
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::t;
+
+/// 执行转义命令
+///
+/// 指定 `as_format` 时先把配置转换为该格式（JSON 会压缩为单行）再转义，
+/// 否则原样转义输入文本
+pub fn run(
+    input: &str,
+    from: Option<Format>,
+    as_format: Option<Format>,
+    verbose: bool,
+) -> Result<()> {
+    let text = match as_format {
+        Some(target) => {
+            let (content, from) = codec::read_input(input, from)?;
+            if verbose {
+                eprintln!("{}", t!("源格式: {}", from.name()));
+                eprintln!("{}", t!("转义前转换为: {}", target.name()));
+            }
+            let value = codec::parse(&content, from)?;
+            codec::serialize(&value, target, false)?
+        }
+        None => codec::read_text(input)?,
+    };
+
+    let escaped = serde_json::to_string(&text).map_err(|e| Error::Convert {
+        message: e.to_string(),
+    })?;
+    println!("{}", escaped);
+
+    Ok(())
+}
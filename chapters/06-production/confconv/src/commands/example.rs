@@ -0,0 +1,40 @@
+//! example 命令实现
+
+use crate::codec;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::schema;
+use crate::t;
+
+/// 执行示例生成命令
+///
+/// 读取 JSON Schema（可以是任意支持的格式），生成一份示例配置
+pub fn run(
+    schema_file: &str,
+    from: Option<Format>,
+    to: Format,
+    output: Option<&str>,
+    pretty: bool,
+    verbose: bool,
+) -> Result<()> {
+    let (schema, format) = codec::read_value(schema_file, from)?;
+    if verbose {
+        eprintln!("Schema: {} ({})", schema_file, format.name());
+    }
+
+    let example = schema::example(&schema);
+    let content = codec::serialize(&example, to, pretty)?;
+
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! export 命令实现
+//!
+//! 把配置导出为写入 AWS 参数存储的操作：
+//! - ssm：每个叶子成为 Parameter Store 中的一个参数
+//! - secretsmanager：整个配置作为 Secrets Manager 中一个密钥的 JSON 值
+//!
+//! 默认输出等价的 `aws` 命令行，`apply` 时直接执行这些命令，
+//! 凭据和区域按 AWS CLI 的方式查找（`AWS_PROFILE`、`AWS_REGION` 等）。
+//! 参数值通过标准输入传给 aws，不出现在命令行参数中
+
+use crate::codec;
+use crate::error::Result;
+use crate::flatten;
+use crate::format::Format;
+use crate::path::{self, Pattern};
+use crate::process;
+use crate::t;
+use crate::verbosity;
+use serde_json::{json, Value};
+use std::process::Command;
+
+/// 执行 export ssm 命令
+///
+/// 参数名为 `prefix` 加上 `/` 连接的路径；与 `secure` 匹配的路径使用 SecureString 类型。
+/// Parameter Store 不接受空值，空字符串和 null 会被跳过
+pub fn ssm(
+    file: &str,
+    format: Option<Format>,
+    prefix: &str,
+    secure: &[Pattern],
+    apply: bool,
+    verbose: bool,
+) -> Result<()> {
+    let (value, _) = codec::read_value(file, format)?;
+    let secure = path::resolve_patterns(secure, &value);
+
+    let mut operations = Vec::new();
+    for entry in flatten::flatten(&value) {
+        let name = format!("{}{}", prefix, entry.path.join("/"));
+        let text = entry.text();
+        if text.is_empty() {
+            if !verbosity::quiet() {
+                eprintln!("{}", t!("警告: 跳过空值参数 {}", name));
+            }
+            continue;
+        }
+        let kind = if secure
+            .iter()
+            .any(|pattern| pattern.matches(&entry.location))
+        {
+            "SecureString"
+        } else {
+            "String"
+        };
+        operations.push(Operation {
+            command: ["ssm", "put-parameter"],
+            input: json!({"Name": name, "Type": kind, "Value": text, "Overwrite": true}),
+            secret: "Value",
+        });
+    }
+
+    run(&operations, apply, verbose)
+}
+
+/// 执行 export secretsmanager 命令
+pub fn secrets_manager(
+    file: &str,
+    format: Option<Format>,
+    secret_id: &str,
+    apply: bool,
+    verbose: bool,
+) -> Result<()> {
+    let (value, _) = codec::read_value(file, format)?;
+    let operation = Operation {
+        command: ["secretsmanager", "put-secret-value"],
+        input: json!({
+            "SecretId": secret_id,
+            "SecretString": codec::serialize(&value, Format::Json, false)?,
+        }),
+        secret: "SecretString",
+    };
+    run(&[operation], apply, verbose)
+}
+
+/// 一个 aws 命令；参数通过 `--cli-input-json` 从标准输入传入，
+/// 密钥不会出现在命令行参数中（其他用户可以从进程列表看到命令行参数）
+struct Operation {
+    /// `aws` 之后的服务和操作
+    command: [&'static str; 2],
+    /// 命令的全部参数
+    input: Value,
+    /// 参数中含有密钥、在详细信息中隐藏的字段
+    secret: &'static str,
+}
+
+impl Operation {
+    /// 命令行部分，不含参数
+    fn line(&self) -> String {
+        format!(
+            "aws {} {} --cli-input-json {}",
+            self.command[0], self.command[1], STDIN
+        )
+    }
+
+    /// 隐藏了密钥的参数
+    fn redacted(&self) -> Value {
+        let mut input = self.input.clone();
+        input[self.secret] = Value::from("***");
+        input
+    }
+}
+
+/// aws 从标准输入读取参数的写法
+const STDIN: &str = "file:///dev/stdin";
+
+/// 输出或执行操作
+///
+/// 输出的命令用 here document 传入参数，直接复制到 shell 中执行时密钥同样不出现在命令行参数中
+fn run(operations: &[Operation], apply: bool, verbose: bool) -> Result<()> {
+    for operation in operations {
+        let line = operation.line();
+        if !apply {
+            println!("{} <<'EOF'\n{}\nEOF", line, operation.input);
+            continue;
+        }
+        if verbose {
+            eprintln!(
+                "{}",
+                t!("执行: {}", format!("{} <<< {}", line, operation.redacted()))
+            );
+        }
+        let mut command = Command::new("aws");
+        command
+            .args(operation.command)
+            .args(["--cli-input-json", STDIN]);
+        process::pipe(command, &line, operation.input.to_string().as_bytes())?;
+    }
+    if apply {
+        eprintln!("{}", t!("已执行 {} 个操作", operations.len()));
+    }
+    Ok(())
+}
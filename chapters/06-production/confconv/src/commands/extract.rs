@@ -0,0 +1,107 @@
+//! extract 命令实现
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::t;
+use serde_json::Value;
+use std::fs;
+use std::io::{self, Read};
+
+/// 执行提取命令
+///
+/// 在任意文本（比如日志）中查找内嵌的 JSON 对象：
+/// - 不指定 `to` 时每个对象输出为一行紧凑 JSON（JSON Lines）
+/// - 指定 `to` 时转换为目标格式，YAML 输出为多文档流
+pub fn run(
+    input: &str,
+    format: Format,
+    to: Option<Format>,
+    pretty: bool,
+    verbose: bool,
+) -> Result<()> {
+    if format != Format::Json {
+        return Err(Error::Convert {
+            message: t!("目前只支持从文本中提取 JSON，不支持 {}", format.name()),
+        });
+    }
+
+    let text = read_text(input)?;
+    let values = find_json_objects(&text);
+
+    if verbose {
+        eprintln!("{}", t!("找到 {} 个 JSON 对象", values.len()));
+    }
+
+    match to {
+        None => {
+            for value in &values {
+                println!("{}", codec::serialize(value, Format::Json, false)?);
+            }
+        }
+        Some(Format::Json) => {
+            for value in &values {
+                println!("{}", codec::serialize(value, Format::Json, pretty)?);
+            }
+        }
+        Some(Format::Yaml) => {
+            for value in &values {
+                print!("---\n{}", codec::serialize(value, Format::Yaml, pretty)?);
+            }
+        }
+        Some(to @ (Format::Toml | Format::Ini | Format::Plugin(_))) => {
+            // TOML 和 INI 没有多文档语法，用空行分隔各个文档；插件格式同样处理
+            let docs = values
+                .iter()
+                .map(|value| codec::serialize(value, to, pretty))
+                .collect::<Result<Vec<_>>>()?;
+            print!("{}", docs.join("\n"));
+        }
+    }
+
+    Ok(())
+}
+
+/// 读取整个输入文本（`-` 表示标准输入）
+fn read_text(input: &str) -> Result<String> {
+    if input == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| Error::FileRead {
+                path: "stdin".to_string(),
+                source: e,
+            })?;
+        Ok(text)
+    } else {
+        // 日志里可能混有非 UTF-8 字节，按有损方式解码
+        let bytes = fs::read(input).map_err(|e| Error::FileRead {
+            path: input.to_string(),
+            source: e,
+        })?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+/// 扫描文本，返回其中所有能完整解析的 JSON 对象
+///
+/// 从每个 `{` 开始尝试解析，成功则跳过整个对象继续扫描，
+/// 失败则从下一个 `{` 重新尝试，因此嵌套在无效片段里的对象也能被找到
+fn find_json_objects(text: &str) -> Vec<Value> {
+    let mut values = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = text[pos..].find('{') {
+        let start = pos + offset;
+        let mut stream = serde_json::Deserializer::from_str(&text[start..]).into_iter::<Value>();
+        match stream.next() {
+            Some(Ok(value)) => {
+                values.push(value);
+                pos = start + stream.byte_offset();
+            }
+            _ => pos = start + 1,
+        }
+    }
+
+    values
+}
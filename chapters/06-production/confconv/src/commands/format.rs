@@ -1,18 +1,83 @@
 //! format 命令实现
 
+use crate::codec::{self, OutputOptions};
+use crate::diagnostic;
 use crate::error::{Error, Result};
 use crate::format::Format;
+use crate::fsutil;
+use crate::lossy;
+use crate::path::Path;
+use crate::splice;
+use crate::t;
+use crate::term;
+use crate::transform::Transforms;
 use std::fs;
 
 /// 执行格式化命令
-pub fn run(file: &str, indent: u8, write: bool, verbose: bool) -> Result<()> {
+///
+/// 逐个报告每个出错的文件，有文件出错时不修改任何文件
+pub fn run(
+    files: &[String],
+    write: bool,
+    preserve_mtime: bool,
+    style: &OutputOptions,
+    transforms: &Transforms,
+    only: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    let only = only.map(Path::parse).transpose()?;
+    // 任何一个文件出错时都不修改其他文件
+    fsutil::transaction(|| {
+        let mut errors = Vec::new();
+        for file in files {
+            let only = only.as_ref();
+            if let Err(e) = format_file(
+                file,
+                write,
+                preserve_mtime,
+                style,
+                transforms,
+                only,
+                verbose,
+            ) {
+                errors.push((file, e));
+            }
+        }
+        if files.len() == 1 {
+            // 只有一个文件时直接返回它的错误
+            return errors.pop().map_or(Ok(()), |(_, e)| Err(e));
+        }
+        for (file, e) in &errors {
+            diagnostic::annotate_error(file, e);
+            term::eprint_wrapped(&format!("✗ {}: {}", file, e));
+        }
+        match errors.len() {
+            0 => Ok(()),
+            failed => Err(Error::FormatFailed {
+                failed,
+                total: files.len(),
+            }),
+        }
+    })
+}
+
+/// 格式化单个文件
+fn format_file(
+    file: &str,
+    write: bool,
+    preserve_mtime: bool,
+    style: &OutputOptions,
+    transforms: &Transforms,
+    only: Option<&Path>,
+    verbose: bool,
+) -> Result<()> {
     let format = Format::from_extension(file).ok_or_else(|| Error::UnknownFormat {
         path: file.to_string(),
     })?;
 
     if verbose {
-        eprintln!("格式: {}", format.name());
-        eprintln!("缩进: {} 空格", indent);
+        eprintln!("{}", t!("格式: {}", format.name()));
+        eprintln!("{}", t!("缩进: {} 空格", style.indent.unwrap_or(2)));
     }
 
     let content = fs::read_to_string(file).map_err(|e| Error::FileRead {
@@ -20,15 +85,13 @@ pub fn run(file: &str, indent: u8, write: bool, verbose: bool) -> Result<()> {
         source: e,
     })?;
 
-    let result = format_content(&content, format, indent)?;
+    let result = format_content(&content, format, style, transforms, only)?;
+    diagnostic::report(file, lossy::check(&content, format))?;
 
     if write {
-        fs::write(file, &result).map_err(|e| Error::FileWrite {
-            path: file.to_string(),
-            source: e,
-        })?;
+        fsutil::write_in_place(file, &result, preserve_mtime)?;
         if verbose {
-            eprintln!("已更新: {}", file);
+            eprintln!("{}", t!("已更新: {}", file));
         }
     } else {
         print!("{}", result);
@@ -37,45 +100,18 @@ pub fn run(file: &str, indent: u8, write: bool, verbose: bool) -> Result<()> {
     Ok(())
 }
 
-/// 格式化内容
-fn format_content(input: &str, format: Format, indent: u8) -> Result<String> {
-    match format {
-        Format::Json => {
-            let value: serde_json::Value =
-                serde_json::from_str(input).map_err(|e| Error::Parse {
-                    format: "JSON",
-                    source: e.to_string(),
-                })?;
-
-            let mut buf = Vec::new();
-            let indent_str = " ".repeat(indent as usize).into_bytes();
-            let formatter = serde_json::ser::PrettyFormatter::with_indent(&indent_str);
-            let mut ser = serde_json::Serializer::with_formatter(&mut buf, formatter);
-            serde::Serialize::serialize(&value, &mut ser).map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })?;
-
-            String::from_utf8(buf).map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })
-        }
-        Format::Yaml => {
-            let value: serde_json::Value = serde_yml::from_str(input).map_err(|e| Error::Parse {
-                format: "YAML",
-                source: e.to_string(),
-            })?;
-            serde_yml::to_string(&value).map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })
-        }
-        Format::Toml => {
-            let value: toml::Value = toml::from_str(input).map_err(|e| Error::Parse {
-                format: "TOML",
-                source: e.to_string(),
-            })?;
-            toml::to_string_pretty(&value).map_err(|e| Error::Convert {
-                message: e.to_string(),
-            })
-        }
+/// 格式化内容，指定了 `only` 时只格式化这个路径上的子树
+fn format_content(
+    input: &str,
+    format: Format,
+    style: &OutputOptions,
+    transforms: &Transforms,
+    only: Option<&Path>,
+) -> Result<String> {
+    let mut value = codec::parse(input, format)?;
+    transforms.apply(&mut value)?;
+    match only {
+        Some(path) if !path.is_root() => splice::format_at(input, format, &value, path, style),
+        _ => codec::serialize_with(&value, format, style),
     }
 }
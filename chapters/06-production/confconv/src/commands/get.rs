@@ -0,0 +1,58 @@
+//! get 命令实现
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::path::{self, Path};
+use serde_json::Value;
+
+/// 值的输出方式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GetOutput {
+    /// 默认：标量输出为 JSON 字面量，复杂值使用源文件的格式
+    Typed,
+    /// 裸标量（字符串不带引号），便于在 shell 中插值
+    Raw,
+    /// 始终输出合法的 JSON
+    Json,
+    /// 使用指定格式输出
+    Format(Format),
+}
+
+/// 执行读取命令
+///
+/// 路径不存在时返回错误，进程以非零状态退出
+pub fn run(
+    file: &str,
+    format: Option<Format>,
+    path: &str,
+    output: GetOutput,
+    pretty: bool,
+) -> Result<()> {
+    let path = Path::parse(path)?;
+    let (value, source_format) = codec::read_value(file, format)?;
+    let found = path::get(&value, &path).ok_or_else(|| Error::PathNotFound {
+        path: path.to_string(),
+    })?;
+
+    let is_scalar = !found.is_object() && !found.is_array();
+    let text = match output {
+        GetOutput::Raw => match found {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            _ => codec::serialize(found, Format::Json, false)?,
+        },
+        GetOutput::Json => codec::serialize(found, Format::Json, pretty)?,
+        GetOutput::Typed if is_scalar => codec::serialize(found, Format::Json, false)?,
+        GetOutput::Typed => codec::serialize(found, source_format, pretty)?,
+        GetOutput::Format(target) => codec::serialize(found, target, pretty)?,
+    };
+
+    if text.ends_with('\n') {
+        print!("{}", text);
+    } else {
+        println!("{}", text);
+    }
+
+    Ok(())
+}
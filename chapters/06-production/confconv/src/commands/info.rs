@@ -0,0 +1,137 @@
+//! info 命令实现
+
+use crate::cli::Cli;
+use crate::compress::Compression;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::i18n::{self, Lang};
+use crate::t;
+use clap::{ArgAction, Command, CommandFactory, ValueEnum};
+use serde_json::{json, Value};
+
+/// 启用的可选 cargo feature
+const FEATURES: &[&str] = &[
+    #[cfg(feature = "wasm")]
+    "wasm",
+    #[cfg(feature = "script")]
+    "script",
+    #[cfg(feature = "grpc")]
+    "grpc",
+    #[cfg(feature = "cloud")]
+    "cloud",
+    #[cfg(feature = "vault")]
+    "vault",
+];
+
+/// 执行信息命令
+///
+/// `json` 为 true 时输出机器可读的 JSON，供包装脚本和编辑器插件探测功能
+pub fn run(json: bool) -> Result<()> {
+    let command = i18n::localize(Cli::command());
+
+    if json {
+        let info = json!({
+            "name": command.get_name(),
+            "version": env!("CARGO_PKG_VERSION"),
+            "formats": Format::value_variants().iter().map(|format| json!({
+                "name": format.extension(),
+                "display_name": format.name(),
+                "extensions": format.extensions(),
+                "plugin": match format {
+                    Format::Plugin(plugin) => Some(plugin.path.display().to_string()),
+                    _ => None,
+                },
+            })).collect::<Vec<_>>(),
+            "compression": Compression::ALL.iter().map(|compression| json!({
+                "name": compression.name(),
+                "extensions": compression.extensions(),
+            })).collect::<Vec<_>>(),
+            "features": FEATURES,
+            "languages": Lang::value_variants()
+                .iter()
+                .filter_map(|lang| Some(lang.to_possible_value()?.get_name().to_string()))
+                .collect::<Vec<_>>(),
+            "global_args": command.get_arguments().filter(|arg| arg.is_global_set()).map(describe_arg).collect::<Vec<_>>(),
+            "commands": command.get_subcommands().filter(|sub| !sub.is_hide_set()).map(describe_command).collect::<Vec<_>>(),
+        });
+        let output = serde_json::to_string_pretty(&info).map_err(|e| Error::Convert {
+            message: e.to_string(),
+        })?;
+        println!("{}", output);
+        return Ok(());
+    }
+
+    println!("{} {}", command.get_name(), env!("CARGO_PKG_VERSION"));
+    let formats: Vec<String> = Format::value_variants()
+        .iter()
+        .map(|format| format!("{} ({})", format.name(), dotted(format.extensions())))
+        .collect();
+    println!("{}", t!("格式: {}", formats.join(", ")));
+    let compression: Vec<String> = Compression::ALL
+        .iter()
+        .map(|c| format!("{} ({})", c.name(), dotted(c.extensions())))
+        .collect();
+    println!("{}", t!("压缩: {}", compression.join(", ")));
+    let features = if FEATURES.is_empty() {
+        t!("无")
+    } else {
+        FEATURES.join(", ")
+    };
+    println!("{}", t!("可选功能: {}", features));
+    let commands: Vec<&str> = command
+        .get_subcommands()
+        .filter(|sub| !sub.is_hide_set())
+        .map(Command::get_name)
+        .collect();
+    println!("{}", t!("命令: {}", commands.join(", ")));
+    Ok(())
+}
+
+/// 描述一个子命令及其参数，嵌套的子命令递归描述
+fn describe_command(command: &Command) -> Value {
+    json!({
+        "name": command.get_name(),
+        "aliases": command.get_visible_aliases().collect::<Vec<_>>(),
+        "about": command.get_about().map(|about| about.to_string()),
+        "args": command
+            .get_arguments()
+            .filter(|arg| !arg.is_global_set() && !arg.is_hide_set())
+            .map(describe_arg)
+            .collect::<Vec<_>>(),
+        "subcommands": command.get_subcommands().filter(|sub| !sub.is_hide_set()).map(describe_command).collect::<Vec<_>>(),
+    })
+}
+
+/// 描述一个参数：名称、写法、是否取值以及可选的取值
+fn describe_arg(arg: &clap::Arg) -> Value {
+    let takes_value = arg.get_action().takes_values();
+    json!({
+        "name": arg.get_id().as_str(),
+        "long": arg.get_long(),
+        "short": arg.get_short(),
+        "positional": arg.is_positional(),
+        "required": arg.is_required_set(),
+        "takes_value": takes_value,
+        "multiple": matches!(arg.get_action(), ArgAction::Append | ArgAction::Count),
+        "possible_values": arg
+            .get_possible_values()
+            .iter()
+            .map(|value| value.get_name().to_string())
+            .collect::<Vec<_>>(),
+        "default": arg
+            .get_default_values()
+            .iter()
+            .map(|value| value.to_string_lossy().into_owned())
+            .collect::<Vec<_>>(),
+        "help": arg.get_help().map(|help| help.to_string()),
+    })
+}
+
+/// 把扩展名列表写成 `.yaml, .yml` 的形式
+fn dotted(extensions: &[&str]) -> String {
+    extensions
+        .iter()
+        .map(|ext| format!(".{}", ext))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,92 @@
+//! join 命令实现
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::t;
+use serde_json::{Map, Value};
+use std::path::Path;
+
+/// 执行合并命令
+///
+/// - `nest_by_filename`：以文件名（不含扩展名）为键，把各文件内容放进同一个对象
+/// - 否则按顺序拼接：YAML 输出多文档流，JSON 输出数组，TOML 不支持
+pub fn run(
+    inputs: &[String],
+    output: Option<&str>,
+    to: Option<Format>,
+    nest_by_filename: bool,
+    pretty: bool,
+    verbose: bool,
+) -> Result<()> {
+    let mut documents = Vec::new();
+    for input in inputs {
+        let (content, format) = codec::read_input(input, None)?;
+        if verbose {
+            eprintln!("{}", t!("读取: {} ({})", input, format.name()));
+        }
+        documents.push((input.as_str(), format, codec::parse(&content, format)?));
+    }
+
+    // 默认使用第一个输入文件的格式
+    let to = to.unwrap_or(documents[0].1);
+
+    let result = if nest_by_filename {
+        let mut root = Map::new();
+        for (input, _, value) in documents {
+            let key = file_stem(input);
+            if root.contains_key(&key) {
+                return Err(Error::Convert {
+                    message: t!("文件名 '{}' 重复，无法作为嵌套的键", key),
+                });
+            }
+            root.insert(key, value);
+        }
+        codec::serialize(&Value::Object(root), to, pretty)?
+    } else {
+        let values: Vec<Value> = documents.into_iter().map(|(_, _, value)| value).collect();
+        match to {
+            Format::Json | Format::Plugin(_) => {
+                codec::serialize(&Value::Array(values), to, pretty)?
+            }
+            Format::Yaml => {
+                // 多文档流：每个文档以 --- 开头
+                let mut out = String::new();
+                for value in &values {
+                    out.push_str("---\n");
+                    out.push_str(&codec::serialize(value, to, pretty)?);
+                }
+                out
+            }
+            Format::Toml | Format::Ini => {
+                return Err(Error::Convert {
+                    message: t!(
+                        "{} 不支持多文档或顶层数组，请使用 --nest-by-filename",
+                        to.name()
+                    ),
+                })
+            }
+        }
+    };
+
+    match output {
+        Some(path) => {
+            fsutil::write(path, &result)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", result),
+    }
+
+    Ok(())
+}
+
+/// 文件名去掉扩展名
+fn file_stem(path: &str) -> String {
+    Path::new(path)
+        .file_stem()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string())
+}
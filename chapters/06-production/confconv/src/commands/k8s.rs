@@ -0,0 +1,187 @@
+//! k8s 命令实现
+//!
+//! - wrap：把配置文件打包为 ConfigMap（或 Secret）清单，文件内容原样放在 `data` 下
+//! - unwrap：从 ConfigMap/Secret 清单中取出内嵌的配置，可以同时转换格式
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::t;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde_json::{json, Map, Value};
+use std::path::Path;
+
+/// 生成的清单
+pub struct Manifest<'a> {
+    /// 资源名称
+    pub name: &'a str,
+    /// 命名空间
+    pub namespace: Option<&'a str>,
+    /// 生成 Secret 而不是 ConfigMap
+    pub secret: bool,
+}
+
+/// 执行 k8s wrap 命令
+///
+/// 每个文件成为 `data` 中的一项，键默认为文件名；能识别格式的文件会先检查语法
+pub fn wrap(
+    files: &[String],
+    key: Option<&str>,
+    manifest: &Manifest,
+    to: Format,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    if key.is_some() && files.len() > 1 {
+        return Err(Error::Convert {
+            message: t!("有多个文件时不能指定 --key"),
+        });
+    }
+
+    let mut data = Map::new();
+    for file in files {
+        let key = match key {
+            Some(key) => key.to_string(),
+            None if file == "-" => {
+                return Err(Error::Convert {
+                    message: t!("从标准输入读取时必须指定 --key 参数"),
+                })
+            }
+            None => Path::new(file)
+                .file_name()
+                .map_or_else(|| file.clone(), |n| n.to_string_lossy().into_owned()),
+        };
+        let content = codec::read_text(file)?;
+        if let Some(format) = Format::from_path(&key) {
+            codec::parse(&content, format)?;
+        }
+        if verbose {
+            eprintln!("{}", t!("打包: {} -> data.{}", file, key));
+        }
+        let content = if manifest.secret {
+            STANDARD.encode(&content)
+        } else {
+            content
+        };
+        if data.insert(key.clone(), Value::String(content)).is_some() {
+            return Err(Error::Convert {
+                message: t!("data 中的键重复: {}", key),
+            });
+        }
+    }
+
+    let mut metadata = Map::new();
+    metadata.insert("name".to_string(), json!(manifest.name));
+    if let Some(namespace) = manifest.namespace {
+        metadata.insert("namespace".to_string(), json!(namespace));
+    }
+    let resource = if manifest.secret {
+        json!({
+            "apiVersion": "v1",
+            "kind": "Secret",
+            "metadata": metadata,
+            "type": "Opaque",
+            "data": data,
+        })
+    } else {
+        json!({
+            "apiVersion": "v1",
+            "kind": "ConfigMap",
+            "metadata": metadata,
+            "data": data,
+        })
+    };
+
+    write(&codec::serialize(&resource, to, true)?, output, verbose)
+}
+
+/// 执行 k8s unwrap 命令
+///
+/// 只有一项数据时可以不指定 `key`；指定 `to` 时按键的扩展名（无法推断时自动识别）解析后转换
+pub fn unwrap(
+    manifest: &str,
+    key: Option<&str>,
+    to: Option<Format>,
+    pretty: bool,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    // 清单通常来自 kubectl 的输出，标准输入或无法推断格式时按 YAML（兼容 JSON）解析
+    let format = Format::from_path(manifest).unwrap_or(Format::Yaml);
+    let resource = codec::parse(&codec::read_text(manifest)?, format)?;
+    let kind = resource.get("kind").and_then(Value::as_str).unwrap_or("");
+    let mut entries = Map::new();
+    match kind {
+        "ConfigMap" => {
+            if let Some(Value::Object(data)) = resource.get("data") {
+                entries.extend(data.clone());
+            }
+        }
+        "Secret" => {
+            if let Some(Value::Object(data)) = resource.get("data") {
+                for (key, value) in data {
+                    let decoded = value
+                        .as_str()
+                        .and_then(|encoded| STANDARD.decode(encoded).ok())
+                        .and_then(|bytes| String::from_utf8(bytes).ok())
+                        .ok_or_else(|| Error::Convert {
+                            message: t!("data.{} 不是有效的 base64 文本", key),
+                        })?;
+                    entries.insert(key.clone(), Value::String(decoded));
+                }
+            }
+            // stringData 是明文，与 data 同名时以 stringData 为准（与 Kubernetes 一致）
+            if let Some(Value::Object(data)) = resource.get("stringData") {
+                entries.extend(data.clone());
+            }
+        }
+        _ => {
+            return Err(Error::Convert {
+                message: t!("{} 不是 ConfigMap 或 Secret", manifest),
+            })
+        }
+    }
+
+    let key = match key {
+        Some(key) => key.to_string(),
+        None if entries.len() == 1 => entries.keys().next().cloned().unwrap_or_default(),
+        None => {
+            let keys: Vec<&str> = entries.keys().map(String::as_str).collect();
+            return Err(Error::Convert {
+                message: t!("有多项数据，请用 --key 指定: {}", keys.join(", ")),
+            });
+        }
+    };
+    let content = match entries.get(&key) {
+        Some(Value::String(content)) => content,
+        _ => return Err(Error::PathNotFound { path: key }),
+    };
+
+    let content = match to {
+        None => content.clone(),
+        Some(to) => {
+            let value = match Format::from_path(&key) {
+                Some(format) => codec::parse(content, format)?,
+                None => codec::parse_any(content)?.0,
+            };
+            codec::serialize(&value, to, pretty)?
+        }
+    };
+    write(&content, output, verbose)
+}
+
+/// 写到输出文件或标准输出
+fn write(content: &str, output: Option<&str>, verbose: bool) -> Result<()> {
+    match output {
+        Some(path) => {
+            fsutil::write(path, content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
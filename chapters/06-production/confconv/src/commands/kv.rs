@@ -0,0 +1,163 @@
+//! kv 命令实现
+//!
+//! 在嵌套配置和键值存储（Consul、etcd）的导出格式之间转换：
+//! - export：每个叶子成为一项，键是 `/` 连接的路径，值以 base64 存放
+//! - import：把这样的导出还原为嵌套配置
+//!
+//! 空对象和空数组没有叶子，不会出现在导出中
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::flatten;
+use crate::format::Format;
+use crate::fsutil;
+use crate::i18n::tr;
+use crate::t;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::ValueEnum;
+use serde_json::{json, Value};
+
+/// 键值存储的导出格式
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum KvFlavor {
+    /// consul kv export/import 的格式（[{"key", "flags", "value"}]，值为 base64）
+    #[value(help = tr("consul kv export/import 的格式（键为明文，值为 base64）"))]
+    Consul,
+    /// etcd JSON API 的格式（键和值都为 base64），导入时也接受 etcdctl -w json 的输出
+    #[value(help = tr("etcd JSON API 的格式（键和值都为 base64），导入时也接受 etcdctl -w json 的输出"))]
+    Etcd,
+}
+
+/// 键值存储中的位置
+pub struct KvLayout<'a> {
+    /// 导出格式
+    pub flavor: KvFlavor,
+    /// 所有键的前缀，例如 `app/prod/`
+    pub prefix: &'a str,
+}
+
+/// 执行 kv export 命令
+pub fn export(
+    file: &str,
+    format: Option<Format>,
+    layout: &KvLayout,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    let (value, _) = codec::read_value(file, format)?;
+
+    let mut items = Vec::new();
+    for entry in flatten::flatten(&value) {
+        if let Some(segment) = entry.path.iter().find(|s| s.contains('/')) {
+            return Err(Error::Convert {
+                message: t!("键 '{}' 含有 '/'，无法作为 KV 路径的一段", segment),
+            });
+        }
+        let key = format!("{}{}", layout.prefix, entry.path.join("/"));
+        let value = STANDARD.encode(entry.text());
+        items.push(match layout.flavor {
+            KvFlavor::Consul => json!({ "key": key, "flags": 0, "value": value }),
+            KvFlavor::Etcd => json!({ "key": STANDARD.encode(key), "value": value }),
+        });
+    }
+
+    let content = codec::serialize(&Value::Array(items), Format::Json, true)? + "\n";
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// 执行 kv import 命令
+///
+/// 只导入 `prefix` 下的键（去掉前缀后按 `/` 拆成路径）；
+/// 值按 YAML 标量解释类型，`strings` 为真时全部作为字符串
+pub fn import(
+    file: &str,
+    layout: &KvLayout,
+    strings: bool,
+    to: Format,
+    pretty: bool,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    let dump = codec::parse(&codec::read_text(file)?, Format::Json)?;
+    // etcdctl get --prefix -w json 的输出把键值对放在 kvs 下
+    let items = match &dump {
+        Value::Array(items) => items,
+        Value::Object(map) => match map.get("kvs") {
+            Some(Value::Array(items)) => items,
+            _ => return Err(invalid(file)),
+        },
+        _ => return Err(invalid(file)),
+    };
+
+    let mut entries = Vec::new();
+    for (i, item) in items.iter().enumerate() {
+        let key = match (layout.flavor, item.get("key")) {
+            (KvFlavor::Consul, Some(Value::String(key))) => key.clone(),
+            (KvFlavor::Etcd, Some(Value::String(key))) => decode(key, i)?,
+            _ => return Err(invalid(file)),
+        };
+        let Some(relative) = key.strip_prefix(layout.prefix) else {
+            if verbose {
+                eprintln!("{}", t!("跳过前缀以外的键: {}", key));
+            }
+            continue;
+        };
+        // Consul 的"目录"是以 / 结尾的空键，没有值
+        let text = match item.get("value") {
+            Some(Value::String(value)) => decode(value, i)?,
+            None | Some(Value::Null) if key.ends_with('/') => continue,
+            None | Some(Value::Null) => String::new(),
+            Some(_) => return Err(invalid(file)),
+        };
+        let path: Vec<String> = relative
+            .trim_matches('/')
+            .split('/')
+            .map(str::to_string)
+            .collect();
+        let value = if strings {
+            Value::String(text)
+        } else {
+            flatten::parse_scalar(&text)
+        };
+        entries.push((path, value));
+    }
+
+    let content = codec::serialize(&flatten::unflatten(entries)?, to, pretty)?;
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+    Ok(())
+}
+
+/// 解码第 `index` 项中的 base64 文本
+fn decode(encoded: &str, index: usize) -> Result<String> {
+    STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| Error::Convert {
+            message: t!("第 {} 项不是有效的 base64 文本", index + 1),
+        })
+}
+
+fn invalid(file: &str) -> Error {
+    Error::Convert {
+        message: t!("{} 不是 KV 导出（应为 key/value 对象的数组）", file),
+    }
+}
@@ -0,0 +1,285 @@
+//! lint 命令实现
+
+use crate::codec::{self, OutputOptions};
+use crate::color::Stream;
+use crate::cycle;
+use crate::diagnostic::{self, render, Diagnostic, Severity};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::lint::{self, Options};
+use crate::lossy;
+use crate::path::{Path, Segment};
+use crate::report::{self, ReportFormat};
+use crate::splice;
+use crate::t;
+use crate::transform::{self, Rename, Transform};
+use serde_json::Value;
+
+/// `--fix` 时修正结果的去向
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FixMode {
+    /// 输出到标准输出，只能检查一个文件
+    Print,
+    /// 原地修改文件（`--fix --write`）
+    Write,
+}
+
+/// 执行检查命令
+///
+/// 逐个检查所有文件并输出发现的问题，有错误级别的问题（包括 `--deny` 指定的警告）时返回错误，
+/// 只有警告时正常结束。
+///
+/// 指定 `fix` 时修正有确定修正方式的问题，只报告剩下的问题：
+/// [`FixMode::Print`] 把修正后的文件输出到标准输出，问题改为输出到标准错误；
+/// [`FixMode::Write`] 原地修改有问题被修正的文件。只重新生成被修正的对象，文件的其余部分保持不变；
+/// 这些对象中有修正后会丢失的注释、锚点时（见 [`splice::rewrite_objects`]）作为这个文件的错误报告，不修改文件。
+///
+/// `report` 为 [`ReportFormat::Html`] 或 [`ReportFormat::Junit`] 时检查完所有文件后输出报告，
+/// 无法解析的文件作为错误列出
+pub fn run(
+    files: &[String],
+    format: Option<Format>,
+    options: &Options,
+    fix: Option<FixMode>,
+    report: ReportFormat,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    if fix == Some(FixMode::Print) && files.len() != 1 {
+        return Err(Error::Convert {
+            message: t!("--fix 一次只能修正一个文件，修正多个文件时请加上 --write"),
+        });
+    }
+    if fix == Some(FixMode::Print) && report != ReportFormat::Text {
+        return Err(Error::Convert {
+            message: t!("--fix 输出修正后的文件时不能同时输出报告，请加上 --write"),
+        });
+    }
+    let stream = match fix {
+        Some(FixMode::Print) => Stream::Stderr,
+        _ => Stream::Stdout,
+    };
+    let (mut errors, mut warnings, mut fixed) = (0, 0, 0);
+    let mut reported = Vec::new();
+    for file in files {
+        let diagnostics = match check_file(file, format, options, fix.is_some()) {
+            Ok(checked) => {
+                fixed += checked.fixed;
+                match (fix, checked.content) {
+                    (Some(FixMode::Print), Some(content)) => print!("{}", content),
+                    (Some(FixMode::Write), Some(content)) if checked.fixed > 0 => {
+                        fsutil::write_in_place(file, &content, false)?;
+                        if verbose {
+                            eprintln!("{}", t!("已更新: {}", file));
+                        }
+                    }
+                    _ => {}
+                }
+                diagnostic::levels().apply(checked.diagnostics)
+            }
+            Err(e) if report == ReportFormat::Text => {
+                eprintln!("✗ {}: {}", file, e);
+                diagnostic::annotate_error(file, &e);
+                errors += 1;
+                continue;
+            }
+            Err(e) => vec![Diagnostic {
+                severity: Severity::Error,
+                code: "syntax",
+                location: None,
+                message: e.to_string(),
+            }],
+        };
+        for diagnostic in &diagnostics {
+            match diagnostic.severity {
+                Severity::Error => errors += 1,
+                Severity::Warning => warnings += 1,
+                Severity::Note => {}
+            }
+            diagnostic::annotate(file, diagnostic);
+            if report != ReportFormat::Text {
+                continue;
+            }
+            let line = render(file, diagnostic, stream);
+            match stream {
+                Stream::Stdout => println!("{}", line),
+                Stream::Stderr => eprintln!("{}", line),
+            }
+        }
+        if report != ReportFormat::Text {
+            reported.push((file.clone(), diagnostics));
+        }
+    }
+    match report {
+        ReportFormat::Text => {}
+        ReportFormat::Html => print!("{}", report::lint_html(&reported)),
+        ReportFormat::Junit => print!("{}", report::lint_junit(&reported)),
+    }
+
+    if !quiet {
+        let summary = t!(
+            "检查了 {} 个文件：{} 个错误，{} 个警告",
+            files.len(),
+            errors,
+            warnings
+        );
+        match fix {
+            Some(_) => eprintln!("{}", t!("{}，修正了 {} 个问题", summary, fixed)),
+            None => eprintln!("{}", summary),
+        }
+    }
+    if errors > 0 {
+        return Err(Error::LintFailed { errors });
+    }
+    Ok(())
+}
+
+/// 一个文件的检查结果
+struct Checked {
+    /// 剩下的问题
+    diagnostics: Vec<Diagnostic>,
+    /// 修正了的问题数
+    fixed: usize,
+    /// 修正时语法正确的文件内容：有问题被修正时重新生成，否则是原文
+    content: Option<String>,
+}
+
+/// 检查单个文件，`fix` 时修正能修正的问题
+fn check_file(file: &str, format: Option<Format>, options: &Options, fix: bool) -> Result<Checked> {
+    let format =
+        format
+            .or_else(|| Format::from_path(file))
+            .ok_or_else(|| Error::UnknownFormat {
+                path: file.to_string(),
+            })?;
+    let content = codec::read_bytes(file)?;
+    let text = std::str::from_utf8(&content).ok();
+
+    let mut diagnostics = match (format, text) {
+        (Format::Yaml, Some(text)) => lint::yaml_anchors(text, options),
+        _ => Vec::new(),
+    };
+    let mut checked = Checked {
+        diagnostics: Vec::new(),
+        fixed: 0,
+        content: None,
+    };
+    // 其他错误（重复的键、TOML 语法错误等）由解析器报告
+    if diagnostics.iter().any(|d| d.severity == Severity::Error) {
+        checked.diagnostics = diagnostics;
+        return Ok(checked);
+    }
+    let mut value = match codec::parse_bytes(&content, format) {
+        Ok(value) => value,
+        Err(e) => {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "syntax",
+                location: None,
+                message: e.to_string(),
+            });
+            checked.diagnostics = diagnostics;
+            return Ok(checked);
+        }
+    };
+
+    // 语法正确时再报告转换会丢失的信息和重复的内容
+    let mut aliases = Vec::new();
+    if let Some(text) = text {
+        diagnostics.extend(lossy::check(text, format));
+        if format == Format::Yaml {
+            aliases = cycle::yaml_alias_paths(text);
+        }
+    }
+    diagnostics.extend(lint::duplicates(&value, &aliases, options));
+
+    // 以下问题都有确定的修正方式；解析后的值已经只保留了重复键的最后一个值，
+    // 缩进在重新生成文件时修正，只需要改名和排序。修正时只重新生成涉及的对象
+    let original = fix.then(|| value.clone());
+    let duplicates = match (format, text) {
+        (Format::Yaml, Some(text)) => lint::yaml_duplicate_keys(text),
+        (Format::Json, Some(text)) => lint::json_duplicate_keys(text),
+        _ => Vec::new(),
+    };
+    let (mut objects, mut fixable): (Vec<Path>, Vec<Diagnostic>) = duplicates.into_iter().unzip();
+    if let (Format::Json, Some(text), Some(width)) = (format, text, options.indent) {
+        if let Some(diagnostic) = lint::json_indentation(text, width) {
+            fixable.push(diagnostic);
+            objects.push(Path::default());
+        }
+    }
+    if let Some(naming) = options.naming.for_file(file) {
+        for misnamed in lint::key_naming(&value, naming) {
+            // 无法自动修正（正则规范、新键名已经存在）的问题仍然报告
+            let renamed = fix
+                && misnamed.fixed.as_ref().is_some_and(|to| {
+                    let rename = Rename {
+                        path: misnamed.path.clone(),
+                        to: to.clone(),
+                    };
+                    rename.apply(&mut value).is_ok()
+                });
+            if renamed {
+                checked.fixed += 1;
+            } else {
+                diagnostics.push(misnamed.diagnostic(naming));
+            }
+        }
+    }
+    if let Some(mode) = options.sorted_keys {
+        fixable.extend(lint::unsorted_keys(&value, mode));
+        if fix {
+            transform::order_keys(&mut value, &[], mode);
+        }
+    }
+
+    if let Some(original) = &original {
+        changed_objects(original, &value, &Path::default(), &mut objects);
+        checked.fixed += fixable.len();
+        checked.content = Some(if checked.fixed > 0 {
+            // 原文只有一行（压缩的 JSON）时保持紧凑
+            let style = OutputOptions {
+                pretty: text.is_none_or(|text| text.trim_end().contains('\n')),
+                indent: options.indent,
+                ..OutputOptions::default()
+            };
+            match text {
+                Some(text) if style.pretty => {
+                    splice::rewrite_objects(text, format, &value, &objects, &style)?
+                }
+                _ => codec::serialize_with(&value, format, &style)?,
+            }
+        } else {
+            String::from_utf8_lossy(&content).into_owned()
+        });
+    } else {
+        diagnostics.extend(fixable);
+    }
+    checked.diagnostics = diagnostics;
+    Ok(checked)
+}
+
+/// `before` 和 `after` 中键名或键的顺序不同的对象（值不同时是值本身）
+fn changed_objects(before: &Value, after: &Value, path: &Path, found: &mut Vec<Path>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) if old.keys().eq(new.keys()) => {
+            for (key, child) in old {
+                changed_objects(
+                    child,
+                    &new[key],
+                    &path.join(Segment::Key(key.clone())),
+                    found,
+                );
+            }
+        }
+        (Value::Array(old), Value::Array(new)) if old.len() == new.len() => {
+            for (index, (old, new)) in old.iter().zip(new).enumerate() {
+                changed_objects(old, new, &path.join(Segment::Index(index as i64)), found);
+            }
+        }
+        (Value::Object(_), Value::Object(_)) => found.push(path.clone()),
+        _ if before != after => found.push(path.clone()),
+        _ => {}
+    }
+}
@@ -0,0 +1,188 @@
+//! merge 命令实现
+
+use super::edit::{open_editor, temp_file};
+use crate::codec::{self, OutputOptions};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::merge::{self, Conflict, MergeKey};
+use crate::path::{self, Path, Segment};
+use crate::t;
+use serde_json::Value;
+use std::fs;
+use std::io::{self, BufRead, Write};
+
+/// 后面的文件与前面已定义的值冲突时怎么办
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OnConflict {
+    /// 后面的覆盖前面的
+    Overwrite,
+    /// 列出所有冲突并返回错误
+    Fail,
+    /// 逐个询问保留哪一边，或者在编辑器中修改
+    Ask,
+}
+
+/// 交互解决冲突时的选择
+enum Choice {
+    Left,
+    Right,
+    Edited(Value),
+}
+
+/// 执行深度合并命令
+///
+/// 按顺序把每个文件叠加到前面的结果上，后面的文件覆盖前面的值，
+/// 指定了合并键的对象数组按键合并（见 [`MergeKey`]）。
+/// [`OnConflict::Fail`] 时不允许覆盖：列出每个冲突的路径和两边的值，并返回错误
+pub fn run(
+    inputs: &[String],
+    output: Option<&str>,
+    to: Option<Format>,
+    on_conflict: OnConflict,
+    merge_keys: &[MergeKey],
+    style: &OutputOptions,
+    verbose: bool,
+) -> Result<()> {
+    let mut layers = Vec::new();
+    for input in inputs {
+        let (value, format) = codec::read_value(input, None)?;
+        if verbose {
+            eprintln!("{}", t!("读取: {} ({})", input, format.name()));
+        }
+        layers.push((input.as_str(), format, value));
+    }
+
+    // 默认使用第一个输入文件的格式
+    let to = to.unwrap_or(layers[0].1);
+
+    let mut result = Value::Object(Default::default());
+    let mut conflict_count = 0;
+    for (i, (input, _, value)) in layers.iter().enumerate() {
+        let conflicts = merge::merge_with(&mut result, value.clone(), merge_keys);
+        if i == 0 {
+            continue;
+        }
+        let mut choices = Vec::new();
+        for conflict in conflicts {
+            // 找到最近一个在该路径上定义了旧值的文件
+            let source = layers[..i]
+                .iter()
+                .rev()
+                .find(|(_, _, layer)| path::get(layer, &conflict.path) == Some(&conflict.old))
+                .map_or("?", |(name, _, _)| name);
+            match on_conflict {
+                OnConflict::Fail => {
+                    eprintln!("{}", t!("冲突: {}", conflict.path));
+                    eprintln!("  {}: {}", source, compact(&conflict.old));
+                    eprintln!("  {}: {}", input, compact(&conflict.new));
+                }
+                OnConflict::Ask => {
+                    let choice = ask(&conflict, source, input)?;
+                    choices.push((conflict, choice));
+                }
+                OnConflict::Overwrite if verbose => {
+                    eprintln!("{}", t!("覆盖: {} ({})", conflict.path, input));
+                }
+                OnConflict::Overwrite => {}
+            }
+            conflict_count += 1;
+        }
+        // 倒序应用，被删除的数组元素才能按原来的下标放回
+        for (conflict, choice) in choices.into_iter().rev() {
+            match choice {
+                Choice::Left => restore(&mut result, &conflict, conflict.old.clone())?,
+                Choice::Right => {}
+                Choice::Edited(value) => restore(&mut result, &conflict, value)?,
+            }
+        }
+    }
+
+    if on_conflict == OnConflict::Fail && conflict_count > 0 {
+        return Err(Error::MergeConflicts {
+            count: conflict_count,
+        });
+    }
+
+    let content = codec::serialize_with(&result, to, style)?;
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// 询问如何解决一处冲突，放弃时返回错误
+fn ask(conflict: &Conflict, left: &str, right: &str) -> Result<Choice> {
+    eprintln!("{}", t!("冲突: {}", conflict.path));
+    eprintln!("  [l] {}: {}", left, compact(&conflict.old));
+    eprintln!("  [r] {}: {}", right, compact(&conflict.new));
+    let stdin = io::stdin();
+    loop {
+        eprint!("{}", t!("保留左边 (l)、右边 (r)、编辑 (e) 还是放弃 (q)？ "));
+        let _ = io::stderr().flush();
+        let mut answer = String::new();
+        let read = stdin
+            .lock()
+            .read_line(&mut answer)
+            .map_err(|e| Error::FileRead {
+                path: "-".to_string(),
+                source: e,
+            })?;
+        match answer.trim() {
+            "l" => return Ok(Choice::Left),
+            "r" => return Ok(Choice::Right),
+            "e" => match edit(&conflict.new) {
+                Ok(value) => return Ok(Choice::Edited(value)),
+                Err(e) => eprintln!("{}", e),
+            },
+            // 输入结束时按放弃处理
+            _ if read == 0 => return Err(Error::MergeConflicts { count: 1 }),
+            "q" => return Err(Error::MergeConflicts { count: 1 }),
+            _ => {}
+        }
+    }
+}
+
+/// 在编辑器中以 YAML 修改值（YAML 可以表示单独的标量）
+fn edit(value: &Value) -> Result<Value> {
+    let original = codec::serialize(value, Format::Yaml, true)?;
+    let tmp = temp_file("confconv-merge-", Format::Yaml, &original)?;
+    open_editor(tmp.path())?;
+    let edited = fs::read_to_string(tmp.path()).map_err(|e| Error::FileRead {
+        path: tmp.path().display().to_string(),
+        source: e,
+    })?;
+    codec::parse(&edited, Format::Yaml)
+}
+
+/// 把冲突路径上的值改为 `value`；按合并键删除的数组元素重新插入原来的位置
+fn restore(result: &mut Value, conflict: &Conflict, value: Value) -> Result<()> {
+    if path::get(result, &conflict.path) == Some(&conflict.new) {
+        path::insert(result, &conflict.path, value)?;
+        return Ok(());
+    }
+    let Some((Segment::Index(index), parents)) = conflict.path.segments().split_last() else {
+        return Ok(());
+    };
+    let parent = parents.iter().fold(Path::default(), |parent, segment| {
+        parent.join(segment.clone())
+    });
+    if let Some(Value::Array(items)) = path::get(result, &parent) {
+        let mut items = items.clone();
+        items.insert((*index as usize).min(items.len()), value);
+        path::insert(result, &parent, Value::Array(items))?;
+    }
+    Ok(())
+}
+
+/// 值的单行 JSON 文本
+fn compact(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
@@ -2,10 +2,90 @@
 //!
 //! 每个子命令对应一个文件，通过 pub use 重新导出
 
+mod anonymize;
+mod args;
+mod check;
+mod complete;
+mod config_path;
 mod convert;
+mod defaults;
+mod diff;
+mod docs;
+mod doctor;
+mod drift;
+mod edit;
+mod env;
+mod escape;
+mod example;
+mod export;
+mod extract;
 mod format;
+mod get;
+mod info;
+mod join;
+mod k8s;
+mod kv;
+mod lint;
+mod merge;
+mod move_path;
+mod resolve;
+mod schema;
+#[cfg(feature = "grpc")]
+mod serve;
+mod sign;
+mod snapshot;
+mod template;
+mod test;
+mod undo;
+mod unescape;
+mod unused;
 mod validate;
+mod verify;
 
+pub use anonymize::run as anonymize;
+pub use args::run as args;
+pub use check::run as check;
+pub use complete::run as complete;
+pub use complete::{completions, Shell};
+pub use config_path::run as config_path;
 pub use convert::run as convert;
+pub use convert::run_map as convert_map;
+pub use convert::{Destination, InputOptions};
+pub use defaults::{apply as defaults_apply, prune as defaults_prune, Defaults};
+pub use diff::run as diff;
+pub use docs::run as docs;
+pub use doctor::run as doctor;
+pub use drift::run as drift;
+pub use edit::run as edit;
+pub use env::run as env;
+pub use env::EnvFormat;
+pub use escape::run as escape;
+pub use example::run as example;
+pub use export::{secrets_manager as export_secrets_manager, ssm as export_ssm};
+pub use extract::run as extract;
 pub use format::run as format;
-pub use validate::run as validate;
+pub use get::run as get;
+pub use get::GetOutput;
+pub use info::run as info;
+pub use join::run as join;
+pub use k8s::{unwrap as k8s_unwrap, wrap as k8s_wrap, Manifest};
+pub use kv::{export as kv_export, import as kv_import, KvFlavor, KvLayout};
+pub use lint::run as lint;
+pub use lint::FixMode;
+pub use merge::{run as merge, OnConflict};
+pub use move_path::run as move_path;
+pub use resolve::{run as resolve, Layout};
+pub use schema::infer as schema_infer;
+#[cfg(feature = "grpc")]
+pub use serve::run as serve;
+pub use sign::run as sign;
+pub use snapshot::run as snapshot;
+pub use template::run as template;
+pub use template::Postprocess;
+pub use test::run as test;
+pub use undo::run as undo;
+pub use unescape::run as unescape;
+pub use unused::{run as unused, Known};
+pub use validate::{run as validate, stream as validate_stream, ValidateFormat};
+pub use verify::run as verify;
+pub use verify::SignatureCheck;
@@ -0,0 +1,43 @@
+//! move 命令实现
+
+use crate::codec;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::path::{self, Path};
+use crate::t;
+
+/// 执行移动命令
+///
+/// 把 `from` 路径上的子树移动到 `to`，缺少的父级会自动创建；
+/// 目标路径已存在时报错，除非指定 `force`。同一个数组中的移动是调整元素顺序，
+/// 见 [`path::relocate`]
+pub fn run(
+    file: &str,
+    format: Option<Format>,
+    from: &str,
+    to: &str,
+    force: bool,
+    write: bool,
+    verbose: bool,
+) -> Result<()> {
+    let (from, to) = (Path::parse(from)?, Path::parse(to)?);
+    let (mut value, format) = codec::read_value(file, format)?;
+    path::relocate(&mut value, &from, &to, force)?;
+
+    if verbose {
+        eprintln!("{}", t!("已移动: {} -> {}", from, to));
+    }
+
+    let result = codec::serialize(&value, format, true)?;
+    if write {
+        fsutil::write_in_place(file, &result, false)?;
+        if verbose {
+            eprintln!("{}", t!("已更新: {}", file));
+        }
+    } else {
+        print!("{}", result);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! resolve 命令实现
+//!
+//! 按常见的目录约定叠加配置：
+//!
+//! ```text
+//! base.yaml              基础配置
+//! overlays/prod.yaml     --env prod 的覆盖
+//! local.yaml             本机覆盖（可选，通常不提交）
+//! ```
+//!
+//! 覆盖文件和本机文件可以是任意支持的格式，按扩展名查找；
+//! 叠加使用合并引擎（见 [`merge`](crate::merge)），最后应用 `--set`
+
+use crate::codec::{self, OutputOptions};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::merge::{self, Assignment, MergeKey};
+use crate::t;
+use clap::ValueEnum;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 覆盖文件所在的默认目录（相对于基础配置）
+const OVERLAY_DIR: &str = "overlays";
+
+/// 本机覆盖文件的文件名（不含扩展名）
+const LOCAL_STEM: &str = "local";
+
+/// 配置的分层布局
+pub struct Layout<'a> {
+    /// 基础配置
+    pub base: &'a str,
+    /// 环境名，选择 `overlays/<环境>.*`
+    pub env: Option<&'a str>,
+    /// 覆盖文件目录，默认为基础配置旁的 `overlays`
+    pub overlays: Option<&'a str>,
+    /// 本机覆盖文件，默认为基础配置旁的 `local.*`（不存在时跳过）
+    pub local: Option<&'a str>,
+    /// 不使用本机覆盖文件
+    pub no_local: bool,
+}
+
+/// 执行 resolve 命令
+pub fn run(
+    layout: &Layout,
+    sets: &[Assignment],
+    merge_keys: &[MergeKey],
+    to: Option<Format>,
+    output: Option<&str>,
+    style: &OutputOptions,
+    verbose: bool,
+) -> Result<()> {
+    let base_dir = Path::new(layout.base)
+        .parent()
+        .unwrap_or(Path::new(""))
+        .to_path_buf();
+    let mut files = vec![layout.base.to_string()];
+    if let Some(env) = layout.env {
+        let dir = layout
+            .overlays
+            .map_or_else(|| base_dir.join(OVERLAY_DIR), PathBuf::from);
+        let overlay = find(&dir, env)?.ok_or_else(|| Error::FileRead {
+            path: dir.join(format!("{}.*", env)).display().to_string(),
+            source: io::Error::new(io::ErrorKind::NotFound, t!("没有环境 '{}' 的覆盖文件", env)),
+        })?;
+        files.push(overlay);
+    }
+    if !layout.no_local {
+        match layout.local {
+            Some(local) => files.push(local.to_string()),
+            None => files.extend(find(&base_dir, LOCAL_STEM)?),
+        }
+    }
+
+    let mut layers = Vec::with_capacity(files.len());
+    let mut base_format = None;
+    for file in &files {
+        let (value, format) = codec::read_value(file, None)?;
+        if verbose {
+            eprintln!("{}", t!("读取: {} ({})", file, format.name()));
+        }
+        base_format.get_or_insert(format);
+        layers.push(value);
+    }
+    let result = merge::resolve(layers, sets, merge_keys)?;
+
+    // 默认使用基础配置的格式
+    let to = to.or(base_format).unwrap_or(Format::Yaml);
+    let content = codec::serialize_with(&result, to, style)?;
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// 在目录中查找 `<stem>.<扩展名>`，扩展名为任意支持的格式；有多个候选时报错
+fn find(dir: &Path, stem: &str) -> Result<Option<String>> {
+    let candidates: Vec<String> = Format::value_variants()
+        .iter()
+        .flat_map(Format::extensions)
+        .map(|ext| dir.join(format!("{}.{}", stem, ext)))
+        .filter(|path| path.is_file())
+        .map(|path| path.display().to_string())
+        .collect();
+    match candidates.as_slice() {
+        [] => Ok(None),
+        [path] => Ok(Some(path.clone())),
+        _ => Err(Error::FileRead {
+            path: dir.join(format!("{}.*", stem)).display().to_string(),
+            source: io::Error::other(t!("有多个候选文件: {}", candidates.join(", "))),
+        }),
+    }
+}
@@ -0,0 +1,37 @@
+//! schema 命令实现
+
+use crate::codec;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::schema::{self, Level};
+use crate::t;
+
+/// 执行 schema infer 命令
+///
+/// 从一个或多个样例文件推断 JSON Schema，样例越多，必填项和取值范围越准确
+pub fn infer(files: &[String], level: Level, output: Option<&str>, verbose: bool) -> Result<()> {
+    let mut samples = Vec::with_capacity(files.len());
+    for file in files {
+        let (value, format) = codec::read_value(file, None)?;
+        if verbose {
+            eprintln!("{}", t!("样例: {} ({})", file, format.name()));
+        }
+        samples.push(value);
+    }
+
+    let schema = schema::infer(&samples, level);
+    let content = format!("{}\n", codec::serialize(&schema, Format::Json, true)?);
+
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,12 @@
+//! serve 命令实现
+
+use crate::error::Result;
+use crate::grpc;
+use crate::t;
+use std::net::SocketAddr;
+
+/// 启动 gRPC 服务，直到进程退出
+pub fn run(listen: SocketAddr) -> Result<()> {
+    eprintln!("{}", t!("gRPC 服务监听 {}", listen));
+    grpc::serve(listen)
+}
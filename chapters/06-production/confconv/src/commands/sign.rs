@@ -0,0 +1,31 @@
+//! sign 命令实现
+
+use crate::codec;
+use crate::error::Result;
+use crate::format::Format;
+use crate::fsutil;
+use crate::signature;
+use crate::t;
+
+/// 执行签名命令
+///
+/// 对配置的规范形式签名，签名写入 `output`，默认为 `<文件>.sig`
+pub fn run(
+    file: &str,
+    format: Option<Format>,
+    key: &str,
+    output: Option<&str>,
+    verbose: bool,
+) -> Result<()> {
+    let (value, _) = codec::read_value(file, format)?;
+    let private_key = codec::read_text(key)?;
+    let content = signature::sign(&value, &private_key)?;
+
+    let path = output.map_or_else(|| format!("{}.sig", file), str::to_string);
+    fsutil::write(&path, content)?;
+    if verbose {
+        eprintln!("{}", t!("已写入签名: {}", path));
+    }
+
+    Ok(())
+}
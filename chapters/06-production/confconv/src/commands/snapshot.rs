@@ -0,0 +1,104 @@
+//! snapshot 命令实现
+
+use super::diff::colored;
+use crate::codec;
+use crate::diff::{self, Options};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::t;
+use crate::transform::{SortMode, Transforms};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 快照目录名，位于被测配置文件所在的目录下
+const SNAPSHOT_DIR: &str = "__confconv_snapshots__";
+
+/// 执行快照命令
+///
+/// 快照是配置的规范形式（键排序后的美化 JSON），与源格式和排版无关。
+/// `update` 时写入快照，否则与已有快照比较，内容变化时列出差异并返回错误
+pub fn run(files: &[String], update: bool, verbose: bool, quiet: bool) -> Result<()> {
+    // 更新多个快照时要么全部写入，要么都不写
+    let changed = fsutil::transaction(|| {
+        let mut changed = 0;
+        for file in files {
+            let snapshot = snapshot_path(file);
+            let (mut value, _) = codec::read_value(file, None)?;
+            Transforms {
+                sort: Some(SortMode::Lexical),
+                ..Default::default()
+            }
+            .apply(&mut value)?;
+            let canonical = format!("{}\n", codec::serialize(&value, Format::Json, true)?);
+
+            if update {
+                if let Some(dir) = snapshot.parent() {
+                    fs::create_dir_all(dir).map_err(|e| Error::FileWrite {
+                        path: dir.display().to_string(),
+                        source: e,
+                    })?;
+                }
+                fsutil::write(&snapshot, &canonical)?;
+                if !quiet {
+                    println!("{}", t!("✓ {} 快照已更新: {}", file, snapshot.display()));
+                }
+                continue;
+            }
+
+            if verbose {
+                eprintln!("{}", t!("比较快照: {}", snapshot.display()));
+            }
+            let stored = match fs::read_to_string(&snapshot) {
+                Ok(stored) => stored,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    println!("{}", t!("✗ {}: 快照不存在（使用 --update 创建）", file));
+                    changed += 1;
+                    continue;
+                }
+                Err(e) => {
+                    return Err(Error::FileRead {
+                        path: snapshot.display().to_string(),
+                        source: e,
+                    })
+                }
+            };
+
+            if stored == canonical {
+                if !quiet {
+                    println!("{}", t!("✓ {} 与快照一致", file));
+                }
+                continue;
+            }
+
+            println!("{}", t!("✗ {} 与快照不一致", file));
+            let old = codec::parse(&stored, Format::Json)?;
+            for change in diff::diff(&old, &value, &Options::default()) {
+                println!("  {}", colored(&change));
+            }
+            changed += 1;
+        }
+        Ok(changed)
+    })?;
+
+    if changed > 0 {
+        return Err(Error::SnapshotMismatch {
+            changed,
+            total: files.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 配置文件对应的快照路径：`dir/config.yaml` → `dir/__confconv_snapshots__/config.yaml.json`
+fn snapshot_path(file: &str) -> PathBuf {
+    let path = Path::new(file);
+    let name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file.to_string());
+    path.parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join(SNAPSHOT_DIR)
+        .join(format!("{}.json", name))
+}
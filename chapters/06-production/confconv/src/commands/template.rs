@@ -0,0 +1,121 @@
+//! template 命令实现
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::fsutil;
+use crate::merge::{self, Assignment};
+use crate::t;
+use std::error::Error as _;
+use tera::{Context, Tera};
+
+/// 模板文件常见的后缀，推断结果格式时会先去掉
+const TEMPLATE_SUFFIXES: &[&str] = &[".tera", ".j2", ".jinja", ".tmpl", ".tpl"];
+
+/// 渲染之后对结果的处理
+#[derive(Clone, Copy, Debug)]
+pub enum Postprocess {
+    /// 原样输出
+    None,
+    /// 检查语法后原样输出
+    Validate,
+    /// 解析后转换为目标格式
+    Convert { to: Format, pretty: bool },
+}
+
+impl Postprocess {
+    /// 由命令行参数确定处理方式，指定了目标格式时总是转换
+    pub fn new(to: Option<Format>, validate: bool, pretty: bool) -> Self {
+        match to {
+            Some(to) => Postprocess::Convert { to, pretty },
+            None if validate => Postprocess::Validate,
+            None => Postprocess::None,
+        }
+    }
+}
+
+/// 执行模板渲染命令
+///
+/// 按顺序合并所有变量文件并应用 `--set` 覆盖，用结果渲染 Tera 模板，
+/// 合并结果的顶层键即模板中可用的变量。
+/// 需要检查或转换时按结果格式解析渲染结果，
+/// 结果格式依次取 `format`、去掉模板后缀后的扩展名、输出文件的扩展名
+pub fn run(
+    template: &str,
+    vars: &[String],
+    sets: &[Assignment],
+    output: Option<&str>,
+    format: Option<Format>,
+    postprocess: Postprocess,
+    verbose: bool,
+) -> Result<()> {
+    let source = codec::read_text(template)?;
+
+    let mut layers = Vec::with_capacity(vars.len());
+    for path in vars {
+        let (value, format) = codec::read_value(path, None)?;
+        if verbose {
+            eprintln!("{}", t!("变量: {} ({})", path, format.name()));
+        }
+        layers.push(value);
+    }
+    let vars = merge::resolve(layers, sets, &[])?;
+    let context = Context::from_value(vars).map_err(|_| Error::Template {
+        message: t!("合并后的变量顶层必须是对象"),
+    })?;
+
+    let rendered = Tera::one_off(&source, &context, false).map_err(|e| Error::Template {
+        message: describe(&e),
+    })?;
+
+    let content = if let Postprocess::None = postprocess {
+        rendered
+    } else {
+        let format = format
+            .or_else(|| Format::from_extension(strip_template_suffix(template)))
+            .or_else(|| output.and_then(Format::from_extension))
+            .ok_or_else(|| Error::UnknownFormat {
+                path: template.to_string(),
+            })?;
+        if verbose {
+            eprintln!("{}", t!("按 {} 解析渲染结果", format.name()));
+        }
+        let value = codec::parse(&rendered, format)?;
+        match postprocess {
+            Postprocess::Convert { to, pretty } => codec::serialize(&value, to, pretty)?,
+            _ => rendered,
+        }
+    };
+
+    match output {
+        Some(path) => {
+            fsutil::write(path, &content)?;
+            if verbose {
+                eprintln!("{}", t!("已写入: {}", path));
+            }
+        }
+        None => print!("{}", content),
+    }
+
+    Ok(())
+}
+
+/// 去掉模板后缀，`config.yaml.tera` → `config.yaml`
+fn strip_template_suffix(path: &str) -> &str {
+    TEMPLATE_SUFFIXES
+        .iter()
+        .find_map(|suffix| path.strip_suffix(suffix))
+        .unwrap_or(path)
+}
+
+/// Tera 的错误信息分层保存，逐层拼接才能看到具体原因
+fn describe(error: &tera::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
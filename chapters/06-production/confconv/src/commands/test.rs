@@ -0,0 +1,235 @@
+//! test 命令实现
+//!
+//! 断言文件的格式：
+//!
+//! ```yaml
+//! tests:
+//!   - path: server.port
+//!     equals: 8080
+//!   - path: server.host
+//!     type: string
+//!     matches: "^[a-z.-]+$"
+//!   - path: debug
+//!     exists: false
+//!     name: 生产环境不能开启调试
+//! ```
+
+use crate::codec;
+use crate::diagnostic::{self, Diagnostic, Severity};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::path::{self, Path};
+use crate::report::{self, Case, ReportFormat};
+use crate::t;
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use serde_json::Value;
+
+/// 断言文件
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Spec {
+    tests: Vec<Assertion>,
+}
+
+/// 针对一个路径的断言，列出的检查全部通过才算通过，一个检查都没写时检查路径存在
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Assertion {
+    /// 路径表达式
+    path: String,
+    /// 输出时代替路径显示的说明
+    name: Option<String>,
+    /// 路径是否应当存在
+    exists: Option<bool>,
+    /// 期望的值（写 `equals: null` 表示期望为 null）
+    #[serde(default, deserialize_with = "present")]
+    equals: Option<Value>,
+    /// 期望的类型
+    #[serde(rename = "type")]
+    kind: Option<Kind>,
+    /// 字符串值应当匹配的正则表达式
+    matches: Option<String>,
+}
+
+/// 可断言的值类型
+#[derive(Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Null,
+    Object,
+    Array,
+}
+
+impl Kind {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::String(_) => Kind::String,
+            Value::Number(n) if n.is_i64() || n.is_u64() => Kind::Integer,
+            Value::Number(_) => Kind::Number,
+            Value::Bool(_) => Kind::Boolean,
+            Value::Null => Kind::Null,
+            Value::Object(_) => Kind::Object,
+            Value::Array(_) => Kind::Array,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::String => "string",
+            Kind::Number => "number",
+            Kind::Integer => "integer",
+            Kind::Boolean => "boolean",
+            Kind::Null => "null",
+            Kind::Object => "object",
+            Kind::Array => "array",
+        }
+    }
+
+    /// 整数也是数字
+    fn accepts(self, value: &Value) -> bool {
+        let actual = Kind::of(value);
+        actual == self || (self == Kind::Number && actual == Kind::Integer)
+    }
+}
+
+/// 字段出现即为 `Some`，即使值是 null
+fn present<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<Value>, D::Error> {
+    Value::deserialize(deserializer).map(Some)
+}
+
+/// 执行断言测试命令
+///
+/// 逐条检查断言并输出结果，最后给出汇总；有失败的断言时返回错误。
+/// `report` 为 [`ReportFormat::Junit`] 时改为输出 JUnit 报告，每条断言是一个测试用例
+pub fn run(
+    file: &str,
+    format: Option<Format>,
+    spec: &str,
+    report: ReportFormat,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    let (value, _) = codec::read_value(file, format)?;
+    let (spec_value, spec_format) = codec::read_value(spec, None)?;
+    let spec: Spec = serde_json::from_value(spec_value).map_err(|e| Error::Parse {
+        format: spec_format.name(),
+        source: t!("断言文件格式错误: {}", e),
+    })?;
+
+    if verbose {
+        eprintln!("{}", t!("共 {} 条断言", spec.tests.len()));
+    }
+
+    let junit = report == ReportFormat::Junit;
+    let mut failed = 0;
+    let mut cases = Vec::new();
+    for assertion in &spec.tests {
+        let label = assertion.name.as_deref().unwrap_or(&assertion.path);
+        let result = check(&value, assertion);
+        if let Err(reason) = &result {
+            failed += 1;
+            diagnostic::annotate(
+                file,
+                &Diagnostic {
+                    severity: Severity::Error,
+                    code: "test",
+                    location: None,
+                    message: format!("{}: {}", label, reason),
+                },
+            );
+        }
+        match result {
+            _ if junit => cases.push(Case {
+                name: label.to_string(),
+                failures: result.err().into_iter().collect(),
+                output: Vec::new(),
+            }),
+            Ok(()) if !quiet => println!("✓ {}", label),
+            Ok(()) => {}
+            Err(reason) => println!("✗ {}: {}", label, reason),
+        }
+    }
+
+    let total = spec.tests.len();
+    if junit {
+        print!(
+            "{}",
+            report::junit(&format!("confconv.test.{}", file), &cases)
+        );
+    } else if !quiet {
+        println!();
+        println!(
+            "{}",
+            t!(
+                "{} 条断言：{} 条通过，{} 条失败",
+                total,
+                total - failed,
+                failed
+            )
+        );
+    }
+
+    if failed > 0 {
+        return Err(Error::TestsFailed { failed, total });
+    }
+    Ok(())
+}
+
+/// 检查一条断言，失败时返回原因
+fn check(root: &Value, assertion: &Assertion) -> std::result::Result<(), String> {
+    let path = Path::parse(&assertion.path).map_err(|e| e.to_string())?;
+    let value = path::get(root, &path);
+
+    match (assertion.exists, value) {
+        (Some(false), Some(actual)) => return Err(t!("路径不应存在，实际值 {}", compact(actual))),
+        (Some(false), None) => return Ok(()),
+        (_, None) => return Err(t!("路径不存在")),
+        (_, Some(_)) => {}
+    }
+    let value = value.expect("上面已处理路径不存在的情况");
+
+    if let Some(expected) = &assertion.equals {
+        if value != expected {
+            return Err(t!("期望 {}，实际为 {}", compact(expected), compact(value)));
+        }
+    }
+
+    if let Some(kind) = assertion.kind {
+        if !kind.accepts(value) {
+            return Err(t!(
+                "期望类型 {}，实际为 {}",
+                kind.name(),
+                Kind::of(value).name()
+            ));
+        }
+    }
+
+    if let Some(pattern) = &assertion.matches {
+        let regex = Regex::new(pattern).map_err(|e| t!("无效的正则表达式: {}", e))?;
+        match value {
+            Value::String(s) if regex.is_match(s) => {}
+            Value::String(s) => return Err(t!("{} 不匹配 /{}/", format!("{:?}", s), pattern)),
+            other => {
+                return Err(t!(
+                    "期望字符串以匹配 /{}/，实际为 {}",
+                    pattern,
+                    compact(other)
+                ))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 值的单行 JSON 文本
+fn compact(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
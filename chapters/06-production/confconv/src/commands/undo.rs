@@ -0,0 +1,61 @@
+//! undo 命令实现
+
+use crate::error::{Error, Result};
+use crate::fsutil;
+use crate::history;
+use crate::stamp::sha256_hex;
+use crate::t;
+use std::fs;
+use std::path::Path;
+
+/// 执行撤销命令
+///
+/// 把文件恢复到最近一次原地修改之前的内容，并删除这条记录，所以可以连续撤销多次。
+/// 文件在那次修改之后又被改过时拒绝恢复，除非指定 `force`
+pub fn run(file: &str, list: bool, force: bool, verbose: bool) -> Result<()> {
+    let target = Path::new(file);
+    let entries = history::entries(target)?;
+    if list {
+        for entry in entries.iter().rev() {
+            println!(
+                "{}  {}  {} -> {}",
+                entry.time,
+                entry.operation,
+                &entry.before[..12],
+                &entry.after[..12]
+            );
+        }
+        return Ok(());
+    }
+
+    let Some(entry) = entries.last() else {
+        return Err(Error::History {
+            path: file.to_string(),
+            message: t!("没有修改记录"),
+        });
+    };
+    let current = fs::read(target).map_err(|e| Error::FileRead {
+        path: file.to_string(),
+        source: e,
+    })?;
+    if sha256_hex(&current) != entry.after && !force {
+        return Err(Error::History {
+            path: file.to_string(),
+            message: t!(
+                "文件在 {} 的修改之后又被改过（使用 --force 仍然恢复）",
+                entry.time
+            ),
+        });
+    }
+
+    let before = history::before(target, entry)?;
+    fsutil::restore_in_place(file, &before)?;
+    history::remove(target, entry);
+    if verbose {
+        eprintln!(
+            "{}",
+            t!("已撤销 {} 在 {} 的修改", entry.operation, entry.time)
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,47 @@
+//! unescape 命令实现
+//!
+//! escape 的逆操作：把 JSON 字符串字面量解开并还原为结构化配置
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::t;
+use serde_json::Value;
+
+/// 执行反转义命令
+///
+/// 输入是一个 JSON 字符串字面量，解开后按 `from` 解析（不指定则自动识别），
+/// 再输出为目标格式（默认与内嵌内容的格式相同）
+pub fn run(
+    input: &str,
+    from: Option<Format>,
+    to: Option<Format>,
+    pretty: bool,
+    verbose: bool,
+) -> Result<()> {
+    let text = codec::read_text(input)?;
+    let inner = match serde_json::from_str::<Value>(text.trim()) {
+        Ok(Value::String(inner)) => inner,
+        _ => {
+            return Err(Error::Parse {
+                format: "JSON",
+                source: t!("输入不是 JSON 字符串字面量"),
+            })
+        }
+    };
+
+    let (value, detected) = match from {
+        Some(format) => (codec::parse(&inner, format)?, format),
+        None => codec::parse_any(&inner)?,
+    };
+    let to = to.unwrap_or(detected);
+
+    if verbose {
+        eprintln!("{}", t!("内嵌格式: {}", detected.name()));
+        eprintln!("{}", t!("目标格式: {}", to.name()));
+    }
+
+    print!("{}", codec::serialize(&value, to, pretty)?);
+
+    Ok(())
+}
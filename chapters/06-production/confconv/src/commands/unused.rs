@@ -0,0 +1,245 @@
+//! unused 命令实现
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::path::{self, Path, Pattern, Segment};
+use crate::t;
+use regex::Regex;
+use serde_json::Value;
+
+/// `$ref` 展开的最大深度
+const MAX_REF_DEPTH: usize = 16;
+
+/// 应用认识的键
+pub enum Known<'a> {
+    /// 键列表文件：每行一个路径，支持 * 和 **，`#` 开头的行是注释
+    Keys(&'a str),
+    /// JSON Schema：`properties`、`patternProperties` 和 `additionalProperties` 描述的键
+    Schema(&'a str),
+}
+
+/// 执行未使用键分析命令
+///
+/// 列出配置中有、但应用不认识的键。一个键不认识时不再列出它下面的键；
+/// 键列表中的路径同时认识它的上级和下级，如 `server.port` 认识 `server`，`metadata` 认识 `metadata.name`
+pub fn run(
+    file: &str,
+    known: Known,
+    from: Option<Format>,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    let (value, format) = codec::read_value(file, from)?;
+    if verbose {
+        eprintln!("{}", t!("源格式: {}", format.name()));
+    }
+
+    let mut unused = Vec::new();
+    match known {
+        Known::Keys(keys_file) => {
+            let patterns = path::resolve_patterns(&read_keys(keys_file)?, &value);
+            by_keys(&value, &Path::default(), &patterns, &mut unused);
+        }
+        Known::Schema(schema_file) => {
+            let (schema, _) = codec::read_value(schema_file, None)?;
+            let walker = Walker { root: &schema };
+            walker.walk(&value, &[&schema], &Path::default(), &mut unused);
+        }
+    }
+
+    if !quiet {
+        for path in &unused {
+            println!("{}", path);
+        }
+    }
+    if unused.is_empty() {
+        if verbose {
+            eprintln!("{}", t!("没有未使用的键"));
+        }
+        return Ok(());
+    }
+    Err(Error::UnusedKeys {
+        count: unused.len(),
+    })
+}
+
+/// 读取键列表文件
+fn read_keys(file: &str) -> Result<Vec<Pattern>> {
+    codec::read_text(file)?
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(Pattern::parse)
+        .collect()
+}
+
+/// 按键列表查找不认识的键
+fn by_keys(value: &Value, path: &Path, patterns: &[Pattern], unused: &mut Vec<Path>) {
+    let children: Vec<(Path, &Value)> = match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (path.join(Segment::Key(key.clone())), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, child)| (path.join(Segment::Index(index as i64)), child))
+            .collect(),
+        _ => return,
+    };
+    for (path, child) in children {
+        if patterns.iter().any(|pattern| pattern.covers(&path)) {
+            continue;
+        }
+        if patterns
+            .iter()
+            .any(|pattern| pattern.may_match_below(&path))
+        {
+            by_keys(child, &path, patterns, unused);
+        } else {
+            unused.push(path);
+        }
+    }
+}
+
+/// 按 Schema 查找不认识的键
+struct Walker<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Walker<'a> {
+    /// `schemas` 是同时适用于 `value` 的所有 Schema（来自 `allOf`、`anyOf` 等）
+    fn walk(&self, value: &Value, schemas: &[&'a Value], path: &Path, unused: &mut Vec<Path>) {
+        let mut nodes = Vec::new();
+        for schema in schemas {
+            self.expand(schema, 0, &mut nodes);
+        }
+        // 没有一个 Schema 描述下面的键或元素时，认为下面的键都认识
+        nodes.retain(|node| describes_children(node));
+        if nodes.is_empty() {
+            return;
+        }
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let path = path.join(Segment::Key(key.clone()));
+                    let matched = self.property(&nodes, key);
+                    if matched.is_empty() {
+                        unused.push(path);
+                    } else {
+                        self.walk(child, &matched, &path, unused);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let item_schemas: Vec<&Value> =
+                    nodes.iter().filter_map(|node| node.get("items")).collect();
+                if item_schemas.is_empty() {
+                    return;
+                }
+                for (index, item) in items.iter().enumerate() {
+                    let path = path.join(Segment::Index(index as i64));
+                    self.walk(item, &item_schemas, &path, unused);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 展开 `$ref` 和组合关键字，得到所有直接描述这个值的 Schema
+    fn expand(&self, schema: &'a Value, depth: usize, nodes: &mut Vec<&'a Value>) {
+        if depth > MAX_REF_DEPTH {
+            return;
+        }
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            if let Some(target) = reference
+                .strip_prefix('#')
+                .and_then(|pointer| self.root.pointer(pointer))
+            {
+                self.expand(target, depth + 1, nodes);
+            }
+        }
+        for key in ["allOf", "anyOf", "oneOf"] {
+            if let Some(Value::Array(parts)) = schema.get(key) {
+                for part in parts {
+                    self.expand(part, depth + 1, nodes);
+                }
+            }
+        }
+        nodes.push(schema);
+    }
+
+    /// 认识键 `key` 的 Schema
+    fn property(&self, nodes: &[&'a Value], key: &str) -> Vec<&'a Value> {
+        let mut matched = Vec::new();
+        for node in nodes {
+            if let Some(property) = node.get("properties").and_then(|p| p.get(key)) {
+                matched.push(property);
+            }
+            if let Some(Value::Object(patterns)) = node.get("patternProperties") {
+                for (pattern, property) in patterns {
+                    if Regex::new(pattern).is_ok_and(|regex| regex.is_match(key)) {
+                        matched.push(property);
+                    }
+                }
+            }
+            match node.get("additionalProperties") {
+                Some(Value::Bool(false)) | None => {}
+                Some(additional) => matched.push(additional),
+            }
+        }
+        matched
+    }
+}
+
+/// Schema 是否描述了下面的键或元素
+fn describes_children(node: &Value) -> bool {
+    [
+        "properties",
+        "patternProperties",
+        "additionalProperties",
+        "items",
+    ]
+    .iter()
+    .any(|key| node.get(key).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(unused: &[Path]) -> Vec<String> {
+        unused.iter().map(Path::to_string).collect()
+    }
+
+    #[test]
+    fn reports_unknown_keys() {
+        let config = json!({
+            "server": {"port": 80, "legacy": {"a": 1}},
+            "metadata": {"name": "x"},
+            "servers": [{"host": "a", "weight": 1}]
+        });
+
+        let patterns: Vec<Pattern> = ["server.port", "metadata", "servers[*].host"]
+            .iter()
+            .map(|p| Pattern::parse(p).unwrap())
+            .collect();
+        let mut unused = Vec::new();
+        by_keys(&config, &Path::default(), &patterns, &mut unused);
+        assert_eq!(paths(&unused), ["server.legacy", "servers[0].weight"]);
+
+        let schema = json!({
+            "properties": {
+                "server": {"$ref": "#/$defs/server"},
+                "metadata": {"type": "object"},
+                "servers": {"items": {"properties": {"host": {}}}}
+            },
+            "$defs": {"server": {"properties": {"port": {}}}}
+        });
+        let mut unused = Vec::new();
+        Walker { root: &schema }.walk(&config, &[&schema], &Path::default(), &mut unused);
+        assert_eq!(paths(&unused), ["server.legacy", "servers[0].weight"]);
+    }
+}
@@ -1,51 +1,259 @@
 //! validate 命令实现
 
+use crate::codec::{self, YamlDocuments};
+use crate::diagnostic;
 use crate::error::{Error, Result};
 use crate::format::Format;
-use std::fs;
+use crate::i18n::tr;
+use crate::lossy;
+use crate::report::{self, Case, ReportFormat};
+use crate::t;
+use crate::term;
+use clap::builder::PossibleValue;
+use clap::ValueEnum;
+use std::io::{self, BufRead};
+use std::sync::OnceLock;
+
+/// validate 的输入格式：配置文件格式，或者 JSON Lines（每行一个 JSON 值）
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ValidateFormat {
+    /// 整个文件是一个文档
+    Document(Format),
+    /// JSON Lines，每行单独验证
+    Jsonl,
+}
+
+impl ValueEnum for ValidateFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        static ALL: OnceLock<Vec<ValidateFormat>> = OnceLock::new();
+        ALL.get_or_init(|| {
+            Format::value_variants()
+                .iter()
+                .copied()
+                .map(ValidateFormat::Document)
+                .chain([ValidateFormat::Jsonl])
+                .collect()
+        })
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            ValidateFormat::Document(format) => format.to_possible_value(),
+            ValidateFormat::Jsonl => {
+                Some(PossibleValue::new("jsonl").help(tr("JSON Lines：每行一个 JSON 值，逐行验证")))
+            }
+        }
+    }
+}
+
+impl ValidateFormat {
+    /// 从文件路径推断格式，`.jsonl` 为 JSON Lines
+    fn from_path(path: &str) -> Option<Self> {
+        if path.to_lowercase().ends_with(".jsonl") {
+            return Some(ValidateFormat::Jsonl);
+        }
+        Format::from_path(path).map(ValidateFormat::Document)
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ValidateFormat::Document(format) => format.name(),
+            ValidateFormat::Jsonl => "JSON Lines",
+        }
+    }
+}
 
 /// 执行验证命令
-pub fn run(file: &str, format: Option<Format>, verbose: bool, quiet: bool) -> Result<()> {
+///
+/// 逐个验证所有文件，某个文件失败不会中断其余文件的验证；
+/// 每个文件尽量报告所有语法错误，而不只是第一个。
+///
+/// `report` 为 [`ReportFormat::Junit`] 时不输出逐个文件的结果，验证完所有文件后输出 JUnit 报告，
+/// 每个文件是一个测试用例
+pub fn run(
+    files: &[String],
+    format: Option<ValidateFormat>,
+    report: ReportFormat,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    let junit = report == ReportFormat::Junit;
+    let mut failed = 0;
+    let mut cases = Vec::new();
+    for file in files {
+        let mut errors = match validate_file(file, format, verbose, quiet || junit) {
+            Ok(errors) => errors,
+            Err(e) => vec![e],
+        };
+        for e in &errors {
+            // 按级别报告的诊断已经输出过注解
+            if !matches!(e, Error::Denied { .. }) {
+                diagnostic::annotate_error(file, e);
+            }
+        }
+        if junit {
+            cases.push(Case {
+                name: file.clone(),
+                failures: errors.iter().map(|e| e.to_string()).collect(),
+                output: Vec::new(),
+            });
+        } else if files.len() == 1 && errors.len() == 1 {
+            // 只有一个文件且只有一个错误时保持原来的行为：直接返回该错误
+            return Err(errors.remove(0));
+        }
+        if errors.is_empty() {
+            continue;
+        }
+        if !junit {
+            for e in &errors {
+                term::eprint_wrapped(&format!("✗ {}: {}", file, e));
+            }
+        }
+        failed += 1;
+    }
+    if junit {
+        print!("{}", report::junit("confconv.validate", &cases));
+    }
+
+    if failed > 0 {
+        return Err(Error::ValidationFailed {
+            failed,
+            total: files.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// 持续验证标准输入中到达的每条记录，直到输入结束
+///
+/// JSON Lines 每行是一条记录（跳过空行），YAML 以 `---` 分隔文档。
+/// 每条记录读完就立即验证并输出一行结果，适合放在日志或配置流水线旁边检查
+pub fn stream(format: ValidateFormat, quiet: bool) -> Result<()> {
+    let records = match format {
+        ValidateFormat::Jsonl => Records::Lines,
+        ValidateFormat::Document(Format::Yaml) => Records::YamlDocuments,
+        _ => {
+            return Err(Error::Convert {
+                message: t!("--stdin-stream 只支持 jsonl 和 yaml"),
+            })
+        }
+    };
+
+    let (mut total, mut failed) = (0, 0);
+    let mut check = |start: usize, text: &str| {
+        total += 1;
+        let syntax = match records {
+            Records::Lines => Format::Json,
+            Records::YamlDocuments => Format::Yaml,
+        };
+        let errors = codec::check(text, syntax);
+        if errors.is_empty() {
+            if !quiet {
+                println!("{}", t!("✓ -:{}: 第 {} 条记录语法正确", start, total));
+            }
+            return;
+        }
+        failed += 1;
+        for e in errors {
+            term::eprint_wrapped(&t!("✗ -:{}: 第 {} 条记录: {}", start, total, e));
+        }
+    };
+
+    let mut documents = YamlDocuments::default();
+    for (i, line) in io::stdin().lock().lines().enumerate() {
+        let line = line.map_err(|e| Error::FileRead {
+            path: "-".to_string(),
+            source: e,
+        })?;
+        let number = i + 1;
+        match records {
+            Records::Lines if line.trim().is_empty() => {}
+            Records::Lines => check(number, &line),
+            Records::YamlDocuments => {
+                if let Some((start, document)) = documents.push(&line) {
+                    check(start, &document);
+                }
+            }
+        }
+    }
+    if let Some((start, document)) = documents.finish() {
+        check(start, &document);
+    }
+
+    if failed > 0 {
+        return Err(Error::RecordsInvalid { failed, total });
+    }
+    Ok(())
+}
+
+/// 流式输入中记录的划分方式
+#[derive(Clone, Copy)]
+enum Records {
+    /// 每行一条
+    Lines,
+    /// `---` 分隔的 YAML 文档
+    YamlDocuments,
+}
+
+/// 验证单个文件，返回找到的所有语法错误
+fn validate_file(
+    file: &str,
+    format: Option<ValidateFormat>,
+    verbose: bool,
+    quiet: bool,
+) -> Result<Vec<Error>> {
     let format = format
-        .or_else(|| Format::from_extension(file))
+        .or_else(|| ValidateFormat::from_path(file))
         .ok_or_else(|| Error::UnknownFormat {
             path: file.to_string(),
         })?;
 
     if verbose {
-        eprintln!("验证格式: {}", format.name());
+        eprintln!("{}", t!("验证格式: {}", format.name()));
     }
 
-    let content = fs::read_to_string(file).map_err(|e| Error::FileRead {
-        path: file.to_string(),
-        source: e,
-    })?;
-
     // 尝试解析以验证语法
-    match format {
-        Format::Json => {
-            let _: serde_json::Value = serde_json::from_str(&content).map_err(|e| Error::Parse {
-                format: "JSON",
-                source: e.to_string(),
-            })?;
-        }
-        Format::Yaml => {
-            let _: serde_json::Value = serde_yml::from_str(&content).map_err(|e| Error::Parse {
-                format: "YAML",
-                source: e.to_string(),
-            })?;
+    let errors = match format {
+        // 按 --yaml-spec、--toml-version、--json-allow-nan 选择解析器；
+        // 插件格式的内容可能是二进制数据，其他格式都按文本读取
+        ValidateFormat::Document(
+            format @ (Format::Json | Format::Yaml | Format::Toml | Format::Ini),
+        ) => {
+            let text = codec::read_text(file)?;
+            let mut errors = codec::check(&text, format);
+            // 语法正确时再报告转换会丢失的信息
+            if errors.is_empty() {
+                errors.extend(diagnostic::report(file, lossy::check(&text, format)).err());
+            }
+            errors
         }
-        Format::Toml => {
-            let _: toml::Value = toml::from_str(&content).map_err(|e| Error::Parse {
-                format: "TOML",
-                source: e.to_string(),
-            })?;
-        }
-    }
+        ValidateFormat::Document(Format::Plugin(plugin)) => plugin
+            .parse(&codec::read_bytes(file)?)
+            .err()
+            .into_iter()
+            .collect(),
+        ValidateFormat::Jsonl => codec::read_text(file)?
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .flat_map(|(i, line)| {
+                codec::check(line, Format::Json)
+                    .into_iter()
+                    .map(move |e| Error::Parse {
+                        format: "JSON Lines",
+                        source: match e {
+                            Error::Parse { source, .. } => t!("第 {} 行: {}", i + 1, source),
+                            other => t!("第 {} 行: {}", i + 1, other),
+                        },
+                    })
+            })
+            .collect(),
+    };
 
-    if !quiet {
-        println!("✓ {} 语法正确 ({})", file, format.name());
+    if errors.is_empty() && !quiet {
+        println!("{}", t!("✓ {} 语法正确 ({})", file, format.name()));
     }
 
-    Ok(())
+    Ok(errors)
 }
@@ -0,0 +1,83 @@
+//! verify 命令实现
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::signature;
+use crate::stamp::{self, Status};
+use crate::t;
+
+/// 签名校验的参数
+pub struct SignatureCheck<'a> {
+    /// 签名文件路径
+    pub sig: &'a str,
+    /// 可信的公钥（PEM 文件路径），来自 `--key` 或用户配置
+    pub key: Option<&'a str>,
+}
+
+/// 执行校验命令
+///
+/// 指定 `signature` 时校验分离签名，否则检查每个文件末尾的完整性标记，
+/// 发现内容在生成后被修改、缺少标记或签名无效时返回错误
+pub fn run(
+    files: &[String],
+    signature: Option<&SignatureCheck>,
+    verbose: bool,
+    quiet: bool,
+) -> Result<()> {
+    if let Some(check) = signature {
+        let [file] = files else {
+            return Err(Error::Signature {
+                message: t!("使用 --sig 时只能校验一个文件"),
+            });
+        };
+        return verify_signature(file, check, quiet);
+    }
+
+    let mut failed = 0;
+    for file in files {
+        let content = codec::read_text(file)?;
+        if verbose {
+            eprintln!("{}", t!("校验: {}", file));
+        }
+        match stamp::check(&content) {
+            Status::Valid => {
+                if !quiet {
+                    println!("{}", t!("✓ {} 未被修改", file));
+                }
+            }
+            Status::Missing => {
+                eprintln!("{}", t!("✗ {}: 没有完整性标记（生成时使用 --stamp）", file));
+                failed += 1;
+            }
+            Status::Modified => {
+                eprintln!("{}", t!("✗ {}: 内容在生成后被修改过", file));
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        return Err(Error::VerificationFailed {
+            failed,
+            total: files.len(),
+        });
+    }
+    Ok(())
+}
+
+/// 校验分离签名
+fn verify_signature(file: &str, check: &SignatureCheck, quiet: bool) -> Result<()> {
+    let key = check.key.ok_or_else(|| Error::Signature {
+        message: t!("没有可信的公钥：用 --key 指定，或在用户配置的 [signature] 段设置 key"),
+    })?;
+    let (value, _) = codec::read_value(file, None)?;
+    let signature_file = codec::read_text(check.sig)?;
+    let public_key = codec::read_text(key)?;
+
+    signature::verify(&value, &signature_file, &public_key)?;
+
+    if !quiet {
+        println!("{}", t!("✓ {} 签名有效", file));
+    }
+    Ok(())
+}
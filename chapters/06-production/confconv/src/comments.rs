@@ -0,0 +1,595 @@
+//! 用键保存注释
+//!
+//! JSON 没有注释，转换时 YAML、TOML 中的注释都会丢失。`convert --comments-as-keys` 把键上方的注释
+//! 和键所在行末尾的注释存入同一对象中紧挨在它前面的 `"_comment_<键>"` 字段：
+//!
+//! ```text
+//! # 监听端口                 {
+//! port: 8080   # HTTP    →     "_comment_port": "监听端口\nHTTP",
+//!                              "port": 8080
+//!                            }
+//! ```
+//!
+//! 反方向转换时再把这些字段（只限同一对象中确实有对应键的）写回注释，
+//! 写成键上方的注释行；YAML、TOML 和 INI 之间转换时注释直接带过去。
+//! 只保留对象键的注释，数组元素和文件开头与第一个键之间隔着空行的注释不保留
+
+use crate::codec::{self, OutputOptions};
+use crate::dialect;
+use crate::error::Result;
+use crate::format::Format;
+use crate::ini::{self, Line, Repeated};
+use crate::path::{Path, Segment};
+use crate::stamp;
+use serde_json::{Map, Value};
+use serde_yml::libyml::parser::{Event, Parser};
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+/// 保存注释的字段名前缀
+pub const PREFIX: &str = "_comment_";
+
+/// 注释：键的路径和注释文本（多行用换行连接，不含 `#`）
+pub type Comments = Vec<(Path, String)>;
+
+/// 格式是否支持注释
+pub fn supports(format: Format) -> bool {
+    format.capabilities().comments
+}
+
+/// 读取输入中的注释：YAML、TOML、INI 取源文件中的注释，其他格式取出 `_comment_` 字段（同时从值中删除）
+pub fn collect(input: &str, format: Format, value: &mut Value) -> Comments {
+    match format {
+        Format::Yaml => yaml_comments(input),
+        Format::Toml => toml_comments(input),
+        Format::Ini => ini_comments(input),
+        _ => take(value),
+    }
+}
+
+/// 按目标格式序列化并带上注释：JSON 等格式写成 `_comment_` 字段，YAML、TOML、INI 写成注释
+pub fn serialize_bytes(
+    value: &Value,
+    format: Format,
+    style: &OutputOptions,
+    comments: &Comments,
+) -> Result<Vec<u8>> {
+    if comments.is_empty() {
+        return codec::serialize_bytes(value, format, style);
+    }
+    if !supports(format) {
+        let mut value = value.clone();
+        embed(&mut value, comments);
+        return codec::serialize_bytes(&value, format, style);
+    }
+    // 完整性标记要包括注释，所以先不加标记，写入注释后再加
+    let plain = OutputOptions {
+        stamp: false,
+        ..style.clone()
+    };
+    let text = codec::serialize_with(value, format, &plain)?;
+    let text = match format {
+        Format::Yaml => insert_yaml(&text, comments),
+        Format::Ini => insert_ini(&text, comments),
+        _ => insert_toml(&text, comments),
+    };
+    if style.stamp && stamp::supports(format) {
+        return Ok(stamp::apply(&text).into_bytes());
+    }
+    Ok(text.into_bytes())
+}
+
+/// 取出并删除所有 `_comment_<键>` 字段（同一对象中有对应的键，且值是字符串）
+pub fn take(value: &mut Value) -> Comments {
+    let mut comments = Vec::new();
+    take_at(value, &Path::default(), &mut comments);
+    comments
+}
+
+fn take_at(value: &mut Value, path: &Path, comments: &mut Comments) {
+    match value {
+        Value::Object(map) => {
+            let fields: Vec<String> = map
+                .iter()
+                .filter(|(field, text)| {
+                    text.is_string()
+                        && field
+                            .strip_prefix(PREFIX)
+                            .is_some_and(|key| map.contains_key(key))
+                })
+                .map(|(field, _)| field.clone())
+                .collect();
+            for field in fields {
+                if let Some(Value::String(text)) = map.shift_remove(&field) {
+                    let key = field[PREFIX.len()..].to_string();
+                    comments.push((path.join(Segment::Key(key)), text));
+                }
+            }
+            for (key, child) in map.iter_mut() {
+                take_at(child, &path.join(Segment::Key(key.clone())), comments);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                take_at(item, &path.join(Segment::Index(index as i64)), comments);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 把注释写成紧挨在键前面的 `_comment_<键>` 字段，找不到的键和已经有注释字段的键跳过
+pub fn embed(value: &mut Value, comments: &Comments) {
+    for (path, text) in comments {
+        let Some((Segment::Key(key), parent)) = path.segments().split_last() else {
+            continue;
+        };
+        let Some(map) = object_mut(value, parent) else {
+            continue;
+        };
+        let field = format!("{}{}", PREFIX, key);
+        if map.contains_key(&field) {
+            continue;
+        }
+        if let Some(index) = map.keys().position(|k| k == key) {
+            map.shift_insert(index, field, Value::String(text.clone()));
+        }
+    }
+}
+
+/// 按路径找到对象
+fn object_mut<'a>(
+    value: &'a mut Value,
+    segments: &[Segment],
+) -> Option<&'a mut Map<String, Value>> {
+    let mut current = value;
+    for segment in segments {
+        current = match (segment, current) {
+            (Segment::Key(key), Value::Object(map)) => map.get_mut(key)?,
+            (Segment::Index(index), Value::Array(items)) => {
+                items.get_mut(usize::try_from(*index).ok()?)?
+            }
+            _ => return None,
+        };
+    }
+    current.as_object_mut()
+}
+
+/// 紧挨在某行上方的注释块，`above` 从近到远给出上方的各行；遇到空行或其他内容为止
+fn comment_block<'a>(above: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut lines: Vec<String> = above
+        .map_while(|line| line.trim().strip_prefix('#').map(uncomment))
+        .collect();
+    lines.reverse();
+    lines
+}
+
+/// 去掉 `#` 之后的一个空格
+fn uncomment(text: &str) -> String {
+    text.strip_prefix(' ')
+        .unwrap_or(text)
+        .trim_end()
+        .to_string()
+}
+
+/// 把注释文本写成注释行
+fn comment_lines(text: &str, indent: &str) -> String {
+    text.lines()
+        .map(|line| {
+            if line.is_empty() {
+                format!("{}#\n", indent)
+            } else {
+                format!("{}# {}\n", indent, line)
+            }
+        })
+        .collect()
+}
+
+/// YAML 中每个键的路径和位置（行、列都从 0 开始），只处理单文档
+fn yaml_keys(input: &str) -> Vec<(Path, usize, usize)> {
+    /// 正在读取的集合
+    enum Frame {
+        /// 映射：当前的键，下一个节点是否是键
+        Mapping(Option<String>, bool),
+        /// 序列：当前元素的下标
+        Sequence(usize),
+    }
+
+    fn current(stack: &[Frame]) -> Path {
+        stack
+            .iter()
+            .fold(Path::default(), |path, frame| match frame {
+                Frame::Mapping(Some(key), _) => path.join(Segment::Key(key.clone())),
+                Frame::Sequence(index) => path.join(Segment::Index(*index as i64)),
+                Frame::Mapping(None, _) => path,
+            })
+    }
+
+    /// 一个节点读完，父集合前进到下一个键或元素
+    fn advance(stack: &mut [Frame]) {
+        match stack.last_mut() {
+            Some(Frame::Mapping(_, next_is_key)) => *next_is_key = true,
+            Some(Frame::Sequence(index)) => *index += 1,
+            None => {}
+        }
+    }
+
+    let mut keys = Vec::new();
+    let mut stack: Vec<Frame> = Vec::new();
+    let mut documents = 0;
+    let mut parser = Parser::new(Cow::Borrowed(input.as_bytes()));
+    while let Ok((event, mark)) = parser.parse_next_event() {
+        let expecting_key = matches!(stack.last(), Some(Frame::Mapping(_, true)));
+        match event {
+            Event::StreamEnd => break,
+            Event::DocumentStart => {
+                documents += 1;
+                if documents > 1 {
+                    return Vec::new();
+                }
+            }
+            Event::MappingStart(_) | Event::SequenceStart(_) => {
+                // 复杂键（集合作为键）不处理，把它当作普通节点跳过
+                if let Some(Frame::Mapping(key, next_is_key @ true)) = stack.last_mut() {
+                    *key = None;
+                    *next_is_key = false;
+                }
+                stack.push(match event {
+                    Event::MappingStart(_) => Frame::Mapping(None, true),
+                    _ => Frame::Sequence(0),
+                });
+            }
+            Event::MappingEnd | Event::SequenceEnd => {
+                stack.pop();
+                advance(&mut stack);
+            }
+            Event::Scalar(scalar) if expecting_key => {
+                let key = String::from_utf8_lossy(&scalar.value).into_owned();
+                let path = current(&stack[..stack.len() - 1]).join(Segment::Key(key.clone()));
+                keys.push((path, mark.line() as usize, mark.column() as usize));
+                if let Some(Frame::Mapping(current, next_is_key)) = stack.last_mut() {
+                    *current = Some(key);
+                    *next_is_key = false;
+                }
+            }
+            Event::Scalar(_) | Event::Alias(_) => advance(&mut stack),
+            _ => {}
+        }
+    }
+    keys
+}
+
+/// YAML 中键的注释
+fn yaml_comments(input: &str) -> Comments {
+    let lines: Vec<&str> = input.lines().collect();
+    yaml_keys(input)
+        .into_iter()
+        .filter_map(|(path, line, column)| {
+            let mut text = comment_block(lines[..line].iter().rev().copied());
+            text.extend(lines.get(line).and_then(|l| yaml_trailing(l, column)));
+            (!text.is_empty()).then(|| (path, text.join("\n")))
+        })
+        .collect()
+}
+
+/// YAML 行末的注释：从 `column` 列开始，引号外前面是空白的 `#`
+fn yaml_trailing(line: &str, column: usize) -> Option<String> {
+    let mut quote: Option<char> = None;
+    let mut previous = ' ';
+    let mut chars = line.char_indices().skip(column).peekable();
+    while let Some((offset, c)) = chars.next() {
+        match quote {
+            Some('"') if c == '\\' => {
+                chars.next();
+            }
+            Some('\'') if c == '\'' && chars.peek().map(|&(_, next)| next) == Some('\'') => {
+                chars.next();
+            }
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if (c == '"' || c == '\'') && " \t:[{,".contains(previous) => quote = Some(c),
+            None if c == '#' && previous.is_whitespace() => {
+                return Some(uncomment(&line[offset + 1..]));
+            }
+            None => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// 把注释写到 YAML 文本中键的上方，缩进与键所在行相同
+pub(crate) fn insert_yaml(text: &str, comments: &Comments) -> String {
+    let mut by_line: BTreeMap<usize, Vec<&str>> = BTreeMap::new();
+    for (path, line, _) in yaml_keys(text) {
+        if let Some((_, comment)) = comments.iter().find(|(p, _)| *p == path) {
+            by_line.entry(line).or_default().push(comment);
+        }
+    }
+    let mut output = String::with_capacity(text.len());
+    for (number, line) in text.split_inclusive('\n').enumerate() {
+        if let Some(comments) = by_line.get(&number) {
+            let indent = &line[..line.len() - line.trim_start().len()];
+            for comment in comments {
+                output.push_str(&comment_lines(comment, indent));
+            }
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+/// TOML 中键和表头的注释；使用 TOML 1.1 语法时读不出注释
+fn toml_comments(input: &str) -> Comments {
+    let Ok(document) = input.parse::<toml_edit::DocumentMut>() else {
+        return Vec::new();
+    };
+    let mut comments = Vec::new();
+    toml_table_comments(document.as_table(), &Path::default(), &mut comments);
+    comments
+}
+
+fn toml_table_comments(table: &toml_edit::Table, path: &Path, comments: &mut Comments) {
+    use toml_edit::Item;
+
+    for (key, item) in table.iter() {
+        let path = path.join(Segment::Key(key.to_string()));
+        let decor = match item {
+            Item::Value(value) => {
+                let key_decor = table.key(key).map(|key| key.leaf_decor());
+                Some((
+                    key_decor.and_then(|decor| decor.prefix()),
+                    value.decor().suffix(),
+                ))
+            }
+            Item::Table(sub) if !sub.is_implicit() => {
+                Some((sub.decor().prefix(), sub.decor().suffix()))
+            }
+            Item::ArrayOfTables(tables) => tables
+                .get(0)
+                .map(|first| (first.decor().prefix(), first.decor().suffix())),
+            _ => None,
+        };
+        if let Some((prefix, suffix)) = decor {
+            let prefix = prefix.and_then(|raw| raw.as_str()).unwrap_or_default();
+            // 前缀的最后一段是键所在行的缩进
+            let above = &prefix[..prefix.rfind('\n').map_or(0, |end| end + 1)];
+            let mut text = comment_block(above.lines().rev());
+            let suffix = suffix.and_then(|raw| raw.as_str()).unwrap_or_default();
+            text.extend(suffix.trim().strip_prefix('#').map(uncomment));
+            if !text.is_empty() {
+                comments.push((path.clone(), text.join("\n")));
+            }
+        }
+        match item {
+            Item::Table(sub) => toml_table_comments(sub, &path, comments),
+            Item::ArrayOfTables(tables) => {
+                for (index, sub) in tables.iter().enumerate() {
+                    toml_table_comments(sub, &path.join(Segment::Index(index as i64)), comments);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// 把注释写到 TOML 文本中键或表头的上方；内联表中的键写不了注释，跳过
+fn insert_toml(text: &str, comments: &Comments) -> String {
+    let Ok(mut document) = text.parse::<toml_edit::DocumentMut>() else {
+        return text.to_string();
+    };
+    for (path, comment) in comments {
+        decorate_toml(document.as_table_mut(), path.segments(), comment);
+    }
+    document.to_string()
+}
+
+fn decorate_toml(table: &mut toml_edit::Table, segments: &[Segment], comment: &str) {
+    use toml_edit::{Decor, Item};
+
+    fn prepend(decor: &mut Decor, comment: &str) {
+        let prefix = decor
+            .prefix()
+            .and_then(|raw| raw.as_str())
+            .unwrap_or_default()
+            .to_string();
+        decor.set_prefix(format!("{}{}", prefix, comment_lines(comment, "")));
+    }
+
+    let Some((Segment::Key(key), rest)) = segments.split_first() else {
+        return;
+    };
+    match (table.get_mut(key), rest) {
+        (Some(Item::Value(_)), []) => {
+            if let Some(mut key) = table.key_mut(key) {
+                prepend(key.leaf_decor_mut(), comment);
+            }
+        }
+        (Some(Item::Table(sub)), []) if !sub.is_implicit() => prepend(sub.decor_mut(), comment),
+        (Some(Item::ArrayOfTables(tables)), []) => {
+            if let Some(first) = tables.get_mut(0) {
+                prepend(first.decor_mut(), comment);
+            }
+        }
+        (Some(Item::Table(sub)), rest) => decorate_toml(sub, rest, comment),
+        (Some(Item::ArrayOfTables(tables)), [Segment::Index(index), rest @ ..]) => {
+            if let Some(sub) = usize::try_from(*index).ok().and_then(|i| tables.get_mut(i)) {
+                decorate_toml(sub, rest, comment);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// INI 中每个键和段所在的行（从 0 开始）和路径，有语法错误时为空
+fn ini_keys(input: &str) -> Vec<(usize, Path)> {
+    let dialect = dialect::current().ini;
+    let mut keys = Vec::new();
+    let mut section = Path::default();
+    for (number, line) in input.lines().enumerate() {
+        match ini::classify(line, &dialect) {
+            Ok(Line::Section(names)) => {
+                section = names
+                    .into_iter()
+                    .fold(Path::default(), |path, name| path.join(Segment::Key(name)));
+                keys.push((number, section.clone()));
+            }
+            Ok(Line::Entry(key, _)) => {
+                let key = match dialect.repeated {
+                    Repeated::Brackets => key.strip_suffix("[]").map_or(key, str::trim_end),
+                    _ => key,
+                };
+                keys.push((number, section.join(Segment::Key(key.to_string()))))
+            }
+            Ok(_) => {}
+            Err(_) => return Vec::new(),
+        }
+    }
+    keys
+}
+
+/// INI 中键和段上方紧挨着的注释（注释符号由 `--ini-dialect` 决定）
+fn ini_comments(input: &str) -> Comments {
+    let dialect = dialect::current().ini;
+    let lines: Vec<&str> = input.lines().collect();
+    let mut comments: Comments = Vec::new();
+    for (number, path) in ini_keys(input) {
+        let mut text: Vec<String> = lines[..number]
+            .iter()
+            .rev()
+            .map_while(|line| match ini::classify(line, &dialect) {
+                Ok(Line::Comment(text)) => Some(uncomment(text)),
+                _ => None,
+            })
+            .collect();
+        text.reverse();
+        // 同一个段出现多次时只取第一次的注释
+        if !text.is_empty() && !comments.iter().any(|(p, _)| *p == path) {
+            comments.push((path, text.join("\n")));
+        }
+    }
+    comments
+}
+
+/// 在 INI 文本中键和段的上方插入注释
+fn insert_ini(text: &str, comments: &Comments) -> String {
+    let mut by_line: BTreeMap<usize, &str> = BTreeMap::new();
+    let mut seen: Vec<Path> = Vec::new();
+    for (number, path) in ini_keys(text) {
+        // 写成重复键的数组只在第一行上方写注释
+        if seen.contains(&path) {
+            continue;
+        }
+        seen.push(path.clone());
+        if let Some((_, comment)) = comments.iter().find(|(p, _)| *p == path) {
+            by_line.insert(number, comment);
+        }
+    }
+    let marker = dialect::current().ini.comment_char();
+    let mut output = String::with_capacity(text.len());
+    for (number, line) in text.split_inclusive('\n').enumerate() {
+        if let Some(comment) = by_line.get(&number) {
+            for line in comment.lines() {
+                output.push(marker);
+                if !line.is_empty() {
+                    output.push(' ');
+                    output.push_str(line);
+                }
+                output.push('\n');
+            }
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn path(text: &str) -> Path {
+        Path::parse(text).unwrap()
+    }
+
+    #[test]
+    fn reads_yaml_comments() {
+        let input = "# 服务\nserver:\n  # 端口\n  # 默认 80\n  port: 80  # HTTP\n\n  host: 'a#b' # 主机\nitems:\n  - name: x # 名称\n";
+        assert_eq!(
+            yaml_comments(input),
+            vec![
+                (path("server"), "服务".to_string()),
+                (path("server.port"), "端口\n默认 80\nHTTP".to_string()),
+                (path("server.host"), "主机".to_string()),
+                (path("items[0].name"), "名称".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_toml_comments() {
+        let input = "# 标题\ntitle = \"t\" # 行末\n\n# 服务\n[server]\nport = 80\n\n# 项目\n[[items]]\n# 名称\nname = \"x\"\n";
+        assert_eq!(
+            toml_comments(input),
+            vec![
+                (path("title"), "标题\n行末".to_string()),
+                (path("server"), "服务".to_string()),
+                (path("items"), "项目".to_string()),
+                (path("items[0].name"), "名称".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn comment_keys_round_trip() {
+        let comments = vec![
+            (path("server.port"), "端口".to_string()),
+            (path("missing"), "无".to_string()),
+        ];
+        let mut value = json!({"server": {"host": "h", "port": 80}});
+        embed(&mut value, &comments);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"server":{"host":"h","_comment_port":"端口","port":80}}"#
+        );
+        assert_eq!(
+            take(&mut value),
+            vec![(path("server.port"), "端口".to_string())]
+        );
+        assert_eq!(value, json!({"server": {"host": "h", "port": 80}}));
+    }
+
+    #[test]
+    fn writes_comments() {
+        let comments = vec![
+            (path("server"), "服务".to_string()),
+            (path("server.port"), "端口\n默认 80".to_string()),
+        ];
+        assert_eq!(
+            insert_yaml("server:\n  host: h\n  port: 80\n", &comments),
+            "# 服务\nserver:\n  host: h\n  # 端口\n  # 默认 80\n  port: 80\n"
+        );
+        assert_eq!(
+            insert_toml("[server]\nhost = \"h\"\nport = 80\n", &comments),
+            "# 服务\n[server]\nhost = \"h\"\n# 端口\n# 默认 80\nport = 80\n"
+        );
+    }
+
+    #[test]
+    fn reads_and_writes_ini_comments() {
+        let input = "; 标题\nname = app\n\n# 服务\n[server]\n; 端口\nport = 80\n";
+        let comments = ini_comments(input);
+        assert_eq!(
+            comments,
+            vec![
+                (path("name"), "标题".to_string()),
+                (path("server"), "服务".to_string()),
+                (path("server.port"), "端口".to_string()),
+            ]
+        );
+        assert_eq!(
+            insert_ini("name = app\n\n[server]\nport = 80\n", &comments),
+            "# 标题\nname = app\n\n# 服务\n[server]\n# 端口\nport = 80\n"
+        );
+    }
+}
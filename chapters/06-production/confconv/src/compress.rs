@@ -0,0 +1,140 @@
+//! 压缩输入
+//!
+//! 按扩展名识别压缩文件并在读取时解压，内层格式由去掉压缩扩展名后的扩展名决定，
+//! 例如 `config.json.gz` 按 JSON 解析。解压后的内容超过 [`MAX_SIZE`] 时报错，
+//! 防止很小的压缩炸弹耗尽内存
+
+use crate::t;
+use std::io::{self, Read, Write};
+
+/// 解压后内容的默认上限
+pub const MAX_SIZE: u64 = 1 << 30;
+
+/// 支持的压缩格式
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    /// `.gz`
+    Gzip,
+    /// `.zst`
+    Zstd,
+    /// `.xz`
+    Xz,
+}
+
+impl Compression {
+    /// 所有支持的压缩格式
+    pub const ALL: [Compression; 3] = [Compression::Gzip, Compression::Zstd, Compression::Xz];
+
+    /// 从文件扩展名识别压缩格式
+    pub fn from_path(path: &str) -> Option<Self> {
+        let ext = path.rsplit_once('.')?.1.to_lowercase();
+        Self::ALL
+            .into_iter()
+            .find(|compression| compression.extensions().contains(&ext.as_str()))
+    }
+
+    /// 识别的扩展名（不含点）
+    pub fn extensions(self) -> &'static [&'static str] {
+        match self {
+            Compression::Gzip => &["gz"],
+            Compression::Zstd => &["zst", "zstd"],
+            Compression::Xz => &["xz"],
+        }
+    }
+
+    /// 压缩格式名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+            Compression::Xz => "xz",
+        }
+    }
+
+    /// 解压全部内容，解压后超过 `limit` 字节时报错
+    pub fn decompress(self, bytes: &[u8], limit: u64) -> io::Result<Vec<u8>> {
+        let mut out = Limited {
+            out: Vec::new(),
+            limit,
+        };
+        match self {
+            Compression::Gzip => {
+                io::copy(
+                    &mut flate2::read::MultiGzDecoder::new(bytes).take(limit + 1),
+                    &mut out,
+                )?;
+            }
+            Compression::Zstd => {
+                let decoder = ruzstd::decoding::StreamingDecoder::new(bytes)
+                    .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+                io::copy(&mut decoder.take(limit + 1), &mut out)?;
+            }
+            Compression::Xz => {
+                lzma_rs::xz_decompress(&mut io::BufReader::new(bytes), &mut out).map_err(|e| {
+                    match e {
+                        lzma_rs::error::Error::IoError(e) => e,
+                        e => io::Error::new(io::ErrorKind::InvalidData, e.to_string()),
+                    }
+                })?;
+            }
+        }
+        Ok(out.out)
+    }
+}
+
+/// 超过上限时报错的输出缓冲区
+struct Limited {
+    out: Vec<u8>,
+    limit: u64,
+}
+
+impl Write for Limited {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if (self.out.len() + buf.len()) as u64 > self.limit {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                t!("解压后的内容超过 {} 字节", self.limit),
+            ));
+        }
+        self.out.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// 去掉压缩扩展名，`config.yaml.zst` → `config.yaml`
+pub fn strip_extension(path: &str) -> &str {
+    match Compression::from_path(path) {
+        Some(_) => path.rsplit_once('.').map_or(path, |(stem, _)| stem),
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decompression_stops_at_the_limit() {
+        let mut xz = Vec::new();
+        lzma_rs::xz_compress(&mut &[0u8; 4096][..], &mut xz).unwrap();
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gzip.write_all(&[0u8; 4096]).unwrap();
+        for (compression, bytes) in [
+            (Compression::Xz, xz),
+            (Compression::Gzip, gzip.finish().unwrap()),
+        ] {
+            assert_eq!(compression.decompress(&bytes, 4096).unwrap().len(), 4096);
+            let error = compression.decompress(&bytes, 1024).unwrap_err();
+            assert_eq!(
+                error.kind(),
+                io::ErrorKind::InvalidData,
+                "{}",
+                compression.name()
+            );
+        }
+    }
+}
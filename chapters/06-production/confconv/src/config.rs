@@ -0,0 +1,381 @@
+//! 配置文件
+//!
+//! 配置来自两个文件，都不存在时使用默认配置：
+//! - 用户配置：`$CONFCONV_CONFIG`，没有设置时为平台配置目录下的 `config.toml`
+//!   （Linux 上是 `$XDG_CONFIG_HOME/confconv/config.toml` 或 `~/.config/confconv/config.toml`，
+//!   其他平台见 [`dirs`](crate::dirs)）
+//! - 项目配置：从当前目录向上查找最近的 `.confconv.toml`，到仓库根目录（包含 `.git` 的目录）为止
+//!
+//! 项目配置中的值覆盖用户配置中的同名值（段按键合并，数组整个替换），
+//! 所以项目可以在 `[profile.default]` 中约定格式化的参数，在项目中任何位置运行时自动生效。
+//! 项目配置随仓库分发，不能设置 `[hooks]`、`[signature]` 和 `[transform]` 段的 `encrypt`：
+//!
+//! ```toml
+//! [transform]
+//! # 每次 convert/format 都会运行的 WASM 变换模块，相对路径相对于配置文件所在目录
+//! wasm = ["normalize.wasm"]
+//! # 每次 convert/format 都会应用的输出规则文件，见 [`rules`](crate::rules)
+//! rules = "compliance.yaml"
+//! # 输出规则中 encrypt 使用的加密命令，从标准输入读取明文，输出密文
+//! encrypt = "age -r age1... -a"
+//!
+//! [hooks]
+//! # 写入文件前后执行的命令，见 [`hooks`](crate::hooks)
+//! post_convert = ["git add \"$CONFCONV_FILE\""]
+//!
+//! [history]
+//! # 每个文件保留的原地修改记录数，见 [`history`](crate::history)
+//! keep = 50
+//!
+//! [cache]
+//! # watch 和 serve 缓存的解析结果数，见 [`cache`](crate::cache)
+//! entries = 256
+//!
+//! [signature]
+//! # verify 没有指定 --key 时信任的公钥（只能写在用户配置中）
+//! key = "keys/release.pub.pem"
+//!
+//! [profile.ci]
+//! # 用 --profile ci 选用的一组参数，见 [`profile`](crate::profile)
+//! deny = ["warnings"]
+//!
+//! [profile.default]
+//! # 没有指定 --profile 时使用的参数
+//! indent = 4
+//!
+//! [[budget]]
+//! # check 命令对匹配文件的大小限制，见 [`budget`](crate::budget)
+//! pattern = "k8s/**/*.yaml"
+//! max_size = "1MB"
+//!
+//! [[naming]]
+//! # lint 命令对匹配文件的键名规范，见 [`lint`](crate::lint)
+//! pattern = "*.toml"
+//! case = "snake"
+//! ```
+
+use crate::dirs;
+use crate::error::{Error, Result};
+use crate::lint::KeyCase;
+use crate::profile::Profile;
+use crate::t;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 项目配置的文件名
+pub const PROJECT_FILE: &str = ".confconv.toml";
+
+/// 没有指定 `--profile` 时使用的配置档
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 合并后的用户配置和项目配置
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// 值变换的默认设置
+    pub transform: TransformConfig,
+    /// 钩子命令
+    pub hooks: HooksConfig,
+    /// 原地修改的历史
+    pub history: HistoryConfig,
+    /// watch 和 serve 的解析结果缓存
+    pub cache: CacheConfig,
+    /// 签名校验
+    pub signature: SignatureConfig,
+    /// 用 `--profile` 选用的配置档
+    pub profile: BTreeMap<String, Profile>,
+    /// check 命令按文件模式使用的大小限制
+    pub budget: Vec<BudgetRule>,
+    /// lint 命令按文件模式使用的键名规范
+    pub naming: Vec<NamingRule>,
+    /// 读取的配置文件，先用户配置后项目配置
+    #[serde(skip)]
+    pub sources: Vec<PathBuf>,
+    /// 每个配置档最后在哪个文件中定义，用于错误信息
+    #[serde(skip)]
+    profile_sources: BTreeMap<String, PathBuf>,
+}
+
+/// `[transform]` 段
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TransformConfig {
+    /// 在命令行指定的模块之前运行的 WASM 变换模块
+    pub wasm: Vec<PathBuf>,
+    /// 没有指定 `--rules` 时使用的输出规则文件
+    pub rules: Option<PathBuf>,
+    /// 输出规则中 `encrypt` 使用的加密命令（只能写在用户配置中）
+    pub encrypt: Option<String>,
+}
+
+/// `[hooks]` 段，每个钩子是按顺序执行的 shell 命令
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HooksConfig {
+    /// 写入文件之前执行，失败时不写入
+    pub pre_write: Vec<String>,
+    /// convert 写出每个结果之后执行
+    pub post_convert: Vec<String>,
+}
+
+/// `[history]` 段
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// 每个文件保留的修改记录数，0 表示不记录
+    pub keep: usize,
+}
+
+/// `[cache]` 段
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// 内存中保留的解析结果数，0 表示不缓存
+    pub entries: usize,
+    /// 同时缓存到磁盘上（缓存目录下的 `parse/`）
+    pub disk: bool,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        CacheConfig {
+            entries: 256,
+            disk: false,
+        }
+    }
+}
+
+/// `[signature]` 段
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignatureConfig {
+    /// verify 没有指定 `--key` 时信任的公钥（PKCS#8 PEM 文件）
+    pub key: Option<PathBuf>,
+}
+
+/// `[[budget]]` 段中的一条规则
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BudgetRule {
+    /// 匹配文件路径的 glob
+    pub pattern: String,
+    /// 字节数上限，如 `64KB`、`1MiB`
+    pub max_size: Option<String>,
+    /// 键的总数上限
+    pub max_keys: Option<usize>,
+    /// 嵌套层数上限
+    pub max_depth: Option<usize>,
+}
+
+/// `[[naming]]` 段中的一条规则，`case` 和 `regex` 只能设置一个
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NamingRule {
+    /// 匹配文件路径的 glob
+    pub pattern: String,
+    /// 键名的大小写风格
+    pub case: Option<KeyCase>,
+    /// 键名必须匹配的正则表达式
+    pub regex: Option<String>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        HistoryConfig { keep: 20 }
+    }
+}
+
+impl Config {
+    /// 用户配置文件的位置，无法确定主目录时返回 None
+    pub fn path() -> Option<PathBuf> {
+        if let Some(path) = env::var_os("CONFCONV_CONFIG").filter(|p| !p.is_empty()) {
+            return Some(PathBuf::from(path));
+        }
+        Some(dirs::config_dir()?.join("config.toml"))
+    }
+
+    /// 从 `start` 向上查找项目配置，经过包含 `.git` 的目录后停止
+    pub fn discover(start: &Path) -> Option<PathBuf> {
+        let mut dir = start.to_path_buf();
+        loop {
+            let candidate = dir.join(PROJECT_FILE);
+            if candidate.is_file() {
+                return Some(candidate);
+            }
+            if dir.join(".git").exists() || !dir.pop() {
+                return None;
+            }
+        }
+    }
+
+    /// 按名称取出配置档和定义它的文件，没有这个配置档时报错
+    pub fn profile(&self, name: &str) -> Result<(&Profile, &Path)> {
+        match (self.profile.get(name), self.profile_sources.get(name)) {
+            (Some(profile), Some(source)) => Ok((profile, source)),
+            _ => Err(Error::Config {
+                path: self
+                    .sources
+                    .last()
+                    .cloned()
+                    .or_else(Config::path)
+                    .map(|path| path.display().to_string())
+                    .unwrap_or_default(),
+                message: t!("没有名为 '{}' 的配置档", name),
+            }),
+        }
+    }
+
+    /// 读取并合并用户配置和项目配置
+    ///
+    /// 这时还没有确定详细程度（配置档可以设置 `verbose`），由调用者按 [`Config::sources`] 输出读取了哪些文件
+    pub fn load() -> Result<Config> {
+        let user = Config::path();
+        let project = env::current_dir()
+            .ok()
+            .and_then(|dir| Config::discover(&dir))
+            .filter(|project| Some(project) != user.as_ref());
+        let mut merged = toml::Table::new();
+        let mut sources = Vec::new();
+        let mut profile_sources = BTreeMap::new();
+        for (path, is_project) in [(user, false), (project, true)] {
+            let Some(path) = path else {
+                continue;
+            };
+            let Some(table) = read_table(&path, is_project)? else {
+                continue;
+            };
+            if let Some(toml::Value::Table(profiles)) = table.get("profile") {
+                for name in profiles.keys() {
+                    profile_sources.insert(name.clone(), path.clone());
+                }
+            }
+            merge(&mut merged, table);
+            sources.push(path);
+        }
+        let mut config: Config =
+            toml::Value::Table(merged)
+                .try_into()
+                .map_err(|e: toml::de::Error| Error::Config {
+                    path: sources
+                        .last()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_default(),
+                    message: e.message().to_string(),
+                })?;
+        config.sources = sources;
+        config.profile_sources = profile_sources;
+        Ok(config)
+    }
+}
+
+/// 读取一个配置文件并检查内容，WASM 模块、规则文件和公钥的相对路径换成相对于配置文件所在目录；文件不存在时返回 None
+fn read_table(path: &Path, is_project: bool) -> Result<Option<toml::Table>> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(Error::FileRead {
+                path: path.display().to_string(),
+                source: e,
+            })
+        }
+    };
+    let invalid = |message: String| Error::Config {
+        path: path.display().to_string(),
+        message,
+    };
+    let mut table: toml::Table =
+        toml::from_str(&text).map_err(|e| invalid(e.message().to_string()))?;
+    toml::Value::Table(table.clone())
+        .try_into::<Config>()
+        .map_err(|e| invalid(e.message().to_string()))?;
+    // 项目配置来自仓库，不能让克隆下来的仓库在本机执行命令
+    if is_project && table.contains_key("hooks") {
+        return Err(invalid(t!("项目配置不能设置钩子，钩子只能写在用户配置中")));
+    }
+    // 公钥决定接受谁的签名，随仓库分发的配置不能指定
+    if is_project && table.contains_key("signature") {
+        return Err(invalid(t!(
+            "项目配置不能设置可信的公钥，公钥只能写在用户配置中"
+        )));
+    }
+    let transform = table.get("transform").and_then(toml::Value::as_table);
+    if is_project && transform.is_some_and(|transform| transform.contains_key("encrypt")) {
+        return Err(invalid(t!(
+            "项目配置不能设置加密命令，加密命令只能写在用户配置中"
+        )));
+    }
+    let dir = path.parent().unwrap_or(Path::new(""));
+    if let Some(toml::Value::String(rules)) = table
+        .get_mut("transform")
+        .and_then(|transform| transform.get_mut("rules"))
+    {
+        *rules = dir.join(&*rules).display().to_string();
+    }
+    if let Some(toml::Value::String(key)) = table
+        .get_mut("signature")
+        .and_then(|signature| signature.get_mut("key"))
+    {
+        *key = dir.join(&*key).display().to_string();
+    }
+    if let Some(toml::Value::Array(modules)) = table
+        .get_mut("transform")
+        .and_then(|transform| transform.get_mut("wasm"))
+    {
+        for module in modules {
+            if let toml::Value::String(module) = module {
+                *module = dir.join(&*module).display().to_string();
+            }
+        }
+    }
+    Ok(Some(table))
+}
+
+/// 把 `overlay` 合并到 `base`：段按键递归合并，其他值整个替换
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(overlay)) => merge(base, overlay),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn discovery_stops_at_the_repository_root() {
+        let root = env::temp_dir().join(format!("confconv-discover-{}", std::process::id()));
+        let nested = root.join("repo").join("a").join("b");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(root.join("repo").join(".git")).unwrap();
+        fs::write(root.join(PROJECT_FILE), "").unwrap();
+        assert_eq!(Config::discover(&nested), None);
+
+        fs::write(root.join("repo").join("a").join(PROJECT_FILE), "").unwrap();
+        assert_eq!(
+            Config::discover(&nested),
+            Some(root.join("repo").join("a").join(PROJECT_FILE))
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn project_values_override_user_values() {
+        let mut user: toml::Table =
+            toml::from_str("[profile.ci]\nindent = 2\ndeny = [\"warnings\"]").unwrap();
+        let project: toml::Table = toml::from_str("[profile.ci]\nindent = 4").unwrap();
+        merge(&mut user, project);
+        let expected: toml::Table =
+            toml::from_str("[profile.ci]\nindent = 4\ndeny = [\"warnings\"]").unwrap();
+        assert_eq!(user, expected);
+    }
+}
@@ -0,0 +1,224 @@
+//! YAML 的自定义标签
+//!
+//! CloudFormation、Ansible 等工具的 YAML 使用程序自己定义的标签（`!Ref name`、`!Sub [...]`），
+//! serde_yml 读到它们时报告难以理解的错误，JSON 和 TOML 也没有对应的写法。`--custom-tags`
+//! 决定怎样处理：
+//! - `error`（默认）：报错，指出第一个标签和它所在的行
+//! - `wrap`：带标签的值读成只有一个键的对象 `{"!Ref": "name"}`，写成任何格式都保持这个对象
+//! - `keep`：同样读成标签对象，写成 YAML 时还原为标签，所以 `validate`、`format` 和 YAML
+//!   之间的转换不会丢失标签；要写成其他格式时报错，需要改用 `wrap`
+//!
+//! 标准标签（`!!str`、`!!timestamp` 等，见 [`crate::yaml_tags`]）和非特定标签 `!` 不受影响。
+//! 写出标签的排版见 [`crate::yaml`]
+
+use crate::dialect;
+use crate::error::{Error, Result};
+use crate::i18n::tr;
+use crate::nonfinite;
+use crate::t;
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use serde_yml::libyml::parser::{Event, Parser};
+use serde_yml::libyml::tag::Tag;
+use std::borrow::Cow;
+
+/// 标准标签的前缀
+const STANDARD: &str = "tag:yaml.org,2002:";
+
+/// 自定义标签的处理方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum CustomTags {
+    /// 读成标签对象，写成 YAML 时还原为标签
+    #[value(help = tr("读成标签对象，写成 YAML 时还原为标签，写成其他格式时报错"))]
+    Keep,
+    /// 读成标签对象 {"!Ref": ...}
+    #[value(help = tr("读成标签对象 {\"!Ref\": ...}，写成任何格式都保持这个对象"))]
+    Wrap,
+    /// 出现自定义标签时报错
+    #[default]
+    #[value(help = tr("出现自定义标签时报错"))]
+    Error,
+}
+
+/// 本次运行的处理方式
+pub fn mode() -> CustomTags {
+    dialect::current().custom_tags
+}
+
+/// 标签是否是自定义标签
+pub fn is_custom(tag: &Tag) -> bool {
+    !matches!(tag.starts_with(STANDARD), Ok(true)) && *tag != *"!"
+}
+
+/// 标签在标签对象中的键，总是以 `!` 开头
+fn key(tag: &str) -> String {
+    if tag.starts_with('!') {
+        tag.to_string()
+    } else {
+        format!("!<{}>", tag)
+    }
+}
+
+/// 把带标签 `tag` 的值包装成标签对象
+pub fn wrap(tag: &Tag, value: Value) -> Value {
+    let mut map = Map::new();
+    map.insert(key(&String::from_utf8_lossy(tag)), value);
+    Value::Object(map)
+}
+
+/// 如果值是标签对象，返回标签（含开头的 `!`）和被标记的值
+pub fn get(value: &Value) -> Option<(&str, &Value)> {
+    match value.as_object() {
+        Some(map) if map.len() == 1 => map
+            .iter()
+            .next()
+            .filter(|(tag, _)| tag.starts_with('!'))
+            .map(|(tag, value)| (tag.as_str(), value)),
+        _ => None,
+    }
+}
+
+/// 值中第一个标签对象的标签
+pub fn first(value: &Value) -> Option<&str> {
+    if let Some((tag, _)) = get(value) {
+        return Some(tag);
+    }
+    match value {
+        Value::Object(map) => map.values().find_map(first),
+        Value::Array(items) => items.iter().find_map(first),
+        _ => None,
+    }
+}
+
+/// 文本中第一个自定义标签和它所在的行
+pub fn find(text: &str) -> Option<(String, usize)> {
+    if !text.contains('!') {
+        return None;
+    }
+    let mut parser = Parser::new(Cow::Borrowed(text.as_bytes()));
+    loop {
+        let (event, mark) = parser.parse_next_event().ok()?;
+        let tag = match event {
+            Event::StreamEnd => return None,
+            Event::Scalar(scalar) => scalar.tag,
+            Event::SequenceStart(start) => start.tag,
+            Event::MappingStart(start) => start.tag,
+            _ => None,
+        };
+        if let Some(tag) = tag.filter(is_custom) {
+            return Some((
+                String::from_utf8_lossy(&tag).into_owned(),
+                mark.line() as usize + 1,
+            ));
+        }
+    }
+}
+
+/// error 模式下出现自定义标签时的错误
+pub fn unsupported(tag: &str, line: usize) -> Error {
+    Error::Parse {
+        format: "YAML",
+        source: t!(
+            "第 {} 行: 不支持自定义标签 {}（可以用 --custom-tags keep 或 wrap 保留）",
+            line,
+            tag
+        ),
+    }
+}
+
+/// 按 YAML 1.2 解析含有自定义标签的文本，`first` 是 [`find`] 找到的第一个标签
+pub fn parse(text: &str, (tag, line): (String, usize)) -> Result<Value> {
+    if mode() == CustomTags::Error {
+        return Err(unsupported(&tag, line));
+    }
+    let value: serde_yml::Value = serde_yml::from_str(text).map_err(|e| Error::Parse {
+        format: "YAML",
+        source: e.to_string(),
+    })?;
+    Ok(from_yaml(value))
+}
+
+/// serde_yml 的值转换为中间表示，自定义标签写成标签对象
+fn from_yaml(value: serde_yml::Value) -> Value {
+    use serde_yml::Value as Yaml;
+    match value {
+        Yaml::Null => Value::Null,
+        Yaml::Bool(b) => Value::Bool(b),
+        Yaml::Number(n) => match (n.as_i64(), n.as_u64()) {
+            (Some(i), _) => Value::from(i),
+            (None, Some(u)) => Value::from(u),
+            _ => nonfinite::float(n.as_f64().unwrap_or(f64::NAN)),
+        },
+        Yaml::String(s) => Value::String(s),
+        Yaml::Sequence(items) => Value::Array(items.into_iter().map(from_yaml).collect()),
+        Yaml::Mapping(mapping) => Value::Object(
+            mapping
+                .into_iter()
+                .map(|(key, child)| (key_text(key), from_yaml(child)))
+                .collect(),
+        ),
+        Yaml::Tagged(tagged) => {
+            let tag = tagged.tag.to_string();
+            let value = from_yaml(tagged.value);
+            // 标准标签已经决定了值的类型
+            if tag.starts_with("!!") || tag.contains(STANDARD) {
+                return value;
+            }
+            let mut map = Map::new();
+            map.insert(key(&tag), value);
+            Value::Object(map)
+        }
+    }
+}
+
+/// 映射的键转为字符串，与 serde_yml 直接读成 JSON 时相同
+fn key_text(key: serde_yml::Value) -> String {
+    match key {
+        serde_yml::Value::String(s) => s,
+        other => serde_yml::to_string(&other)
+            .map(|text| text.trim_end().to_string())
+            .unwrap_or_default(),
+    }
+}
+
+/// keep 模式下要写成格式 `format` 时检查值中没有标签对象
+pub fn check_target(value: &Value, format: &str) -> Result<()> {
+    match first(value) {
+        Some(tag) if mode() == CustomTags::Keep => Err(Error::Convert {
+            message: t!(
+                "自定义标签 {} 无法写成 {}（可以用 --custom-tags wrap 写成对象）",
+                tag,
+                format
+            ),
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn wraps_and_restores_custom_tags() {
+        let text = "a: !Ref name\nb: !Sub [x, y]\nc: !!str 1\n";
+        let first = find(text).unwrap();
+        assert_eq!(first, ("!Ref".to_string(), 1));
+        let value = serde_yml::from_str(text).map(from_yaml).unwrap();
+        assert_eq!(
+            value,
+            json!({"a": {"!Ref": "name"}, "b": {"!Sub": ["x", "y"]}, "c": "1"})
+        );
+        let layout = crate::yaml::Layout {
+            width: None,
+            quote: crate::codec::Quote::Auto,
+            tags: true,
+        };
+        assert_eq!(
+            crate::yaml::to_string(&value, &layout).unwrap(),
+            "a: !Ref name\nb: !Sub\n- x\n- 'y'\nc: '1'\n"
+        );
+        assert_eq!(find("a: !!str x\nb: ! y\n"), None);
+    }
+}
@@ -0,0 +1,220 @@
+//! 循环引用检测
+//!
+//! 中间表示是一棵树，本身不会有环；环只可能出现在解析之前的引用里。YAML 的别名可以
+//! 引用包含它自己的锚点（`a: &a {b: *a}`），serde_yml 展开时只会报告
+//! "recursion limit exceeded"，看不出是哪里出了问题。解析失败时用这里的函数
+//! 重新读取事件，找出环并给出从锚点到别名的路径。
+//!
+//! 读取事件时跟踪每个节点路径的 [`visit_leaves`] 也用来找出别名和带标签的标量所在的位置
+
+use crate::path::{Path, Segment};
+use crate::t;
+use serde_yml::libyml::parser::{Anchor, Event, Parser, Scalar};
+use std::borrow::Cow;
+use std::fmt;
+
+/// 一个引用了外层锚点的别名
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cycle {
+    /// 锚点名
+    pub anchor: String,
+    /// 锚点所在的路径
+    pub defined: Path,
+    /// 别名所在的路径
+    pub alias: Path,
+    /// 别名的行号和列号（从 1 开始）
+    pub location: (usize, usize),
+}
+
+impl fmt::Display for Cycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            t!(
+                "别名形成循环: {} (&{}) → {} (*{}，第 {} 行)",
+                display(&self.defined),
+                self.anchor,
+                display(&self.alias),
+                self.anchor,
+                self.location.0
+            )
+        )
+    }
+}
+
+/// 路径的显示文本，根路径显示为 `.`
+fn display(path: &Path) -> String {
+    if path.is_root() {
+        ".".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// 正在读取的集合
+pub(crate) struct Frame {
+    /// 集合所在的路径
+    path: Path,
+    /// 集合上的锚点
+    anchor: Option<Anchor>,
+    /// 映射中等待值的键，序列中下一项的下标
+    next: Next,
+}
+
+enum Next {
+    /// 映射：None 表示下一个节点是键
+    Key(Option<String>),
+    /// 序列
+    Index(i64),
+}
+
+/// 找出 YAML 文本中第一个引用了外层锚点的别名；语法错误或没有环时返回 None
+pub fn yaml_alias_cycle(text: &str) -> Option<Cycle> {
+    visit_leaves(text, |stack, leaf, path, location| {
+        let Leaf::Alias(anchor) = leaf else {
+            return None;
+        };
+        let frame = stack.iter().find(|f| f.anchor.as_ref() == Some(anchor))?;
+        Some(Cycle {
+            anchor: name(anchor),
+            defined: frame.path.clone(),
+            alias: path.clone(),
+            location,
+        })
+    })
+}
+
+/// YAML 文本中所有别名所在的路径，这些位置的值是锚点处的副本
+pub fn yaml_alias_paths(text: &str) -> Vec<Path> {
+    let mut paths = Vec::new();
+    visit_leaves(text, |_, leaf, path, _| {
+        if let Leaf::Alias(_) = leaf {
+            paths.push(path.clone());
+        }
+        None::<()>
+    });
+    paths
+}
+
+/// 事件流中的叶子节点
+pub(crate) enum Leaf<'a> {
+    /// 别名
+    Alias(&'a Anchor),
+    /// 标量，`is_key` 表示它是映射的键
+    Scalar {
+        scalar: &'a Scalar<'a>,
+        is_key: bool,
+    },
+}
+
+/// 依次把每个叶子节点、它所在的路径和位置交给 `f`，`f` 返回 Some 时停止
+///
+/// `stack` 是节点外层正在读取的集合；键的路径最后一段是 `?`；语法错误时停止
+pub(crate) fn visit_leaves<R>(
+    text: &str,
+    mut f: impl FnMut(&[Frame], Leaf, &Path, (usize, usize)) -> Option<R>,
+) -> Option<R> {
+    let mut parser = Parser::new(Cow::Borrowed(text.as_bytes()));
+    let mut stack: Vec<Frame> = Vec::new();
+    loop {
+        let (event, mark) = parser.parse_next_event().ok()?;
+        let location = (mark.line() as usize + 1, mark.column() as usize + 1);
+        // 当前节点的路径；映射的键本身也是节点，它们的路径用不到
+        let path = match stack.last() {
+            None => Path::default(),
+            Some(frame) => match &frame.next {
+                Next::Key(Some(key)) => frame.path.join(Segment::Key(key.clone())),
+                Next::Key(None) => frame.path.join(Segment::Key("?".to_string())),
+                Next::Index(index) => frame.path.join(Segment::Index(*index)),
+            },
+        };
+        let anchor = match event {
+            Event::StreamEnd => return None,
+            Event::SequenceStart(start) => {
+                stack.push(Frame {
+                    path,
+                    anchor: start.anchor,
+                    next: Next::Index(0),
+                });
+                continue;
+            }
+            Event::MappingStart(start) => {
+                stack.push(Frame {
+                    path,
+                    anchor: start.anchor,
+                    next: Next::Key(None),
+                });
+                continue;
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                stack.pop()?;
+                advance(&mut stack, "?".to_string());
+                continue;
+            }
+            Event::Scalar(scalar) => {
+                let is_key = stack
+                    .last()
+                    .is_some_and(|frame| matches!(frame.next, Next::Key(None)));
+                let leaf = Leaf::Scalar {
+                    scalar: &scalar,
+                    is_key,
+                };
+                if let Some(result) = f(&stack, leaf, &path, location) {
+                    return Some(result);
+                }
+                advance(
+                    &mut stack,
+                    String::from_utf8_lossy(&scalar.value).into_owned(),
+                );
+                continue;
+            }
+            Event::Alias(anchor) => anchor,
+            _ => continue,
+        };
+        if let Some(result) = f(&stack, Leaf::Alias(&anchor), &path, location) {
+            return Some(result);
+        }
+        advance(&mut stack, format!("*{}", name(&anchor)));
+    }
+}
+
+/// 读完一个节点后移动到下一个位置，`key` 是这个节点作为映射的键时的文本
+fn advance(stack: &mut [Frame], key: String) {
+    if let Some(frame) = stack.last_mut() {
+        frame.next = match &frame.next {
+            Next::Key(None) => Next::Key(Some(key)),
+            Next::Key(Some(_)) => Next::Key(None),
+            Next::Index(index) => Next::Index(index + 1),
+        };
+    }
+}
+
+/// 锚点的名字（libyml 只提供带引号的 Debug 输出）
+pub fn name(anchor: &Anchor) -> String {
+    let debug = format!("{:?}", anchor);
+    debug
+        .strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(&debug)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_alias_inside_its_own_anchor() {
+        let cycle = yaml_alias_cycle("ok: &x 1\na: &a\n  b:\n    - *x\n    - c: *a\n").unwrap();
+        assert_eq!(cycle.anchor, "a");
+        assert_eq!(cycle.defined.to_string(), "a");
+        assert_eq!(cycle.alias.to_string(), "a.b[1].c");
+        assert_eq!(cycle.location, (5, 10));
+    }
+
+    #[test]
+    fn ordinary_aliases_are_not_cycles() {
+        assert_eq!(yaml_alias_cycle("a: &a {x: 1}\nb: {y: *a}\n"), None);
+    }
+}
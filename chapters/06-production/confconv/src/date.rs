@@ -0,0 +1,75 @@
+//! 日期时间规范化
+//!
+//! 不同工具写出的日期时间格式各不相同（`2024-01-02 03:04:05+08:00`、
+//! RFC 2822、TOML 的日期时间类型……），规范化为 RFC 3339 后才能直接比较
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, SecondsFormat, Utc};
+use serde_json::Value;
+
+/// toml 把日期时间序列化为只有这一个键的对象
+pub const TOML_DATETIME_KEY: &str = "$__toml_private_datetime";
+
+/// 带时区偏移的日期时间格式（RFC 3339 之外的常见写法）
+const OFFSET_FORMATS: &[&str] = &[
+    "%Y-%m-%d %H:%M:%S%.f%:z",
+    "%Y-%m-%d %H:%M:%S%.f %:z",
+    "%Y-%m-%d %H:%M:%S%.f %z",
+    "%Y-%m-%dT%H:%M:%S%.f%z",
+];
+
+/// 不带时区偏移的日期时间格式
+const NAIVE_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// 如果值是 TOML 日期时间对象，返回其中的文本
+pub fn toml_datetime(value: &Value) -> Option<&str> {
+    match value.as_object() {
+        Some(map) if map.len() == 1 => map.get(TOML_DATETIME_KEY)?.as_str(),
+        _ => None,
+    }
+}
+
+/// 把可识别的日期时间文本规范化为 RFC 3339，无法识别时返回 None
+///
+/// - 带时区偏移的日期时间：`2024-01-02T03:04:05+08:00`，UTC 写作 `Z`；
+///   `utc` 为 true 时转换到 UTC
+/// - 不带偏移的日期时间：`2024-01-02T03:04:05`，不知道时区所以不做转换
+/// - 纯日期：`2024-01-02`
+pub fn normalize(text: &str, utc: bool) -> Option<String> {
+    let text = text.trim();
+    // 至少要以 YYYY-MM-DD 开头，避免把任意文本交给 RFC 2822 解析
+    let looks_like_date = text.len() >= 10 && text.as_bytes()[4] == b'-';
+    let with_offset = looks_like_date
+        .then(|| parse_with_offset(text))
+        .flatten()
+        .or_else(|| DateTime::parse_from_rfc2822(text).ok());
+    if let Some(datetime) = with_offset {
+        return Some(if utc {
+            datetime
+                .with_timezone(&Utc)
+                .to_rfc3339_opts(SecondsFormat::AutoSi, true)
+        } else {
+            datetime.to_rfc3339_opts(SecondsFormat::AutoSi, true)
+        });
+    }
+    if !looks_like_date {
+        return None;
+    }
+    if let Some(naive) = NAIVE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+    {
+        return Some(naive.format("%Y-%m-%dT%H:%M:%S%.f").to_string());
+    }
+    NaiveDate::parse_from_str(text, "%Y-%m-%d")
+        .ok()
+        .map(|date| date.format("%Y-%m-%d").to_string())
+}
+
+/// 解析带时区偏移的日期时间
+fn parse_with_offset(text: &str) -> Option<DateTime<FixedOffset>> {
+    DateTime::parse_from_rfc3339(text).ok().or_else(|| {
+        OFFSET_FORMATS
+            .iter()
+            .find_map(|f| DateTime::parse_from_str(text, f).ok())
+    })
+}
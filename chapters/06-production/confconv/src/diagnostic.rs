@@ -0,0 +1,334 @@
+//! 诊断信息
+//!
+//! 错误会中断命令，诊断则是命令完成时附带报告的问题：lint 的检查结果、转换时悄悄丢失的
+//! 信息（见 [`crate::lossy`]）等。所有命令都按同样的格式输出，方便编辑器和 CI 识别。
+//!
+//! 与 rustc 的 lint 级别一样，可以用全局参数调整警告的级别：`--deny` 把警告变成错误，
+//! `--warn` 保持为警告，`--allow` 不再报告。参数是规则名或 `warnings`（所有警告），
+//! 指定规则名的优先于 `warnings`，同一规则指定了多个级别时取最严格的。
+//! 错误级别的问题（语法错误等）不受影响。
+//!
+//! `--annotate github` 时每个诊断、validate 和 lint 报告的文件错误、test 未通过的断言以及命令最终失败的错误
+//! 还会以 GitHub Actions 工作流命令 `::error file=…,line=…,col=…,title=…::说明` 的形式输出到标准错误，
+//! 不需要其他工具就能在 PR 的代码行上显示
+
+use crate::color::{self, Color, Stream};
+use crate::error::{Error, Result};
+use crate::i18n::tr;
+use crate::t;
+use crate::term;
+use crate::verbosity;
+use clap::ValueEnum;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// 警告级别的规则名，`--deny`、`--warn`、`--allow` 只接受这些名字和 `warnings`
+pub const CODES: &[&str] = &[
+    "unused-anchor",
+    "duplicate-anchor",
+    "alias-fanout",
+    "duplicate-subtree",
+    "similar-key-value",
+    "key-naming",
+    "duplicate-key",
+    "unsorted-keys",
+    "indentation",
+    "number-precision",
+    "nonfinite-null",
+    "key-retyped",
+    "target-null",
+    "target-datetime",
+    "target-root",
+];
+
+/// 表示所有警告的名字
+pub const WARNINGS: &str = "warnings";
+
+/// 问题的严重程度
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// 补充说明，例如如何避免前面的警告
+    Note,
+    /// 可能是有意为之，只提示
+    Warning,
+    /// 解析时就会出错
+    Error,
+}
+
+/// 一个问题
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    /// 严重程度
+    pub severity: Severity,
+    /// 规则名，例如 `unused-anchor`
+    pub code: &'static str,
+    /// 行号和列号（从 1 开始），无法定位时为 None
+    pub location: Option<(usize, usize)>,
+    /// 说明
+    pub message: String,
+}
+
+/// 由 `--deny`、`--warn`、`--allow` 指定的级别
+#[derive(Clone, Debug, Default)]
+pub struct Levels {
+    /// 作为错误的规则
+    pub deny: Vec<String>,
+    /// 作为警告的规则
+    pub warn: Vec<String>,
+    /// 不报告的规则
+    pub allow: Vec<String>,
+}
+
+impl Levels {
+    /// 规则的级别：None 表示不报告
+    fn level(&self, code: &str) -> Option<Severity> {
+        let named = |name: &str| {
+            if self.deny.iter().any(|c| c == name) {
+                Some(Some(Severity::Error))
+            } else if self.warn.iter().any(|c| c == name) {
+                Some(Some(Severity::Warning))
+            } else if self.allow.iter().any(|c| c == name) {
+                Some(None)
+            } else {
+                None
+            }
+        };
+        named(code)
+            .or_else(|| named(WARNINGS))
+            .unwrap_or(Some(Severity::Warning))
+    }
+
+    /// 按级别调整警告：提示跟随同一规则的警告，不报告的规则连同提示一起去掉
+    pub fn apply(&self, diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        diagnostics
+            .into_iter()
+            .filter_map(|mut diagnostic| {
+                if diagnostic.severity == Severity::Error {
+                    return Some(diagnostic);
+                }
+                let level = self.level(diagnostic.code)?;
+                if diagnostic.severity == Severity::Warning {
+                    diagnostic.severity = level;
+                }
+                Some(diagnostic)
+            })
+            .collect()
+    }
+}
+
+static LEVELS: OnceLock<Levels> = OnceLock::new();
+
+/// 设置本次运行的级别，只有第一次调用有效
+pub fn init(levels: Levels) {
+    let _ = LEVELS.set(levels);
+}
+
+/// 当前的级别，没有调用过 [`init`] 时所有警告都是警告
+pub fn levels() -> &'static Levels {
+    LEVELS.get_or_init(Levels::default)
+}
+
+/// 额外输出的 CI 注解
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Annotate {
+    /// GitHub Actions 的工作流命令
+    #[value(help = tr("GitHub Actions 的工作流命令（::error file=…,line=…::说明）"))]
+    Github,
+}
+
+static ANNOTATE: OnceLock<Option<Annotate>> = OnceLock::new();
+
+/// 设置本次运行输出的注解，只有第一次调用有效
+pub fn init_annotate(annotate: Option<Annotate>) {
+    let _ = ANNOTATE.set(annotate);
+}
+
+/// 按 `--annotate` 输出一个诊断的注解
+pub fn annotate(file: &str, diagnostic: &Diagnostic) {
+    let Some(Annotate::Github) = ANNOTATE.get().copied().flatten() else {
+        return;
+    };
+    let command = match diagnostic.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "notice",
+    };
+    let mut properties = Vec::new();
+    if file != "-" {
+        properties.push(format!("file={}", escape_property(file)));
+    }
+    // 语法错误通常只在说明中写出位置
+    let location = diagnostic
+        .location
+        .or_else(|| location_in(&diagnostic.message));
+    if let Some((line, column)) = location {
+        properties.push(format!("line={}", line));
+        if column > 0 {
+            properties.push(format!("col={}", column));
+        }
+    }
+    properties.push(format!("title={}", escape_property(diagnostic.code)));
+    eprintln!(
+        "::{} {}::{}",
+        command,
+        properties.join(","),
+        escape_data(&diagnostic.message)
+    );
+}
+
+/// 按 `--annotate` 输出一个文件的错误的注解，标题是错误的类别（见 [`Error::kind`]）
+pub fn annotate_error(file: &str, error: &Error) {
+    annotate(
+        error.file().unwrap_or(file),
+        &Diagnostic {
+            severity: Severity::Error,
+            code: error.kind(),
+            location: None,
+            message: error.to_string(),
+        },
+    );
+}
+
+/// 解析器错误说明中的行号和列号（没有列号时为 0）
+fn location_in(message: &str) -> Option<(usize, usize)> {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    let pattern = PATTERN.get_or_init(|| {
+        Regex::new(r"line (\d+)(?:,? column (\d+))?|第 (\d+) 行").expect("正则表达式有效")
+    });
+    let captures = pattern.captures(message)?;
+    let number = |i| captures.get(i).and_then(|m| m.as_str().parse().ok());
+    Some((number(1).or_else(|| number(3))?, number(2).unwrap_or(0)))
+}
+
+/// 工作流命令中说明部分的转义
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// 工作流命令中属性值的转义
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
+
+/// 检查 `--deny`、`--warn`、`--allow` 的参数
+pub fn parse_code(code: &str) -> std::result::Result<String, String> {
+    if code == WARNINGS || CODES.contains(&code) {
+        Ok(code.to_string())
+    } else {
+        Err(t!(
+            "未知的规则: {}（可用: {}, {}）",
+            code,
+            WARNINGS,
+            CODES.join(", ")
+        ))
+    }
+}
+
+/// 按当前级别输出一个文件的诊断信息，有被 `--deny` 变成错误的警告时返回错误
+pub fn report(file: &str, diagnostics: Vec<Diagnostic>) -> Result<()> {
+    let mut diagnostics = levels().apply(diagnostics);
+    // -q 时只输出错误
+    if verbosity::quiet() {
+        diagnostics.retain(|d| d.severity == Severity::Error);
+    }
+    print(file, &diagnostics);
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    if errors > 0 {
+        return Err(Error::Denied { errors });
+    }
+    Ok(())
+}
+
+/// 按 `文件:行:列: 级别[规则]: 说明` 的形式输出，级别按 `stream` 是否着色加上颜色
+pub fn render(file: &str, diagnostic: &Diagnostic, stream: Stream) -> String {
+    render_with(file, diagnostic, Some(stream))
+}
+
+/// 与 [`render`] 相同但不着色，用于写入报告文件
+pub fn plain(file: &str, diagnostic: &Diagnostic) -> String {
+    render_with(file, diagnostic, None)
+}
+
+fn render_with(file: &str, diagnostic: &Diagnostic, stream: Option<Stream>) -> String {
+    let (level, color) = match diagnostic.severity {
+        Severity::Error => (tr("错误"), Color::Red),
+        Severity::Warning => (tr("警告"), Color::Yellow),
+        Severity::Note => (tr("提示"), Color::Cyan),
+    };
+    let level = format!("{}[{}]", level, diagnostic.code);
+    let level = match stream {
+        Some(stream) => color::paint(stream, color, true, &level),
+        None => level,
+    };
+    let location = match diagnostic.location {
+        Some((line, column)) => format!("{}:{}:{}", file, line, column),
+        None => file.to_string(),
+    };
+    format!("{}: {}: {}", location, level, diagnostic.message)
+}
+
+/// 把诊断信息输出到标准错误
+pub fn print(file: &str, diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        term::eprint_wrapped(&render(file, diagnostic, Stream::Stderr));
+        annotate(file, diagnostic);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diagnostic(severity: Severity, code: &'static str) -> Diagnostic {
+        Diagnostic {
+            severity,
+            code,
+            location: None,
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn named_rules_override_warnings() {
+        let levels = Levels {
+            deny: vec![WARNINGS.to_string()],
+            warn: vec!["key-retyped".to_string()],
+            allow: vec!["nonfinite-null".to_string()],
+        };
+        let applied = levels.apply(vec![
+            diagnostic(Severity::Warning, "number-precision"),
+            diagnostic(Severity::Warning, "key-retyped"),
+            diagnostic(Severity::Warning, "nonfinite-null"),
+            diagnostic(Severity::Note, "nonfinite-null"),
+            diagnostic(Severity::Error, "syntax"),
+        ]);
+        let severities: Vec<_> = applied.iter().map(|d| (d.code, d.severity)).collect();
+        assert_eq!(
+            severities,
+            vec![
+                ("number-precision", Severity::Error),
+                ("key-retyped", Severity::Warning),
+                ("syntax", Severity::Error)
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_locations_in_parser_errors() {
+        assert_eq!(
+            location_in("JSON 解析失败: expected value at line 3 column 7"),
+            Some((3, 7))
+        );
+        assert_eq!(
+            location_in("TOML parse error at line 2, column 5"),
+            Some((2, 5))
+        );
+        assert_eq!(location_in("INI 解析失败: 第 4 行: 键名为空"), Some((4, 0)));
+        assert_eq!(escape_property("a:b,c%"), "a%3Ab%2Cc%25");
+    }
+}
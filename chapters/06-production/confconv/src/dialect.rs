@@ -0,0 +1,249 @@
+//! 格式方言
+//!
+//! 同一格式的不同规范版本，用全局参数选择，对本次运行中所有的解析和序列化生效：
+//! - `--toml-version`：TOML 规范版本。1.0（默认）只接受 1.0 的语法，输出也只用 1.0 的写法，
+//!   兼容固定在旧版解析器上的程序；1.1 还接受多行内联表、`\e` 和 `\xHH` 转义、
+//!   省略秒的时间等新语法，输出时用更短的 `\e`、`\xHH` 转义控制字符。
+//!   按 1.0 输出时，1.1 中省略了秒的时间会补上 `:00`
+//! - `--yaml-spec`：YAML 规范版本，决定没有引号的标量如何解释。1.2（默认）只有
+//!   `true`/`false` 是布尔；1.1 中 `yes`/`no`/`on`/`off` 也是布尔，`0777` 是八进制，
+//!   还支持合并键 `<<`（见 [`crate::yaml11`]）。输出时 serde_yml 已经给这些写法的字符串加上引号，
+//!   两种版本的解析器读到的都是字符串
+//! - `--json-allow-nan`：JSON 中的无穷大和 NaN，见 [`crate::nonfinite`]
+//! - `--yaml-tags`：YAML 中 `!!timestamp` 和 `!!binary` 标签的处理方式，见 [`crate::yaml_tags`]
+//! - `--custom-tags`：YAML 中自定义标签（`!Ref name`）的处理方式，见 [`crate::custom_tags`]
+//! - `--ini-dialect`：INI 的分隔符、注释符号、重复的键和 git config 风格的子段，见 [`crate::ini`]
+
+use crate::custom_tags::CustomTags;
+use crate::date::{self, TOML_DATETIME_KEY};
+use crate::i18n::tr;
+use crate::ini::IniDialect;
+use crate::yaml_tags::YamlTags;
+use clap::ValueEnum;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// TOML 规范版本
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum TomlVersion {
+    /// TOML 1.0
+    #[default]
+    #[value(name = "1.0", help = tr("TOML 1.0，兼容旧版解析器"))]
+    V1_0,
+    /// TOML 1.1
+    #[value(name = "1.1", help = tr("TOML 1.1，接受多行内联表和新的转义序列"))]
+    V1_1,
+}
+
+/// YAML 规范版本
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum YamlSpec {
+    /// YAML 1.1
+    #[value(name = "1.1", help = tr("YAML 1.1，yes/no/on/off 是布尔，0777 是八进制"))]
+    V1_1,
+    /// YAML 1.2
+    #[default]
+    #[value(name = "1.2", help = tr("YAML 1.2，只有 true/false 是布尔"))]
+    V1_2,
+}
+
+/// JSON 输出无穷大和 NaN 的方式
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum NanPolicy {
+    /// 写成 null
+    #[value(help = tr("写成 null"))]
+    Null,
+    /// 写成字符串 "NaN"、"Infinity"、"-Infinity"
+    #[value(help = tr("写成字符串 \"NaN\"、\"Infinity\"、\"-Infinity\""))]
+    String,
+    /// 直接写出 NaN、Infinity、-Infinity，不是标准 JSON，只有宽松的解析器接受
+    #[value(help = tr("直接写出 NaN、Infinity、-Infinity（不是标准 JSON，只有宽松的解析器接受）"))]
+    Literal,
+}
+
+/// 本次运行使用的方言
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Dialect {
+    /// TOML 规范版本
+    pub toml: TomlVersion,
+    /// YAML 规范版本
+    pub yaml: YamlSpec,
+    /// JSON 中无穷大和 NaN 的输出方式，None 表示不保留它们（读成 null）
+    pub json_nan: Option<NanPolicy>,
+    /// YAML 中 `!!timestamp` 和 `!!binary` 标签的处理方式
+    pub yaml_tags: YamlTags,
+    /// YAML 中自定义标签的处理方式
+    pub custom_tags: CustomTags,
+    /// INI 的分隔符、注释符号、重复键和子段写法
+    pub ini: IniDialect,
+}
+
+static DIALECT: OnceLock<Dialect> = OnceLock::new();
+
+/// 设置本次运行的方言，只有第一次调用有效
+pub fn init(dialect: Dialect) {
+    let _ = DIALECT.set(dialect);
+}
+
+/// 当前的方言，没有调用过 [`init`] 时使用默认值
+pub fn current() -> Dialect {
+    *DIALECT.get_or_init(Dialect::default)
+}
+
+/// 给省略了秒的 TOML 日期时间补上 `:00`，没有这样的值时不复制
+pub fn pad_toml_seconds(value: &Value) -> Cow<'_, Value> {
+    fn short(value: &Value) -> bool {
+        match value {
+            Value::Object(map) => match date::toml_datetime(value) {
+                Some(text) => padded(text).is_some(),
+                None => map.values().any(short),
+            },
+            Value::Array(items) => items.iter().any(short),
+            _ => false,
+        }
+    }
+    fn pad(value: &mut Value) {
+        if let Some(text) = date::toml_datetime(value).and_then(padded) {
+            *value = serde_json::json!({ TOML_DATETIME_KEY: text });
+            return;
+        }
+        match value {
+            Value::Object(map) => map.values_mut().for_each(pad),
+            Value::Array(items) => items.iter_mut().for_each(pad),
+            _ => {}
+        }
+    }
+
+    if !short(value) {
+        return Cow::Borrowed(value);
+    }
+    let mut value = value.clone();
+    pad(&mut value);
+    Cow::Owned(value)
+}
+
+/// 时间部分只有 `HH:MM` 时返回补上秒的文本
+fn padded(text: &str) -> Option<String> {
+    // 时间部分在日期之后（`1979-05-27T07:32`、`1979-05-27 07:32Z`）或单独出现（`07:32`）
+    let start = match text.as_bytes().get(10) {
+        Some(b'T' | b't' | b' ') => 11,
+        _ if text.as_bytes().get(2) == Some(&b':') => 0,
+        _ => return None,
+    };
+    let end = start + 5;
+    if text.len() < end || text.as_bytes().get(end) == Some(&b':') {
+        return None;
+    }
+    Some(format!("{}:00{}", &text[..end], &text[end..]))
+}
+
+/// 把 TOML 1.0 输出中基本字符串里的 `\uXXXX`/`\UXXXXXXXX` 转义改写为 1.1 的短写法：
+/// U+001B 写成 `\e`，其他 U+00FF 以内的字符写成 `\xHH`
+pub fn toml_short_escapes(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+    let starts = |i: usize, quote: &str| chars[i..].iter().take(3).collect::<String>() == quote;
+    while i < chars.len() {
+        let (quote, basic) = match chars[i] {
+            '"' if starts(i, "\"\"\"") => ("\"\"\"", true),
+            '"' => ("\"", true),
+            '\'' if starts(i, "'''") => ("'''", false),
+            '\'' => ("'", false),
+            '#' => {
+                // 注释原样保留到行尾
+                while i < chars.len() && chars[i] != '\n' {
+                    out.push(chars[i]);
+                    i += 1;
+                }
+                continue;
+            }
+            c => {
+                out.push(c);
+                i += 1;
+                continue;
+            }
+        };
+        out.push_str(quote);
+        i += quote.chars().count();
+        let close = quote.chars().next().unwrap_or('"');
+        while i < chars.len() {
+            let c = chars[i];
+            if basic && c == '\\' {
+                let digits = match chars.get(i + 1) {
+                    Some('u') => 4,
+                    Some('U') => 8,
+                    _ => 0,
+                };
+                let code = chars
+                    .get(i + 2..i + 2 + digits)
+                    .filter(|_| digits > 0)
+                    .and_then(|hex| u32::from_str_radix(&hex.iter().collect::<String>(), 16).ok());
+                match code {
+                    Some(0x1B) => out.push_str("\\e"),
+                    Some(code) if code <= 0xFF => out.push_str(&format!("\\x{:02X}", code)),
+                    _ => {
+                        // 其他转义（包括 `\\` 和 `\"`）连同下一个字符原样复制
+                        out.push(c);
+                        out.extend(chars.get(i + 1));
+                        i += 2;
+                        continue;
+                    }
+                }
+                i += 2 + digits;
+                continue;
+            }
+            if c == close {
+                // 多行字符串的结束符前可以再有一到两个引号，它们属于内容
+                let run = chars[i..].iter().take_while(|&&q| q == close).count();
+                if quote.len() == 1 || run >= 3 {
+                    let consumed = if quote.len() == 1 { 1 } else { run };
+                    out.extend(&chars[i..i + consumed]);
+                    i += consumed;
+                    break;
+                }
+                out.extend(&chars[i..i + run]);
+                i += run;
+                continue;
+            }
+            out.push(c);
+            i += 1;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_escapes_in_basic_strings() {
+        assert_eq!(
+            toml_short_escapes("a = \"x\\u001By\\u0007\\u2028\"\n"),
+            "a = \"x\\ey\\x07\\u2028\"\n"
+        );
+        assert_eq!(
+            toml_short_escapes("\"k\\u0001\" = \"\"\"\nq\\u001b\"\"\"\"\n"),
+            "\"k\\x01\" = \"\"\"\nq\\e\"\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn pads_times_without_seconds() {
+        assert_eq!(padded("07:32").as_deref(), Some("07:32:00"));
+        assert_eq!(
+            padded("1979-05-27T07:32Z").as_deref(),
+            Some("1979-05-27T07:32:00Z")
+        );
+        assert_eq!(padded("1979-05-27 07:32:10"), None);
+        assert_eq!(padded("1979-05-27"), None);
+    }
+
+    #[test]
+    fn literal_strings_and_escaped_backslashes_are_untouched() {
+        let text = "a = 'C:\\u001B'\nb = \"\\\\u001B\"\nc = '''\\u0007'''\n";
+        assert_eq!(toml_short_escapes(text), text);
+    }
+}
@@ -0,0 +1,504 @@
+//! 结构化比较
+//!
+//! 比较两个值的中间表示，给出逐路径的差异，与两边的源格式无关
+
+use crate::path::{self, Path, Pattern, Segment};
+use serde_json::{Map, Value};
+
+/// 比较选项，用于忽略语义上无关的差异
+#[derive(Debug, Default)]
+pub struct Options {
+    /// 数组按多重集比较，不在意元素顺序
+    pub ignore_order: bool,
+    /// 对象的键不区分大小写
+    pub ignore_case: bool,
+    /// 两个数字之差不超过该值时视为相等
+    pub numeric_tolerance: Option<f64>,
+    /// 只比较这些路径（及其子树），为空表示全部比较
+    pub only: Vec<Pattern>,
+    /// 不比较这些路径（及其子树）
+    pub ignore: Vec<Pattern>,
+}
+
+impl Options {
+    /// 路径上的差异是否应当报告
+    fn selected(&self, path: &Path) -> bool {
+        !self.ignore.iter().any(|p| p.covers(path))
+            && (self.only.is_empty() || self.only.iter().any(|p| p.covers(path)))
+    }
+
+    /// 路径本身未被选中，但它的后代可能被 `--only` 选中
+    fn selects_below(&self, path: &Path) -> bool {
+        !self.ignore.iter().any(|p| p.covers(path))
+            && self.only.iter().any(|p| p.may_match_below(path))
+    }
+
+    /// 按两边的值展开 `only` 和 `ignore` 中的负数下标
+    fn resolve(&self, left: &Value, right: &Value) -> Options {
+        let resolve = |patterns: &[Pattern]| {
+            let mut resolved = path::resolve_patterns(patterns, left);
+            for pattern in path::resolve_patterns(patterns, right) {
+                if !resolved.contains(&pattern) {
+                    resolved.push(pattern);
+                }
+            }
+            resolved
+        };
+        Options {
+            ignore_order: self.ignore_order,
+            ignore_case: self.ignore_case,
+            numeric_tolerance: self.numeric_tolerance,
+            only: resolve(&self.only),
+            ignore: resolve(&self.ignore),
+        }
+    }
+
+    /// 只保留 `path` 处的值中被选中的部分，没有选中任何内容时为 None
+    fn prune(&self, value: &Value, path: &Path) -> Option<Value> {
+        let selected = self.selected(path);
+        if !selected && !self.selects_below(path) {
+            return None;
+        }
+        let pruned = match value {
+            Value::Object(map) => Value::Object(
+                map.iter()
+                    .filter_map(|(key, child)| {
+                        let child = self.prune(child, &path.join(Segment::Key(key.clone())))?;
+                        Some((key.clone(), child))
+                    })
+                    .collect(),
+            ),
+            Value::Array(items) => Value::Array(
+                items
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, child)| {
+                        self.prune(child, &path.join(Segment::Index(i as i64)))
+                    })
+                    .collect(),
+            ),
+            _ if selected => value.clone(),
+            _ => return None,
+        };
+        let empty = match &pruned {
+            Value::Object(map) => map.is_empty(),
+            Value::Array(items) => items.is_empty(),
+            _ => false,
+        };
+        (selected || !empty).then_some(pruned)
+    }
+}
+
+/// 一处差异
+#[derive(Debug)]
+pub enum Change<'a> {
+    /// 只在右边存在
+    Added { path: Path, value: &'a Value },
+    /// 只在左边存在
+    Removed { path: Path, value: &'a Value },
+    /// 两边都存在但值不同
+    Changed {
+        path: Path,
+        old: &'a Value,
+        new: &'a Value,
+    },
+}
+
+/// 比较两个值，按出现顺序返回所有差异
+pub fn diff<'a>(left: &'a Value, right: &'a Value, options: &Options) -> Vec<Change<'a>> {
+    let options = options.resolve(left, right);
+    let mut changes = Vec::new();
+    compare(left, right, &Path::default(), &options, &mut changes);
+    changes
+}
+
+/// 分别位于 `left_path` 和 `right_path` 的两个值在给定选项下是否相等
+///
+/// 两边各按自己的路径筛选后再比较
+fn equal_at(
+    left: &Value,
+    left_path: &Path,
+    right: &Value,
+    right_path: &Path,
+    options: &Options,
+) -> bool {
+    if options.only.is_empty() && options.ignore.is_empty() {
+        let mut changes = Vec::new();
+        compare(left, right, left_path, options, &mut changes);
+        return changes.is_empty();
+    }
+    let plain = Options {
+        ignore_order: options.ignore_order,
+        ignore_case: options.ignore_case,
+        numeric_tolerance: options.numeric_tolerance,
+        ..Options::default()
+    };
+    match (
+        options.prune(left, left_path),
+        options.prune(right, right_path),
+    ) {
+        (Some(left), Some(right)) => {
+            let mut changes = Vec::new();
+            compare(&left, &right, left_path, &plain, &mut changes);
+            changes.is_empty()
+        }
+        (left, right) => left.is_none() && right.is_none(),
+    }
+}
+
+/// 记录只在一边存在的值
+///
+/// 值所在的路径被筛掉、但 `--only` 选中了它的某些后代时，
+/// 改为逐个记录这些后代
+fn record<'a>(
+    value: &'a Value,
+    path: Path,
+    added: bool,
+    options: &Options,
+    changes: &mut Vec<Change<'a>>,
+) {
+    if options.selected(&path) {
+        changes.push(if added {
+            Change::Added { path, value }
+        } else {
+            Change::Removed { path, value }
+        });
+    } else if options.selects_below(&path) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    record(
+                        child,
+                        path.join(Segment::Key(key.clone())),
+                        added,
+                        options,
+                        changes,
+                    );
+                }
+            }
+            Value::Array(items) => {
+                for (i, child) in items.iter().enumerate() {
+                    record(
+                        child,
+                        path.join(Segment::Index(i as i64)),
+                        added,
+                        options,
+                        changes,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn compare<'a>(
+    left: &'a Value,
+    right: &'a Value,
+    path: &Path,
+    options: &Options,
+    changes: &mut Vec<Change<'a>>,
+) {
+    if !options.selected(path) {
+        // 只有 --only 选中了后代时才需要继续向下比较
+        if !options.selects_below(path) {
+            return;
+        }
+        match (left, right) {
+            (Value::Object(_), Value::Object(_)) | (Value::Array(_), Value::Array(_)) => {}
+            _ => {
+                record(left, path.clone(), false, options, changes);
+                record(right, path.clone(), true, options, changes);
+                return;
+            }
+        }
+    }
+
+    match (left, right) {
+        (Value::Object(a), Value::Object(b)) => compare_objects(a, b, path, options, changes),
+        (Value::Array(a), Value::Array(b)) if options.ignore_order => {
+            compare_unordered(a, b, path, options, changes)
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (i, (x, y)) in a.iter().zip(b).enumerate() {
+                compare(x, y, &path.join(Segment::Index(i as i64)), options, changes);
+            }
+            for (i, value) in a.iter().enumerate().skip(b.len()) {
+                record(
+                    value,
+                    path.join(Segment::Index(i as i64)),
+                    false,
+                    options,
+                    changes,
+                );
+            }
+            for (i, value) in b.iter().enumerate().skip(a.len()) {
+                record(
+                    value,
+                    path.join(Segment::Index(i as i64)),
+                    true,
+                    options,
+                    changes,
+                );
+            }
+        }
+        (Value::Number(a), Value::Number(b)) => {
+            let same = match (options.numeric_tolerance, a.as_f64(), b.as_f64()) {
+                (Some(eps), Some(x), Some(y)) => (x - y).abs() <= eps,
+                _ => a == b,
+            };
+            if !same {
+                changes.push(Change::Changed {
+                    path: path.clone(),
+                    old: left,
+                    new: right,
+                });
+            }
+        }
+        _ => {
+            if left != right {
+                changes.push(Change::Changed {
+                    path: path.clone(),
+                    old: left,
+                    new: right,
+                });
+            }
+        }
+    }
+}
+
+/// 比较对象：先按原样匹配键，忽略大小写时再为剩下的键按小写匹配
+fn compare_objects<'a>(
+    left: &'a Map<String, Value>,
+    right: &'a Map<String, Value>,
+    path: &Path,
+    options: &Options,
+    changes: &mut Vec<Change<'a>>,
+) {
+    let entries: Vec<(&String, &Value)> = right.iter().collect();
+    let mut matched = vec![false; entries.len()];
+    for (key, value) in left {
+        let exact = entries.iter().position(|(k, _)| *k == key);
+        let index = exact.or_else(|| {
+            if !options.ignore_case {
+                return None;
+            }
+            entries.iter().enumerate().position(|(i, (k, _))| {
+                !matched[i] && !left.contains_key(*k) && k.eq_ignore_ascii_case(key)
+            })
+        });
+        let child = path.join(Segment::Key(key.clone()));
+        match index {
+            Some(i) => {
+                matched[i] = true;
+                compare(value, entries[i].1, &child, options, changes);
+            }
+            None => record(value, child, false, options, changes),
+        }
+    }
+    for (i, (key, value)) in entries.into_iter().enumerate() {
+        if !matched[i] {
+            record(
+                value,
+                path.join(Segment::Key(key.clone())),
+                true,
+                options,
+                changes,
+            );
+        }
+    }
+}
+
+/// 不考虑顺序比较数组：每个元素在另一边找一个尚未配对的相等元素，
+/// 找不到的左边元素算删除，右边剩下的算新增
+fn compare_unordered<'a>(
+    left: &'a [Value],
+    right: &'a [Value],
+    path: &Path,
+    options: &Options,
+    changes: &mut Vec<Change<'a>>,
+) {
+    let mut used = vec![false; right.len()];
+    for (i, value) in left.iter().enumerate() {
+        let child = path.join(Segment::Index(i as i64));
+        let found = right.iter().enumerate().position(|(j, other)| {
+            !used[j]
+                && equal_at(
+                    value,
+                    &child,
+                    other,
+                    &path.join(Segment::Index(j as i64)),
+                    options,
+                )
+        });
+        match found {
+            Some(j) => used[j] = true,
+            None => record(value, child, false, options, changes),
+        }
+    }
+    for (j, value) in right.iter().enumerate() {
+        if !used[j] {
+            record(
+                value,
+                path.join(Segment::Index(j as i64)),
+                true,
+                options,
+                changes,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// 差异的简写：`+路径`、`-路径`、`~路径`
+    fn summary(left: &Value, right: &Value, options: &Options) -> Vec<String> {
+        diff(left, right, options)
+            .iter()
+            .map(|change| match change {
+                Change::Added { path, .. } => format!("+{}", path),
+                Change::Removed { path, .. } => format!("-{}", path),
+                Change::Changed { path, .. } => format!("~{}", path),
+            })
+            .collect()
+    }
+
+    fn patterns(list: &[&str]) -> Vec<Pattern> {
+        list.iter().map(|p| Pattern::parse(p).unwrap()).collect()
+    }
+
+    #[test]
+    fn unordered_arrays_compare_as_multisets() {
+        let unordered = Options {
+            ignore_order: true,
+            ..Options::default()
+        };
+        let left = json!({"tags": ["a", "b", "a", {"k": 1}]});
+        assert!(summary(
+            &left,
+            &json!({"tags": [{"k": 1}, "a", "b", "a"]}),
+            &unordered
+        )
+        .is_empty());
+        // 重复的元素要配对相同的次数
+        assert_eq!(
+            summary(
+                &left,
+                &json!({"tags": ["b", "a", "b", {"k": 1}]}),
+                &unordered
+            ),
+            ["-tags[2]", "+tags[2]"]
+        );
+        assert_eq!(
+            summary(&left, &json!({"tags": ["a", "b", {"k": 1}]}), &unordered),
+            ["-tags[2]"]
+        );
+        assert_eq!(
+            summary(&json!(["a", "b"]), &json!(["b", "a"]), &Options::default()),
+            ["~[0]", "~[1]"]
+        );
+    }
+
+    #[test]
+    fn numeric_tolerance_is_inclusive() {
+        let tolerant = Options {
+            numeric_tolerance: Some(0.5),
+            ..Options::default()
+        };
+        let left = json!({"a": 1.0, "b": 1.0, "c": 1, "d": "1"});
+        let right = json!({"a": 1.5, "b": 1.5001, "c": 0.5, "d": "1.5"});
+        assert_eq!(summary(&left, &right, &tolerant), ["~b", "~d"]);
+        assert_eq!(
+            summary(&left, &right, &Options::default()),
+            ["~a", "~b", "~c", "~d"]
+        );
+        // 多重集比较时元素的配对也按容差
+        let both = Options {
+            ignore_order: true,
+            numeric_tolerance: Some(0.1),
+            ..Options::default()
+        };
+        assert!(summary(&json!([1.0, 2.0]), &json!([2.05, 0.95]), &both).is_empty());
+    }
+
+    #[test]
+    fn only_and_ignore_select_subtrees() {
+        let left = json!({
+            "server": {"host": "a", "port": 80, "tls": {"cert": "x"}},
+            "debug": false
+        });
+        let right = json!({
+            "server": {"host": "b", "port": 81, "tls": {"cert": "y"}, "extra": 1},
+            "debug": true
+        });
+        let only = Options {
+            only: patterns(&["server.tls", "server.extra"]),
+            ..Options::default()
+        };
+        assert_eq!(
+            summary(&left, &right, &only),
+            ["~server.tls.cert", "+server.extra"]
+        );
+
+        let ignore = Options {
+            ignore: patterns(&["server.tls", "debug"]),
+            ..Options::default()
+        };
+        assert_eq!(
+            summary(&left, &right, &ignore),
+            ["~server.host", "~server.port", "+server.extra"]
+        );
+
+        // 被忽略的子树优先于 --only
+        let both = Options {
+            only: patterns(&["server"]),
+            ignore: patterns(&["server.*t"]),
+            ..Options::default()
+        };
+        assert_eq!(
+            summary(&left, &right, &both),
+            ["~server.tls.cert", "+server.extra"]
+        );
+
+        // 只在一边存在的子树按 --only 选中的后代逐个报告
+        let nested = Options {
+            only: patterns(&["new.keep"]),
+            ..Options::default()
+        };
+        assert_eq!(
+            summary(&json!({}), &json!({"new": {"keep": 1, "drop": 2}}), &nested),
+            ["+new.keep"]
+        );
+    }
+
+    #[test]
+    fn negative_indices_and_unordered_elements_use_their_own_paths() {
+        let ignore_last = Options {
+            ignore: patterns(&["list[-1]"]),
+            ..Options::default()
+        };
+        assert!(summary(
+            &json!({"list": [1, 2, 3]}),
+            &json!({"list": [1, 2, 4]}),
+            &ignore_last
+        )
+        .is_empty());
+
+        // 右边的 3 位于 list[0]，没有被忽略，不能与左边被忽略的 list[2] 配对
+        let unordered = Options {
+            ignore_order: true,
+            ..ignore_last
+        };
+        assert_eq!(
+            summary(
+                &json!({"list": [1, 2, 3]}),
+                &json!({"list": [3, 1, 9]}),
+                &unordered
+            ),
+            ["-list[1]", "+list[0]"]
+        );
+    }
+}
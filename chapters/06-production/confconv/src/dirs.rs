@@ -0,0 +1,41 @@
+//! 平台相关的目录
+//!
+//! 配置、缓存和状态放在各平台约定的位置，由 directories crate 确定：
+//!
+//! | 平台    | 配置                                    | 缓存                              | 状态                                     |
+//! |---------|-----------------------------------------|-----------------------------------|------------------------------------------|
+//! | Linux   | `$XDG_CONFIG_HOME` 或 `~/.config`       | `$XDG_CACHE_HOME` 或 `~/.cache`    | `$XDG_STATE_HOME` 或 `~/.local/state`    |
+//! | macOS   | `~/Library/Application Support`         | `~/Library/Caches`                | `~/Library/Application Support`          |
+//! | Windows | `%APPDATA%\confconv\config`             | `%LOCALAPPDATA%\confconv\cache`   | `%LOCALAPPDATA%\confconv\data`           |
+//!
+//! Linux 和 macOS 上在表中的目录之下再建 `confconv` 目录。只有 Linux 有单独的状态目录，
+//! 其他平台使用本地数据目录
+
+use directories::ProjectDirs;
+use std::path::PathBuf;
+
+/// 本应用的目录，无法确定主目录时为 None
+fn project() -> Option<ProjectDirs> {
+    ProjectDirs::from("", "", "confconv")
+}
+
+/// 配置目录
+pub fn config_dir() -> Option<PathBuf> {
+    Some(project()?.config_dir().to_path_buf())
+}
+
+/// 缓存目录：可以随时删除的数据
+pub fn cache_dir() -> Option<PathBuf> {
+    Some(project()?.cache_dir().to_path_buf())
+}
+
+/// 状态目录：需要在多次运行之间保留、但不是配置的数据
+pub fn state_dir() -> Option<PathBuf> {
+    let project = project()?;
+    Some(
+        project
+            .state_dir()
+            .unwrap_or_else(|| project.data_local_dir())
+            .to_path_buf(),
+    )
+}
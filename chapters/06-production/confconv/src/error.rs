@@ -2,6 +2,8 @@
 //!
 //! 生产级项目应该有清晰的错误类型，而不是到处用 Box<dyn Error>
 
+use crate::i18n::tr;
+use crate::t;
 use std::fmt;
 use std::io;
 
@@ -13,39 +15,270 @@ pub enum Error {
     /// 文件写入错误
     FileWrite { path: String, source: io::Error },
     /// 格式解析错误
-    Parse { format: &'static str, source: String },
+    Parse {
+        format: &'static str,
+        source: String,
+    },
     /// 格式转换错误
     Convert { message: String },
     /// 无法推断格式
     UnknownFormat { path: String },
+    /// 部分文件验证失败
+    ValidationFailed { failed: usize, total: usize },
+    /// 部分文件无法格式化
+    FormatFailed { failed: usize, total: usize },
+    /// 流式验证时部分记录语法错误
+    RecordsInvalid { failed: usize, total: usize },
+    /// 部分断言未通过
+    TestsFailed { failed: usize, total: usize },
+    /// 尽量转换时有部分内容没有转换
+    BestEffort { problems: usize },
+    /// 检查发现错误
+    LintFailed { errors: usize },
+    /// 部分文件超出大小预算
+    BudgetExceeded { failed: usize, total: usize },
+    /// 有被 `--deny` 视为错误的警告
+    Denied { errors: usize },
+    /// 部分文件与快照不一致
+    SnapshotMismatch { changed: usize, total: usize },
+    /// 不可信输入超出资源限制
+    LimitExceeded { limit: &'static str, max: usize },
+    /// 部分文件的完整性校验失败
+    VerificationFailed { failed: usize, total: usize },
+    /// 签名或密钥错误
+    Signature { message: String },
+    /// 路径语法错误或无法在该路径上操作
+    InvalidPath { path: String, message: String },
+    /// 路径不存在
+    PathNotFound { path: String },
+    /// 路径已存在
+    PathExists { path: String },
+    /// 模板渲染错误
+    Template { message: String },
+    /// 外部命令执行失败
+    ExternalCommand { command: String, message: String },
+    /// 比较的两个文件存在差异
+    Differences { count: usize },
+    /// 配置与基准不一致
+    Drift { count: usize, files: usize },
+    /// 无法完成漂移检测
+    DriftCheck { source: Box<Error> },
+    /// 修改历史有误或无法撤销
+    History { path: String, message: String },
+    /// 配置中有应用不认识的键
+    UnusedKeys { count: usize },
+    /// 严格合并时发现冲突
+    MergeConflicts { count: usize },
+    /// 批量编辑中的某个操作失败
+    Operation { index: usize, source: Box<Error> },
+    /// 用户配置文件有误
+    Config { path: String, message: String },
+    /// WASM 变换模块加载或执行失败
+    Wasm { module: String, message: String },
+    /// 变换脚本编译或执行失败
+    Script { script: String, message: String },
+    /// 服务启动或运行失败
+    Service { message: String },
+    /// 无法解析密钥引用
+    Secret { reference: String, message: String },
 }
 
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::FileRead { path, source } => {
-                write!(f, "无法读取文件 '{}': {}", path, source)
+                write!(f, "{}", t!("无法读取文件 '{}': {}", path, source))
             }
             Error::FileWrite { path, source } => {
-                write!(f, "无法写入文件 '{}': {}", path, source)
+                write!(f, "{}", t!("无法写入文件 '{}': {}", path, source))
             }
             Error::Parse { format, source } => {
-                write!(f, "{} 解析失败: {}", format, source)
+                write!(f, "{}", t!("{} 解析失败: {}", tr(format), source))
             }
             Error::Convert { message } => {
-                write!(f, "转换失败: {}", message)
+                write!(f, "{}", t!("转换失败: {}", message))
             }
             Error::UnknownFormat { path } => {
+                let message = t!(
+                    "无法从文件扩展名推断格式: {}\n支持的扩展名: .json, .yaml, .yml, .toml（压缩文件可再加 .gz、.zst、.xz）",
+                    path
+                );
+                write!(f, "{}", message)
+            }
+            Error::ValidationFailed { failed, total } => {
+                write!(f, "{}", t!("{} 个文件中有 {} 个验证失败", total, failed))
+            }
+            Error::FormatFailed { failed, total } => {
+                let message = t!(
+                    "{} 个文件中有 {} 个无法格式化，没有修改任何文件",
+                    total,
+                    failed
+                );
+                write!(f, "{}", message)
+            }
+            Error::RecordsInvalid { failed, total } => {
+                write!(f, "{}", t!("{} 条记录中有 {} 条验证失败", total, failed))
+            }
+            Error::TestsFailed { failed, total } => {
+                write!(f, "{}", t!("{} 条断言中有 {} 条未通过", total, failed))
+            }
+            Error::BestEffort { problems } => {
+                write!(f, "{}", t!("已尽量转换，{} 处内容因出错被跳过", problems))
+            }
+            Error::LintFailed { errors } => {
+                write!(f, "{}", t!("检查发现 {} 个错误", errors))
+            }
+            Error::BudgetExceeded { failed, total } => {
+                write!(f, "{}", t!("{} 个文件中有 {} 个超出限制", total, failed))
+            }
+            Error::Denied { errors } => {
+                write!(f, "{}", t!("{} 个警告被 --deny 视为错误", errors))
+            }
+            Error::SnapshotMismatch { changed, total } => {
                 write!(
                     f,
-                    "无法从文件扩展名推断格式: {}\n支持的扩展名: .json, .yaml, .yml, .toml",
-                    path
+                    "{}",
+                    t!("{} 个文件中有 {} 个与快照不一致", total, changed)
+                )
+            }
+            Error::LimitExceeded { limit, max } => {
+                write!(f, "{}", t!("输入超出限制: {}最多为 {}", tr(limit), max))
+            }
+            Error::VerificationFailed { failed, total } => {
+                write!(f, "{}", t!("{} 个文件中有 {} 个校验失败", total, failed))
+            }
+            Error::Signature { message } => {
+                write!(f, "{}", t!("签名错误: {}", message))
+            }
+            Error::InvalidPath { path, message } => {
+                write!(f, "{}", t!("无效的路径 '{}': {}", path, message))
+            }
+            Error::PathNotFound { path } => {
+                write!(f, "{}", t!("路径不存在: {}", path))
+            }
+            Error::PathExists { path } => {
+                write!(f, "{}", t!("路径已存在: {}（使用 --force 覆盖）", path))
+            }
+            Error::Template { message } => {
+                write!(f, "{}", t!("模板渲染失败: {}", message))
+            }
+            Error::ExternalCommand { command, message } => {
+                write!(f, "{}", t!("执行 '{}' 失败: {}", command, message))
+            }
+            Error::Differences { count } => {
+                write!(f, "{}", t!("发现 {} 处差异", count))
+            }
+            Error::Drift { count, files } => {
+                write!(f, "{}", t!("{} 个文件中发现 {} 处漂移", files, count))
+            }
+            Error::DriftCheck { source } => {
+                write!(f, "{}", t!("无法完成漂移检测: {}", source))
+            }
+            Error::History { path, message } => {
+                write!(f, "{}", t!("无法撤销 '{}': {}", path, message))
+            }
+            Error::UnusedKeys { count } => {
+                write!(f, "{}", t!("发现 {} 个未使用的键", count))
+            }
+            Error::MergeConflicts { count } => {
+                write!(f, "{}", t!("发现 {} 处合并冲突，未输出结果", count))
+            }
+            Error::Operation { index, source } => {
+                write!(
+                    f,
+                    "{}",
+                    t!("第 {} 个操作失败，所有修改均未生效: {}", index, source)
                 )
             }
+            Error::Config { path, message } => {
+                write!(f, "{}", t!("配置文件 '{}' 有误: {}", path, message))
+            }
+            Error::Wasm { module, message } => {
+                write!(f, "{}", t!("WASM 模块 '{}' 执行失败: {}", module, message))
+            }
+            Error::Script { script, message } => {
+                write!(f, "{}", t!("脚本 '{}' 执行失败: {}", script, message))
+            }
+            Error::Service { message } => {
+                write!(f, "{}", t!("服务错误: {}", message))
+            }
+            Error::Secret { reference, message } => {
+                write!(f, "{}", t!("无法解析密钥引用 '{}': {}", reference, message))
+            }
         }
     }
 }
 
+impl Error {
+    /// 进程退出码：一般的错误为 1，需要与“检查未通过”区分的错误为 2
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::DriftCheck { .. } => 2,
+            _ => 1,
+        }
+    }
+
+    /// 错误的类别，用作 CI 注解的标题
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Error::FileRead { .. } => "read",
+            Error::FileWrite { .. } => "write",
+            Error::Parse { .. } => "parse",
+            Error::Convert { .. } => "convert",
+            Error::UnknownFormat { .. } | Error::FormatFailed { .. } => "format",
+            Error::ValidationFailed { .. } | Error::RecordsInvalid { .. } => "validation",
+            Error::TestsFailed { .. } => "test",
+            Error::BestEffort { .. } => "best-effort",
+            Error::LintFailed { .. } | Error::Denied { .. } => "lint",
+            Error::BudgetExceeded { .. } => "budget",
+            Error::SnapshotMismatch { .. } => "snapshot",
+            Error::LimitExceeded { .. } => "limit",
+            Error::VerificationFailed { .. } | Error::Signature { .. } => "signature",
+            Error::InvalidPath { .. } | Error::PathNotFound { .. } | Error::PathExists { .. } => {
+                "path"
+            }
+            Error::Template { .. } => "template",
+            Error::ExternalCommand { .. } => "command",
+            Error::Differences { .. } => "diff",
+            Error::Drift { .. } => "drift",
+            Error::DriftCheck { source } | Error::Operation { source, .. } => source.kind(),
+            Error::History { .. } => "history",
+            Error::UnusedKeys { .. } => "unused-keys",
+            Error::MergeConflicts { .. } => "merge",
+            Error::Config { .. } => "config",
+            Error::Wasm { .. } => "wasm",
+            Error::Script { .. } => "script",
+            Error::Service { .. } => "service",
+            Error::Secret { .. } => "secret",
+        }
+    }
+
+    /// 错误所在的文件（错误中带有路径时）
+    pub fn file(&self) -> Option<&str> {
+        match self {
+            Error::FileRead { path, .. }
+            | Error::FileWrite { path, .. }
+            | Error::UnknownFormat { path }
+            | Error::History { path, .. }
+            | Error::Config { path, .. } => Some(path),
+            Error::DriftCheck { source } | Error::Operation { source, .. } => source.file(),
+            _ => None,
+        }
+    }
+
+    /// 是否只是汇总：各个文件或断言的问题已经逐个报告（和注解）过了
+    pub fn is_summary(&self) -> bool {
+        matches!(
+            self,
+            Error::ValidationFailed { .. }
+                | Error::FormatFailed { .. }
+                | Error::TestsFailed { .. }
+                | Error::LintFailed { .. }
+                | Error::Denied { .. }
+        )
+    }
+}
+
 impl std::error::Error for Error {}
 
 /// 结果类型别名
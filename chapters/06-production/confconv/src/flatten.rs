@@ -0,0 +1,150 @@
+//! 扁平化
+//!
+//! 把嵌套结构展开为 (路径, 标量) 列表，供 env、args 等需要"键 = 值"形式输出的命令共用；
+//! 也可以反过来把这样的列表还原为嵌套结构
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::path::{Path, Segment};
+use crate::t;
+use serde_json::{Map, Value};
+
+/// 扁平化后的一项：路径的各段和叶子上的标量值
+#[derive(Debug)]
+pub struct Entry<'a> {
+    /// 路径各段（数组元素用下标表示）
+    pub path: Vec<String>,
+    /// 同一路径，可用于与 [`crate::path::Pattern`] 匹配
+    pub location: Path,
+    /// 叶子值（不会是对象或数组）
+    pub value: &'a Value,
+}
+
+impl Entry<'_> {
+    /// 叶子值的文本形式：字符串不带引号，null 为空字符串
+    pub fn text(&self) -> String {
+        scalar_text(self.value)
+    }
+}
+
+/// 展开为叶子列表，空对象和空数组没有叶子，不会出现在结果中
+pub fn flatten(value: &Value) -> Vec<Entry<'_>> {
+    let mut entries = Vec::new();
+    walk(value, &mut Vec::new(), &Path::default(), &mut entries);
+    entries
+}
+
+fn walk<'a>(
+    value: &'a Value,
+    path: &mut Vec<String>,
+    location: &Path,
+    entries: &mut Vec<Entry<'a>>,
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                path.push(key.clone());
+                walk(
+                    child,
+                    path,
+                    &location.join(Segment::Key(key.clone())),
+                    entries,
+                );
+                path.pop();
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter().enumerate() {
+                path.push(i.to_string());
+                walk(
+                    child,
+                    path,
+                    &location.join(Segment::Index(i as i64)),
+                    entries,
+                );
+                path.pop();
+            }
+        }
+        _ => entries.push(Entry {
+            path: path.clone(),
+            location: location.clone(),
+            value,
+        }),
+    }
+}
+
+/// 标量的文本形式：字符串不带引号，null 为空字符串
+pub fn scalar_text(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// 把文本按 YAML 标量解释（`3` 是数字、`true` 是布尔、`null` 是空值），
+/// 其他写法（包括空文本和看起来像对象或数组的）一律作为字符串
+pub fn parse_scalar(text: &str) -> Value {
+    match codec::parse(text, Format::Yaml) {
+        Ok(value @ (Value::Bool(_) | Value::Number(_))) => value,
+        Ok(Value::Null) if !text.trim().is_empty() => Value::Null,
+        _ => Value::String(text.to_string()),
+    }
+}
+
+/// 把 (路径, 值) 列表还原为嵌套结构
+///
+/// 各段都是对象的键；键恰好是 `0..n` 的对象最后还原为数组。
+/// 同一路径既是叶子又有子项时报错
+pub fn unflatten(entries: Vec<(Vec<String>, Value)>) -> Result<Value> {
+    let mut root = Map::new();
+    for (path, value) in entries {
+        let Some((last, parents)) = path.split_last() else {
+            continue;
+        };
+        let conflict = || Error::Convert {
+            message: t!("{} 既是值又包含子项", path.join("/")),
+        };
+        let mut current = &mut root;
+        for segment in parents {
+            current = match current
+                .entry(segment.clone())
+                .or_insert_with(|| Value::Object(Map::new()))
+            {
+                Value::Object(map) => map,
+                _ => return Err(conflict()),
+            };
+        }
+        if current.contains_key(last) {
+            return Err(conflict());
+        }
+        current.insert(last.clone(), value);
+    }
+    Ok(restore_arrays(Value::Object(root)))
+}
+
+/// 把键恰好是 `0..n` 的对象转换为数组
+fn restore_arrays(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let is_array =
+                !map.is_empty() && (0..map.len()).all(|i| map.contains_key(i.to_string().as_str()));
+            if is_array {
+                let mut map = map;
+                Value::Array(
+                    (0..map.len())
+                        .map(|i| restore_arrays(map.remove(&i.to_string()).unwrap_or_default()))
+                        .collect(),
+                )
+            } else {
+                Value::Object(
+                    map.into_iter()
+                        .map(|(key, child)| (key, restore_arrays(child)))
+                        .collect(),
+                )
+            }
+        }
+        other => other,
+    }
+}
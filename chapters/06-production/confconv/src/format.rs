@@ -1,9 +1,15 @@
 //! 配置文件格式定义
 
+use crate::compress;
+use crate::i18n::tr;
+use crate::plugin::{self, Plugin};
+use crate::t;
+use clap::builder::PossibleValue;
 use clap::ValueEnum;
+use std::sync::OnceLock;
 
 /// 支持的配置文件格式
-#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Format {
     /// JSON 格式
     Json,
@@ -11,17 +17,70 @@ pub enum Format {
     Yaml,
     /// TOML 格式
     Toml,
+    /// INI 格式，见 [`crate::ini`]
+    Ini,
+    /// 由外部插件提供的格式
+    Plugin(&'static Plugin),
+}
+
+/// 可选的取值包括内置格式和在 `PATH` 中发现的插件
+impl ValueEnum for Format {
+    fn value_variants<'a>() -> &'a [Self] {
+        static ALL: OnceLock<Vec<Format>> = OnceLock::new();
+        ALL.get_or_init(|| {
+            [Format::Json, Format::Yaml, Format::Toml, Format::Ini]
+                .into_iter()
+                .chain(plugin::discover().iter().map(Format::Plugin))
+                .collect()
+        })
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        let value = PossibleValue::new(self.extension());
+        Some(match self {
+            Format::Json => value.help(tr("JSON 格式")),
+            Format::Yaml => value.help(tr("YAML 格式")),
+            Format::Toml => value.help(tr("TOML 格式")),
+            Format::Ini => value.help(tr("INI 格式（段对应表，所有值都是字符串）")),
+            Format::Plugin(plugin) => value.help(t!("插件 {}", plugin.path.display())),
+        })
+    }
 }
 
 impl Format {
     /// 从文件扩展名推断格式
     pub fn from_extension(path: &str) -> Option<Self> {
         let ext = path.rsplit('.').next()?.to_lowercase();
-        match ext.as_str() {
-            "json" => Some(Format::Json),
-            "yaml" | "yml" => Some(Format::Yaml),
-            "toml" => Some(Format::Toml),
-            _ => None,
+        Self::value_variants()
+            .iter()
+            .copied()
+            .find(|format| format.extensions().contains(&ext.as_str()))
+    }
+
+    /// 从文件路径推断格式，压缩文件按内层扩展名推断（`a.json.gz` → JSON）
+    pub fn from_path(path: &str) -> Option<Self> {
+        Self::from_extension(compress::strip_extension(path))
+    }
+
+    /// 获取输出文件使用的扩展名
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+            Format::Ini => "ini",
+            Format::Plugin(plugin) => plugin.name,
+        }
+    }
+
+    /// 识别的扩展名（不含点），第一个即输出文件使用的扩展名
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Format::Json => &["json"],
+            Format::Yaml => &["yaml", "yml"],
+            Format::Toml => &["toml"],
+            Format::Ini => &["ini", "cfg"],
+            Format::Plugin(plugin) => std::slice::from_ref(&plugin.name),
         }
     }
 
@@ -31,6 +90,71 @@ impl Format {
             Format::Json => "JSON",
             Format::Yaml => "YAML",
             Format::Toml => "TOML",
+            Format::Ini => "INI",
+            Format::Plugin(plugin) => plugin.name,
+        }
+    }
+
+    /// 格式能表示哪些内容，插件格式按 JSON 的能力对待
+    pub fn capabilities(&self) -> Capabilities {
+        match self {
+            Format::Json | Format::Plugin(_) => Capabilities {
+                nulls: true,
+                datetimes: false,
+                comments: false,
+                non_string_keys: false,
+                binary: false,
+                multi_document: false,
+                scalar_root: true,
+            },
+            Format::Yaml => Capabilities {
+                nulls: true,
+                // YAML 1.2 的核心模式没有时间戳类型
+                datetimes: false,
+                comments: true,
+                non_string_keys: true,
+                binary: true,
+                multi_document: true,
+                scalar_root: true,
+            },
+            Format::Toml => Capabilities {
+                nulls: false,
+                datetimes: true,
+                comments: true,
+                non_string_keys: false,
+                binary: false,
+                multi_document: false,
+                scalar_root: false,
+            },
+            // 所有值都是字符串，见 crate::ini
+            Format::Ini => Capabilities {
+                nulls: false,
+                datetimes: false,
+                comments: true,
+                non_string_keys: false,
+                binary: false,
+                multi_document: false,
+                scalar_root: false,
+            },
         }
     }
 }
+
+/// 格式的能力，转换前据此检查哪些值在目标格式中无法原样表示，见 [`crate::lossy::check_target`]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Capabilities {
+    /// 有 null
+    pub nulls: bool,
+    /// 有日期时间类型
+    pub datetimes: bool,
+    /// 有注释语法
+    pub comments: bool,
+    /// 映射的键可以是数字、布尔等非字符串的值
+    pub non_string_keys: bool,
+    /// 有二进制数据类型（如 YAML 的 `!!binary`）
+    pub binary: bool,
+    /// 一个文件可以包含多个文档
+    pub multi_document: bool,
+    /// 顶层可以是对象以外的值
+    pub scalar_root: bool,
+}
@@ -0,0 +1,432 @@
+//! 文件写入工具
+//!
+//! 原地修改（--write）时先写临时文件再重命名，保证不会留下写了一半的文件；
+//! 同时保留原文件的权限位（Unix 下尽量保留属主），可选保留修改时间，
+//! 并把修改前的内容记入历史（见 [`history`](crate::history)）。
+//! 两种写入都会先触发 `pre_write` 钩子。
+//!
+//! 一次写多个文件的命令（批量 format、convert 等）在 [`transaction`] 中执行：
+//! 所有输出先暂存，命令成功后才一起写入；其中任何一个文件写入失败时，已经替换的文件会被还原，
+//! 不会留下一半已迁移的仓库。替换输出文件时沿用原文件的权限位，`0600` 的文件不会变成所有人可读。
+//! 对象存储（`s3://`、`gs://`）上的输出不在事务中，直接写入；管道、设备等不是普通文件的目标
+//! 和有多个硬链接的输出文件不能用改名替换，在其他文件都替换完成后直接写入，失败时无法还原
+
+use crate::error::{Error, Result};
+use crate::history;
+use crate::hooks::{self, Hook};
+use crate::remote;
+use std::cell::RefCell;
+use std::fs::{self, File, Metadata};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// 事务中暂存的一次写入
+struct Staged {
+    path: String,
+    content: Vec<u8>,
+    /// 原地写入：保留原文件的元数据
+    in_place: Option<InPlace>,
+}
+
+#[derive(Clone, Copy)]
+struct InPlace {
+    preserve_mtime: bool,
+    record: bool,
+}
+
+thread_local! {
+    /// 当前事务中暂存的写入，不在事务中时为 None
+    static STAGED: RefCell<Option<Vec<Staged>>> = const { RefCell::new(None) };
+}
+
+/// 在事务中执行 `f`：其中的文件写入先暂存，`f` 成功后一起写入，失败时什么也不写
+///
+/// 写入时先把所有内容写到临时文件，再逐个替换目标文件；替换到一半失败时把已替换的文件还原
+pub fn transaction<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    let nested = STAGED.with(|staged| {
+        let mut staged = staged.borrow_mut();
+        let nested = staged.is_some();
+        staged.get_or_insert_with(Vec::new);
+        nested
+    });
+    // 嵌套的事务并入外层
+    if nested {
+        return f();
+    }
+    let result = f();
+    let staged = STAGED.with(|staged| staged.borrow_mut().take().unwrap_or_default());
+    let value = result?;
+    commit(staged)?;
+    Ok(value)
+}
+
+/// 在事务中时暂存写入并返回 true
+fn stage(path: &str, content: &[u8], in_place: Option<InPlace>) -> bool {
+    STAGED.with(|staged| {
+        let mut staged = staged.borrow_mut();
+        let Some(staged) = staged.as_mut() else {
+            return false;
+        };
+        // 同一个文件写了多次时只保留最后一次
+        staged.retain(|entry| entry.path != path);
+        staged.push(Staged {
+            path: path.to_string(),
+            content: content.to_vec(),
+            in_place,
+        });
+        true
+    })
+}
+
+/// 写入（或覆盖）输出文件，路径是 `s3://`、`gs://` URI 时写到对象存储
+pub fn write(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> Result<()> {
+    let path = path.as_ref();
+    let display = path.display().to_string();
+    hooks::run(Hook::PreWrite, &[("CONFCONV_FILE", &display)])?;
+    if remote::is_remote(&display) {
+        return remote::write(&display, content.as_ref());
+    }
+    if stage(&display, content.as_ref(), None) {
+        return Ok(());
+    }
+    fs::write(path, content).map_err(|e| Error::FileWrite {
+        path: display,
+        source: e,
+    })
+}
+
+/// 原地写入文件，保留原文件的元数据
+///
+/// - 权限位：始终保留（例如可执行位）
+/// - 属主/属组：Unix 下尽力保留，没有权限时忽略
+/// - 修改时间：仅在 `preserve_mtime` 为 true 时保留
+pub fn write_in_place(path: &str, content: &str, preserve_mtime: bool) -> Result<()> {
+    replace(
+        path,
+        content.as_bytes(),
+        InPlace {
+            preserve_mtime,
+            record: true,
+        },
+    )
+}
+
+/// 同 [`write_in_place`]，但不记入历史，用于 undo 恢复旧版本
+pub fn restore_in_place(path: &str, content: &[u8]) -> Result<()> {
+    replace(
+        path,
+        content,
+        InPlace {
+            preserve_mtime: false,
+            record: false,
+        },
+    )
+}
+
+/// 写出只有所有者可以读写的文件（Unix 下为 `0600`），用于历史记录、缓存等私有数据
+pub fn write_private(path: &Path, contents: &[u8]) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(contents)
+}
+
+fn replace(path: &str, content: &[u8], options: InPlace) -> Result<()> {
+    hooks::run(Hook::PreWrite, &[("CONFCONV_FILE", path)])?;
+    if stage(path, content, Some(options)) {
+        return Ok(());
+    }
+    let to_error = |e| Error::FileWrite {
+        path: path.to_string(),
+        source: e,
+    };
+
+    // 解析符号链接，替换的是链接指向的真实文件而不是链接本身
+    let target = fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    let metadata = fs::metadata(&target).ok();
+
+    // 文件还不存在或不是普通文件时，没有元数据需要保留，直接写入
+    let Some(metadata) = metadata.filter(|m| m.is_file()) else {
+        return fs::write(&target, content).map_err(to_error);
+    };
+
+    let before = if options.record {
+        fs::read(&target).ok()
+    } else {
+        None
+    };
+    let tmp =
+        write_temp(&target, content, Some(&metadata), options.preserve_mtime).map_err(to_error)?;
+    if let Err(e) = fs::rename(&tmp, &target) {
+        let _ = fs::remove_file(&tmp);
+        return Err(to_error(e));
+    }
+    if let Some(before) = before {
+        history::record(&target, &before, content);
+    }
+    Ok(())
+}
+
+/// 把内容写到目标旁边的临时文件，`metadata` 是要保留的原文件元数据（修改时间只在 `preserve_mtime` 时保留）
+fn write_temp(
+    target: &Path,
+    content: &[u8],
+    metadata: Option<&Metadata>,
+    preserve_mtime: bool,
+) -> io::Result<PathBuf> {
+    let tmp = temp_path(target, "tmp");
+    let result = (|| {
+        let mut file = File::create(&tmp)?;
+        // 先设置权限再写入内容，原文件不可读的用户在写入过程中也读不到
+        if let Some(metadata) = metadata {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.write_all(content)?;
+        if let Some(metadata) = metadata {
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::MetadataExt;
+                // 非 root 用户通常无法修改属主，失败时保持默认即可
+                let _ =
+                    std::os::unix::fs::fchown(&file, Some(metadata.uid()), Some(metadata.gid()));
+            }
+            if preserve_mtime {
+                if let Ok(modified) = metadata.modified() {
+                    file.set_modified(modified)?;
+                }
+            }
+        }
+        file.sync_all()
+    })();
+    match result {
+        Ok(()) => Ok(tmp),
+        Err(e) => {
+            let _ = fs::remove_file(&tmp);
+            Err(e)
+        }
+    }
+}
+
+/// 准备好的一次替换
+struct Prepared {
+    path: String,
+    target: PathBuf,
+    tmp: PathBuf,
+    /// 原文件改名后的备份，替换完成前用于还原
+    backup: Option<PathBuf>,
+    /// 需要记入历史的原内容
+    before: Option<Vec<u8>>,
+    content: Vec<u8>,
+}
+
+/// 提交事务：先写所有临时文件，再逐个替换，失败时还原
+fn commit(staged: Vec<Staged>) -> Result<()> {
+    let mut prepared: Vec<Prepared> = Vec::with_capacity(staged.len());
+    let mut direct = Vec::new();
+    for entry in staged {
+        let metadata = fs::metadata(&entry.path).ok();
+        if metadata.as_ref().is_some_and(|metadata| {
+            !metadata.is_file() || (entry.in_place.is_none() && hard_links(metadata) > 1)
+        }) {
+            direct.push(entry);
+            continue;
+        }
+        let target = fs::canonicalize(&entry.path).unwrap_or_else(|_| PathBuf::from(&entry.path));
+        let in_place = entry.in_place.filter(|_| metadata.is_some());
+        let before = in_place
+            .filter(|options| options.record)
+            .and_then(|_| fs::read(&target).ok());
+        let written = write_temp(
+            &target,
+            &entry.content,
+            metadata.as_ref(),
+            in_place.is_some_and(|options| options.preserve_mtime),
+        );
+        match written {
+            Ok(tmp) => prepared.push(Prepared {
+                path: entry.path,
+                target,
+                tmp,
+                backup: None,
+                before,
+                content: entry.content,
+            }),
+            Err(e) => {
+                discard(&prepared);
+                return Err(Error::FileWrite {
+                    path: entry.path,
+                    source: e,
+                });
+            }
+        }
+    }
+
+    for i in 0..prepared.len() {
+        if let Err(e) = swap(&mut prepared[i]) {
+            rollback(&prepared[..i]);
+            discard(&prepared[i..]);
+            return Err(Error::FileWrite {
+                path: prepared[i].path.clone(),
+                source: e,
+            });
+        }
+    }
+
+    for entry in prepared {
+        if let Some(backup) = &entry.backup {
+            let _ = fs::remove_file(backup);
+        }
+        if let Some(before) = &entry.before {
+            history::record(&entry.target, before, &entry.content);
+        }
+    }
+
+    for entry in direct {
+        fs::write(&entry.path, &entry.content).map_err(|e| Error::FileWrite {
+            path: entry.path,
+            source: e,
+        })?;
+    }
+    Ok(())
+}
+
+/// 文件的硬链接数，不支持的平台上视为 1
+fn hard_links(metadata: &Metadata) -> u64 {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        metadata.nlink()
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = metadata;
+        1
+    }
+}
+
+/// 把原文件改名为备份，再把临时文件改名为目标文件
+fn swap(entry: &mut Prepared) -> io::Result<()> {
+    if entry.target.exists() {
+        let backup = temp_path(&entry.target, "bak");
+        fs::rename(&entry.target, &backup)?;
+        entry.backup = Some(backup);
+    }
+    if let Err(e) = fs::rename(&entry.tmp, &entry.target) {
+        if let Some(backup) = entry.backup.take() {
+            let _ = fs::rename(&backup, &entry.target);
+        }
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// 还原已经替换的文件：有备份的改回原文件，原来不存在的删除
+fn rollback(done: &[Prepared]) {
+    for entry in done.iter().rev() {
+        match &entry.backup {
+            Some(backup) => {
+                let _ = fs::rename(backup, &entry.target);
+            }
+            None => {
+                let _ = fs::remove_file(&entry.target);
+            }
+        }
+    }
+}
+
+/// 删除还没有用到的临时文件
+fn discard(pending: &[Prepared]) {
+    for entry in pending {
+        let _ = fs::remove_file(&entry.tmp);
+    }
+}
+
+/// 同目录下的临时文件路径（保证 rename 不跨文件系统），`kind` 区分临时文件和备份
+fn temp_path(target: &Path, kind: &str) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target.with_file_name(format!(
+        ".{}.confconv-{}.{}",
+        name,
+        std::process::id(),
+        kind
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    #[test]
+    fn transaction_writes_all_or_nothing() {
+        let dir = env::temp_dir().join(format!("confconv-transaction-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let a = dir.join("a.txt");
+        let b = dir.join("b.txt");
+        fs::write(&a, "old").unwrap();
+
+        let result: Result<()> = transaction(|| {
+            write(&a, "new")?;
+            write(&b, "new")?;
+            Err(Error::Convert {
+                message: "失败".into(),
+            })
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old");
+        assert!(!b.exists());
+
+        transaction(|| {
+            write(&a, "new")?;
+            write(&b, "new")
+        })
+        .unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "new");
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn outputs_keep_permissions_and_pipes_stay_pipes() {
+        use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+        use std::process::Command;
+
+        let dir = env::temp_dir().join(format!("confconv-special-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let secret = dir.join("secret.yaml");
+        fs::write(&secret, "old").unwrap();
+        fs::set_permissions(&secret, fs::Permissions::from_mode(0o600)).unwrap();
+        transaction(|| write(&secret, "new")).unwrap();
+        assert_eq!(fs::read_to_string(&secret).unwrap(), "new");
+        assert_eq!(
+            fs::metadata(&secret).unwrap().permissions().mode() & 0o777,
+            0o600
+        );
+
+        let fifo = dir.join("fifo");
+        assert!(Command::new("mkfifo")
+            .arg(&fifo)
+            .status()
+            .unwrap()
+            .success());
+        let reader = {
+            let fifo = fifo.clone();
+            std::thread::spawn(move || fs::read_to_string(fifo).unwrap())
+        };
+        transaction(|| write(&fifo, "through the pipe")).unwrap();
+        assert_eq!(reader.join().unwrap(), "through the pipe");
+        assert!(fs::metadata(&fifo).unwrap().file_type().is_fifo());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
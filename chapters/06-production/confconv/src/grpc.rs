@@ -0,0 +1,140 @@
+//! gRPC 服务
+//!
+//! 协议定义在 `proto/confconv.proto`，构建时由 `build.rs` 生成代码。
+//! 请求中的文档都按不可信输入解析（见 [`codec::parse_untrusted`]），
+//! 输入有误时返回 `INVALID_ARGUMENT`
+
+use crate::codec::{self, OutputOptions};
+use crate::diff::{self, Change};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::t;
+use clap::ValueEnum;
+use proto::confconv_server::{Confconv, ConfconvServer};
+use proto::{
+    change, ConvertRequest, ConvertResponse, DiffRequest, DiffResponse, Document, ValidateRequest,
+    ValidateResponse,
+};
+use serde_json::Value;
+use std::net::SocketAddr;
+use tonic::{Request, Response, Status};
+
+/// 由 `proto/confconv.proto` 生成的消息和服务
+#[allow(clippy::all)]
+pub mod proto {
+    tonic::include_proto!("confconv.v1");
+}
+
+/// 在 `addr` 上提供服务，直到进程退出
+pub fn serve(addr: SocketAddr) -> Result<()> {
+    let error = |message: String| Error::Service { message };
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| error(e.to_string()))?;
+    runtime
+        .block_on(
+            tonic::transport::Server::builder()
+                .add_service(ConfconvServer::new(Service))
+                .serve(addr),
+        )
+        .map_err(|e| error(e.to_string()))
+}
+
+/// 服务实现，每个请求独立处理，不保存状态
+struct Service;
+
+#[tonic::async_trait]
+impl Confconv for Service {
+    async fn convert(
+        &self,
+        request: Request<ConvertRequest>,
+    ) -> std::result::Result<Response<ConvertResponse>, Status> {
+        let request = request.into_inner();
+        let (value, _) = parse(request.input.as_ref())?;
+        let to =
+            format(&request.to)?.ok_or_else(|| Status::invalid_argument(t!("缺少目标格式")))?;
+        let style = OutputOptions {
+            pretty: request.pretty,
+            ..OutputOptions::default()
+        };
+        let content = codec::serialize_with(&value, to, &style).map_err(invalid)?;
+        Ok(Response::new(ConvertResponse { content }))
+    }
+
+    async fn validate(
+        &self,
+        request: Request<ValidateRequest>,
+    ) -> std::result::Result<Response<ValidateResponse>, Status> {
+        let request = request.into_inner();
+        let response = match parse(request.input.as_ref()) {
+            Ok((_, format)) => ValidateResponse {
+                valid: true,
+                error: String::new(),
+                format: format.extension().to_string(),
+            },
+            Err(status) => ValidateResponse {
+                valid: false,
+                error: status.message().to_string(),
+                format: String::new(),
+            },
+        };
+        Ok(Response::new(response))
+    }
+
+    async fn diff(
+        &self,
+        request: Request<DiffRequest>,
+    ) -> std::result::Result<Response<DiffResponse>, Status> {
+        let request = request.into_inner();
+        let (old, _) = parse(request.old.as_ref())?;
+        let (new, _) = parse(request.new.as_ref())?;
+        let options = diff::Options {
+            ignore_order: request.ignore_order,
+            ignore_case: request.ignore_case,
+            ..diff::Options::default()
+        };
+        let changes = diff::diff(&old, &new, &options)
+            .iter()
+            .map(|change| match change {
+                Change::Added { path, value } => proto::Change {
+                    kind: change::Kind::Added.into(),
+                    path: path.to_string(),
+                    old: String::new(),
+                    new: value.to_string(),
+                },
+                Change::Removed { path, value } => proto::Change {
+                    kind: change::Kind::Removed.into(),
+                    path: path.to_string(),
+                    old: value.to_string(),
+                    new: String::new(),
+                },
+                Change::Changed { path, old, new } => proto::Change {
+                    kind: change::Kind::Changed.into(),
+                    path: path.to_string(),
+                    old: old.to_string(),
+                    new: new.to_string(),
+                },
+            })
+            .collect();
+        Ok(Response::new(DiffResponse { changes }))
+    }
+}
+
+/// 按不可信输入解析请求中的文档
+fn parse(document: Option<&Document>) -> std::result::Result<(Value, Format), Status> {
+    let document = document.ok_or_else(|| Status::invalid_argument(t!("缺少文档")))?;
+    codec::parse_untrusted(document.content.as_bytes(), format(&document.format)?).map_err(invalid)
+}
+
+/// 按名称查找格式，空字符串表示未指定
+fn format(name: &str) -> std::result::Result<Option<Format>, Status> {
+    if name.is_empty() {
+        return Ok(None);
+    }
+    Format::from_str(name, true)
+        .map(Some)
+        .map_err(|_| Status::invalid_argument(t!("未知的格式: {}", name)))
+}
+
+/// 把错误转换为 `INVALID_ARGUMENT`
+fn invalid(error: Error) -> Status {
+    Status::invalid_argument(error.to_string())
+}
@@ -0,0 +1,211 @@
+//! 原地修改的历史
+//!
+//! `--write` 等原地修改文件的操作会把修改前的内容记录到状态目录（见 [`dirs`](crate::dirs)）
+//! 下的 `history` 中，`confconv undo FILE` 可以恢复到上一个版本。每个文件一个子目录
+//! （按规范化后的路径的 sha256 命名），每次修改对应两个文件：
+//! - `<时间戳>.json`：文件路径、时间、命令以及修改前后内容的 sha256
+//! - `<时间戳>.gz`：修改前的完整内容
+//!
+//! 保存完整内容而不是内容的哈希加差异：配置文件通常只有几 KB，压缩后更小，
+//! 完整副本让每条记录都能单独恢复，不依赖更早或更晚的记录，按 `keep` 删除旧记录时
+//! 也不需要重新计算差异；记录中的 sha256 只用来判断文件在那次修改之后有没有再被改过
+//!
+//! 每个文件默认保留最近 20 次修改，可以在配置文件中调整，0 表示不记录：
+//!
+//! ```toml
+//! [history]
+//! keep = 50
+//! ```
+//!
+//! 记录中有修改前的完整内容，可能包含密钥，所以目录只对所有者开放（0700），
+//! 文件只对所有者可读写（0600）
+//!
+//! 没有调用过 [`init`] 时（如作为库使用）不记录
+
+use crate::compress::{self, Compression};
+use crate::config::HistoryConfig;
+use crate::dirs;
+use crate::error::{Error, Result};
+use crate::fsutil;
+use crate::log;
+use crate::stamp::sha256_hex;
+use crate::t;
+use chrono::DateTime;
+use flate2::write::GzEncoder;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+static HISTORY: OnceLock<(HistoryConfig, String)> = OnceLock::new();
+
+/// 一次修改
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Entry {
+    /// 记录的标识（时间戳），也是记录的文件名
+    #[serde(skip)]
+    pub id: String,
+    /// 被修改的文件
+    pub path: String,
+    /// 修改的时间（RFC 3339）
+    pub time: String,
+    /// 执行的命令，如 `format`
+    pub operation: String,
+    /// 修改前内容的 sha256
+    pub before: String,
+    /// 修改后内容的 sha256
+    pub after: String,
+}
+
+/// 设置本次运行的历史配置和正在执行的命令，只有第一次调用有效
+pub fn init(config: HistoryConfig, operation: &str) {
+    let _ = HISTORY.set((config, operation.to_string()));
+}
+
+/// 记录一次修改；失败时只给出警告，不影响修改本身
+pub fn record(target: &Path, before: &[u8], after: &[u8]) {
+    let Some((config, operation)) = HISTORY.get() else {
+        return;
+    };
+    if config.keep == 0 || before == after {
+        return;
+    }
+    if let Err(e) = try_record(target, before, after, operation, config.keep) {
+        log!(
+            Normal,
+            "警告: 无法记录 {} 的修改历史: {}",
+            target.display(),
+            e
+        );
+    }
+}
+
+fn try_record(
+    target: &Path,
+    before: &[u8],
+    after: &[u8],
+    operation: &str,
+    keep: usize,
+) -> io::Result<()> {
+    let dir = dir(target).ok_or_else(|| io::Error::other(t!("无法确定状态目录")))?;
+    create_private_dir(&dir)?;
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    // 固定宽度，按文件名排序即按时间排序
+    let id = format!("{:020}", now.as_nanos());
+    let entry = Entry {
+        id: id.clone(),
+        path: target.display().to_string(),
+        time: DateTime::from_timestamp(now.as_secs() as i64, now.subsec_nanos())
+            .map(|time| time.to_rfc3339())
+            .unwrap_or_default(),
+        operation: operation.to_string(),
+        before: sha256_hex(before),
+        after: sha256_hex(after),
+    };
+
+    let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(before)?;
+    fsutil::write_private(&dir.join(format!("{}.gz", id)), &encoder.finish()?)?;
+    let json = serde_json::to_string_pretty(&entry).map_err(io::Error::other)?;
+    fsutil::write_private(&dir.join(format!("{}.json", id)), json.as_bytes())?;
+
+    // 只保留最近的 keep 条
+    let ids = ids(&dir)?;
+    for old in &ids[..ids.len().saturating_sub(keep)] {
+        remove_files(&dir, old);
+    }
+    Ok(())
+}
+
+/// 文件的所有修改记录，从旧到新
+pub fn entries(target: &Path) -> Result<Vec<Entry>> {
+    let Some(dir) = dir(target).filter(|dir| dir.is_dir()) else {
+        return Ok(Vec::new());
+    };
+    let read_error = |path: &Path, e| Error::FileRead {
+        path: path.display().to_string(),
+        source: e,
+    };
+    let mut entries = Vec::new();
+    for id in ids(&dir).map_err(|e| read_error(&dir, e))? {
+        let path = dir.join(format!("{}.json", id));
+        let text = fs::read_to_string(&path).map_err(|e| read_error(&path, e))?;
+        let mut entry: Entry = serde_json::from_str(&text).map_err(|e| Error::Parse {
+            format: "JSON",
+            source: format!("{}: {}", path.display(), e),
+        })?;
+        entry.id = id;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// 读取修改前的内容
+pub fn before(target: &Path, entry: &Entry) -> Result<Vec<u8>> {
+    let path = dir(target)
+        .unwrap_or_default()
+        .join(format!("{}.gz", entry.id));
+    fs::read(&path)
+        .and_then(|bytes| Compression::Gzip.decompress(&bytes, compress::MAX_SIZE))
+        .map_err(|e| Error::FileRead {
+            path: path.display().to_string(),
+            source: e,
+        })
+}
+
+/// 删除一条记录
+pub fn remove(target: &Path, entry: &Entry) {
+    if let Some(dir) = dir(target) {
+        remove_files(&dir, &entry.id);
+    }
+}
+
+/// 文件的历史目录
+fn dir(target: &Path) -> Option<PathBuf> {
+    let path = fs::canonicalize(target)
+        .or_else(|_| std::path::absolute(target))
+        .ok()?;
+    let hash = sha256_hex(path.to_string_lossy().as_bytes());
+    Some(dirs::state_dir()?.join("history").join(&hash[..16]))
+}
+
+/// 创建文件的历史目录，它和上一级的 `history` 目录都只对所有者开放
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        builder.mode(0o700);
+        builder.create(dir)?;
+        // 已经存在的目录不受 mode 影响
+        for dir in [Some(dir), dir.parent()].into_iter().flatten() {
+            fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(())
+    }
+    #[cfg(not(unix))]
+    builder.create(dir)
+}
+
+/// 目录中所有记录的标识，从旧到新
+fn ids(dir: &Path) -> io::Result<Vec<String>> {
+    let mut ids: Vec<String> = fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let name = entry.ok()?.file_name().into_string().ok()?;
+            name.strip_suffix(".json").map(str::to_string)
+        })
+        .collect();
+    ids.sort();
+    Ok(ids)
+}
+
+fn remove_files(dir: &Path, id: &str) {
+    let _ = fs::remove_file(dir.join(format!("{}.json", id)));
+    let _ = fs::remove_file(dir.join(format!("{}.gz", id)));
+}
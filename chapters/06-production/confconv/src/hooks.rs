@@ -0,0 +1,83 @@
+//! 钩子
+//!
+//! 在配置文件的 `[hooks]` 段中配置，每个钩子是按顺序执行的 shell 命令列表：
+//!
+//! ```toml
+//! [hooks]
+//! # 写入每个文件之前执行，失败（非零退出）时不写入
+//! pre_write = ["test -w \"$(dirname \"$CONFCONV_FILE\")\""]
+//! # convert 写出每个结果之后执行
+//! post_convert = ["git add \"$CONFCONV_FILE\""]
+//! ```
+//!
+//! 命令通过环境变量获得操作信息：
+//! - `CONFCONV_HOOK`：钩子名称
+//! - `CONFCONV_OPERATION`：执行的命令（如 `convert`、`format`）
+//! - `CONFCONV_FILE`：写入的文件，输出到标准输出时为 `-`
+//! - `CONFCONV_INPUT`、`CONFCONV_FORMAT`：post_convert 的输入文件和目标格式（如 `yaml`）
+//!
+//! 钩子的标准输出会转到标准错误，不会混入 confconv 的输出
+
+use crate::config::HooksConfig;
+use crate::error::{Error, Result};
+use crate::log;
+use crate::process;
+use crate::t;
+use std::sync::OnceLock;
+
+static HOOKS: OnceLock<(HooksConfig, String)> = OnceLock::new();
+
+/// 钩子的触发时机
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hook {
+    /// 写入文件之前
+    PreWrite,
+    /// convert 写出结果之后
+    PostConvert,
+}
+
+impl Hook {
+    /// 配置文件和 `CONFCONV_HOOK` 中使用的名称
+    pub fn name(self) -> &'static str {
+        match self {
+            Hook::PreWrite => "pre_write",
+            Hook::PostConvert => "post_convert",
+        }
+    }
+}
+
+/// 设置本次运行的钩子和正在执行的命令，只有第一次调用有效
+pub fn init(hooks: HooksConfig, operation: &str) {
+    let _ = HOOKS.set((hooks, operation.to_string()));
+}
+
+/// 触发钩子，`vars` 是额外的环境变量；没有调用过 [`init`] 时什么也不做
+pub fn run(hook: Hook, vars: &[(&str, &str)]) -> Result<()> {
+    let Some((hooks, operation)) = HOOKS.get() else {
+        return Ok(());
+    };
+    let commands = match hook {
+        Hook::PreWrite => &hooks.pre_write,
+        Hook::PostConvert => &hooks.post_convert,
+    };
+    for line in commands {
+        log!(Debug, "执行 {} 钩子: {}", hook.name(), line);
+        let mut command = process::shell(line);
+        command
+            .env("CONFCONV_HOOK", hook.name())
+            .env("CONFCONV_OPERATION", operation)
+            .envs(vars.iter().copied())
+            .stdout(std::io::stderr());
+        let status = command.status().map_err(|e| Error::ExternalCommand {
+            command: line.clone(),
+            message: e.to_string(),
+        })?;
+        if !status.success() {
+            return Err(Error::ExternalCommand {
+                command: line.clone(),
+                message: t!("{} 钩子失败（{}）", hook.name(), status),
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,1217 @@
+//! 英文消息目录
+//!
+//! 键是源代码中的中文原文，必须与原文（包括标点和占位符）完全一致
+
+/// 中文原文和英文译文
+pub(super) const MESSAGES: &[(&str, &str)] = &[
+    // 命令行帮助：全局
+    ("配置文件格式转换工具", "Configuration file format converter"),
+    ("显示详细信息", "Show detailed information"),
+    ("安静模式", "Quiet mode"),
+    (
+        "界面语言（默认按 LC_ALL、LC_MESSAGES、LANG 环境变量选择）",
+        "Interface language (defaults to LC_ALL, LC_MESSAGES or LANG)",
+    ),
+    ("中文", "Chinese"),
+    ("英文", "English"),
+    ("示例：", "Examples:"),
+    // 命令行帮助：值的取值
+    ("JSON 格式", "JSON format"),
+    ("YAML 格式", "YAML format"),
+    ("TOML 格式", "TOML format"),
+    (
+        "按字符逐个比较（item10 排在 item2 前面）",
+        "Compare character by character (item10 sorts before item2)",
+    ),
+    (
+        "自然排序：数字部分按数值比较（item2 排在 item10 前面）",
+        "Natural order: digit runs compare numerically (item2 sorts before item10)",
+    ),
+    (
+        "只给出宽泛的类型（整数也记为 number），不限制必填和取值",
+        "Broad types only (integers become number), no required keys or value constraints",
+    ),
+    (
+        "区分整数，在所有样例中都出现的键记为必填",
+        "Distinguish integers; keys present in every sample are required",
+    ),
+    (
+        "所有出现过的键都必填，禁止额外的键，取值很少的字符串生成 enum，数字生成观察到的 minimum/maximum",
+        "Every seen key is required and extra keys are forbidden; strings with few values become enum, numbers get the observed minimum/maximum",
+    ),
+    (
+        ".env 文件格式（KEY=value，必要时加双引号）",
+        ".env file format (KEY=value, double-quoted when needed)",
+    ),
+    (
+        "可直接 eval 的 shell 赋值语句（KEY='value'）",
+        "Shell assignments that can be eval'ed (KEY='value')",
+    ),
+    // 命令行帮助：通用参数
+    ("输入文件路径（使用 - 表示标准输入）", "Input file path (- for standard input)"),
+    ("输入文件路径", "Input file paths"),
+    ("输出文件路径", "Output file path"),
+    (
+        "输出目录（每个输入、每种目标格式各生成一个文件）",
+        "Output directory (one file per input and target format)",
+    ),
+    ("源格式（从标准输入读取时必需）", "Source format (required when reading standard input)"),
+    ("源格式（不指定则从扩展名推断）", "Source format (inferred from the extension if omitted)"),
+    ("目标格式（可重复指定多个）", "Target format (repeat for several)"),
+    ("目标格式", "Target format"),
+    ("美化输出", "Pretty-print the output"),
+    ("指定格式", "Format"),
+    ("指定格式（不指定则从扩展名推断）", "Format (inferred from the extension if omitted)"),
+    ("配置文件路径", "Configuration file path"),
+    ("配置文件路径（使用 - 表示标准输入）", "Configuration file path (- for standard input)"),
+    ("文件路径", "File paths"),
+    ("文件路径（使用 - 表示标准输入）", "File path (- for standard input)"),
+    ("原地修改文件", "Modify the file in place"),
+    (
+        "配置文件或目录路径（目录会被递归遍历）",
+        "Configuration files or directories (directories are walked recursively)",
+    ),
+    (
+        "遍历目录时遵守 .gitignore（默认开启）",
+        "Respect .gitignore when walking directories (default)",
+    ),
+    ("遍历目录时不遵守 .gitignore", "Do not respect .gitignore when walking directories"),
+    // 命令行帮助：值变换
+    (
+        "YAML 输出的行宽：超长字符串折叠换行，短数组写成 [a, b] 形式",
+        "Line width for YAML output: long strings are folded, short arrays are written as [a, b]",
+    ),
+    (
+        "在输出末尾追加 sha256 完整性标记注释（JSON 不支持），之后可用 verify 检查",
+        "Append a sha256 integrity comment to the output (not for JSON), checked later by verify",
+    ),
+    (
+        "优先排在最前的键（逗号分隔），其余的键排序后排在后面",
+        "Keys to put first (comma separated); the remaining keys are sorted after them",
+    ),
+    ("对所有对象的键排序", "Sort the keys of every object"),
+    (
+        "对元素全是标量的数组排序（使用 --sort 指定的排序方式）",
+        "Sort arrays of scalars (using the order chosen by --sort)",
+    ),
+    (
+        "解码路径上的 base64 值（如 Secret 的 data.*），内容是文档时展开为结构，可重复",
+        "Decode base64 values at the path (e.g. data.* of a Secret), expanding documents into structure; repeatable",
+    ),
+    (
+        "输出前把路径上的值编码为 base64（结构先序列化为 JSON），可重复",
+        "Encode values at the path as base64 before output (structures are serialized as JSON first); repeatable",
+    ),
+    (
+        "把内嵌 JSON/YAML 文档的字符串展开为结构，不指定路径时处理所有字符串，可重复",
+        "Expand strings holding JSON/YAML documents into structure, all strings if no path is given; repeatable",
+    ),
+    (
+        "输出前把路径上的结构重新序列化为 JSON 字符串，可重复",
+        "Serialize structures at the path back into JSON strings before output; repeatable",
+    ),
+    (
+        "把日期时间字符串和 TOML 日期时间规范化为 RFC 3339",
+        "Normalize date/time strings and TOML datetimes to RFC 3339",
+    ),
+    (
+        "规范化日期时间时把带偏移的时间转换到 UTC",
+        "Convert date/times with an offset to UTC while normalizing",
+    ),
+    (
+        "把路径上的时长（30s、1.5h、1h30m）换算为秒，可重复",
+        "Convert durations at the path (30s, 1.5h, 1h30m) to seconds; repeatable",
+    ),
+    (
+        "把路径上的大小（512Mi、2GB）换算为字节，可重复",
+        "Convert sizes at the path (512Mi, 2GB) to bytes; repeatable",
+    ),
+    (
+        "反过来把 --durations/--sizes 路径上的值换算为带单位的写法（1h30m、512Mi）",
+        "Instead write values at --durations/--sizes paths with units (1h30m, 512Mi)",
+    ),
+    (
+        "运行 WASM 变换模块（在沙箱中执行，导出 transform），可重复，排在配置文件中的模块之后",
+        "Run a WASM transform module (sandboxed, exporting transform); repeatable, runs after modules from the config file",
+    ),
+    (
+        "按规则文件批量转换（如 configs/*.yaml -> dist/{stem}.json），代替输入文件和输出参数",
+        "Convert in bulk following a rules file (such as configs/*.yaml -> dist/{stem}.json) instead of inputs and output options",
+    ),
+    ("标准输出是终端时也输出二进制数据", "Write binary output even when standard output is a terminal"),
+    ("以十六进制转储显示输出（只用于标准输出）", "Show the output as a hex dump (standard output only)"),
+    ("以 base64 显示输出（只用于标准输出）", "Show the output as base64 (standard output only)"),
+    (
+        "监视输入文件，修改后重新转换（按 Ctrl-C 退出）",
+        "Watch the input files and convert again when they change (Ctrl-C to stop)",
+    ),
+    (
+        "监视模式下每次转换成功后执行的命令（如重启开发服务器）",
+        "Command to run after each successful conversion in watch mode (such as restarting a dev server)",
+    ),
+    ("监视模式下每次转换前清屏", "Clear the screen before each conversion in watch mode"),
+    (
+        "运行 Rhai 变换脚本，脚本中读写的变量 value 即整个文档，可重复",
+        "Run a Rhai transform script that reads and writes the variable value (the whole document); repeatable",
+    ),
+    (
+        "把中间 JSON 交给外部命令处理（如 'jq .spec'），用其输出的 JSON 继续转换，可重复",
+        "Pipe the intermediate JSON through an external command (such as 'jq .spec') and continue with the JSON it outputs; repeatable",
+    ),
+    // 命令行帮助：各命令
+    ("转换配置文件格式", "Convert configuration files between formats"),
+    ("验证配置文件语法", "Validate configuration file syntax"),
+    ("格式化配置文件", "Format configuration files"),
+    ("缩进空格数（1-8）", "Number of spaces to indent (1-8)"),
+    ("原地修改文件（保留文件权限）", "Modify files in place (keeping permissions)"),
+    ("原地修改时保留文件的修改时间", "Keep the modification time when modifying in place"),
+    ("把多个配置文件合并为一个文档", "Join several configuration files into one document"),
+    (
+        "目标格式（默认使用第一个输入文件的格式）",
+        "Target format (defaults to the format of the first input)",
+    ),
+    (
+        "以文件名（不含扩展名）为键嵌套各文件内容，否则拼接为多文档/数组",
+        "Nest each file under its file name (without extension) instead of concatenating into a multi-document stream/array",
+    ),
+    (
+        "从任意文本（如日志）中提取内嵌的配置对象",
+        "Extract embedded configuration objects from arbitrary text (such as logs)",
+    ),
+    ("要提取的内嵌格式", "Embedded format to extract"),
+    (
+        "转换为目标格式（默认每个对象输出为一行 JSON）",
+        "Convert to the target format (by default each object is printed as one line of JSON)",
+    ),
+    ("把配置转义为 JSON 字符串字面量", "Escape a configuration into a JSON string literal"),
+    ("源格式（配合 --as 使用）", "Source format (used with --as)"),
+    (
+        "转义前先转换为该格式（不指定则原样转义输入文本）",
+        "Convert to this format before escaping (the input text is escaped as is if omitted)",
+    ),
+    ("把 JSON 字符串字面量还原为结构化配置", "Turn a JSON string literal back into structured configuration"),
+    (
+        "内嵌内容的格式（不指定则自动识别）",
+        "Format of the embedded content (detected automatically if omitted)",
+    ),
+    (
+        "目标格式（默认与内嵌内容的格式相同）",
+        "Target format (defaults to the format of the embedded content)",
+    ),
+    ("把路径上的子树移动到新路径", "Move the subtree at a path to a new path"),
+    ("原路径（点号分隔）", "Source path (dot separated)"),
+    (
+        "新路径（点号分隔，缺少的父级会自动创建）",
+        "Destination path (dot separated, missing parents are created)",
+    ),
+    ("新路径已存在时覆盖", "Overwrite the destination if it exists"),
+    ("读取路径上的值", "Read the value at a path"),
+    ("路径表达式（如 servers[0].host）", "Path expression (such as servers[0].host)"),
+    (
+        "输出裸标量（字符串不带引号），便于在 shell 中使用",
+        "Print bare scalars (strings without quotes) for use in shell scripts",
+    ),
+    ("始终输出合法的 JSON", "Always print valid JSON"),
+    (
+        "以指定格式输出复杂值（默认使用源文件的格式）",
+        "Print complex values in this format (defaults to the source format)",
+    ),
+    ("编辑配置文件", "Edit a configuration file"),
+    (
+        "不指定 --ops 时在 $EDITOR 中打开文件，保存后验证并写回",
+        "Without --ops the file is opened in $EDITOR, then validated and written back on save",
+    ),
+    (
+        "操作文件：按顺序执行的 set/unset/move/rename 操作列表",
+        "Operations file: a list of set/unset/move/rename operations applied in order",
+    ),
+    (
+        "在编辑器中以该格式编辑，保存后转换回原格式",
+        "Edit in this format and convert back to the original format on save",
+    ),
+    (
+        "原地修改文件（配合 --ops 使用，交互式编辑总是写回）",
+        "Modify the file in place (with --ops; interactive edits are always written back)",
+    ),
+    ("把配置导出为环境变量", "Export a configuration as environment variables"),
+    ("输出形式", "Output style"),
+    ("变量名前缀", "Variable name prefix"),
+    ("嵌套键之间的连接符", "Separator between nested keys"),
+    ("每行前加 export", "Prefix every line with export"),
+    ("把配置展开为命令行参数", "Expand a configuration into command-line arguments"),
+    (
+        "参数模板，{key} 为路径，{value} 为值",
+        "Argument template, {key} is the path and {value} is the value",
+    ),
+    (
+        "用单引号转义每个参数并输出为一行",
+        "Single-quote every argument and print them on one line",
+    ),
+    ("比较两个配置文件的内容", "Compare the contents of two configuration files"),
+    ("旧文件路径（使用 - 表示标准输入）", "Old file path (- for standard input)"),
+    ("新文件路径", "New file path"),
+    (
+        "指定两边的格式（不指定则各自从扩展名推断）",
+        "Format of both sides (each is inferred from its extension if omitted)",
+    ),
+    ("数组不考虑元素顺序", "Ignore the order of array elements"),
+    ("对象的键不区分大小写", "Compare object keys case-insensitively"),
+    ("数字之差不超过该值时视为相等", "Treat numbers as equal when they differ by at most this much"),
+    (
+        "只比较匹配的路径，可重复，支持 * 和 **（如 spec、items[*].name）",
+        "Only compare matching paths; repeatable, supports * and ** (such as spec, items[*].name)",
+    ),
+    (
+        "不比较匹配的路径，可重复，支持 * 和 **（如 metadata、**.password）",
+        "Skip matching paths; repeatable, supports * and ** (such as metadata, **.password)",
+    ),
+    (
+        "按顺序深度合并多个配置文件，后面的覆盖前面的",
+        "Deep-merge configuration files in order, later files override earlier ones",
+    ),
+    ("输入文件路径，按顺序叠加", "Input file paths, layered in order"),
+    (
+        "不允许后面的文件覆盖前面已定义的值，报告所有冲突并以非零状态退出",
+        "Forbid later files from overriding defined values; report every conflict and exit non-zero",
+    ),
+    ("用配置文件中的变量渲染 Tera 模板", "Render a Tera template with variables from configuration files"),
+    ("模板文件路径（使用 - 表示标准输入）", "Template file path (- for standard input)"),
+    (
+        "变量文件路径，可重复，按顺序深度合并，顶层的键即模板中可用的变量",
+        "Variables file; repeatable and deep-merged in order, top-level keys become template variables",
+    ),
+    (
+        "覆盖单个变量（path=value），可重复，在所有变量文件之后应用",
+        "Override one variable (path=value); repeatable, applied after all variables files",
+    ),
+    (
+        "渲染结果的格式（不指定则从模板或输出文件的扩展名推断）",
+        "Format of the rendered output (inferred from the template or output extension if omitted)",
+    ),
+    ("把渲染结果转换为目标格式", "Convert the rendered output to the target format"),
+    (
+        "检查渲染结果的语法，有错误时不输出",
+        "Check the syntax of the rendered output and print nothing on errors",
+    ),
+    ("美化输出（仅在转换时生效）", "Pretty-print the output (only when converting)"),
+    ("按断言文件检查配置中的值", "Check configuration values against an assertions file"),
+    ("断言文件路径", "Assertions file path"),
+    (
+        "指定配置文件的格式（不指定则从扩展名推断）",
+        "Format of the configuration file (inferred from the extension if omitted)",
+    ),
+    (
+        "把配置的规范形式保存为快照，或检查配置是否偏离快照",
+        "Save the canonical form of configurations as snapshots, or check them against snapshots",
+    ),
+    (
+        "快照保存在配置文件所在目录的 __confconv_snapshots__/ 下",
+        "Snapshots are stored in __confconv_snapshots__/ next to the configuration file",
+    ),
+    ("写入（或覆盖）快照", "Write (or overwrite) snapshots"),
+    (
+        "与快照比较，不一致时以非零状态退出（默认行为）",
+        "Compare with snapshots and exit non-zero on mismatch (default)",
+    ),
+    ("JSON Schema 相关操作", "JSON Schema operations"),
+    ("从样例配置推断 JSON Schema", "Infer a JSON Schema from sample configurations"),
+    ("样例文件路径，可以有多个", "Sample file paths, one or more"),
+    ("推断的严格程度", "How strict the inferred schema is"),
+    (
+        "把配置中的值替换为假值，保留结构和类型，便于在问题报告中分享",
+        "Replace values with fakes while keeping structure and types, for sharing in bug reports",
+    ),
+    ("保留原值的路径，可重复，支持 * 和 **", "Paths whose values are kept; repeatable, supports * and **"),
+    (
+        "随机种子，指定时可以重现同样的假值（默认每次运行随机选择）",
+        "Random seed; set it to reproduce the same fake values (random on each run by default)",
+    ),
+    ("按 JSON Schema 生成示例配置", "Generate an example configuration from a JSON Schema"),
+    ("Schema 文件路径（使用 - 表示标准输入）", "Schema file path (- for standard input)"),
+    (
+        "Schema 文件的格式（不指定则从扩展名推断）",
+        "Format of the schema file (inferred from the extension if omitted)",
+    ),
+    (
+        "检查文件中哪些内容无法无损转换为其他格式",
+        "Report which parts of a file cannot be converted to other formats losslessly",
+    ),
+    (
+        "检查生成的文件是否被手工修改过，或校验分离签名",
+        "Check whether generated files were edited by hand, or verify detached signatures",
+    ),
+    (
+        "校验分离签名（由 sign 生成）而不是完整性标记",
+        "Verify a detached signature (created by sign) instead of the integrity comment",
+    ),
+    (
+        "可信的公钥（PKCS#8 PEM），不指定时使用用户配置 [signature] 段的 key，都没有时报错",
+        "Trusted public key (PKCS#8 PEM); defaults to key in the [signature] section of the user config, and verification fails without one",
+    ),
+    (
+        "对配置的规范形式做 Ed25519 签名，重新排版或转换格式后签名仍然有效",
+        "Sign the canonical form of a configuration with Ed25519; the signature survives reformatting and conversion",
+    ),
+    ("私钥文件（PKCS#8 PEM）", "Private key file (PKCS#8 PEM)"),
+    (
+        "输出版本、支持的格式、压缩方式和可用的命令",
+        "Print the version, supported formats, compression and available commands",
+    ),
+    (
+        "启动 gRPC 服务，提供 Convert、Validate、Diff 接口（协议见 proto/confconv.proto）",
+        "Start a gRPC service with Convert, Validate and Diff calls (see proto/confconv.proto)",
+    ),
+    ("监听地址", "Address to listen on"),
+    (
+        "输出机器可读的 JSON（包括每个命令的参数）",
+        "Print machine-readable JSON (including the arguments of every command)",
+    ),
+    ("签名文件路径（默认为 <文件>.sig）", "Signature file path (defaults to <file>.sig)"),
+    // 错误
+    ("错误: {}", "error: {}"),
+    ("无法读取文件 '{}': {}", "cannot read file '{}': {}"),
+    ("无法写入文件 '{}': {}", "cannot write file '{}': {}"),
+    ("{} 解析失败: {}", "failed to parse {}: {}"),
+    ("转换失败: {}", "conversion failed: {}"),
+    (
+        "无法从文件扩展名推断格式: {}\n支持的扩展名: .json, .yaml, .yml, .toml（压缩文件可再加 .gz、.zst、.xz）",
+        "cannot infer the format from the file extension: {}\nsupported extensions: .json, .yaml, .yml, .toml (compressed files may add .gz, .zst or .xz)",
+    ),
+    ("{} 个文件中有 {} 个验证失败", "{1} of {0} files failed validation"),
+    (
+        "{} 个文件中有 {} 个无法格式化，没有修改任何文件",
+        "{1} of {0} files could not be formatted; no files were changed",
+    ),
+    ("没有扩展名可以识别的配置文件", "no configuration files with a recognized extension"),
+    ("{} 条断言中有 {} 条未通过", "{1} of {0} assertions failed"),
+    ("{} 个文件中有 {} 个与快照不一致", "{1} of {0} files do not match their snapshots"),
+    ("输入超出限制: {}最多为 {}", "input exceeds a limit: {} is at most {}"),
+    ("解压后的内容超过 {} 字节", "decompressed content exceeds {} bytes"),
+    ("{} 个文件中有 {} 个校验失败", "{1} of {0} files failed verification"),
+    ("签名错误: {}", "signature error: {}"),
+    ("无效的路径 '{}': {}", "invalid path '{}': {}"),
+    ("路径不存在: {}", "path does not exist: {}"),
+    ("路径已存在: {}（使用 --force 覆盖）", "path already exists: {} (use --force to overwrite)"),
+    ("模板渲染失败: {}", "template rendering failed: {}"),
+    ("执行 '{}' 失败: {}", "running '{}' failed: {}"),
+    ("发现 {} 处差异", "found {} differences"),
+    ("发现 {} 处合并冲突，未输出结果", "found {} merge conflicts, no output written"),
+    (
+        "第 {} 个操作失败，所有修改均未生效: {}",
+        "operation {} failed, no changes were applied: {}",
+    ),
+    ("配置文件 '{}' 有误: {}", "invalid config file '{}': {}"),
+    ("WASM 模块 '{}' 执行失败: {}", "WASM module '{}' failed: {}"),
+    ("脚本 '{}' 执行失败: {}", "script '{}' failed: {}"),
+    ("服务错误: {}", "service error: {}"),
+    ("输入", "input"),
+    ("输入字节数", "input size in bytes"),
+    ("嵌套深度", "nesting depth"),
+    ("值的数量", "number of values"),
+    ("操作文件", "operations file"),
+    ("规则文件", "rules file"),
+    ("不是有效的 UTF-8: {}", "not valid UTF-8: {}"),
+    ("解析器内部错误", "internal parser error"),
+    ("从标准输入读取时必须指定 --from 参数", "--from is required when reading standard input"),
+    (
+        "多个输入文件或多个目标格式时必须指定 --out-dir 参数",
+        "--out-dir is required with several input files or target formats",
+    ),
+    ("应为 path=value 的形式", "expected path=value"),
+    // 错误：路径
+    ("下标缺少结束的 ']'", "index is missing the closing ']'"),
+    ("带引号的键缺少结束的 '\"'", "quoted key is missing the closing '\"'"),
+    ("路径不能以 '.' 开头", "path cannot start with '.'"),
+    ("位置 {} 处应为 '.' 或 '['", "expected '.' or '[' at position {}"),
+    ("[+] 只能出现在最后一段（第 {} 段）", "[+] may only be the last segment (found in segment {})"),
+    ("转义符 '\\' 后缺少字符", "missing character after the escape '\\'"),
+    ("键中出现了 '{}'，请用双引号括起来", "key contains '{}', quote it with double quotes"),
+    ("路径中存在空的段", "path contains an empty segment"),
+    ("无效的数组下标 '{}'", "invalid array index '{}'"),
+    ("不能移动根节点", "cannot move the root"),
+    ("不能移动到 '{}' 自身或它的子路径下", "cannot move '{}' into itself or below it"),
+    ("只能重命名对象的键", "only object keys can be renamed"),
+    ("对象", "object"),
+    ("数组", "array"),
+    ("根节点不是{}", "the root is not an {}"),
+    ("'{}' 不是{}", "'{}' is not an {}"),
+    ("'{}' 下标越界", "'{}' index out of range"),
+    // 错误：签名
+    ("无法读取私钥: {}", "cannot read the private key: {}"),
+    ("无法读取公钥: {}", "cannot read the public key: {}"),
+    ("签名不是由指定的公钥生成的", "the signature was not made with the given public key"),
+    (
+        "签名与内容不匹配，内容可能已被修改",
+        "the signature does not match the content, which may have been modified",
+    ),
+    ("签名文件格式错误: {}", "malformed signature file: {}"),
+    ("缺少文件头", "missing header"),
+    ("不支持的算法 '{}'", "unsupported algorithm '{}'"),
+    ("公钥缺失或无效", "missing or invalid public key"),
+    ("签名缺失或无效", "missing or invalid signature"),
+    ("公钥无效", "invalid public key"),
+    // 错误：值变换
+    ("时长", "duration"),
+    ("大小", "size"),
+    ("{} 不是字符串，无法按 base64 解码", "{} is not a string and cannot be base64-decoded"),
+    ("{} 不是有效的 base64: {}", "{} is not valid base64: {}"),
+    ("{} 解码后不是 UTF-8 文本", "{} does not decode to UTF-8 text"),
+    ("{} 不是可识别的{}: {}", "{} is not a recognizable {}: {}"),
+    ("{} 的{}超出可以表示的范围: {}", "the {1} at {0} is out of the representable range: {2}"),
+    // 命令输出
+    ("源格式: {}", "Source format: {}"),
+    ("目标格式: {}", "Target format: {}"),
+    ("已写入: {}", "Written: {}"),
+    ("已更新: {}", "Updated: {}"),
+    ("读取: {} ({})", "Reading: {} ({})"),
+    (
+        "警告: JSON 不支持注释，JSON 输出不会追加完整性标记",
+        "warning: JSON has no comments, JSON output gets no integrity comment",
+    ),
+    ("比较 {} ({}) 与 {} ({})", "Comparing {} ({}) with {} ({})"),
+    ("没有差异", "No differences"),
+    ("  转换为任何格式都会：", "  Converting to any format will:"),
+    ("  ✓ {}: 可以无损转换", "  ✓ {}: converts losslessly"),
+    ("诊断完成", "Diagnosis complete"),
+    ("丢失 {} 行注释", "lose {} comment lines"),
+    (
+        "展开锚点和别名（&name / *name），重复的内容会被复制",
+        "expand anchors and aliases (&name / *name), duplicating the repeated content",
+    ),
+    ("丢失类型标签（!tag）", "lose type tags (!tag)"),
+    (
+        "把日期时间变成 {{\"{}\": ...}} 对象（可用 --normalize-dates 转为字符串）: {}",
+        "turn datetimes into {{\"{}\": ...}} objects (use --normalize-dates to get strings): {}",
+    ),
+    ("TOML 的顶层必须是表（对象）", "the TOML top level must be a table (object)"),
+    ("TOML 没有 null: {}", "TOML has no null: {}"),
+    ("转换结果无法解析: {}", "the converted output cannot be parsed: {}"),
+    ("值会改变: {}", "values change: {}"),
+    ("等 {} 处", "and {} more"),
+    ("已应用 {} 个操作", "Applied {} operations"),
+    ("以 {} 格式编辑: {}", "Editing as {}: {}"),
+    ("内容未修改: {}", "Unchanged: {}"),
+    ("{}\n编辑内容已保留在: {}", "{}\nyour edits were kept in: {}"),
+    ("编辑器异常退出（{}），文件未修改", "the editor exited abnormally ({}), file not modified"),
+    ("转义前转换为: {}", "Converting before escaping to: {}"),
+    (
+        "目前只支持从文本中提取 JSON，不支持 {}",
+        "only JSON can be extracted from text at the moment, not {}",
+    ),
+    ("找到 {} 个 JSON 对象", "Found {} JSON objects"),
+    ("格式: {}", "Format: {}"),
+    ("缩进: {} 空格", "Indent: {} spaces"),
+    (
+        "文件名 '{}' 重复，无法作为嵌套的键",
+        "file name '{}' appears twice and cannot be used as a nesting key",
+    ),
+    (
+        "{} 不支持多文档或顶层数组，请使用 --nest-by-filename",
+        "{} supports neither multiple documents nor top-level arrays, use --nest-by-filename",
+    ),
+    ("冲突: {}", "Conflict: {}"),
+    ("覆盖: {} ({})", "Overriding: {} ({})"),
+    ("已移动: {} -> {}", "Moved: {} -> {}"),
+    ("样例: {} ({})", "Sample: {} ({})"),
+    ("已写入签名: {}", "Signature written: {}"),
+    ("✓ {} 快照已更新: {}", "✓ {} snapshot updated: {}"),
+    ("比较快照: {}", "Comparing snapshot: {}"),
+    (
+        "✗ {}: 快照不存在（使用 --update 创建）",
+        "✗ {}: no snapshot (create one with --update)",
+    ),
+    ("✓ {} 与快照一致", "✓ {} matches its snapshot"),
+    ("✗ {} 与快照不一致", "✗ {} does not match its snapshot"),
+    ("变量: {} ({})", "Variables: {} ({})"),
+    ("合并后的变量顶层必须是对象", "the merged variables must be an object at the top level"),
+    ("按 {} 解析渲染结果", "Parsing the rendered output as {}"),
+    ("断言文件格式错误: {}", "malformed assertions file: {}"),
+    ("共 {} 条断言", "{} assertions"),
+    (
+        "{} 条断言：{} 条通过，{} 条失败",
+        "{} assertions: {} passed, {} failed",
+    ),
+    ("路径不应存在，实际值 {}", "the path should not exist, but is {}"),
+    ("路径不存在", "the path does not exist"),
+    ("期望 {}，实际为 {}", "expected {}, got {}"),
+    ("期望类型 {}，实际为 {}", "expected type {}, got {}"),
+    ("无效的正则表达式: {}", "invalid regular expression: {}"),
+    ("{} 不匹配 /{}/", "{} does not match /{}/"),
+    ("期望字符串以匹配 /{}/，实际为 {}", "expected a string matching /{}/, got {}"),
+    ("输入不是 JSON 字符串字面量", "the input is not a JSON string literal"),
+    ("内嵌格式: {}", "Embedded format: {}"),
+    ("验证格式: {}", "Validating as: {}"),
+    ("✓ {} 语法正确 ({})", "✓ {} is valid ({})"),
+    ("使用 --sig 时只能校验一个文件", "only one file can be verified with --sig"),
+    ("校验: {}", "Verifying: {}"),
+    ("✓ {} 未被修改", "✓ {} is unmodified"),
+    (
+        "✗ {}: 没有完整性标记（生成时使用 --stamp）",
+        "✗ {}: no integrity comment (generate it with --stamp)",
+    ),
+    ("✗ {}: 内容在生成后被修改过", "✗ {}: modified after it was generated"),
+    (
+        "没有可信的公钥：用 --key 指定，或在用户配置的 [signature] 段设置 key",
+        "no trusted public key: pass --key or set key in the [signature] section of the user config",
+    ),
+    ("✓ {} 签名有效", "✓ {} has a valid signature"),
+    ("压缩: {}", "Compression: {}"),
+    ("可选功能: {}", "Optional features: {}"),
+    ("无", "none"),
+    ("命令: {}", "Commands: {}"),
+    ("插件 {}", "plugin {}"),
+    ("插件输出的不是合法的 JSON: {}", "the plugin did not output valid JSON: {}"),
+    ("{} 钩子失败（{}）", "{} hook failed ({})"),
+    // WASM 变换
+    ("模块没有导出 memory", "the module does not export memory"),
+    ("输入过大", "the input is too large"),
+    ("返回的输出超出了模块内存", "the returned output lies outside the module memory"),
+    ("输出的不是合法的 JSON: {}", "the output is not valid JSON: {}"),
+    ("编译时未启用 wasm 功能", "built without the wasm feature"),
+    // 批量转换规则
+    ("第 {} 条规则: {}", "rule {}: {}"),
+    ("简写规则的形式为 '输入 -> 输出'", "a short rule has the form 'input -> output'"),
+    ("未知的格式: {}", "unknown format: {}"),
+    ("占位符缺少 '}}': {}", "placeholder is missing '}}': {}"),
+    ("未知的占位符 {{{}}}，可用的有 {}", "unknown placeholder {{{}}}; available: {}"),
+    ("警告: 规则 '{}' 没有匹配任何文件", "warning: rule '{}' matched no files"),
+    ("{} 和 {} 都会写入 {}", "both {} and {} would be written to {}"),
+    ("{} 的输出会覆盖输入文件", "the output for {} would overwrite the input file"),
+    ("已写入: {} -> {}", "Written: {} -> {}"),
+    // 标准输出
+    (
+        "输出是二进制数据，不会写到终端；使用 --force 强制输出，或用 --hex/--base64 显示",
+        "the output is binary and will not be written to a terminal; use --force to write it anyway, or --hex/--base64 to display it",
+    ),
+    (
+        "{} 的输出是二进制数据，只能用 convert 命令输出",
+        "{} produces binary output, which only the convert command can write",
+    ),
+    // 对象存储
+    ("编译时未启用 cloud 功能", "built without the cloud feature"),
+    // 监视模式
+    ("监视模式不能从标准输入读取", "watch mode cannot read from standard input"),
+    ("等待文件修改…（按 Ctrl-C 退出）", "Waiting for changes… (Ctrl-C to stop)"),
+    // gRPC 服务
+    ("gRPC 服务监听 {}", "gRPC service listening on {}"),
+    ("缺少目标格式", "missing target format"),
+    ("缺少文档", "missing document"),
+    // 变换脚本
+    ("脚本删除了变量 value", "the script removed the variable value"),
+    ("编译时未启用 script 功能", "built without the script feature"),
+    // k8s
+    ("Kubernetes ConfigMap/Secret 打包和解包", "Pack and unpack Kubernetes ConfigMaps/Secrets"),
+    (
+        "把配置文件打包为 ConfigMap（或 Secret）清单，文件内容放在 data 下",
+        "Wrap config files into a ConfigMap (or Secret) manifest, with file contents under data",
+    ),
+    (
+        "配置文件路径，每个文件成为 data 中的一项（使用 - 表示标准输入）",
+        "Config file paths; each file becomes one entry in data (use - for stdin)",
+    ),
+    ("资源名称", "Resource name"),
+    ("命名空间", "Namespace"),
+    (
+        "data 中的键（默认为文件名，只有一个文件时可以指定）",
+        "Key in data (defaults to the file name; only allowed with a single file)",
+    ),
+    (
+        "生成 Secret（内容以 base64 存放）而不是 ConfigMap",
+        "Generate a Secret (contents stored as base64) instead of a ConfigMap",
+    ),
+    ("清单的格式", "Manifest format"),
+    (
+        "从 ConfigMap/Secret 清单中取出内嵌的配置，可同时转换格式",
+        "Extract the embedded config from a ConfigMap/Secret manifest, optionally converting it",
+    ),
+    ("清单文件路径（使用 - 表示标准输入）", "Manifest file path (use - for stdin)"),
+    ("要取出的 data 键（只有一项时可以省略）", "data key to extract (optional when there is only one)"),
+    ("转换为目标格式（默认原样输出）", "Convert to this format (default: output as-is)"),
+    ("有多个文件时不能指定 --key", "--key cannot be used with multiple files"),
+    ("从标准输入读取时必须指定 --key 参数", "--key is required when reading standard input"),
+    ("打包: {} -> data.{}", "Wrapping: {} -> data.{}"),
+    ("data 中的键重复: {}", "duplicate key in data: {}"),
+    ("data.{} 不是有效的 base64 文本", "data.{} is not valid base64 text"),
+    ("{} 不是 ConfigMap 或 Secret", "{} is not a ConfigMap or Secret"),
+    ("有多项数据，请用 --key 指定: {}", "multiple data entries; choose one with --key: {}"),
+    // kv
+    (
+        "与 Consul/etcd 键值存储的导出格式互相转换",
+        "Convert to and from Consul/etcd key/value exports",
+    ),
+    (
+        "把配置展开为键值对，输出 consul kv import 或 etcd 可用的 JSON 数组",
+        "Flatten a config into key/value pairs as a JSON array for consul kv import or etcd",
+    ),
+    (
+        "把键值存储的导出还原为嵌套配置",
+        "Rebuild a nested config from a key/value store export",
+    ),
+    ("导出格式", "Export flavor"),
+    ("所有键的前缀", "Prefix for all keys"),
+    ("导出文件路径（使用 - 表示标准输入）", "Export file path (use - for stdin)"),
+    (
+        "只导入该前缀下的键，并从路径中去掉前缀",
+        "Only import keys under this prefix, stripping it from the paths",
+    ),
+    (
+        "所有值都作为字符串（默认按 YAML 标量解释数字、布尔和 null）",
+        "Keep all values as strings (by default numbers, booleans and null are read as YAML scalars)",
+    ),
+    (
+        "consul kv export/import 的格式（键为明文，值为 base64）",
+        "consul kv export/import format (plain keys, base64 values)",
+    ),
+    (
+        "etcd JSON API 的格式（键和值都为 base64），导入时也接受 etcdctl -w json 的输出",
+        "etcd JSON API format (base64 keys and values); import also accepts etcdctl -w json output",
+    ),
+    ("键 '{}' 含有 '/'，无法作为 KV 路径的一段", "key '{}' contains '/' and cannot be a KV path segment"),
+    ("跳过前缀以外的键: {}", "Skipping key outside the prefix: {}"),
+    ("第 {} 项不是有效的 base64 文本", "item {} is not valid base64 text"),
+    (
+        "{} 不是 KV 导出（应为 key/value 对象的数组）",
+        "{} is not a KV export (expected an array of key/value objects)",
+    ),
+    ("{} 既是值又包含子项", "{} is both a value and a parent of other keys"),
+    // Vault
+    (
+        "把 vault:路径#字段 形式的字符串替换为 HashiCorp Vault 中的值（使用 VAULT_ADDR、VAULT_TOKEN）",
+        "Replace vault:path#field strings with values from HashiCorp Vault (uses VAULT_ADDR, VAULT_TOKEN)",
+    ),
+    ("无法解析密钥引用 '{}': {}", "cannot resolve secret reference '{}': {}"),
+    ("密钥中没有字段 {}", "the secret has no field {}"),
+    ("未设置 VAULT_TOKEN，也没有 ~/.vault-token", "VAULT_TOKEN is not set and ~/.vault-token does not exist"),
+    ("响应中没有 data", "the response has no data"),
+    ("编译时未启用 vault 功能", "built without the vault feature"),
+    // export
+    (
+        "导出到 AWS Parameter Store 或 Secrets Manager",
+        "Export to AWS Parameter Store or Secrets Manager",
+    ),
+    (
+        "把配置展开为 SSM Parameter Store 参数，输出 aws ssm put-parameter 命令或直接执行",
+        "Flatten a config into SSM Parameter Store parameters, printing or running aws ssm put-parameter commands",
+    ),
+    (
+        "把整个配置作为 Secrets Manager 密钥的 JSON 值，输出 aws secretsmanager 命令或直接执行",
+        "Store the whole config as the JSON value of a Secrets Manager secret, printing or running the aws secretsmanager command",
+    ),
+    ("参数名前缀", "Parameter name prefix"),
+    (
+        "使用 SecureString 类型的路径（支持通配），可重复",
+        "Paths stored as SecureString (wildcards allowed); can be repeated",
+    ),
+    (
+        "直接执行（需要已安装并配置 AWS CLI），而不是输出命令",
+        "Run the operations (requires an installed and configured AWS CLI) instead of printing them",
+    ),
+    ("密钥的名称或 ARN", "Secret name or ARN"),
+    ("警告: 跳过空值参数 {}", "warning: skipping empty parameter {}"),
+    ("执行: {}", "Running: {}"),
+    ("已执行 {} 个操作", "Ran {} operations"),
+    // 格式方言
+    (
+        "TOML 规范版本，决定解析时接受和输出时使用的语法",
+        "TOML spec version: which syntax is accepted when parsing and used when writing",
+    ),
+    ("TOML 1.0，兼容旧版解析器", "TOML 1.0, compatible with older parsers"),
+    (
+        "TOML 1.1，接受多行内联表和新的转义序列",
+        "TOML 1.1, accepts multi-line inline tables and the new escape sequences",
+    ),
+    (
+        "YAML 规范版本，决定没有引号的标量（yes、on、0777 等）如何解释",
+        "YAML spec version: how unquoted scalars (yes, on, 0777, ...) are interpreted",
+    ),
+    (
+        "YAML 1.1，yes/no/on/off 是布尔，0777 是八进制",
+        "YAML 1.1: yes/no/on/off are booleans, 0777 is octal",
+    ),
+    ("YAML 1.2，只有 true/false 是布尔", "YAML 1.2: only true/false are booleans"),
+    ("不支持含有多个文档的 YAML", "YAML with multiple documents is not supported"),
+    ("意外的 YAML 事件", "unexpected YAML event"),
+    ("别名引用了未定义的锚点", "alias refers to an undefined anchor"),
+    (
+        "合并键 << 的值必须是映射或映射的列表",
+        "the value of the merge key << must be a mapping or a list of mappings",
+    ),
+    ("无法按标签解释标量: {}", "cannot interpret scalar according to its tag: {}"),
+    (
+        "保留无穷大和 NaN：JSON 输入接受 NaN、Infinity 字面量，JSON 输出按 MODE 写出（默认 literal）",
+        "Keep infinities and NaN: JSON input accepts NaN/Infinity literals, JSON output writes them according to MODE (default literal)",
+    ),
+    ("写成 null", "write null"),
+    (
+        "写成字符串 \"NaN\"、\"Infinity\"、\"-Infinity\"",
+        "write the strings \"NaN\", \"Infinity\", \"-Infinity\"",
+    ),
+    (
+        "直接写出 NaN、Infinity、-Infinity（不是标准 JSON，只有宽松的解析器接受）",
+        "write bare NaN, Infinity, -Infinity (not standard JSON; only lenient parsers accept it)",
+    ),
+    // lint
+    ("检查配置文件中容易引起混淆的写法", "Check config files for confusing constructs"),
+    (
+        "检查 YAML 的锚点和别名（没有被引用或重复定义的锚点、未定义或在定义之前使用的别名、被引用次数过多的锚点、循环引用），以及所有格式中重复的键、重复的子树、取值相同的相似键、不符合命名规范的键名；指定 --sorted-keys、--indent 时还检查键的顺序和 JSON 的缩进。--fix 修正其中有确定修正方式的问题。有错误时以非零状态退出",
+        "Checks YAML anchors and aliases (anchors that are never aliased or defined twice, aliases that are undefined or used before their anchor, anchors aliased too many times, and cycles), plus duplicate keys, duplicate subtrees, similar keys with the same value and key names that break the naming convention in every format; with --sorted-keys and --indent it also checks key order and JSON indentation. --fix corrects the problems that have a definite fix. Exits with a non-zero status when errors are found",
+    ),
+    (
+        "同一锚点被别名引用超过这个次数时给出警告",
+        "Warn when an anchor is aliased more than this many times",
+    ),
+    ("错误", "error"),
+    ("警告", "warning"),
+    ("检查了 {} 个文件：{} 个错误，{} 个警告", "Checked {} files: {} errors, {} warnings"),
+    ("检查发现 {} 个错误", "lint found {} errors"),
+    (
+        "锚点 &{} 已在第 {} 行定义，之后的别名只会引用这里的值",
+        "anchor &{} was already defined on line {}; later aliases refer to this value only",
+    ),
+    ("别名 *{} 在锚点定义（第 {} 行）之前使用", "alias *{} is used before its anchor is defined (line {})"),
+    ("别名 *{} 引用了未定义的锚点", "alias *{} refers to an undefined anchor"),
+    ("锚点 &{} 没有被任何别名引用", "anchor &{} is never aliased"),
+    (
+        "锚点 &{} 被引用了 {} 次（超过 {} 次），修改它会影响所有引用处",
+        "anchor &{} is aliased {} times (more than {}); changing it affects every alias",
+    ),
+    // 循环引用
+    (
+        "别名形成循环: {} (&{}) → {} (*{}，第 {} 行)",
+        "aliases form a cycle: {} (&{}) → {} (*{}, line {})",
+    ),
+    // 尽量转换
+    (
+        "尽量转换：跳过出错的文件继续转换其余文件，多文档 YAML 转换为文档的数组（出错的文档写成 null），最后汇总报告所有问题",
+        "Best effort: skip files that fail and keep converting the rest; multi-document YAML becomes an array of documents (failed documents become null); report all problems at the end",
+    ),
+    ("{} 的第 {} 个文档", "{} document {}"),
+    (
+        "已尽量转换，{} 处内容因出错被跳过",
+        "converted on a best-effort basis; {} parts were skipped because of errors",
+    ),
+    // 诊断信息
+    ("提示", "note"),
+    ("使用 --json-allow-nan 可以保留无穷大和 NaN", "use --json-allow-nan to keep infinities and NaN"),
+    ("键 {} 不是字符串，转换后是字符串 \"{}\"", "key {} is not a string; it becomes the string \"{}\""),
+    ("{} 不能用 JSON 表示，转换为 null", "{} cannot be represented in JSON and becomes null"),
+    (
+        "{} 的值 {} 不能用 JSON 表示，转换为 null",
+        "the value {1} at {0} cannot be represented in JSON and becomes null",
+    ),
+    (
+        "整数 {} 超出 64 位整数的范围，转换为浮点数 {}",
+        "integer {} is out of the 64-bit range and becomes the float {}",
+    ),
+    (
+        "数字 {} 超出双精度浮点数的精度，转换为 {}",
+        "number {} has more precision than a double and becomes {}",
+    ),
+    // 诊断级别
+    (
+        "把警告视为错误：规则名，或者 warnings 表示所有警告（可多次指定）",
+        "Treat warnings as errors: a rule name, or warnings for all warnings (repeatable)",
+    ),
+    (
+        "保持为警告，用来排除 --deny warnings 中的规则（可多次指定）",
+        "Keep as warnings, to exempt rules from --deny warnings (repeatable)",
+    ),
+    ("不报告这些警告（可多次指定）", "Do not report these warnings (repeatable)"),
+    ("未知的规则: {}（可用: {}, {}）", "unknown rule: {} (available: {}, {})"),
+    ("{} 个警告被 --deny 视为错误", "{} warnings denied by --deny"),
+    // 详细程度
+    (
+        "显示详细信息（-v 处理过程，-vv 调试信息，-vvv 跟踪中间表示）",
+        "Show more output (-v progress, -vv debug information, -vvv trace the intermediate value)",
+    ),
+    ("安静模式：只输出错误和命令要求的数据", "Quiet mode: print only errors and the requested data"),
+    ("读取配置文件: {}", "read configuration file: {}"),
+    ("执行 {} 钩子: {}", "running {} hook: {}"),
+    ("读取: {}（{} 字节）", "read: {} ({} bytes)"),
+    ("按 {} 解析用时 {}", "parsed as {} in {}"),
+    ("解析结果: {}", "parsed value: {}"),
+    ("按 {} 序列化: {}", "serializing as {}: {}"),
+    // 颜色
+    ("何时给诊断信息和差异着色", "When to color diagnostics and differences"),
+    (
+        "输出到终端时着色，遵循 NO_COLOR 和 CLICOLOR_FORCE",
+        "Color output to terminals, honoring NO_COLOR and CLICOLOR_FORCE",
+    ),
+    ("总是着色", "Always color"),
+    ("从不着色", "Never color"),
+    (
+        "折行和截断使用的宽度（默认检测终端，0 表示不折行也不截断）",
+        "Width used for wrapping and truncation (detected from the terminal by default; 0 disables both)",
+    ),
+    (
+        "不使用分页器（默认在输出到终端时使用 $PAGER 或 less）",
+        "Do not use a pager (by default output to a terminal goes through $PAGER or less)",
+    ),
+    // 补全
+    (
+        "输出 shell 补全脚本，补全子命令、选项和配置文件中的键路径",
+        "Print a shell completion script that completes subcommands, options and key paths inside configuration files",
+    ),
+    ("shell 类型", "Shell type"),
+    (
+        "补全脚本使用的内部命令：补全最后一个词，每行输出一个候选",
+        "Internal command used by completion scripts: complete the last word, one candidate per line",
+    ),
+    (
+        "命令行中子命令及之后的词，最后一个是正在补全的词",
+        "Words from the subcommand on; the last one is the word being completed",
+    ),
+    // 配置档
+    (
+        "使用配置文件中 [profile.NAME] 段的参数作为默认值",
+        "Use the options in the [profile.NAME] section of the configuration file as defaults",
+    ),
+    ("没有名为 '{}' 的配置档", "no profile named '{}'"),
+    (
+        "配置档 '{}' 中的 '{}' 不是任何命令的参数",
+        "'{1}' in profile '{0}' is not an option of any command",
+    ),
+    ("配置档 '{}' 中 '{}' 的值 {} 无效", "invalid value {2} for '{1}' in profile '{0}'"),
+    // 项目配置
+    (
+        "项目配置不能设置钩子，钩子只能写在用户配置中",
+        "project configuration cannot set hooks; put hooks in the user configuration",
+    ),
+    // config path
+    ("配置文件相关操作", "Configuration file operations"),
+    (
+        "输出用户配置、项目配置、缓存目录和状态目录的位置",
+        "Print the locations of the user configuration, project configuration, cache and state directories",
+    ),
+    ("输出机器可读的 JSON", "Print machine-readable JSON"),
+    ("无法确定", "unknown"),
+    ("{}（不存在）", "{} (does not exist)"),
+    ("未找到", "not found"),
+    ("用户配置: {}", "user configuration: {}"),
+    ("项目配置: {}", "project configuration: {}"),
+    ("缓存目录: {}", "cache directory: {}"),
+    ("状态目录: {}", "state directory: {}"),
+    // 注释
+    (
+        "转换为 JSON 时把 YAML、TOML 的注释存入 \"_comment_<键>\" 字段，转换为 YAML、TOML 时把这些字段写回注释",
+        "When converting to JSON, store YAML and TOML comments in \"_comment_<key>\" fields; when converting to YAML or TOML, write those fields back as comments",
+    ),
+    // TOML 对象数组
+    (
+        "TOML 中对象数组的写法：tables（[[...]] 段）、inline 或 auto（一行放得下时内联），可以用 PATH= 只指定某些数组（可多次指定）",
+        "How TOML writes arrays of objects: tables ([[...]] sections), inline, or auto (inline when it fits on one line); prefix with PATH= to target specific arrays (repeatable)",
+    ),
+    ("写法应为 tables、inline 或 auto", "style must be tables, inline or auto"),
+    // docs
+    ("生成配置的 Markdown 参考文档", "Generate a Markdown reference for a configuration"),
+    (
+        "每个键一行，列出类型、默认值和说明。说明取自 Schema 的 description 或配置文件中键上的注释",
+        "One row per key with its type, default and description. Descriptions come from the schema's description or from comments on the key in the configuration file",
+    ),
+    (
+        "JSON Schema 文件，提供类型、默认值、说明和可选值",
+        "JSON Schema file providing types, defaults, descriptions and allowed values",
+    ),
+    ("文档标题（默认为“<文件> 配置参考”）", "Document title (defaults to \"<file> configuration reference\")"),
+    ("{} 配置参考", "{} configuration reference"),
+    ("键", "Key"),
+    ("类型", "Type"),
+    ("默认值", "Default"),
+    ("说明", "Description"),
+    ("（必填）", "(required)"),
+    ("可选值: {}", "allowed values: {}"),
+    // defaults apply
+    ("按 JSON Schema 的默认值补全配置", "Fill in a configuration with JSON Schema defaults"),
+    (
+        "用 Schema 中的 default 补全缺少的键，输出完整的生效配置",
+        "Fill in missing keys with the schema's default values and print the full effective configuration",
+    ),
+    ("JSON Schema 文件", "JSON Schema file"),
+    ("目标格式（不指定则与源格式相同）", "Target format (same as the source format if omitted)"),
+    ("已补全: {}", "Filled in: {}"),
+    // defaults prune
+    ("删去值等于默认值的键，只留下有意修改的部分", "Remove keys whose values equal the defaults, keeping only intentional overrides"),
+    (
+        "默认值取自 Schema 中的 default，或参考配置文件中同一路径的值",
+        "Defaults come from the schema's default values, or from the same paths in a reference configuration file",
+    ),
+    ("参考配置文件，其中的值视为默认值", "Reference configuration file whose values are treated as defaults"),
+    ("已删去: {}", "Removed: {}"),
+    // resolve
+    (
+        "按 base + overlays/<环境> + local 的约定叠加配置，输出最终生效的配置",
+        "Layer configuration using the base + overlays/<env> + local convention and print the effective configuration",
+    ),
+    (
+        "覆盖文件在基础配置旁的 overlays 目录中，本机覆盖文件 local.* 与基础配置放在一起、不存在时跳过；文件可以是任意支持的格式",
+        "Overlays live in the overlays directory next to the base configuration; the local override local.* sits beside the base and is skipped when missing. Files may be in any supported format",
+    ),
+    ("基础配置文件", "Base configuration file"),
+    ("环境名，叠加 overlays/<环境>.* 中的覆盖", "Environment name; layers the overrides in overlays/<env>.*"),
+    ("覆盖文件所在目录（默认为基础配置旁的 overlays）", "Directory holding the overlays (defaults to overlays next to the base configuration)"),
+    ("本机覆盖文件（默认为基础配置旁的 local.*）", "Local override file (defaults to local.* next to the base configuration)"),
+    ("不叠加本机覆盖文件", "Do not layer the local override file"),
+    ("覆盖单个值（path=value），可重复，在所有文件之后应用", "Override a single value (path=value); repeatable, applied after all files"),
+    ("目标格式（默认使用基础配置的格式）", "Target format (defaults to the base configuration's format)"),
+    ("没有环境 '{}' 的覆盖文件", "no overlay for environment '{}'"),
+    ("有多个候选文件: {}", "multiple candidate files: {}"),
+    // 合并键
+    (
+        "对象数组按合并键逐个元素合并，而不是整体覆盖（[PATH=]KEY，可重复），如 '**.containers=name'",
+        "Merge arrays of objects element by element using a merge key instead of replacing them ([PATH=]KEY, repeatable), e.g. '**.containers=name'",
+    ),
+    ("缺少合并键", "missing merge key"),
+    // drift
+    ("检查线上配置相对基准配置的漂移", "Check how live configuration drifts from a golden reference"),
+    (
+        "每个文件分别与基准比较：~ 值与基准不同，- 缺少基准中的键，+ 基准中没有的键。退出码：0 没有漂移，1 发现漂移，2 无法完成检查",
+        "Each file is compared with the reference: ~ value differs, - key from the reference is missing, + key not in the reference. Exit codes: 0 no drift, 1 drift found, 2 the check could not run",
+    ),
+    ("审定的基准配置", "Approved reference configuration"),
+    ("要检查的配置文件，可以有多个", "Configuration files to check; may be several"),
+    ("不报告基准中没有的键", "Do not report keys missing from the reference"),
+    ("输出 JSON 格式的报告", "Print the report as JSON"),
+    ("检查 {} ({})", "Checking {} ({})"),
+    ("没有漂移", "No drift"),
+    ("{} 个文件中发现 {} 处漂移", "drift found in {} file(s): {} difference(s)"),
+    ("无法完成漂移检测: {}", "drift check could not run: {}"),
+    // unused
+    ("列出配置中有、但应用不认识的键，用于清理废弃的设置", "List keys present in the configuration but unknown to the application, to clean up dead settings"),
+    (
+        "一个键不认识时不再列出它下面的键。发现未使用的键时以非零状态退出",
+        "Keys below an unknown key are not listed separately. Exits with a non-zero status when unused keys are found",
+    ),
+    (
+        "键列表文件：每行一个路径，支持 * 和 **，# 开头的行是注释；server.port 同时认识 server",
+        "Key list file: one path per line, * and ** supported, lines starting with # are comments; server.port also makes server known",
+    ),
+    (
+        "JSON Schema 文件，properties、patternProperties 和 additionalProperties 中的键视为认识",
+        "JSON Schema file; keys in properties, patternProperties and additionalProperties are known",
+    ),
+    ("没有未使用的键", "No unused keys"),
+    ("发现 {} 个未使用的键", "found {} unused key(s)"),
+    // merge --interactive
+    (
+        "逐个询问冲突保留哪一边（l 左边、r 右边、e 在编辑器中修改、q 放弃），写出解决后的结果",
+        "Ask which side to keep for each conflict (l left, r right, e edit in the editor, q abort) and write the resolved result",
+    ),
+    ("保留左边 (l)、右边 (r)、编辑 (e) 还是放弃 (q)？ ", "Keep left (l), right (r), edit (e) or abort (q)? "),
+    // undo
+    ("撤销最近一次对文件的原地修改（--write、edit 等）", "Undo the most recent in-place change to a file (--write, edit, etc.)"),
+    (
+        "修改前的内容记录在状态目录的 history 中，每撤销一次回退一个版本",
+        "Previous contents are recorded under history in the state directory; each undo steps back one version",
+    ),
+    ("列出修改记录（从新到旧），不恢复", "List recorded changes (newest first) without restoring"),
+    ("文件在那次修改之后又被改过时仍然恢复", "Restore even if the file was changed after that modification"),
+    ("警告: 无法记录 {} 的修改历史: {}", "warning: could not record history for {}: {}"),
+    ("无法确定状态目录", "cannot determine the state directory"),
+    ("没有修改记录", "no recorded changes"),
+    (
+        "文件在 {} 的修改之后又被改过（使用 --force 仍然恢复）",
+        "the file was changed after the modification at {} (use --force to restore anyway)",
+    ),
+    ("已撤销 {} 在 {} 的修改", "Undid the {} change made at {}"),
+    ("无法撤销 '{}': {}", "cannot undo '{}': {}"),
+    // transform pipeline
+    ("只保留路径（及其子树），其余的键都删除，支持通配，可重复", "Keep only PATH (and its subtree), dropping everything else; supports wildcards; repeatable"),
+    ("删除路径，支持通配，可重复", "Remove PATH; supports wildcards; repeatable"),
+    ("重命名键（如 server.addr=address），可重复", "Rename a key (e.g. server.addr=address); repeatable"),
+    (
+        "把路径上的标量转换为 string、number、integer 或 boolean（如 'ports.*=integer'），可重复",
+        "Convert scalars at PATH to string, number, integer or boolean (e.g. 'ports.*=integer'); repeatable",
+    ),
+    (
+        "把路径上的值替换为 ***（在脚本和外部命令之后执行），支持通配，可重复",
+        "Replace values at PATH with *** (runs after scripts and external commands); supports wildcards; repeatable",
+    ),
+    ("应为 path=key 的形式", "expected path=key"),
+    ("缺少新的键名", "missing the new key name"),
+    ("应为 path=type 的形式", "expected path=type"),
+    (
+        "未知的类型: {}（可用 string、number、integer、boolean）",
+        "unknown type: {} (use string, number, integer or boolean)",
+    ),
+    ("{} 无法转换为{}: {}", "{} cannot be converted to {}: {}"),
+    ("字符串", "string"),
+    ("数字（整数保持为整数）", "number (integers stay integers)"),
+    ("整数（小数部分为 0 的数字也可以）", "integer (numbers with a zero fractional part are accepted)"),
+    ("布尔值（true/false、yes/no、on/off、1/0）", "boolean (true/false, yes/no, on/off, 1/0)"),
+    ("数字", "number"),
+    ("整数", "integer"),
+    ("布尔值", "boolean"),
+    // output options
+    ("JSON 输出的缩进空格数（1-8），指定时即为美化输出", "Indent width for JSON output (1-8); implies pretty output"),
+    ("YAML 字符串值的引号写法", "How string values are quoted in YAML output"),
+    ("null 的处理方式", "How null values are handled"),
+    ("只在需要时加引号", "Quote only when needed"),
+    ("所有字符串值都用双引号", "Double-quote every string value"),
+    ("保持为 null（TOML 没有 null，会报错）", "Keep nulls (TOML has no null and reports an error)"),
+    ("删除值为 null 的键和数组元素", "Drop keys and array elements whose value is null"),
+    ("遇到 null 时报错并给出路径", "Fail on the first null, reporting its path"),
+    ("{} 的值为 null", "{} is null"),
+    // target capabilities
+    ("{} 的顶层只能是对象", "the top level of {} must be an object"),
+    (
+        "{} 是日期时间，{} 没有日期时间类型，会写成标记对象（可以用 --normalize-dates 转换为字符串）",
+        "{} is a datetime; {} has no datetime type, so it is written as a marker object (use --normalize-dates to turn it into a string)",
+    ),
+    ("{} 的值为 null，{} 没有 null（可以用 --nulls omit 删除）", "{} is null; {} has no null (use --nulls omit to drop it)"),
+    // validate --stdin-stream
+    ("JSON Lines：每行一个 JSON 值，逐行验证", "JSON Lines: one JSON value per line, validated line by line"),
+    ("--stdin-stream 只支持 jsonl 和 yaml", "--stdin-stream only supports jsonl and yaml"),
+    ("✓ -:{}: 第 {} 条记录语法正确", "✓ -:{}: record {} is valid"),
+    ("✗ -:{}: 第 {} 条记录: {}", "✗ -:{}: record {}: {}"),
+    ("{} 条记录中有 {} 条验证失败", "{1} of {0} records failed validation"),
+    ("指定格式（jsonl 表示每行一个 JSON 值）", "Input format (jsonl means one JSON value per line)"),
+    (
+        "持续读取标准输入，每条记录（JSON Lines 的一行或 YAML 的一个文档）到达时立即验证并输出结果",
+        "Keep reading standard input and validate each record (a JSON Lines line or a YAML document) as soon as it arrives",
+    ),
+    ("第 {} 行: {}", "line {}: {}"),
+    // check (size budget)
+    ("[[budget]] 的 pattern 无效: {}", "invalid pattern in [[budget]]: {}"),
+    ("文件有 {} 字节，超过了 {} 字节（{}）的限制", "file is {} bytes, over the limit of {} bytes ({})"),
+    ("共有 {} 个键，超过了 {} 个的限制", "{} keys in total, over the limit of {}"),
+    ("嵌套了 {} 层，超过了 {} 层的限制", "nested {} levels deep, over the limit of {}"),
+    ("无效的字节数: {}（例如 64KB、1MiB）", "invalid size: {} (e.g. 64KB, 1MiB)"),
+    ("✓ {}: {} 字节，{} 个键，{} 层", "✓ {}: {} bytes, {} keys, {} levels"),
+    ("检查了 {} 个文件：{} 个超出限制", "checked {} files: {} over budget"),
+    ("{} 个文件中有 {} 个超出限制", "{1} of {0} files exceed their budget"),
+    ("检查配置文件是否超出大小预算", "Check config files against a size budget"),
+    (
+        "限制文件的字节数、键的总数和嵌套层数，超出时以非零状态退出，用于在 CI 中提前发现下游系统（如 1MB 的 ConfigMap）无法接受的配置。配置文件中可以用 [[budget]] 按文件模式设置限制，命令行参数对所有文件生效并覆盖配置",
+        "Limits file size, total key count and nesting depth and exits non-zero when a file exceeds them, so CI can reject configs that downstream systems (such as 1MB ConfigMaps) would refuse. Limits can be set per file pattern with [[budget]] in the config file; command-line options apply to every file and override the config",
+    ),
+    ("文件字节数上限（如 64KB、1MiB）", "Maximum file size (e.g. 64KB, 1MiB)"),
+    ("所有对象中键的总数上限", "Maximum total number of keys across all objects"),
+    ("对象和数组的嵌套层数上限", "Maximum nesting depth of objects and arrays"),
+    // lint key naming
+    ("[[naming]] 的 pattern 无效: {}", "invalid pattern in [[naming]]: {}"),
+    ("[[naming]] 的 regex 无效: {}", "invalid regex in [[naming]]: {}"),
+    ("[[naming]] 需要设置 case 或 regex 之一", "[[naming]] needs exactly one of case or regex"),
+    ("{} 不符合 {} 命名，应为 {}", "{} is not {}; expected {}"),
+    ("{} 的键名不匹配 {}", "the key of {} does not match {}"),
+    ("{} 不符合 {} 命名", "{} is not {}"),
+    (
+        "--fix 一次只能修正一个文件，修正多个文件时请加上 --write",
+        "--fix can only print one fixed file at a time; add --write to fix several files in place",
+    ),
+    ("要求键名使用的大小写风格（覆盖配置文件中的 [[naming]]）", "Case convention keys must follow (overrides [[naming]] in the config file)"),
+    ("要求键名匹配的正则表达式（覆盖配置文件中的 [[naming]]）", "Regex keys must match (overrides [[naming]] in the config file)"),
+    // lint --fix
+    (
+        "修正有确定修正方式的问题（键名大小写、重复的键、键的顺序、缩进），修正后的文件输出到标准输出（只能指定一个文件）。只重新生成被修正的对象，其中有注释、锚点或流式集合时报错",
+        "Fix problems that have a deterministic fix (key case, duplicate keys, key order, indentation) and print the fixed file to standard output (one file only). Only the fixed objects are rewritten; fails if they contain comments, anchors or flow collections",
+    ),
+    ("与 --fix 一起使用时原地修改文件，可以同时修正多个文件", "With --fix, rewrite files in place; several files can be fixed at once"),
+    ("要求对象的键按顺序排列（默认按字符逐个比较）", "Require object keys to be sorted (lexical by default)"),
+    ("要求 JSON 的缩进是 N 个空格的整数倍，修正时按 N 个空格缩进", "Require JSON indentation to be a multiple of N spaces; fixes indent with N spaces"),
+    ("{}，修正了 {} 个问题", "{}; fixed {} problems"),
+    ("键 {} 已在第 {} 行出现，只有最后一个值生效", "key {} already appears on line {}; only the last value takes effect"),
+    ("{} 重复出现，只有最后一个值生效", "{} appears more than once; only the last value takes effect"),
+    ("{} 的键没有排序：{} 应该排在 {} 之前", "keys of {} are not sorted: {} should come before {}"),
+    ("缩进不是 {} 个空格的整数倍（共 {} 行）", "indentation is not a multiple of {} spaces ({} lines)"),
+    // parse cache
+    ("无法写入解析缓存 {}: {}", "cannot write the parse cache {}: {}"),
+    ("使用缓存的解析结果（{} 字节）", "using the cached parse result ({} bytes)"),
+    // --yaml-tags
+    ("YAML 中 !!timestamp 和 !!binary 标签的处理方式", "How YAML !!timestamp and !!binary tags are handled"),
+    ("读成日期时间和二进制数据（写出时二进制数据是 base64 字符串）", "Read them as datetimes and binary data (binary data is written as a base64 string)"),
+    ("保持为字符串", "Keep them as strings"),
+    ("出现这两个标签时报错", "Fail when either tag is used"),
+    ("{}（第 {} 行）: {}", "{} (line {}): {}"),
+    ("不允许使用 {} 标签（--yaml-tags error）", "the {} tag is not allowed (--yaml-tags error)"),
+    ("不是有效的 base64: {}", "not valid base64: {}"),
+    ("不是有效的时间戳: {}", "not a valid timestamp: {}"),
+    // lint duplicates
+    ("至少包含 N 个值的相同子树出现在多处时给出警告", "Warn when an identical subtree with at least N values appears in several places"),
+    (
+        "{} 处内容完全相同（各 {} 个值）: {}，可以改用锚点或 include 共享",
+        "{} identical subtrees ({} values each): {}; consider sharing them with an anchor or include",
+    ),
+    (
+        "{} 和 {} 的键名相似且值相同（{}），可能是重复或拼错的配置",
+        "{} and {} have similar keys and the same value ({}); possibly a duplicated or misspelt setting",
+    ),
+    // --custom-tags
+    ("YAML 中自定义标签（!Ref、!Sub 等）的处理方式", "How custom YAML tags (!Ref, !Sub, ...) are handled"),
+    ("读成标签对象，写成 YAML 时还原为标签，写成其他格式时报错", "Read them as tag objects and restore the tags in YAML output; fail for other output formats"),
+    ("读成标签对象 {\"!Ref\": ...}，写成任何格式都保持这个对象", "Read them as tag objects {\"!Ref\": ...} and keep these objects in every output format"),
+    ("出现自定义标签时报错", "Fail when a custom tag is used"),
+    (
+        "第 {} 行: 不支持自定义标签 {}（可以用 --custom-tags keep 或 wrap 保留）",
+        "line {}: custom tag {} is not supported (use --custom-tags keep or wrap to preserve it)",
+    ),
+    (
+        "自定义标签 {} 无法写成 {}（可以用 --custom-tags wrap 写成对象）",
+        "custom tag {} cannot be written as {} (use --custom-tags wrap to write it as an object)",
+    ),
+    // --order-file
+    ("按排序文件中路径的先后排列键（每行一个路径，支持通配），没有列出的键排在后面", "Order keys by the paths listed in FILE (one path per line, wildcards allowed); unlisted keys come after them"),
+    // format --only
+    ("只格式化路径上的子树（如 server.tls），文件的其余部分保持不变，值变换也只作用于这个子树", "Format only the subtree at PATH (e.g. server.tls), leaving the rest of the file unchanged; transforms also apply only to this subtree"),
+    ("--only 只支持 JSON、YAML 和 TOML", "--only supports only JSON, YAML and TOML"),
+    (
+        "无法只格式化 {}：拼接后文档的内容发生了变化（子树中的锚点可能在别处被引用，或者它位于流式集合中）",
+        "cannot format only {}: the document content changed after splicing (an anchor in the subtree may be referenced elsewhere, or it is inside a flow collection)",
+    ),
+    ("TOML 中 --only 只能指定用 [表头] 写出的表: {}", "in TOML, --only must name a table written with a [header]: {}"),
+    ("{} 的子表没有写在一起，无法只格式化这个表", "the subtables of {} are not written together, so the table cannot be formatted on its own"),
+    (
+        "{} 中有注释、锚点或流式集合，修正后会丢失，没有修改文件",
+        "{} contains comments, anchors or flow collections that the fix would lose; the file was not changed",
+    ),
+    // --rules
+    ("按规则文件对路径上的值脱敏、计算摘要或加密（默认使用配置文件 [transform] 段的 rules）", "Redact, hash or encrypt values at paths according to a rules file (defaults to rules in the [transform] config section)"),
+    ("有 encrypt 规则，但用户配置的 [transform] 段没有设置加密命令 encrypt", "there are encrypt rules, but no encrypt command is set in the [transform] section of the user config"),
+    ("项目配置不能设置加密命令，加密命令只能写在用户配置中", "the project config cannot set the encrypt command; it can only be set in the user config"),
+    (
+        "项目配置不能设置可信的公钥，公钥只能写在用户配置中",
+        "the project config cannot set a trusted public key; it can only be set in the user config",
+    ),
+    // --report-format html
+    ("报告的格式（-f/--format 指定的是输入的格式）", "Report format (-f/--format selects the input format)"),
+    ("每个问题一行文本", "One line of text per finding"),
+    ("独立的 HTML 文件（可折叠的树，差异着色）", "Standalone HTML file (collapsible tree, colored changes)"),
+    ("--fix 输出修正后的文件时不能同时输出报告，请加上 --write", "--fix cannot print the fixed file and a report at the same time; add --write"),
+    ("confconv 比较报告", "confconv diff report"),
+    ("confconv 检查报告", "confconv lint report"),
+    ("{} 处差异：{} 处新增，{} 处删除，{} 处修改", "{} differences: {} added, {} removed, {} changed"),
+    ("没有问题", "No problems"),
+    ("{} 个错误，{} 个警告", "{} errors, {} warnings"),
+    ("级别", "Level"),
+    ("规则", "Rule"),
+    ("位置", "Location"),
+    // --report-format junit
+    ("JUnit 风格的 XML，供 CI 在测试页面中显示", "JUnit-style XML for CI test result pages"),
+    ("报告的格式", "Report format"),
+    ("{} 个问题，第一个: {}", "{} problems, the first: {}"),
+    // INI
+    ("INI 格式（段对应表，所有值都是字符串）", "INI format (sections map to tables, all values are strings)"),
+    ("段名缺少 ]", "section header is missing ]"),
+    ("段名 [{}] 中有空的部分", "section header [{}] has an empty part"),
+    ("缺少分隔符 {}: {}", "missing separator {}: {}"),
+    ("键名为空", "empty key"),
+    ("重复的键 {}", "duplicate key {}"),
+    ("{} 已经是一个值，不能再作为段", "{} is already a value and cannot be used as a section"),
+    ("INI 的顶层只能是对象", "the top level of INI must be an object"),
+    ("{} 的键名无法写成 INI", "the key of {} cannot be written in INI"),
+    ("{} 的键名无法写成 INI 的段名", "the key of {} cannot be written as an INI section name"),
+    ("{} 含有换行，INI 无法表示", "{} contains a line break, which INI cannot represent"),
+    ("{} 的值为 null，INI 没有 null（可以用 --nulls omit 删除）", "{} is null, and INI has no null (use --nulls omit to drop it)"),
+    // --annotate
+    ("同时把诊断信息输出为 CI 的注解，显示在 PR 的代码行上", "Also print diagnostics as CI annotations so they show inline on the PR"),
+    ("GitHub Actions 的工作流命令（::error file=…,line=…::说明）", "GitHub Actions workflow commands (::error file=…,line=…::message)"),
+    // --ini-dialect
+    ("INI 方言：git、npmrc，或者 separator=、comments=、repeated=、subsections= 设置，用逗号分隔", "INI dialect: git, npmrc, or comma-separated separator=, comments=, repeated= and subsections= settings"),
+    ("未知的 INI 方言设置: {}（可用: git、npmrc、default、separator=any|eq|colon、comments=any|semicolon|hash、repeated=error|array|brackets、subsections=dotted|quoted）", "unknown INI dialect setting: {} (available: git, npmrc, default, separator=any|eq|colon, comments=any|semicolon|hash, repeated=error|array|brackets, subsections=dotted|quoted)"),
+    ("子段名 {} 应当写在双引号中", "subsection name {} should be in double quotes"),
+    ("{} 是数组，INI 没有数组（可以用 --ini-dialect repeated=array）", "{} is an array, and INI has no arrays (use --ini-dialect repeated=array)"),
+    ("{} 是少于两个元素的数组，写成重复的键后无法还原", "{} is an array with fewer than two elements, which cannot round-trip as repeated keys"),
+    ("{} 的嵌套超过两层，git config 风格的子段无法表示", "{} is nested more than two levels deep, which git config subsections cannot represent"),
+    ("{} 在数组中，INI 无法表示嵌套的数组和对象", "{} is inside an array, and INI cannot represent nested arrays or objects"),
+    // env 变量名冲突
+    ("{} 和 {} 都对应环境变量 {}", "{} and {} both map to the environment variable {}"),
+];
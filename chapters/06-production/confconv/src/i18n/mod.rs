@@ -0,0 +1,246 @@
+//! 界面语言
+//!
+//! 源代码里的帮助文本和提示信息都用中文书写，同时作为消息目录的键：
+//! 选择英文时按原文在 [`en`] 目录中查找译文，找不到时回退到中文原文。
+//! 带参数的消息用 [`t!`](crate::t) 宏生成，占位符写法与 `format!` 相同（只支持 `{}`），
+//! 译文中可以用 `{0}`、`{1}` 调整参数的顺序
+
+mod en;
+
+use clap::{Command, ValueEnum};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fmt::{Display, Write};
+use std::sync::OnceLock;
+
+/// 界面语言
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum Lang {
+    /// 中文
+    #[value(help = tr("中文"))]
+    Zh,
+    /// 英文
+    #[value(help = tr("英文"))]
+    En,
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// 设置界面语言，只有第一次调用有效
+pub fn init(lang: Lang) {
+    let _ = LANG.set(lang);
+}
+
+/// 当前的界面语言，没有调用过 [`init`] 时按环境变量确定
+pub fn current() -> Lang {
+    *LANG.get_or_init(from_env)
+}
+
+/// 从命令行参数中的 `--lang` 确定界面语言，没有指定时按环境变量确定
+///
+/// 需要在解析参数之前确定语言，帮助信息才能使用对应的语言输出
+pub fn detect(args: impl IntoIterator<Item = OsString>) -> Lang {
+    let mut args = args
+        .into_iter()
+        .map(|arg| arg.to_string_lossy().into_owned());
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        let value = match arg.strip_prefix("--lang") {
+            Some("") => args.next(),
+            Some(rest) => rest.strip_prefix('=').map(str::to_string),
+            None => None,
+        };
+        // 这里不能用 ValueEnum::from_str：它会生成带帮助文本的取值，提前确定了界面语言
+        match value.map(|v| v.to_ascii_lowercase()).as_deref() {
+            Some("zh") => return Lang::Zh,
+            Some("en") => return Lang::En,
+            _ => {}
+        }
+    }
+    from_env()
+}
+
+/// 按 `LC_ALL`、`LC_MESSAGES`、`LANG` 确定界面语言
+///
+/// 未设置或为 `C`/`POSIX` 时使用中文，`zh` 开头使用中文，其他语言环境使用英文
+pub fn from_env() -> Lang {
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty());
+    match locale.as_deref() {
+        None | Some("C" | "POSIX") => Lang::Zh,
+        Some(locale) if locale.starts_with("C.") || locale.starts_with("zh") => Lang::Zh,
+        Some(_) => Lang::En,
+    }
+}
+
+/// 翻译一条消息，当前语言为中文或目录中没有译文时返回原文
+pub fn tr(text: &str) -> &str {
+    match current() {
+        Lang::Zh => text,
+        Lang::En => catalog().get(text).copied().unwrap_or(text),
+    }
+}
+
+/// 英文消息目录
+fn catalog() -> &'static HashMap<&'static str, &'static str> {
+    static CATALOG: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+    CATALOG.get_or_init(|| en::MESSAGES.iter().copied().collect())
+}
+
+/// 用参数填充消息模板：`{}` 依次取参数，`{n}` 取第 n 个参数，`{{` 和 `}}` 表示花括号本身
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                output.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                output.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    index.push(c);
+                }
+                let index = if index.is_empty() {
+                    next += 1;
+                    next - 1
+                } else {
+                    index.parse().unwrap_or(usize::MAX)
+                };
+                if let Some(arg) = args.get(index) {
+                    let _ = write!(output, "{}", arg);
+                }
+            }
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// 生成当前语言的消息，用法与 `format!` 相同，但模板必须是字符串字面量
+#[macro_export]
+macro_rules! t {
+    ($text:literal $(,)?) => {
+        $crate::i18n::fill($crate::i18n::tr($text), &[])
+    };
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::tr($text),
+            &[$(&$arg as &dyn ::std::fmt::Display),+],
+        )
+    };
+}
+
+/// 把命令的说明和参数帮助翻译为当前语言
+///
+/// 说明按段落翻译，以“示例：”开头的段落只翻译开头，命令行本身保持不变
+pub fn localize(command: Command) -> Command {
+    if current() == Lang::Zh {
+        return command;
+    }
+    let mut command = command;
+    if let Some(about) = command.get_about().map(|s| translate_doc(&s.to_string())) {
+        command = command.about(about);
+    }
+    if let Some(about) = command
+        .get_long_about()
+        .map(|s| translate_doc(&s.to_string()))
+    {
+        command = command.long_about(about);
+    }
+    command
+        .mut_args(|arg| {
+            let help = arg.get_help().map(|s| translate_doc(&s.to_string()));
+            let long_help = arg.get_long_help().map(|s| translate_doc(&s.to_string()));
+            let arg = match help {
+                Some(help) => arg.help(help),
+                None => arg,
+            };
+            match long_help {
+                Some(help) => arg.long_help(help),
+                None => arg,
+            }
+        })
+        .mut_subcommands(localize)
+}
+
+/// 按段落翻译文档文本
+fn translate_doc(text: &str) -> String {
+    text.split("\n\n")
+        .map(|paragraph| match paragraph.strip_prefix("示例：") {
+            Some(rest) => format!("{}{}", tr("示例："), rest),
+            None => tr(paragraph).to_string(),
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 统计模板中的占位符数量
+    fn placeholders(template: &str) -> usize {
+        template
+            .replace("{{", "")
+            .replace("}}", "")
+            .matches('{')
+            .count()
+    }
+
+    #[test]
+    fn translations_keep_placeholders() {
+        for (zh, en) in en::MESSAGES {
+            assert_eq!(placeholders(zh), placeholders(en), "{}", zh);
+        }
+    }
+
+    #[test]
+    fn catalog_has_no_duplicates() {
+        assert_eq!(catalog().len(), en::MESSAGES.len());
+    }
+
+    /// 命令行帮助中的每一段说明都要有译文
+    #[test]
+    fn help_is_translated() {
+        fn check(command: &Command) {
+            let docs = [command.get_about(), command.get_long_about()]
+                .into_iter()
+                .chain(
+                    command
+                        .get_arguments()
+                        .flat_map(|arg| [arg.get_help(), arg.get_long_help()]),
+                )
+                .flatten();
+            for doc in docs {
+                for paragraph in doc.to_string().split("\n\n") {
+                    if !paragraph.starts_with("示例：") {
+                        assert!(catalog().contains_key(paragraph), "{}", paragraph);
+                    }
+                }
+            }
+            command.get_subcommands().for_each(check);
+        }
+        check(&<crate::cli::Cli as clap::CommandFactory>::command());
+    }
+
+    #[test]
+    fn fill_placeholders() {
+        assert_eq!(fill("{} / {}", &[&1, &2]), "1 / 2");
+        assert_eq!(fill("{1} / {0}", &[&1, &2]), "2 / 1");
+        assert_eq!(fill("{{{}}}", &[&"a"]), "{a}");
+    }
+}
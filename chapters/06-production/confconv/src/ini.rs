@@ -0,0 +1,607 @@
+//! INI 格式
+//!
+//! 读写旧服务常用的 `.ini` / `.cfg` 文件，段对应中间表示中的表：
+//!
+//! ```text
+//! name = app              {
+//!                           "name": "app",
+//! [server]                  "server": {
+//! host = 0.0.0.0              "host": "0.0.0.0",
+//! port: 8080          →       "port": "8080",
+//!                             "tls": {
+//! [server.tls]                  "cert": "/etc/app.pem"
+//! cert = "/etc/app.pem"       }
+//!                           }
+//!                         }
+//! ```
+//!
+//! - 第一个段之前的键属于顶层；段名中的 `.` 表示嵌套，`[server.tls]` 是 `server` 表中的 `tls` 表，
+//!   键名中的 `.` 没有特殊含义
+//! - 键和值用 `=` 或 `:` 分隔（以先出现的为准），两边的空白会被去掉；值两端成对的引号会被去掉，
+//!   引号里面不做转义处理
+//! - 整行以 `;` 或 `#` 开头的是注释；行末的 `;`、`#` 属于值本身。`--comments-as-keys` 时
+//!   键和段上方紧挨着的注释与其他格式一样保留（见 [`crate::comments`]）
+//! - 同一个段可以出现多次，键合并在一起；同一个段中重复的键是错误
+//! - 所有值都读成字符串，需要数字或布尔值时可以用 `--coerce` 转换
+//!
+//! 写出时对象按同样的规则写成段（段中的键写在它的子段之前），数字和布尔值写成文本，
+//! 两端有空白的值加上引号。INI 没有 null，也不能表示含有换行的值，遇到时报错
+//!
+//! ## 方言
+//!
+//! 各种程序的 INI 写法不同，全局参数 `--ini-dialect` 用逗号分隔的设置选择（后面的覆盖前面的）：
+//! - `separator=any|eq|colon`：键和值的分隔符，`eq` 时键名中可以有 `:`（如 npmrc 的 `@scope:registry`）
+//! - `comments=any|semicolon|hash`：注释符号
+//! - `repeated=error|array|brackets`：重复的键。`array` 收集成数组，写出时每个元素一行；
+//!   `brackets` 按 npm 的写法，`key[] = v` 的各行组成数组
+//! - `subsections=dotted|quoted`：子段写成 `[a.b]`，还是 git config 的 `[a "b"]`（只有两层，
+//!   引号内可以有 `.`，`\"` 和 `\\` 是转义）
+//! - 预设 `git`（`separator=eq,repeated=array,subsections=quoted`）和
+//!   `npmrc`（`separator=eq,repeated=brackets`）
+
+use crate::date;
+use crate::dialect;
+use crate::error::{Error, Result};
+use crate::nonfinite;
+use crate::path::{Path, Segment};
+use crate::t;
+use serde_json::{Map, Value};
+use std::fmt::Write;
+
+/// 键和值的分隔符
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Separator {
+    /// `=` 或 `:`，以先出现的为准，写出时用 `=`
+    #[default]
+    Any,
+    /// 只有 `=`
+    Equals,
+    /// 只有 `:`
+    Colon,
+}
+
+/// 注释符号
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Comments {
+    /// `;` 或 `#`，写出时用 `#`
+    #[default]
+    Any,
+    /// 只有 `;`
+    Semicolon,
+    /// 只有 `#`
+    Hash,
+}
+
+/// 重复的键
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Repeated {
+    /// 报错，也不能写出数组
+    #[default]
+    Error,
+    /// 收集成数组
+    Array,
+    /// `key[]` 的各行组成数组
+    Brackets,
+}
+
+/// 子段的写法
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Subsections {
+    /// `[a.b]`
+    #[default]
+    Dotted,
+    /// `[a "b"]`
+    Quoted,
+}
+
+/// INI 方言，见模块文档
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct IniDialect {
+    /// 分隔符
+    pub separator: Separator,
+    /// 注释符号
+    pub comments: Comments,
+    /// 重复的键
+    pub repeated: Repeated,
+    /// 子段的写法
+    pub subsections: Subsections,
+}
+
+impl IniDialect {
+    /// 解析 `--ini-dialect` 的参数
+    pub fn parse(text: &str) -> std::result::Result<Self, String> {
+        let mut dialect = IniDialect::default();
+        for item in text.split(',').map(str::trim) {
+            let (name, value) = item.split_once('=').unwrap_or((item, ""));
+            match (name, value) {
+                ("default", "") => dialect = IniDialect::default(),
+                ("git", "") => {
+                    dialect.separator = Separator::Equals;
+                    dialect.repeated = Repeated::Array;
+                    dialect.subsections = Subsections::Quoted;
+                }
+                ("npmrc", "") => {
+                    dialect.separator = Separator::Equals;
+                    dialect.repeated = Repeated::Brackets;
+                }
+                ("separator", "any") => dialect.separator = Separator::Any,
+                ("separator", "eq") => dialect.separator = Separator::Equals,
+                ("separator", "colon") => dialect.separator = Separator::Colon,
+                ("comments", "any") => dialect.comments = Comments::Any,
+                ("comments", "semicolon") => dialect.comments = Comments::Semicolon,
+                ("comments", "hash") => dialect.comments = Comments::Hash,
+                ("repeated", "error") => dialect.repeated = Repeated::Error,
+                ("repeated", "array") => dialect.repeated = Repeated::Array,
+                ("repeated", "brackets") => dialect.repeated = Repeated::Brackets,
+                ("subsections", "dotted") => dialect.subsections = Subsections::Dotted,
+                ("subsections", "quoted") => dialect.subsections = Subsections::Quoted,
+                _ => {
+                    return Err(t!(
+                        "未知的 INI 方言设置: {}（可用: git、npmrc、default、separator=any|eq|colon、comments=any|semicolon|hash、repeated=error|array|brackets、subsections=dotted|quoted）",
+                        item
+                    ))
+                }
+            }
+        }
+        Ok(dialect)
+    }
+
+    /// 注释符号
+    fn comment_chars(&self) -> &'static [char] {
+        match self.comments {
+            Comments::Any => &[';', '#'],
+            Comments::Semicolon => &[';'],
+            Comments::Hash => &['#'],
+        }
+    }
+
+    /// 写出注释时使用的符号
+    pub fn comment_char(&self) -> char {
+        match self.comments {
+            Comments::Semicolon => ';',
+            _ => '#',
+        }
+    }
+
+    /// 分隔符
+    fn separator_chars(&self) -> &'static [char] {
+        match self.separator {
+            Separator::Any => &['=', ':'],
+            Separator::Equals => &['='],
+            Separator::Colon => &[':'],
+        }
+    }
+}
+
+/// 本次运行的方言
+fn current() -> IniDialect {
+    dialect::current().ini
+}
+
+/// 一行 INI 文本
+pub(crate) enum Line<'a> {
+    /// 空行
+    Blank,
+    /// 注释，不含开头的注释符号
+    Comment(&'a str),
+    /// 段名的各部分
+    Section(Vec<String>),
+    /// 键和去掉引号之前的值
+    Entry(&'a str, &'a str),
+}
+
+/// 按方言识别一行文本，语法错误时返回说明
+pub(crate) fn classify<'a>(
+    line: &'a str,
+    dialect: &IniDialect,
+) -> std::result::Result<Line<'a>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(Line::Blank);
+    }
+    if let Some(text) = line.strip_prefix(dialect.comment_chars()) {
+        return Ok(Line::Comment(text));
+    }
+    if let Some(rest) = line.strip_prefix('[') {
+        let name = rest.strip_suffix(']').ok_or_else(|| t!("段名缺少 ]"))?;
+        let section = match (dialect.subsections, name.split_once(char::is_whitespace)) {
+            (Subsections::Quoted, Some((section, sub))) => {
+                let sub = unescape_subsection(sub.trim())
+                    .ok_or_else(|| t!("子段名 {} 应当写在双引号中", sub.trim()))?;
+                vec![section.trim().to_string(), sub]
+            }
+            _ => name
+                .split('.')
+                .map(|part| part.trim().to_string())
+                .collect(),
+        };
+        if section.iter().any(String::is_empty) {
+            return Err(t!("段名 [{}] 中有空的部分", name));
+        }
+        return Ok(Line::Section(section));
+    }
+    let at = line.find(dialect.separator_chars()).ok_or_else(|| {
+        let separators: String = dialect.separator_chars().iter().collect();
+        t!("缺少分隔符 {}: {}", separators, line)
+    })?;
+    let key = line[..at].trim();
+    if key.is_empty() {
+        return Err(t!("键名为空"));
+    }
+    Ok(Line::Entry(key, line[at + 1..].trim()))
+}
+
+/// git config 的子段名：去掉双引号，处理 `\"` 和 `\\`
+fn unescape_subsection(text: &str) -> Option<String> {
+    let inner = text.strip_prefix('"')?.strip_suffix('"')?;
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push(chars.next()?),
+            c => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+/// 解析 INI 文本
+pub fn parse(input: &str) -> Result<Value> {
+    parse_with(input, &current())
+}
+
+/// 按指定的方言解析 INI 文本
+pub fn parse_with(input: &str, dialect: &IniDialect) -> Result<Value> {
+    let mut root = Map::new();
+    let mut section: Vec<String> = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let error = |message: String| Error::Parse {
+            format: "INI",
+            source: t!("第 {} 行: {}", i + 1, message),
+        };
+        match classify(line, dialect).map_err(error)? {
+            Line::Blank | Line::Comment(_) => {}
+            Line::Section(name) => {
+                section = name;
+                table(&mut root, &section).map_err(error)?;
+            }
+            Line::Entry(key, value) => {
+                let table = table(&mut root, &section).map_err(error)?;
+                let value = Value::String(unquote(value).to_string());
+                let bracketed = match dialect.repeated {
+                    Repeated::Brackets => key.strip_suffix("[]").map(str::trim_end),
+                    _ => None,
+                };
+                if let Some(key) = bracketed {
+                    match table
+                        .entry(key.to_string())
+                        .or_insert_with(|| Value::Array(Vec::new()))
+                    {
+                        Value::Array(items) => items.push(value),
+                        _ => return Err(error(t!("重复的键 {}", key))),
+                    }
+                    continue;
+                }
+                match table.get_mut(key) {
+                    None => {
+                        table.insert(key.to_string(), value);
+                    }
+                    Some(Value::Array(items)) if dialect.repeated == Repeated::Array => {
+                        items.push(value)
+                    }
+                    Some(Value::String(first)) if dialect.repeated == Repeated::Array => {
+                        let first = Value::String(std::mem::take(first));
+                        table.insert(key.to_string(), Value::Array(vec![first, value]));
+                    }
+                    Some(_) => return Err(error(t!("重复的键 {}", key))),
+                }
+            }
+        }
+    }
+    Ok(Value::Object(root))
+}
+
+/// 段对应的表，不存在时创建
+fn table<'a>(
+    root: &'a mut Map<String, Value>,
+    section: &[String],
+) -> std::result::Result<&'a mut Map<String, Value>, String> {
+    let mut table = root;
+    for name in section {
+        table = match table
+            .entry(name.clone())
+            .or_insert_with(|| Value::Object(Map::new()))
+        {
+            Value::Object(map) => map,
+            _ => return Err(t!("{} 已经是一个值，不能再作为段", name)),
+        };
+    }
+    Ok(table)
+}
+
+/// 去掉值两端成对的引号
+fn unquote(text: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = text
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    text
+}
+
+/// 写成 INI 文本，顶层必须是对象
+pub fn to_string(value: &Value) -> Result<String> {
+    to_string_with(value, &current())
+}
+
+/// 按指定的方言写成 INI 文本
+pub fn to_string_with(value: &Value, dialect: &IniDialect) -> Result<String> {
+    let Value::Object(root) = value else {
+        return Err(Error::Convert {
+            message: t!("INI 的顶层只能是对象"),
+        });
+    };
+    let mut writer = Writer {
+        out: String::new(),
+        dialect,
+    };
+    writer.keys(root, &Path::default())?;
+    writer.sections(root, &mut Vec::new(), &Path::default())?;
+    Ok(writer.out)
+}
+
+/// 值是否写成段（日期时间和非有限浮点数的标记对象写成值）
+fn is_table(value: &Value) -> bool {
+    value.is_object() && date::toml_datetime(value).is_none() && nonfinite::get(value).is_none()
+}
+
+/// 按方言写出 INI 文本
+struct Writer<'d> {
+    out: String,
+    dialect: &'d IniDialect,
+}
+
+impl Writer<'_> {
+    /// 键名能否原样写出
+    fn writable_key(&self, name: &str) -> bool {
+        !name.is_empty()
+            && name.trim() == name
+            && !name.starts_with('[')
+            && !name.starts_with(self.dialect.comment_chars())
+            && !name.contains(self.dialect.separator_chars())
+            && !name.contains(['\n', '\r'])
+            && !(self.dialect.repeated == Repeated::Brackets && name.ends_with("[]"))
+    }
+
+    /// 写出表中不是段的键
+    fn keys(&mut self, map: &Map<String, Value>, path: &Path) -> Result<()> {
+        for (key, child) in map.iter().filter(|(_, child)| !is_table(child)) {
+            let path = path.join(Segment::Key(key.clone()));
+            if !self.writable_key(key) {
+                return Err(Error::Convert {
+                    message: t!("{} 的键名无法写成 INI", path),
+                });
+            }
+            let Value::Array(items) = child else {
+                self.entry(key, &scalar(child, &path)?);
+                continue;
+            };
+            let key = match self.dialect.repeated {
+                Repeated::Error => {
+                    return Err(Error::Convert {
+                        message: t!(
+                            "{} 是数组，INI 没有数组（可以用 --ini-dialect repeated=array）",
+                            path
+                        ),
+                    })
+                }
+                // 一个元素的数组读回来是字符串，没有元素的数组无法写出
+                Repeated::Array if items.len() < 2 => {
+                    return Err(Error::Convert {
+                        message: t!("{} 是少于两个元素的数组，写成重复的键后无法还原", path),
+                    })
+                }
+                Repeated::Array => key.clone(),
+                Repeated::Brackets => format!("{}[]", key),
+            };
+            for (i, item) in items.iter().enumerate() {
+                let text = scalar(item, &path.join(Segment::Index(i as i64)))?;
+                self.entry(&key, &text);
+            }
+        }
+        Ok(())
+    }
+
+    /// 写出一个键
+    fn entry(&mut self, key: &str, text: &str) {
+        let separator = match self.dialect.separator {
+            Separator::Colon => ":",
+            _ => " =",
+        };
+        // 两端的空白和成对的引号会在读取时被去掉，所以加上引号
+        if text.trim() != text || unquote(text) != text {
+            let _ = writeln!(self.out, "{}{} \"{}\"", key, separator, text);
+        } else {
+            let _ = writeln!(self.out, "{}{} {}", key, separator, text);
+        }
+    }
+
+    /// 段头，`names` 是从顶层开始的各级段名
+    fn header(&self, names: &[String], path: &Path) -> Result<String> {
+        let invalid = || Error::Convert {
+            message: t!("{} 的键名无法写成 INI 的段名", path),
+        };
+        // git config 风格中段名后的空白开始子段名
+        let special: &[char] = match self.dialect.subsections {
+            Subsections::Dotted => &['[', ']', '.', '\n', '\r'],
+            Subsections::Quoted => &['[', ']', '.', '\n', '\r', '"', ' ', '\t'],
+        };
+        let plain =
+            |name: &String| !name.is_empty() && name.trim() == name && !name.contains(special);
+        match (self.dialect.subsections, names) {
+            (Subsections::Quoted, [section]) if plain(section) => Ok(format!("[{}]", section)),
+            (Subsections::Quoted, [section, sub]) if plain(section) => {
+                if sub.contains(['\n', '\r']) {
+                    return Err(invalid());
+                }
+                let sub = sub.replace('\\', "\\\\").replace('"', "\\\"");
+                Ok(format!("[{} \"{}\"]", section, sub))
+            }
+            (Subsections::Quoted, [_, _, _, ..]) => Err(Error::Convert {
+                message: t!("{} 的嵌套超过两层，git config 风格的子段无法表示", path),
+            }),
+            (Subsections::Dotted, names) if names.iter().all(plain) => {
+                Ok(format!("[{}]", names.join(".")))
+            }
+            _ => Err(invalid()),
+        }
+    }
+
+    /// 写出表中的段，`names` 是上层段名
+    fn sections(
+        &mut self,
+        map: &Map<String, Value>,
+        names: &mut Vec<String>,
+        path: &Path,
+    ) -> Result<()> {
+        for (key, child) in map {
+            let Value::Object(table) = child else {
+                continue;
+            };
+            if !is_table(child) {
+                continue;
+            }
+            let path = path.join(Segment::Key(key.clone()));
+            names.push(key.clone());
+            let header = self.header(names, &path)?;
+            // 只有子段的表不需要单独的段，读取时由子段隐含
+            if table.is_empty() || table.values().any(|child| !is_table(child)) {
+                if !self.out.is_empty() {
+                    self.out.push('\n');
+                }
+                self.out.push_str(&header);
+                self.out.push('\n');
+                self.keys(table, &path)?;
+            }
+            self.sections(table, names, &path)?;
+            names.pop();
+        }
+        Ok(())
+    }
+}
+
+/// 值的文本
+fn scalar(value: &Value, path: &Path) -> Result<String> {
+    if let Some(text) = date::toml_datetime(value) {
+        return Ok(text.to_string());
+    }
+    if let Some(float) = nonfinite::get(value) {
+        return Ok(nonfinite::marker_text(float).to_string());
+    }
+    let message = match value {
+        Value::String(text) if !text.contains(['\n', '\r']) => return Ok(text.clone()),
+        Value::String(_) => t!("{} 含有换行，INI 无法表示", path),
+        Value::Number(n) => return Ok(n.to_string()),
+        Value::Bool(b) => return Ok(b.to_string()),
+        Value::Null => t!(
+            "{} 的值为 null，INI 没有 null（可以用 --nulls omit 删除）",
+            path
+        ),
+        Value::Array(_) | Value::Object(_) => t!("{} 在数组中，INI 无法表示嵌套的数组和对象", path),
+    };
+    Err(Error::Convert { message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn sections_map_to_nested_tables() {
+        let text = "; 注释\nname = app\n\n[server]\nhost = 0.0.0.0\nport: 8080\n\n\
+                    [server.tls]\ncert = \"/etc/app.pem\"\n\n[server]\npadded = ' x '\n";
+        let value = parse(text).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "name": "app",
+                "server": {
+                    "host": "0.0.0.0",
+                    "port": "8080",
+                    "tls": {"cert": "/etc/app.pem"},
+                    "padded": " x "
+                }
+            })
+        );
+        assert_eq!(
+            to_string(&value).unwrap(),
+            "name = app\n\n[server]\nhost = 0.0.0.0\nport = 8080\npadded = \" x \"\n\n\
+             [server.tls]\ncert = /etc/app.pem\n"
+        );
+        assert!(parse("[a]\nb = 1\nb = 2\n").is_err());
+        assert!(to_string(&json!({"a": [1]})).is_err());
+    }
+
+    #[test]
+    fn reads_and_writes_npmrc() {
+        let dialect = IniDialect::parse("npmrc").unwrap();
+        let text = "; npm\nregistry = https://registry.npmjs.org/\n\
+                    @scope:registry = https://npm.example.com/\n\
+                    //npm.example.com/:_authToken = abc\n\
+                    ca[] = first\nca[] = second\n";
+        let value = parse_with(text, &dialect).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "registry": "https://registry.npmjs.org/",
+                "@scope:registry": "https://npm.example.com/",
+                "//npm.example.com/:_authToken": "abc",
+                "ca": ["first", "second"]
+            })
+        );
+        assert_eq!(
+            to_string_with(&value, &dialect).unwrap(),
+            "registry = https://registry.npmjs.org/\n@scope:registry = https://npm.example.com/\n\
+             //npm.example.com/:_authToken = abc\nca[] = first\nca[] = second\n"
+        );
+        // 默认方言以先出现的 : 分隔
+        assert_eq!(
+            parse("@scope:registry = x\n").unwrap(),
+            json!({"@scope": "registry = x"})
+        );
+    }
+
+    #[test]
+    fn reads_and_writes_git_config() {
+        let dialect = IniDialect::parse("git").unwrap();
+        let text = "[core]\n\tbare = false\n[remote \"origin\"]\n\turl = git@example.com:a/b.git\n\
+                    \tfetch = +refs/heads/*:refs/remotes/origin/*\n\tfetch = +refs/tags/*:refs/tags/*\n\
+                    [branch \"release/1.0\"]\n\tremote = origin\n";
+        let value = parse_with(text, &dialect).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "core": {"bare": "false"},
+                "remote": {"origin": {
+                    "url": "git@example.com:a/b.git",
+                    "fetch": ["+refs/heads/*:refs/remotes/origin/*", "+refs/tags/*:refs/tags/*"]
+                }},
+                "branch": {"release/1.0": {"remote": "origin"}}
+            })
+        );
+        let written = to_string_with(&value, &dialect).unwrap();
+        assert_eq!(
+            written,
+            "[core]\nbare = false\n\n[remote \"origin\"]\nurl = git@example.com:a/b.git\n\
+             fetch = +refs/heads/*:refs/remotes/origin/*\nfetch = +refs/tags/*:refs/tags/*\n\n\
+             [branch \"release/1.0\"]\nremote = origin\n"
+        );
+        assert_eq!(parse_with(&written, &dialect).unwrap(), value);
+        assert!(to_string_with(&json!({"a": {"b": {"c": {"d": "x"}}}}), &dialect).is_err());
+        assert!(IniDialect::parse("separator=tab").is_err());
+    }
+}
@@ -0,0 +1,65 @@
+//! confconv 核心库
+//!
+//! 命令行工具的全部功能都在这里实现，`main.rs` 只负责解析参数和分发命令。
+//! 其他程序（以及 fuzz 测试）可以直接使用这些模块，
+//! 处理不可信的输入时请使用 [`codec::parse_untrusted`]
+
+pub mod budget;
+pub mod cache;
+pub mod cli;
+pub mod codec;
+pub mod color;
+pub mod commands;
+pub mod comments;
+pub mod compress;
+pub mod config;
+pub mod custom_tags;
+pub mod cycle;
+pub mod date;
+pub mod diagnostic;
+pub mod dialect;
+pub mod diff;
+pub mod dirs;
+pub mod error;
+pub mod flatten;
+pub mod format;
+pub mod fsutil;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod ini;
+pub mod lint;
+pub mod lossy;
+pub mod mapping;
+pub mod merge;
+pub mod nonfinite;
+pub mod ops;
+pub mod output;
+pub mod pager;
+pub mod path;
+pub mod plugin;
+pub mod process;
+pub mod profile;
+pub mod remote;
+pub mod report;
+pub mod rules;
+pub mod schema;
+pub mod script;
+pub mod signature;
+pub mod splice;
+pub mod stamp;
+pub mod term;
+pub mod toml_arrays;
+pub mod transform;
+pub mod units;
+pub mod value;
+pub mod vault;
+pub mod verbosity;
+pub mod walk;
+pub mod wasm;
+pub mod watch;
+pub mod yaml;
+pub mod yaml11;
+pub mod yaml_tags;
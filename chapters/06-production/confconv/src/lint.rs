@@ -0,0 +1,1086 @@
+//! 配置文件检查
+//!
+//! 找出语法正确但容易引起混淆的写法。YAML 的锚点和别名在大的 Helm values 文件里
+//! 尤其难以追踪，这里直接读取 libyml 的事件检查：
+//! - `unused-anchor`：定义了锚点却从未被别名引用
+//! - `duplicate-anchor`：同一文档中重复定义的锚点，之后的别名只会引用最后一次定义
+//! - `undefined-alias`：别名引用了未定义的锚点，或者在锚点定义之前就使用（解析时会报错）
+//! - `alias-fanout`：同一锚点被引用的次数过多，修改它会影响很多地方
+//! - `alias-cycle`：别名引用了包含它自己的锚点（见 [`crate::cycle`]）
+//!
+//! 解析之后还会检查重复的内容（所有格式都适用）：
+//! - `duplicate-subtree`：较大的相同子树出现在多个路径下，可以改用锚点或 include 共享
+//! - `similar-key-value`：相似的键（如 `timeout` 和 `timeout_ms`）取了相同的值，可能是重复或拼错的配置
+//! - `key-naming`：键名不符合约定的命名规范（如 TOML 用 snake_case、JSON 用 camelCase），
+//!   规范用 `--key-case`/`--key-pattern` 或配置文件中的 `[[naming]]` 按文件模式设置：
+//!
+//! - `duplicate-key`：同一个映射中重复的键（JSON 和 YAML 解析时只保留最后一个值）
+//! - `unsorted-keys`：指定了 `--sorted-keys` 时，对象的键没有按顺序排列
+//! - `indentation`：指定了 `--indent` 时，JSON 的缩进不是这么多个空格的整数倍
+//!
+//! 其中 `key-naming`（按大小写风格时）、`duplicate-key`、`unsorted-keys` 和 `indentation`
+//! 有确定的修正方式，`lint --fix` 会改好后重新输出整个文件。
+//!
+//! ```toml
+//! [[naming]]
+//! pattern = "*.toml"
+//! case = "snake"
+//!
+//! [[naming]]
+//! pattern = "web/**/*.json"
+//! regex = "^[a-z][a-zA-Z0-9]*$"
+//! ```
+
+use crate::config::NamingRule;
+use crate::cycle::{self, name};
+pub use crate::diagnostic::{Diagnostic, Severity};
+use crate::error::{Error, Result};
+use crate::path::{Path, Segment};
+use crate::t;
+use crate::transform::SortMode;
+use crate::walk::FilePattern;
+use clap::ValueEnum;
+use regex::Regex;
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use serde_yml::libyml::parser::{Anchor, Event, Parser};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+/// 检查选项
+#[derive(Clone, Debug)]
+pub struct Options {
+    /// 同一锚点被引用超过这个次数时报告 `alias-fanout`
+    pub max_alias_fanout: usize,
+    /// 至少包含这么多个标量的相同子树出现在多处时报告 `duplicate-subtree`
+    pub min_duplicate_size: usize,
+    /// 每个文件适用的键名规范
+    pub naming: NamingRules,
+    /// 要求对象的键按这种方式排序，None 表示不检查
+    pub sorted_keys: Option<SortMode>,
+    /// 要求 JSON 的缩进是这么多个空格的整数倍，None 表示不检查
+    pub indent: Option<u8>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            max_alias_fanout: 10,
+            min_duplicate_size: 8,
+            naming: NamingRules::default(),
+            sorted_keys: None,
+            indent: None,
+        }
+    }
+}
+
+/// 键名的大小写风格
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyCase {
+    /// snake_case
+    #[value(help = "snake_case")]
+    Snake,
+    /// camelCase
+    #[value(help = "camelCase")]
+    Camel,
+    /// kebab-case
+    #[value(help = "kebab-case")]
+    Kebab,
+    /// PascalCase
+    #[value(help = "PascalCase")]
+    Pascal,
+    /// SCREAMING_SNAKE_CASE
+    #[value(help = "SCREAMING_SNAKE_CASE")]
+    ScreamingSnake,
+}
+
+impl KeyCase {
+    /// 把键名改写为这种风格，开头的 `_`、`$` 等前缀保持不变
+    pub fn convert(self, key: &str) -> String {
+        let start = key.find(|c: char| c.is_alphanumeric()).unwrap_or(key.len());
+        let (prefix, rest) = key.split_at(start);
+        let words = split_words(rest);
+        let capitalize = |word: &str| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| {
+                    first
+                        .to_uppercase()
+                        .chain(chars.flat_map(char::to_lowercase))
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let body: String = match self {
+            KeyCase::Snake => words
+                .iter()
+                .map(|w| w.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            KeyCase::Kebab => words
+                .iter()
+                .map(|w| w.to_lowercase())
+                .collect::<Vec<_>>()
+                .join("-"),
+            KeyCase::ScreamingSnake => words
+                .iter()
+                .map(|w| w.to_uppercase())
+                .collect::<Vec<_>>()
+                .join("_"),
+            KeyCase::Pascal => words.iter().map(|w| capitalize(w)).collect(),
+            KeyCase::Camel => words
+                .iter()
+                .enumerate()
+                .map(|(i, w)| {
+                    if i == 0 {
+                        w.to_lowercase()
+                    } else {
+                        capitalize(w)
+                    }
+                })
+                .collect(),
+        };
+        format!("{}{}", prefix, body)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            KeyCase::Snake => "snake_case",
+            KeyCase::Camel => "camelCase",
+            KeyCase::Kebab => "kebab-case",
+            KeyCase::Pascal => "PascalCase",
+            KeyCase::ScreamingSnake => "SCREAMING_SNAKE_CASE",
+        }
+    }
+}
+
+/// 把键名拆成单词：按 `_`、`-`、空格、`.` 以及大小写的变化拆分，`HTTPServer` 拆为 `HTTP` 和 `Server`
+fn split_words(key: &str) -> Vec<String> {
+    let chars: Vec<char> = key.chars().collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if matches!(c, '_' | '-' | ' ' | '.') {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if previous.is_lowercase()
+                || previous.is_ascii_digit()
+                || (previous.is_uppercase() && next_is_lower)
+            {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// 一个文件的键名规范
+#[derive(Clone, Debug)]
+pub enum Naming {
+    /// 大小写风格，不符合的键可以自动改名
+    Case(KeyCase),
+    /// 键名必须匹配的正则表达式
+    Pattern(Regex),
+}
+
+impl Naming {
+    /// 检查一个键名，不符合时返回修正后的键名（无法自动修正时为 None）
+    fn check(&self, key: &str) -> std::result::Result<(), Option<String>> {
+        match self {
+            Naming::Case(case) => {
+                let fixed = case.convert(key);
+                if fixed == key {
+                    Ok(())
+                } else {
+                    Err(Some(fixed))
+                }
+            }
+            Naming::Pattern(regex) if regex.is_match(key) => Ok(()),
+            Naming::Pattern(_) => Err(None),
+        }
+    }
+}
+
+/// 按文件模式选用的键名规范
+#[derive(Clone, Debug, Default)]
+pub struct NamingRules {
+    /// 配置中的规则，后面的规则优先
+    rules: Vec<(FilePattern, Naming)>,
+    /// 命令行参数，对所有文件生效
+    overrides: Option<Naming>,
+}
+
+impl NamingRules {
+    /// 编译配置中的规则；`source` 是定义规则的配置文件，用于错误信息
+    pub fn new(rules: &[NamingRule], overrides: Option<Naming>, source: &str) -> Result<Self> {
+        let invalid = |message: String| Error::Config {
+            path: source.to_string(),
+            message,
+        };
+        let mut compiled = Vec::with_capacity(rules.len());
+        for rule in rules {
+            let pattern = FilePattern::new(&rule.pattern)
+                .map_err(|e| invalid(t!("[[naming]] 的 pattern 无效: {}", e)))?;
+            let naming = match (rule.case, &rule.regex) {
+                (Some(case), None) => Naming::Case(case),
+                (None, Some(regex)) => Naming::Pattern(
+                    Regex::new(regex)
+                        .map_err(|e| invalid(t!("[[naming]] 的 regex 无效: {}", e)))?,
+                ),
+                _ => return Err(invalid(t!("[[naming]] 需要设置 case 或 regex 之一"))),
+            };
+            compiled.push((pattern, naming));
+        }
+        Ok(NamingRules {
+            rules: compiled,
+            overrides,
+        })
+    }
+
+    /// 适用于 `path` 的规范：命令行参数优先，其次是最后一条匹配的规则
+    pub fn for_file(&self, path: &str) -> Option<&Naming> {
+        self.overrides.as_ref().or_else(|| {
+            self.rules
+                .iter()
+                .rev()
+                .find(|(pattern, _)| pattern.matches(path))
+                .map(|(_, naming)| naming)
+        })
+    }
+}
+
+/// 不符合键名规范的键
+#[derive(Clone, Debug)]
+pub struct Misnamed {
+    /// 键所在的路径
+    pub path: Path,
+    /// 修正后的键名，无法自动修正时为 None
+    pub fixed: Option<String>,
+}
+
+impl Misnamed {
+    /// 对应的诊断信息
+    pub fn diagnostic(&self, naming: &Naming) -> Diagnostic {
+        let message = match (naming, &self.fixed) {
+            (Naming::Case(case), Some(fixed)) => {
+                t!("{} 不符合 {} 命名，应为 {}", self.path, case.name(), fixed)
+            }
+            (Naming::Pattern(regex), _) => {
+                t!("{} 的键名不匹配 {}", self.path, regex.as_str())
+            }
+            (Naming::Case(case), None) => t!("{} 不符合 {} 命名", self.path, case.name()),
+        };
+        Diagnostic {
+            severity: Severity::Warning,
+            code: "key-naming",
+            location: None,
+            message,
+        }
+    }
+}
+
+/// 找出不符合规范的键，子对象中的键排在父级的键之前，按这个顺序改名时路径始终有效
+pub fn key_naming(value: &Value, naming: &Naming) -> Vec<Misnamed> {
+    fn walk(value: &Value, path: &Path, naming: &Naming, found: &mut Vec<Misnamed>) {
+        match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let path = path.join(Segment::Key(key.clone()));
+                    walk(child, &path, naming, found);
+                    if let Err(fixed) = naming.check(key) {
+                        found.push(Misnamed { path, fixed });
+                    }
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    walk(
+                        item,
+                        &path.join(Segment::Index(index as i64)),
+                        naming,
+                        found,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut found = Vec::new();
+    walk(value, &Path::default(), naming, &mut found);
+    found
+}
+
+/// 同一个值出现超过这么多次时不再比较键名，常见的值（端口、`enabled` 等）重复是正常的
+const MAX_SAME_VALUE: usize = 20;
+
+/// 检查 YAML 文本中锚点和别名的用法，结果按位置排序
+///
+/// 语法错误时返回一个 `syntax` 问题，之前发现的问题也会保留
+pub fn yaml_anchors(text: &str, options: &Options) -> Vec<Diagnostic> {
+    let mut parser = Parser::new(Cow::Borrowed(text.as_bytes()));
+    let mut diagnostics = Vec::new();
+    let mut document = Document::default();
+    loop {
+        let (event, mark) = match parser.parse_next_event() {
+            Ok(next) => next,
+            Err(e) => {
+                diagnostics.extend(document.finish(options));
+                let mark = e.mark();
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Error,
+                    code: "syntax",
+                    location: Some((mark.line() as usize + 1, mark.column() as usize + 1)),
+                    message: e.to_string(),
+                });
+                break;
+            }
+        };
+        let location = (mark.line() as usize + 1, mark.column() as usize + 1);
+        match event {
+            Event::StreamEnd => break,
+            // 锚点只在所属的文档内有效
+            Event::DocumentEnd => diagnostics.extend(std::mem::take(&mut document).finish(options)),
+            Event::Scalar(scalar) => document.define(scalar.anchor, location),
+            Event::SequenceStart(start) => document.define(start.anchor, location),
+            Event::MappingStart(start) => document.define(start.anchor, location),
+            Event::Alias(anchor) => document.alias(anchor, location),
+            _ => {}
+        }
+    }
+    if let Some(cycle) = cycle::yaml_alias_cycle(text) {
+        diagnostics.push(Diagnostic {
+            severity: Severity::Error,
+            code: "alias-cycle",
+            location: Some(cycle.location),
+            message: cycle.to_string(),
+        });
+    }
+    diagnostics.sort_by_key(|d| d.location);
+    diagnostics
+}
+
+/// 检查解析后的值中重复的内容
+///
+/// `aliases` 是 YAML 别名所在的路径：别名展开出的副本本来就是共享的，不算重复
+pub fn duplicates(value: &Value, aliases: &[Path], options: &Options) -> Vec<Diagnostic> {
+    let mut diagnostics = duplicate_subtrees(value, aliases, options);
+    diagnostics.extend(similar_keys(value, aliases));
+    diagnostics
+}
+
+/// 相同子树的所有位置和其中标量的个数，从大到小报告，已报告的子树内部不再重复报告
+fn duplicate_subtrees(value: &Value, aliases: &[Path], options: &Options) -> Vec<Diagnostic> {
+    let mut groups = HashMap::new();
+    let mut subtrees = Subtrees {
+        aliases,
+        min_size: options.min_duplicate_size,
+        groups: &mut groups,
+    };
+    subtrees.visit(value, &Path::default());
+    let mut groups: Vec<(usize, Vec<Path>)> = groups
+        .into_values()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+    groups.sort_by_cached_key(|(size, paths)| (std::cmp::Reverse(*size), paths[0].to_string()));
+
+    let mut reported: Vec<Path> = Vec::new();
+    let mut diagnostics = Vec::new();
+    for (size, paths) in groups {
+        if paths
+            .iter()
+            .all(|path| reported.iter().any(|r| r.is_prefix_of(path)))
+        {
+            continue;
+        }
+        let list: Vec<String> = paths.iter().map(Path::to_string).collect();
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            code: "duplicate-subtree",
+            location: None,
+            message: t!(
+                "{} 处内容完全相同（各 {} 个值）: {}，可以改用锚点或 include 共享",
+                paths.len(),
+                size,
+                list.join(", ")
+            ),
+        });
+        reported.extend(paths);
+    }
+    diagnostics
+}
+
+/// 按规范文本给子树分组
+struct Subtrees<'a> {
+    aliases: &'a [Path],
+    min_size: usize,
+    /// 规范文本 → (标量个数, 所在路径)
+    groups: &'a mut HashMap<String, (usize, Vec<Path>)>,
+}
+
+impl Subtrees<'_> {
+    /// 返回子树的规范文本（对象的键排序）和其中标量的个数
+    fn visit(&mut self, value: &Value, path: &Path) -> (String, usize) {
+        let (canonical, size) = match value {
+            Value::Object(map) => {
+                let mut entries: Vec<(&String, String, usize)> = map
+                    .iter()
+                    .map(|(key, child)| {
+                        let (text, size) = self.visit(child, &path.join(Segment::Key(key.clone())));
+                        (key, text, size)
+                    })
+                    .collect();
+                entries.sort_by(|a, b| a.0.cmp(b.0));
+                let size = entries.iter().map(|(_, _, size)| size).sum();
+                let fields: Vec<String> = entries
+                    .iter()
+                    .map(|(key, text, _)| format!("{}:{}", Value::String(key.to_string()), text))
+                    .collect();
+                (format!("{{{}}}", fields.join(",")), size)
+            }
+            Value::Array(items) => {
+                let mut size = 0;
+                let mut texts = Vec::with_capacity(items.len());
+                for (index, item) in items.iter().enumerate() {
+                    let (text, n) = self.visit(item, &path.join(Segment::Index(index as i64)));
+                    size += n;
+                    texts.push(text);
+                }
+                (format!("[{}]", texts.join(",")), size)
+            }
+            scalar => return (scalar.to_string(), 1),
+        };
+        if size >= self.min_size && !self.aliases.iter().any(|a| a.is_prefix_of(path)) {
+            let group = self
+                .groups
+                .entry(canonical.clone())
+                .or_insert_with(|| (size, Vec::new()));
+            group.1.push(path.clone());
+        }
+        (canonical, size)
+    }
+}
+
+/// 相同的值出现在相似的键下
+fn similar_keys(value: &Value, aliases: &[Path]) -> Vec<Diagnostic> {
+    let mut scalars: BTreeMap<String, Vec<(Path, String)>> = BTreeMap::new();
+    collect_scalars(value, &Path::default(), aliases, &mut scalars);
+    let mut diagnostics = Vec::new();
+    for (text, entries) in scalars {
+        if entries.len() > MAX_SAME_VALUE {
+            continue;
+        }
+        for (i, (path, key)) in entries.iter().enumerate() {
+            for (other_path, other_key) in &entries[i + 1..] {
+                if key != other_key && similar(key, other_key) {
+                    diagnostics.push(Diagnostic {
+                        severity: Severity::Warning,
+                        code: "similar-key-value",
+                        location: None,
+                        message: t!(
+                            "{} 和 {} 的键名相似且值相同（{}），可能是重复或拼错的配置",
+                            path,
+                            other_path,
+                            text
+                        ),
+                    });
+                }
+            }
+        }
+    }
+    diagnostics
+}
+
+/// 收集对象中不太常见的标量（至少 4 个字符的字符串、绝对值大于 1 的数），按值分组
+fn collect_scalars(
+    value: &Value,
+    path: &Path,
+    aliases: &[Path],
+    scalars: &mut BTreeMap<String, Vec<(Path, String)>>,
+) {
+    if aliases.contains(path) {
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                let path = path.join(Segment::Key(key.clone()));
+                let notable = match child {
+                    Value::String(s) => s.chars().count() >= 4,
+                    Value::Number(n) => n.as_f64().is_some_and(|f| f.abs() > 1.0),
+                    _ => false,
+                };
+                if notable && !aliases.contains(&path) {
+                    scalars
+                        .entry(child.to_string())
+                        .or_default()
+                        .push((path.clone(), key.clone()));
+                }
+                collect_scalars(child, &path, aliases, scalars);
+            }
+        }
+        Value::Array(items) => {
+            for (index, item) in items.iter().enumerate() {
+                collect_scalars(
+                    item,
+                    &path.join(Segment::Index(index as i64)),
+                    aliases,
+                    scalars,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 两个键名是否相似：忽略大小写和 `_`、`-` 后相同，或者一个包含另一个，或者编辑距离不超过 2
+fn similar(a: &str, b: &str) -> bool {
+    let normalize = |key: &str| -> Vec<char> {
+        key.chars()
+            .filter(|c| !matches!(c, '_' | '-' | ' '))
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let (a, b) = (normalize(a), normalize(b));
+    if a == b {
+        return true;
+    }
+    let (short, long) = if a.len() <= b.len() {
+        (&a, &b)
+    } else {
+        (&b, &a)
+    };
+    if short.len() < 4 {
+        return false;
+    }
+    long.windows(short.len()).any(|w| w == short.as_slice()) || edit_distance(&a, &b) <= 2
+}
+
+/// Levenshtein 编辑距离
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current.push(
+                (previous[j] + cost)
+                    .min(previous[j + 1] + 1)
+                    .min(current[j] + 1),
+            );
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// YAML 中同一个映射里重复的键：映射的路径和诊断信息，位置是重复出现的那个键
+///
+/// 只比较标量键；语法错误时返回已经发现的问题
+pub fn yaml_duplicate_keys(text: &str) -> Vec<(Path, Diagnostic)> {
+    /// 正在读取的集合和它的路径；映射记录读过的键和它们所在的行，以及最后读到的键
+    enum Collection {
+        Mapping {
+            path: Path,
+            keys: HashMap<String, usize>,
+            key: String,
+            expect_key: bool,
+        },
+        Sequence {
+            path: Path,
+            index: i64,
+        },
+    }
+    /// 读完一个节点，映射在键和值之间切换，序列移到下一个元素
+    fn advance(stack: &mut [Collection]) {
+        match stack.last_mut() {
+            Some(Collection::Mapping { expect_key, .. }) => *expect_key = !*expect_key,
+            Some(Collection::Sequence { index, .. }) => *index += 1,
+            None => {}
+        }
+    }
+    /// 下一个节点的路径
+    fn child(stack: &[Collection]) -> Path {
+        match stack.last() {
+            Some(Collection::Mapping { path, key, .. }) => path.join(Segment::Key(key.clone())),
+            Some(Collection::Sequence { path, index }) => path.join(Segment::Index(*index)),
+            None => Path::default(),
+        }
+    }
+
+    let mut parser = Parser::new(Cow::Borrowed(text.as_bytes()));
+    let mut stack = Vec::new();
+    let mut diagnostics = Vec::new();
+    while let Ok((event, mark)) = parser.parse_next_event() {
+        match event {
+            Event::StreamEnd => break,
+            Event::MappingStart(_) => stack.push(Collection::Mapping {
+                path: child(&stack),
+                keys: HashMap::new(),
+                key: String::new(),
+                expect_key: true,
+            }),
+            Event::SequenceStart(_) => stack.push(Collection::Sequence {
+                path: child(&stack),
+                index: 0,
+            }),
+            Event::MappingEnd | Event::SequenceEnd => {
+                stack.pop();
+                advance(&mut stack);
+            }
+            Event::Scalar(scalar) => {
+                if let Some(Collection::Mapping {
+                    path,
+                    keys,
+                    key: last,
+                    expect_key: true,
+                }) = stack.last_mut()
+                {
+                    let key = String::from_utf8_lossy(&scalar.value).into_owned();
+                    let line = mark.line() as usize + 1;
+                    // 多个合并键 `<<` 是常见写法，不算重复
+                    if key != "<<" {
+                        if let Some(previous) = keys.insert(key.clone(), line) {
+                            let diagnostic = Diagnostic {
+                                severity: Severity::Warning,
+                                code: "duplicate-key",
+                                location: Some((line, mark.column() as usize + 1)),
+                                message: t!(
+                                    "键 {} 已在第 {} 行出现，只有最后一个值生效",
+                                    key,
+                                    previous
+                                ),
+                            };
+                            diagnostics.push((path.clone(), diagnostic));
+                        }
+                    }
+                    *last = key;
+                }
+                advance(&mut stack);
+            }
+            Event::Alias(_) => advance(&mut stack),
+            _ => {}
+        }
+    }
+    diagnostics
+}
+
+/// JSON 中同一个对象里重复的键：对象的路径和诊断信息
+///
+/// serde_json 解析时不报错，只保留最后一个值，这里单独读一遍找出重复的路径；
+/// 语法错误时返回已经发现的问题
+pub fn json_duplicate_keys(text: &str) -> Vec<(Path, Diagnostic)> {
+    /// 读取一个值，把其中重复的键的路径记到 `found`
+    struct Seed<'a> {
+        path: Path,
+        found: &'a mut Vec<Path>,
+    }
+
+    impl<'de> DeserializeSeed<'de> for Seed<'_> {
+        type Value = ();
+
+        fn deserialize<D: de::Deserializer<'de>>(
+            self,
+            deserializer: D,
+        ) -> std::result::Result<(), D::Error> {
+            deserializer.deserialize_any(self)
+        }
+    }
+
+    impl<'de> Visitor<'de> for Seed<'_> {
+        type Value = ();
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "a JSON value")
+        }
+
+        fn visit_bool<E>(self, _: bool) -> std::result::Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_i64<E>(self, _: i64) -> std::result::Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_u64<E>(self, _: u64) -> std::result::Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_f64<E>(self, _: f64) -> std::result::Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_str<E>(self, _: &str) -> std::result::Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_unit<E>(self) -> std::result::Result<(), E> {
+            Ok(())
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+            let Seed { path, found } = self;
+            let mut index = 0;
+            while seq
+                .next_element_seed(Seed {
+                    path: path.join(Segment::Index(index)),
+                    found: &mut *found,
+                })?
+                .is_some()
+            {
+                index += 1;
+            }
+            Ok(())
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+            let Seed { path, found } = self;
+            let mut seen = HashSet::new();
+            while let Some(key) = map.next_key::<String>()? {
+                let path = path.join(Segment::Key(key.clone()));
+                if !seen.insert(key) {
+                    found.push(path.clone());
+                }
+                map.next_value_seed(Seed {
+                    path,
+                    found: &mut *found,
+                })?;
+            }
+            Ok(())
+        }
+    }
+
+    let mut found = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_str(text);
+    let _ = Seed {
+        path: Path::default(),
+        found: &mut found,
+    }
+    .deserialize(&mut deserializer);
+    found
+        .into_iter()
+        .map(|path| {
+            let diagnostic = Diagnostic {
+                severity: Severity::Warning,
+                code: "duplicate-key",
+                location: None,
+                message: t!("{} 重复出现，只有最后一个值生效", path),
+            };
+            (path.parent(), diagnostic)
+        })
+        .collect()
+}
+
+/// 键没有按 `mode` 排序的对象，每个对象报告第一处顺序错误
+pub fn unsorted_keys(value: &Value, mode: SortMode) -> Vec<Diagnostic> {
+    fn walk(value: &Value, path: &Path, mode: SortMode, diagnostics: &mut Vec<Diagnostic>) {
+        match value {
+            Value::Object(map) => {
+                let keys: Vec<&String> = map.keys().collect();
+                if let Some(pair) = keys
+                    .windows(2)
+                    .find(|pair| mode.compare(pair[0], pair[1]).is_gt())
+                {
+                    let object = if path.is_root() {
+                        ".".to_string()
+                    } else {
+                        path.to_string()
+                    };
+                    diagnostics.push(Diagnostic {
+                        severity: Severity::Warning,
+                        code: "unsorted-keys",
+                        location: None,
+                        message: t!(
+                            "{} 的键没有排序：{} 应该排在 {} 之前",
+                            object,
+                            pair[1],
+                            pair[0]
+                        ),
+                    });
+                }
+                for (key, child) in map {
+                    walk(
+                        child,
+                        &path.join(Segment::Key(key.clone())),
+                        mode,
+                        diagnostics,
+                    );
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    walk(
+                        item,
+                        &path.join(Segment::Index(index as i64)),
+                        mode,
+                        diagnostics,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
+    let mut diagnostics = Vec::new();
+    walk(value, &Path::default(), mode, &mut diagnostics);
+    diagnostics
+}
+
+/// JSON 中缩进不是 `width` 个空格整数倍（或者含有制表符）的行，只在第一行报告并给出行数
+pub fn json_indentation(text: &str, width: u8) -> Option<Diagnostic> {
+    let width = usize::from(width);
+    let mut bad = text.lines().enumerate().filter(|(_, line)| {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        indent.contains('\t') || indent.len() % width != 0
+    });
+    let (first, _) = bad.next()?;
+    Some(Diagnostic {
+        severity: Severity::Warning,
+        code: "indentation",
+        location: Some((first + 1, 1)),
+        message: t!(
+            "缩进不是 {} 个空格的整数倍（共 {} 行）",
+            width,
+            bad.count() + 1
+        ),
+    })
+}
+
+/// 锚点的一次定义
+struct Definition {
+    location: (usize, usize),
+    uses: usize,
+}
+
+/// 一个文档中锚点和别名的使用情况
+#[derive(Default)]
+struct Document {
+    /// 每个锚点当前有效的定义
+    anchors: BTreeMap<Anchor, Definition>,
+    /// 被后来的同名定义覆盖的定义
+    replaced: Vec<(String, Definition)>,
+    /// 引用时还没有定义的别名
+    pending: Vec<(Anchor, (usize, usize))>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Document {
+    fn define(&mut self, anchor: Option<Anchor>, location: (usize, usize)) {
+        let Some(anchor) = anchor else {
+            return;
+        };
+        let name = name(&anchor);
+        let definition = Definition { location, uses: 0 };
+        if let Some(previous) = self.anchors.insert(anchor, definition) {
+            self.diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "duplicate-anchor",
+                location: Some(location),
+                message: t!(
+                    "锚点 &{} 已在第 {} 行定义，之后的别名只会引用这里的值",
+                    name,
+                    previous.location.0
+                ),
+            });
+            self.replaced.push((name, previous));
+        }
+    }
+
+    fn alias(&mut self, anchor: Anchor, location: (usize, usize)) {
+        match self.anchors.get_mut(&anchor) {
+            Some(definition) => definition.uses += 1,
+            None => self.pending.push((anchor, location)),
+        }
+    }
+
+    /// 文档结束时汇总问题
+    fn finish(mut self, options: &Options) -> Vec<Diagnostic> {
+        for (anchor, location) in std::mem::take(&mut self.pending) {
+            let message = match self.anchors.get_mut(&anchor) {
+                Some(definition) => {
+                    // 已经报告了错误，不再把这个定义算作没有被引用
+                    definition.uses += 1;
+                    t!(
+                        "别名 *{} 在锚点定义（第 {} 行）之前使用",
+                        name(&anchor),
+                        definition.location.0
+                    )
+                }
+                None => t!("别名 *{} 引用了未定义的锚点", name(&anchor)),
+            };
+            self.diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                code: "undefined-alias",
+                location: Some(location),
+                message,
+            });
+        }
+
+        let current = self
+            .anchors
+            .iter()
+            .map(|(anchor, definition)| (name(anchor), definition));
+        let definitions = self
+            .replaced
+            .iter()
+            .map(|(name, definition)| (name.clone(), definition))
+            .chain(current);
+        let mut diagnostics = Vec::new();
+        for (name, definition) in definitions {
+            if definition.uses == 0 {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    code: "unused-anchor",
+                    location: Some(definition.location),
+                    message: t!("锚点 &{} 没有被任何别名引用", name),
+                });
+            } else if definition.uses > options.max_alias_fanout {
+                diagnostics.push(Diagnostic {
+                    severity: Severity::Warning,
+                    code: "alias-fanout",
+                    location: Some(definition.location),
+                    message: t!(
+                        "锚点 &{} 被引用了 {} 次（超过 {} 次），修改它会影响所有引用处",
+                        name,
+                        definition.uses,
+                        options.max_alias_fanout
+                    ),
+                });
+            }
+        }
+        self.diagnostics.extend(diagnostics);
+        self.diagnostics
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(text: &str, options: &Options) -> Vec<(&'static str, usize)> {
+        yaml_anchors(text, options)
+            .into_iter()
+            .map(|d| (d.code, d.location.map_or(0, |(line, _)| line)))
+            .collect()
+    }
+
+    #[test]
+    fn reports_unused_and_duplicate_anchors() {
+        let text = "a: &x 1\nb: &y 2\nc: *y\nd: &y 3\ne: *y\n";
+        assert_eq!(
+            codes(text, &Options::default()),
+            vec![("unused-anchor", 1), ("duplicate-anchor", 4)]
+        );
+    }
+
+    #[test]
+    fn reports_aliases_before_definition() {
+        let text = "a: *x\nb: &x 1\nc: *z\n";
+        let diagnostics = yaml_anchors(text, &Options::default());
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].code, "undefined-alias");
+        assert!(diagnostics[0].message.contains("*x"));
+        assert_eq!(diagnostics[1].location, Some((3, 4)));
+    }
+
+    #[test]
+    fn reports_fanout_per_document() {
+        let text = "a: &x 1\nb: [*x, *x, *x]\n---\nc: *x\n";
+        let options = Options {
+            max_alias_fanout: 2,
+            ..Options::default()
+        };
+        assert_eq!(
+            codes(text, &options),
+            vec![("alias-fanout", 1), ("undefined-alias", 4)]
+        );
+    }
+
+    #[test]
+    fn converts_between_key_cases() {
+        assert_eq!(KeyCase::Snake.convert("maxHTTPRetries"), "max_http_retries");
+        assert_eq!(KeyCase::Camel.convert("max-retry_count"), "maxRetryCount");
+        assert_eq!(KeyCase::Pascal.convert("api_v2"), "ApiV2");
+        assert_eq!(KeyCase::ScreamingSnake.convert("logLevel"), "LOG_LEVEL");
+        assert_eq!(KeyCase::Kebab.convert("_internalName"), "_internal-name");
+
+        let value: Value =
+            serde_json::from_str(r#"{"okKey": 1, "bad_key": {"Inner": 2}}"#).unwrap();
+        let found: Vec<String> = key_naming(&value, &Naming::Case(KeyCase::Camel))
+            .iter()
+            .map(|m| format!("{}={}", m.path, m.fixed.as_deref().unwrap_or("")))
+            .collect();
+        assert_eq!(found, ["bad_key.Inner=inner", "bad_key=badKey"]);
+    }
+
+    #[test]
+    fn reports_duplicate_keys() {
+        let yaml = yaml_duplicate_keys("a: 1\nb:\n  c: 1\n  c: 2\na: 3\n<<: {}\n<<: {}\n");
+        let found: Vec<_> = yaml
+            .iter()
+            .map(|(path, d)| (path.to_string(), d.location, d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "b".to_string(),
+                    Some((4, 3)),
+                    "键 c 已在第 3 行出现，只有最后一个值生效"
+                ),
+                (
+                    String::new(),
+                    Some((5, 1)),
+                    "键 a 已在第 1 行出现，只有最后一个值生效"
+                ),
+            ]
+        );
+        let json = json_duplicate_keys(r#"{"a": [{"b": 1, "b": 2}], "a": null}"#);
+        let found: Vec<_> = json
+            .iter()
+            .map(|(path, d)| (path.to_string(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("a[0]".to_string(), "a[0].b 重复出现，只有最后一个值生效"),
+                (String::new(), "a 重复出现，只有最后一个值生效")
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_duplicated_content_but_not_aliases() {
+        let text =
+            "base: &b {a: 1, b: 2, c: 3}\nx: *b\ny: {c: 3, b: 2, a: 1}\nz: {a: 1, b: 2, c: 3}\n\
+                    timeout: 30\nlimits: {timeout_ms: 30, retries: 30}\n";
+        let value: Value = serde_yml::from_str(text).unwrap();
+        let aliases = cycle::yaml_alias_paths(text);
+        let options = Options {
+            min_duplicate_size: 3,
+            ..Options::default()
+        };
+        let diagnostics = duplicates(&value, &aliases, &options);
+        let messages: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.code, d.message.as_str()))
+            .collect();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert_eq!(messages[0].0, "duplicate-subtree");
+        assert!(messages[0].1.contains("base, y, z"));
+        assert_eq!(messages[1].0, "similar-key-value");
+        assert!(messages[1].1.starts_with("timeout 和 limits.timeout_ms"));
+    }
+}
@@ -0,0 +1,413 @@
+//! 有损转换检查
+//!
+//! 所有格式都先解析为 `serde_json::Value`，有些内容在这一步就会悄悄改变：
+//! - `number-precision`：超出双精度浮点数精度的小数被截断，超出 64 位范围的 JSON 整数变成浮点数
+//! - `nonfinite-null`：YAML 的 `.inf`、`.nan` 和 TOML 的 `inf`、`nan` 变成 null
+//!   （指定 `--json-allow-nan` 时保留，见 [`crate::nonfinite`]）
+//! - `key-retyped`：YAML 映射中的数字、布尔和 null 键变成字符串
+//!
+//! 这些都不是错误，convert、validate、format 和 lint 把它们作为警告输出。
+//!
+//! convert 在写出之前还会用 [`check_target`] 按目标格式的能力（见 [`Format::capabilities`]）
+//! 检查变换后的值，列出无法原样写出的路径：
+//! - `target-null`：目标格式没有 null
+//! - `target-datetime`：目标格式没有日期时间类型，TOML 日期时间会写成标记对象
+//! - `target-root`：目标格式的顶层只能是对象
+
+use crate::codec::NullPolicy;
+use crate::date;
+use crate::diagnostic::{Diagnostic, Severity};
+use crate::dialect::{self, TomlVersion, YamlSpec};
+use crate::format::Format;
+use crate::nonfinite;
+use crate::path::{Path, Segment};
+use crate::t;
+use crate::yaml11;
+use regex::Regex;
+use serde_json::Value;
+use serde_yml::libyml::parser::{Event, Parser, ScalarStyle};
+use std::borrow::Cow;
+use std::sync::OnceLock;
+
+/// 找出解析为中间表示时会丢失的信息，结果按位置排序
+///
+/// 只检查语法正确的输入，插件格式不检查
+pub fn check(input: &str, format: Format) -> Vec<Diagnostic> {
+    let mut diagnostics = match format {
+        Format::Json => json(input),
+        Format::Yaml => yaml(input),
+        Format::Toml => toml(input),
+        // 所有值都读成字符串，不会丢失信息
+        Format::Ini | Format::Plugin(_) => Vec::new(),
+    };
+    diagnostics.sort_by_key(|d| d.location.unwrap_or((usize::MAX, 0)));
+    if diagnostics.iter().any(|d| d.code == "nonfinite-null") {
+        diagnostics.push(Diagnostic {
+            severity: Severity::Note,
+            code: "nonfinite-null",
+            location: None,
+            message: t!("使用 --json-allow-nan 可以保留无穷大和 NaN"),
+        });
+    }
+    diagnostics
+}
+
+/// 检查值能否在目标格式中原样表示，每个无法表示的路径一条警告
+///
+/// `nulls` 不是 [`NullPolicy::Keep`] 时 null 已经另行处理，不再检查
+pub fn check_target(value: &Value, target: Format, nulls: NullPolicy) -> Vec<Diagnostic> {
+    let capabilities = target.capabilities();
+    let mut diagnostics = Vec::new();
+    if !capabilities.scalar_root && (!value.is_object() || date::toml_datetime(value).is_some()) {
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            code: "target-root",
+            location: None,
+            message: t!("{} 的顶层只能是对象", target.name()),
+        });
+    }
+    let mut check = |value: &Value, path: &Path| {
+        let message = if date::toml_datetime(value).is_some() && !capabilities.datetimes {
+            t!(
+                "{} 是日期时间，{} 没有日期时间类型，会写成标记对象（可以用 --normalize-dates 转换为字符串）",
+                path,
+                target.name()
+            )
+        } else if value.is_null() && !capabilities.nulls && nulls == NullPolicy::Keep {
+            t!(
+                "{} 的值为 null，{} 没有 null（可以用 --nulls omit 删除）",
+                path,
+                target.name()
+            )
+        } else {
+            return;
+        };
+        let code = if value.is_null() {
+            "target-null"
+        } else {
+            "target-datetime"
+        };
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            code,
+            location: None,
+            message,
+        });
+    };
+    walk_values(value, &Path::default(), &mut check);
+    diagnostics
+}
+
+/// 深度优先访问所有值，日期时间和非有限浮点数的标记对象作为一个值，不再访问里面的键
+fn walk_values(value: &Value, path: &Path, f: &mut dyn FnMut(&Value, &Path)) {
+    f(value, path);
+    if date::toml_datetime(value).is_some() || nonfinite::get(value).is_some() {
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                walk_values(child, &path.join(Segment::Key(key.clone())), f);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                walk_values(item, &path.join(Segment::Index(i as i64)), f);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// JSON：逐个检查字符串以外的数字字面量
+fn json(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let (mut line, mut column) = (1, 0);
+    let (mut in_string, mut escaped) = (false, false);
+    let mut chars = input.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        if c == '\n' {
+            (line, column) = (line + 1, 0);
+            continue;
+        }
+        column += 1;
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '-' | '0'..='9' => {
+                let location = (line, column);
+                let mut end = start + 1;
+                while let Some(&(i, c)) = chars.peek() {
+                    if !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')) {
+                        break;
+                    }
+                    (end, column) = (i + 1, column + 1);
+                    chars.next();
+                }
+                if let Some(message) = number(&input[start..end], true) {
+                    diagnostics.push(warning("number-precision", location, message));
+                }
+            }
+            _ => {}
+        }
+    }
+    diagnostics
+}
+
+/// YAML：检查纯标量（加了引号或标签的标量按写法保留）
+fn yaml(input: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut parser = Parser::new(Cow::Borrowed(input.as_bytes()));
+    // 每层集合：序列为 None，映射为下一个节点是否是键
+    let mut stack: Vec<Option<bool>> = Vec::new();
+    while let Ok((event, mark)) = parser.parse_next_event() {
+        let location = (mark.line() as usize + 1, mark.column() as usize + 1);
+        let key = stack.last() == Some(&Some(true));
+        match event {
+            Event::StreamEnd => break,
+            Event::SequenceStart(_) => {
+                stack.push(None);
+                continue;
+            }
+            Event::MappingStart(_) => {
+                stack.push(Some(true));
+                continue;
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                stack.pop();
+            }
+            Event::Scalar(scalar) if scalar.style == ScalarStyle::Plain && scalar.tag.is_none() => {
+                let text = String::from_utf8_lossy(&scalar.value);
+                diagnostics.extend(plain(&text, key, location));
+            }
+            Event::Scalar(_) | Event::Alias(_) => {}
+            _ => continue,
+        }
+        if let Some(Some(next)) = stack.last_mut() {
+            *next = !*next;
+        }
+    }
+    diagnostics
+}
+
+/// 检查一个 YAML 纯标量，`key` 表示它是映射的键
+fn plain(text: &str, key: bool, location: (usize, usize)) -> Option<Diagnostic> {
+    static NONFINITE: OnceLock<Regex> = OnceLock::new();
+    static FLOAT: OnceLock<Regex> = OnceLock::new();
+
+    if key {
+        // 只有这些字符开头的纯标量可能不是字符串，其余的不必再解释一遍
+        if !text.is_empty() && !text.starts_with(|c: char| "0123456789+-.~nNtTfFyYoO".contains(c)) {
+            return None;
+        }
+        let value = match dialect::current().yaml {
+            YamlSpec::V1_1 => yaml11::resolve(text),
+            YamlSpec::V1_2 => serde_yml::from_str(text).ok()?,
+        };
+        if value.is_string() || value.is_object() {
+            return None;
+        }
+        return Some(warning(
+            "key-retyped",
+            location,
+            t!("键 {} 不是字符串，转换后是字符串 \"{}\"", text, value),
+        ));
+    }
+
+    let nonfinite = NONFINITE.get_or_init(|| {
+        Regex::new(r"^([-+]?\.(inf|Inf|INF)|\.(nan|NaN|NAN))$").expect("正则表达式是常量")
+    });
+    if nonfinite.is_match(text) {
+        if nonfinite::enabled() {
+            return None;
+        }
+        return Some(warning(
+            "nonfinite-null",
+            location,
+            t!("{} 不能用 JSON 表示，转换为 null", text),
+        ));
+    }
+    let float = FLOAT.get_or_init(|| {
+        Regex::new(r"^[-+]?([0-9]+(\.[0-9]*)?|\.[0-9]+)([eE][-+]?[0-9]+)?$")
+            .expect("正则表达式是常量")
+    });
+    if !float.is_match(text) {
+        return None;
+    }
+    number(text, false).map(|message| warning("number-precision", location, message))
+}
+
+/// TOML：数字都能保留，只检查 inf 和 nan（找到的是路径而不是位置）
+fn toml(input: &str) -> Vec<Diagnostic> {
+    if nonfinite::enabled() {
+        return Vec::new();
+    }
+    let value = match dialect::current().toml {
+        TomlVersion::V1_0 => toml::from_str(input).ok().and_then(|mut value| {
+            nonfinite::toml10::mark(&mut value);
+            serde_json::to_value(value).ok()
+        }),
+        TomlVersion::V1_1 => toml11::from_str(input).ok().and_then(|mut value| {
+            nonfinite::toml11::mark(&mut value);
+            serde_json::to_value(value).ok()
+        }),
+    };
+    let mut diagnostics = Vec::new();
+    if let Some(value) = value {
+        markers(&value, &Path::default(), &mut diagnostics);
+    }
+    diagnostics
+}
+
+/// 找出所有标记对象
+fn markers(value: &Value, path: &Path, diagnostics: &mut Vec<Diagnostic>) {
+    if let Some(float) = nonfinite::get(value) {
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            code: "nonfinite-null",
+            location: None,
+            message: t!("{} 的值 {} 不能用 JSON 表示，转换为 null", path, float),
+        });
+        return;
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                markers(child, &path.join(Segment::Key(key.clone())), diagnostics);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                markers(child, &path.join(Segment::Index(index as i64)), diagnostics);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 检查数字字面量，`integers` 表示超出 64 位范围的整数会变成浮点数（JSON），
+/// 否则只检查小数
+fn number(text: &str, integers: bool) -> Option<String> {
+    let integer = text
+        .bytes()
+        .all(|b| b.is_ascii_digit() || b == b'-' || b == b'+');
+    if integer {
+        if !integers || text.parse::<i64>().is_ok() || text.parse::<u64>().is_ok() {
+            return None;
+        }
+        let value: f64 = text.parse().ok()?;
+        return Some(t!(
+            "整数 {} 超出 64 位整数的范围，转换为浮点数 {}",
+            text,
+            format!("{:e}", value)
+        ));
+    }
+    let value: f64 = text.parse().ok()?;
+    if !value.is_finite() || digits(text) == digits(&format!("{:e}", value)) {
+        return None;
+    }
+    Some(t!("数字 {} 超出双精度浮点数的精度，转换为 {}", text, value))
+}
+
+/// 数字字面量解析为 64 位整数或双精度浮点数时是否会丢失精度
+pub(crate) fn loses_precision(text: &str) -> bool {
+    number(text, true).is_some()
+}
+
+/// 数字的有效数字（去掉指数、小数点和首尾的 0）
+fn digits(text: &str) -> String {
+    let mantissa = text.split(['e', 'E']).next().unwrap_or_default();
+    let digits: String = mantissa.chars().filter(char::is_ascii_digit).collect();
+    digits.trim_matches('0').to_string()
+}
+
+fn warning(code: &'static str, location: (usize, usize), message: String) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Warning,
+        code,
+        location: Some(location),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(input: &str, format: Format) -> Vec<(&'static str, Option<(usize, usize)>)> {
+        check(input, format)
+            .into_iter()
+            .map(|d| (d.code, d.location))
+            .collect()
+    }
+
+    #[test]
+    fn reports_json_numbers_that_lose_precision() {
+        let input = "{\"a\": 0.1, \"b\": \"12345678901234567890123\",\n \"c\": 12345678901234567890123,\n \"d\": 0.12345678901234567890, \"e\": 1e400}";
+        assert_eq!(
+            codes(input, Format::Json),
+            vec![
+                ("number-precision", Some((2, 7))),
+                ("number-precision", Some((3, 7)))
+            ]
+        );
+        assert!(check(
+            "[18446744073709551615, -9223372036854775808, 1.50e2]",
+            Format::Json
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn reports_retyped_keys_and_nonfinite_yaml_values() {
+        let input = "1: a\n'2': b\nname: c\nx: .inf\ny: [.nan, '.nan']\n";
+        assert_eq!(
+            codes(input, Format::Yaml),
+            vec![
+                ("key-retyped", Some((1, 1))),
+                ("nonfinite-null", Some((4, 4))),
+                ("nonfinite-null", Some((5, 5))),
+                ("nonfinite-null", None)
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_nonfinite_toml_values_by_path() {
+        let diagnostics = check("a = 1.5\n[b]\nc = [1.0, -inf]\n", Format::Toml);
+        assert_eq!(diagnostics.len(), 2);
+        assert!(diagnostics[0].message.contains("b.c[1]"));
+    }
+
+    #[test]
+    fn checks_values_against_target_capabilities() {
+        let value = serde_json::json!({
+            "at": {date::TOML_DATETIME_KEY: "2024-01-02T03:04:05Z"},
+            "list": [1, null]
+        });
+        let codes = |target, nulls| {
+            check_target(&value, target, nulls)
+                .into_iter()
+                .map(|d| d.code)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(codes(Format::Toml, NullPolicy::Keep), vec!["target-null"]);
+        assert!(codes(Format::Toml, NullPolicy::Omit).is_empty());
+        assert_eq!(
+            codes(Format::Json, NullPolicy::Keep),
+            vec!["target-datetime"]
+        );
+        assert_eq!(
+            check_target(&serde_json::json!([1]), Format::Toml, NullPolicy::Keep)[0].code,
+            "target-root"
+        );
+    }
+}
@@ -5,44 +5,839 @@
 //! ## 功能
 //! - convert: 格式转换
 //! - validate: 语法验证
+//! - lint: 检查容易引起混淆的写法（如 YAML 锚点）
+//! - check: 检查大小预算
 //! - format: 格式化
+//! - join: 合并多个文件
+//! - extract: 从文本中提取内嵌对象
+//! - escape / unescape: 转义为字符串字面量及还原
+//! - move: 移动路径上的子树
+//! - get: 读取路径上的值
+//! - edit: 按操作文件批量编辑
+//! - undo: 撤销最近一次原地修改
+//! - env: 导出为环境变量
+//! - args: 展开为命令行参数
+//! - diff: 比较两个文件的内容
+//! - merge: 深度合并多个文件
+//! - resolve: 按 base + overlays + local 的约定叠加配置
+//! - template: 渲染模板
+//! - test: 按断言文件检查配置
+//! - snapshot: 快照测试
+//! - schema infer: 从样例推断 JSON Schema
+//! - defaults apply / prune: 按 Schema 的默认值补全配置，或删去等于默认值的键
+//! - k8s wrap / unwrap: 打包为 ConfigMap/Secret 及取出
+//! - kv export / import: 与 Consul/etcd 键值导出互相转换
+//! - export ssm / secretsmanager: 导出到 AWS 参数存储
+//! - anonymize: 匿名化配置中的值
+//! - example: 按 Schema 生成示例配置
+//! - docs: 生成配置的 Markdown 参考文档
+//! - drift: 检查线上配置相对基准配置的漂移
+//! - unused: 列出应用不认识的键
+//! - doctor: 诊断跨格式转换的损失
+//! - verify: 校验生成文件的完整性或分离签名
+//! - sign: 对规范形式签名
+//! - info: 输出版本、支持的格式和命令等信息
+//! - config path: 输出配置文件、缓存和状态目录的位置
+//! - completions: 输出 shell 补全脚本
+//! - serve: gRPC 服务（需要启用 grpc 功能）
 
-mod cli;
-mod commands;
-mod error;
-mod format;
-
-use clap::Parser;
-use cli::{Cli, Commands};
+use clap::{ArgMatches, CommandFactory, FromArgMatches};
+use confconv::budget::{Budget, Limits};
+use confconv::cli::{
+    Cli, Commands, ConfigCommand, DefaultsCommand, ExportCommand, K8sCommand, KvCommand,
+    SchemaCommand,
+};
+use confconv::codec::OutputOptions;
+use confconv::color::Stream;
+use confconv::commands::{
+    self, Defaults, Destination, FixMode, GetOutput, InputOptions, Known, KvLayout, Layout,
+    Manifest, OnConflict, Postprocess, SignatureCheck,
+};
+use confconv::config::{Config, DEFAULT_PROFILE};
+use confconv::diagnostic::{self, Levels};
+use confconv::dialect::{self, Dialect};
+use confconv::lint::{Naming, NamingRules};
+use confconv::output::{Encoding, StdoutOptions};
+use confconv::pager::Pager;
+use confconv::verbosity::{self, Verbosity};
+use confconv::watch::{self, WatchOptions};
+use confconv::{cache, color, diff, history, hooks, i18n, lint, log, profile, t, term, walk};
+use std::io::IsTerminal;
 
 fn main() {
+    // 先确定界面语言，帮助信息和错误信息都使用这个语言
+    i18n::init(i18n::detect(std::env::args_os()));
+
     // 解析命令行参数
-    let cli = Cli::parse();
+    // 帮助信息按终端宽度排版（--term-width 这时还没有解析）
+    let mut command = i18n::localize(Cli::command());
+    if let Some(width) = term::width(Stream::Stdout) {
+        command = command.term_width(width);
+    }
+    let matches = command.clone().get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+
+    // 读取用户配置；选用了配置档时把其中的参数作为默认值重新解析命令行
+    let annotate = cli.annotate;
+    let fail = move |e: confconv::error::Error| -> ! {
+        term::eprint_wrapped(&t!("错误: {}", e));
+        diagnostic::init_annotate(annotate);
+        diagnostic::annotate_error("-", &e);
+        std::process::exit(1);
+    };
+    let config = Config::load().unwrap_or_else(|e| fail(e));
+    // 没有指定 --profile 时使用配置中的 default 配置档（如果有）
+    let profile = cli.profile.clone().or_else(|| {
+        config
+            .profile
+            .contains_key(DEFAULT_PROFILE)
+            .then(|| DEFAULT_PROFILE.to_string())
+    });
+    let (matches, cli) = match profile.as_deref() {
+        Some(name) => {
+            let path = subcommand_path(&matches);
+            let command = config
+                .profile(name)
+                .and_then(|(profile, source)| {
+                    let source = source.display().to_string();
+                    profile::apply(command, &path, name, profile, &source)
+                })
+                .unwrap_or_else(|e| fail(e));
+            let matches = command.get_matches();
+            let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
+            (matches, cli)
+        }
+        None => (matches, cli),
+    };
+    verbosity::init(Verbosity::new(cli.verbose, cli.quiet));
+    color::init(cli.color);
+    term::init(cli.term_width);
+    // 命令模块只区分是否显示处理过程，更详细的信息由底层模块按全局的详细程度输出
+    let verbose = cli.verbose > 0;
+    for source in &config.sources {
+        log!(Debug, "读取配置文件: {}", source.display());
+    }
+
+    // 设置本次运行的钩子和修改历史
+    let operation = matches.subcommand_name().unwrap_or_default();
+    hooks::init(config.hooks, operation);
+    history::init(config.history, operation);
+    dialect::init(Dialect {
+        toml: cli.toml_version,
+        yaml: cli.yaml_spec,
+        json_nan: cli.json_allow_nan,
+        yaml_tags: cli.yaml_tags,
+        custom_tags: cli.custom_tags,
+        ini: cli.ini_dialect,
+    });
+    diagnostic::init(Levels {
+        deny: cli.deny,
+        warn: cli.warn,
+        allow: cli.allow,
+    });
+    diagnostic::init_annotate(cli.annotate);
+    let pager = if cli.no_pager || !pageable(&matches) {
+        None
+    } else {
+        Pager::start()
+    };
 
     // 执行对应的命令
     let result = match cli.command {
         Commands::Convert {
-            input,
+            inputs,
             output,
+            out_dir,
+            map,
             from,
             to,
             pretty,
-        } => commands::convert(&input, output.as_deref(), from, to, pretty, cli.verbose),
+            width,
+            indent,
+            quote,
+            nulls,
+            toml_arrays,
+            stamp,
+            comments_as_keys,
+            force,
+            hex,
+            base64,
+            watch,
+            exec,
+            clear,
+            best_effort,
+            transform,
+        } => {
+            let style = OutputOptions {
+                pretty: pretty || indent.is_some(),
+                indent,
+                width: width.map(usize::from),
+                quote,
+                nulls,
+                stamp,
+                toml_arrays,
+            };
+            let transforms = transform.transforms(&config.transform);
+            let options = InputOptions {
+                from,
+                best_effort,
+                comments_as_keys,
+                verbose,
+            };
+            match map {
+                Some(rules) => commands::convert_map(&rules, &style, &transforms, &options),
+                None => {
+                    let encoding = match (hex, base64) {
+                        (true, _) => Encoding::Hex,
+                        (_, true) => Encoding::Base64,
+                        _ => Encoding::Raw,
+                    };
+                    let destination = Destination::new(
+                        output.as_deref(),
+                        out_dir.as_deref(),
+                        StdoutOptions { encoding, force },
+                    );
+                    let convert = || {
+                        commands::convert(&inputs, &destination, &to, &style, &transforms, &options)
+                    };
+                    if watch {
+                        cache::init(&config.cache);
+                        watch::run(&inputs, &WatchOptions { exec, clear }, convert)
+                    } else {
+                        convert()
+                    }
+                }
+            }
+        }
+
+        Commands::Validate {
+            files,
+            format,
+            stdin_stream,
+            report_format,
+            walk,
+        } => match format {
+            // clap 保证 --stdin-stream 时指定了格式
+            Some(format) if stdin_stream => commands::validate_stream(format, cli.quiet),
+            _ => walk::collect_files(&files, walk.respect_gitignore()).and_then(|files| {
+                commands::validate(&files, format, report_format, verbose, cli.quiet)
+            }),
+        },
+
+        Commands::Check {
+            files,
+            format,
+            max_size,
+            max_keys,
+            max_depth,
+            walk,
+        } => {
+            let overrides = Limits {
+                max_size,
+                max_keys,
+                max_depth,
+            };
+            let source = config
+                .sources
+                .last()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            Budget::new(&config.budget, overrides, &source).and_then(|budget| {
+                let files = walk::collect_files(&files, walk.respect_gitignore())?;
+                commands::check(&files, format, &budget, verbose, cli.quiet)
+            })
+        }
 
-        Commands::Validate { file, format } => {
-            commands::validate(&file, format, cli.verbose, cli.quiet)
+        Commands::Lint {
+            files,
+            format,
+            max_alias_fanout,
+            min_duplicate_size,
+            key_case,
+            key_pattern,
+            sorted_keys,
+            indent,
+            fix,
+            write,
+            report_format,
+            walk,
+        } => {
+            let overrides = match (key_case, key_pattern) {
+                (Some(case), _) => Some(Naming::Case(case)),
+                (_, Some(regex)) => Some(Naming::Pattern(regex)),
+                _ => None,
+            };
+            let source = config
+                .sources
+                .last()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            NamingRules::new(&config.naming, overrides, &source).and_then(|naming| {
+                let files = walk::collect_files(&files, walk.respect_gitignore())?;
+                let options = lint::Options {
+                    max_alias_fanout,
+                    min_duplicate_size,
+                    naming,
+                    sorted_keys,
+                    indent,
+                };
+                let fix = match (fix, write) {
+                    (false, _) => None,
+                    (true, false) => Some(FixMode::Print),
+                    (true, true) => Some(FixMode::Write),
+                };
+                commands::lint(
+                    &files,
+                    format,
+                    &options,
+                    fix,
+                    report_format,
+                    verbose,
+                    cli.quiet,
+                )
+            })
         }
 
         Commands::Format {
-            file,
+            files,
             indent,
             write,
-        } => commands::format(&file, indent, write, cli.verbose),
+            preserve_mtime,
+            width,
+            toml_arrays,
+            only,
+            transform,
+            walk,
+        } => walk::require_files(&files, walk.respect_gitignore()).and_then(|files| {
+            let style = OutputOptions {
+                pretty: true,
+                indent: Some(indent),
+                width: width.map(usize::from),
+                toml_arrays,
+                ..OutputOptions::default()
+            };
+            let transforms = transform.transforms(&config.transform);
+            commands::format(
+                &files,
+                write,
+                preserve_mtime,
+                &style,
+                &transforms,
+                only.as_deref(),
+                verbose,
+            )
+        }),
+
+        Commands::Join {
+            inputs,
+            output,
+            to,
+            nest_by_filename,
+            pretty,
+        } => commands::join(
+            &inputs,
+            output.as_deref(),
+            to,
+            nest_by_filename,
+            pretty,
+            verbose,
+        ),
+
+        Commands::Extract {
+            input,
+            format,
+            to,
+            pretty,
+        } => commands::extract(&input, format, to, pretty, verbose),
+
+        Commands::Escape {
+            input,
+            from,
+            as_format,
+        } => commands::escape(&input, from, as_format, verbose),
+
+        Commands::Unescape {
+            input,
+            from,
+            to,
+            pretty,
+        } => commands::unescape(&input, from, to, pretty, verbose),
+
+        Commands::Move {
+            file,
+            from,
+            to,
+            format,
+            force,
+            write,
+        } => commands::move_path(&file, format, &from, &to, force, write, verbose),
+
+        Commands::Get {
+            file,
+            path,
+            format,
+            raw,
+            json,
+            output,
+            pretty,
+        } => {
+            let output = match (raw, json, output) {
+                (true, _, _) => GetOutput::Raw,
+                (_, true, _) => GetOutput::Json,
+                (_, _, Some(format)) => GetOutput::Format(format),
+                _ => GetOutput::Typed,
+            };
+            commands::get(&file, format, &path, output, pretty)
+        }
+
+        Commands::Edit {
+            file,
+            format,
+            ops,
+            as_format,
+            write,
+        } => commands::edit(&file, format, ops.as_deref(), as_format, write, verbose),
+
+        Commands::Undo { file, list, force } => commands::undo(&file, list, force, verbose),
+
+        Commands::Env {
+            file,
+            format,
+            output,
+            prefix,
+            separator,
+            export,
+        } => commands::env(&file, format, output, &prefix, &separator, export),
+
+        Commands::Args {
+            file,
+            format,
+            template,
+            separator,
+            quote,
+        } => commands::args(&file, format, &template, &separator, quote),
+
+        Commands::Diff {
+            left,
+            right,
+            format,
+            ignore_order,
+            ignore_case,
+            numeric_tolerance,
+            only,
+            ignore,
+            report_format,
+        } => commands::diff(
+            &left,
+            &right,
+            format,
+            &diff::Options {
+                ignore_order,
+                ignore_case,
+                numeric_tolerance,
+                only,
+                ignore,
+            },
+            report_format,
+            verbose,
+            cli.quiet,
+        ),
+
+        Commands::Drift {
+            golden,
+            files,
+            ignore_order,
+            only,
+            ignore,
+            allow_extra,
+            json,
+        } => commands::drift(
+            &golden,
+            &files,
+            &diff::Options {
+                ignore_order,
+                only,
+                ignore,
+                ..diff::Options::default()
+            },
+            allow_extra,
+            json,
+            verbose,
+            cli.quiet,
+        ),
+
+        Commands::Unused {
+            file,
+            known_keys,
+            schema,
+            from,
+        } => {
+            // clap 保证 --known-keys 和 --schema 恰好指定了一个
+            let known = match &known_keys {
+                Some(keys) => Known::Keys(keys),
+                None => Known::Schema(schema.as_deref().unwrap_or_default()),
+            };
+            commands::unused(&file, known, from, verbose, cli.quiet)
+        }
+
+        Commands::Merge {
+            inputs,
+            output,
+            to,
+            strict,
+            interactive,
+            merge_keys,
+            pretty,
+            stamp,
+        } => commands::merge(
+            &inputs,
+            output.as_deref(),
+            to,
+            if interactive {
+                OnConflict::Ask
+            } else if strict {
+                OnConflict::Fail
+            } else {
+                OnConflict::Overwrite
+            },
+            &merge_keys,
+            &OutputOptions {
+                pretty,
+                stamp,
+                ..OutputOptions::default()
+            },
+            verbose,
+        ),
+
+        Commands::Resolve {
+            base,
+            env,
+            overlays,
+            local,
+            no_local,
+            sets,
+            merge_keys,
+            to,
+            output,
+            pretty,
+            stamp,
+        } => commands::resolve(
+            &Layout {
+                base: &base,
+                env: env.as_deref(),
+                overlays: overlays.as_deref(),
+                local: local.as_deref(),
+                no_local,
+            },
+            &sets,
+            &merge_keys,
+            to,
+            output.as_deref(),
+            &OutputOptions {
+                pretty,
+                stamp,
+                ..OutputOptions::default()
+            },
+            verbose,
+        ),
+
+        Commands::Template {
+            template,
+            vars,
+            sets,
+            output,
+            format,
+            to,
+            validate,
+            pretty,
+        } => commands::template(
+            &template,
+            &vars,
+            &sets,
+            output.as_deref(),
+            format,
+            Postprocess::new(to, validate, pretty),
+            verbose,
+        ),
+
+        Commands::Test {
+            file,
+            spec,
+            format,
+            report_format,
+        } => commands::test(&file, format, &spec, report_format, verbose, cli.quiet),
+
+        Commands::Snapshot {
+            files,
+            update,
+            check: _,
+        } => commands::snapshot(&files, update, verbose, cli.quiet),
+
+        Commands::Schema { command } => match command {
+            SchemaCommand::Infer {
+                files,
+                level,
+                output,
+            } => commands::schema_infer(&files, level, output.as_deref(), verbose),
+        },
+
+        Commands::Defaults { command } => match command {
+            DefaultsCommand::Apply {
+                file,
+                schema,
+                from,
+                to,
+                output,
+            } => commands::defaults_apply(&file, &schema, from, to, output.as_deref(), verbose),
+            DefaultsCommand::Prune {
+                file,
+                schema,
+                reference,
+                from,
+                to,
+                output,
+            } => {
+                // clap 保证 --schema 和 --reference 恰好指定了一个
+                let defaults = match &reference {
+                    Some(reference) => Defaults::Reference(reference),
+                    None => Defaults::Schema(schema.as_deref().unwrap_or_default()),
+                };
+                commands::defaults_prune(&file, defaults, from, to, output.as_deref(), verbose)
+            }
+        },
+
+        Commands::K8s { command } => match command {
+            K8sCommand::Wrap {
+                files,
+                name,
+                namespace,
+                key,
+                secret,
+                to,
+                output,
+            } => commands::k8s_wrap(
+                &files,
+                key.as_deref(),
+                &Manifest {
+                    name: &name,
+                    namespace: namespace.as_deref(),
+                    secret,
+                },
+                to,
+                output.as_deref(),
+                verbose,
+            ),
+            K8sCommand::Unwrap {
+                manifest,
+                key,
+                to,
+                pretty,
+                output,
+            } => commands::k8s_unwrap(
+                &manifest,
+                key.as_deref(),
+                to,
+                pretty,
+                output.as_deref(),
+                verbose,
+            ),
+        },
+
+        Commands::Kv { command } => match command {
+            KvCommand::Export {
+                file,
+                format,
+                flavor,
+                prefix,
+                output,
+            } => commands::kv_export(
+                &file,
+                format,
+                &KvLayout {
+                    flavor,
+                    prefix: &prefix,
+                },
+                output.as_deref(),
+                verbose,
+            ),
+            KvCommand::Import {
+                file,
+                flavor,
+                prefix,
+                strings,
+                to,
+                pretty,
+                output,
+            } => commands::kv_import(
+                &file,
+                &KvLayout {
+                    flavor,
+                    prefix: &prefix,
+                },
+                strings,
+                to,
+                pretty,
+                output.as_deref(),
+                verbose,
+            ),
+        },
+
+        Commands::Export { command } => match command {
+            ExportCommand::Ssm {
+                file,
+                format,
+                prefix,
+                secure,
+                apply,
+            } => commands::export_ssm(&file, format, &prefix, &secure, apply, verbose),
+            ExportCommand::SecretsManager {
+                file,
+                format,
+                secret_id,
+                apply,
+            } => commands::export_secrets_manager(&file, format, &secret_id, apply, verbose),
+        },
+
+        Commands::Anonymize {
+            input,
+            from,
+            output,
+            keep,
+            seed,
+        } => commands::anonymize(&input, from, output.as_deref(), &keep, seed, verbose),
+
+        Commands::Example {
+            schema,
+            from,
+            to,
+            output,
+            pretty,
+        } => commands::example(&schema, from, to, output.as_deref(), pretty, verbose),
+
+        Commands::Docs {
+            file,
+            schema,
+            from,
+            title,
+            output,
+        } => commands::docs(
+            &file,
+            schema.as_deref(),
+            from,
+            title.as_deref(),
+            output.as_deref(),
+            verbose,
+        ),
+
+        Commands::Doctor { file, format } => commands::doctor(&file, format, verbose),
+
+        Commands::Verify { files, sig, key } => {
+            let key = key.or_else(|| {
+                config
+                    .signature
+                    .key
+                    .as_ref()
+                    .map(|key| key.display().to_string())
+            });
+            commands::verify(
+                &files,
+                sig.as_deref()
+                    .map(|sig| SignatureCheck {
+                        sig,
+                        key: key.as_deref(),
+                    })
+                    .as_ref(),
+                verbose,
+                cli.quiet,
+            )
+        }
+
+        Commands::Sign {
+            file,
+            format,
+            key,
+            output,
+        } => commands::sign(&file, format, &key, output.as_deref(), verbose),
+
+        Commands::Info { json } => commands::info(json),
+
+        Commands::Config { command } => match command {
+            ConfigCommand::Path { json } => commands::config_path(json),
+        },
+
+        Commands::Completions { shell } => commands::completions(shell),
+
+        Commands::Complete { words } => commands::complete(&words),
+
+        #[cfg(feature = "grpc")]
+        Commands::Serve { listen } => {
+            cache::init(&config.cache);
+            commands::serve(listen)
+        }
     };
 
     // 处理错误
+    if let Err(e) = &result {
+        term::eprint_wrapped(&t!("错误: {}", e));
+        if !e.is_summary() {
+            diagnostic::annotate_error(input_file(&matches).unwrap_or("-"), e);
+        }
+    }
+    if let Some(pager) = pager {
+        pager.finish();
+    }
     if let Err(e) = result {
-        eprintln!("错误: {}", e);
-        std::process::exit(1);
+        std::process::exit(e.exit_code());
+    }
+}
+
+/// 子命令唯一的输入文件，用作错误注解的位置
+fn input_file(matches: &ArgMatches) -> Option<&str> {
+    let mut current = matches;
+    while let Some((_, sub)) = current.subcommand() {
+        current = sub;
+    }
+    ["file", "input", "inputs", "files"].iter().find_map(|id| {
+        let mut values = current.try_get_many::<String>(id).ok()??;
+        let file = values.next()?;
+        values.next().is_none().then_some(file.as_str())
+    })
+}
+
+/// 命令行选择的子命令路径，如 `["kv", "export"]`
+fn subcommand_path(matches: &ArgMatches) -> Vec<&str> {
+    let mut path = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        path.push(name);
+        current = sub;
+    }
+    path
+}
+
+/// 是否使用分页器：serve 和 --watch 会一直运行，不适合分页；
+/// 交互式的命令（不带 `--ops` 的 edit、`merge --interactive`）会打开编辑器或提示输入，
+/// 从终端读取标准输入（某个参数是 `-`，包括默认值）时分页器也会与命令争抢键盘输入
+fn pageable(matches: &ArgMatches) -> bool {
+    let mut current = matches;
+    while let Some((name, sub)) = current.subcommand() {
+        let flag = |id: &str| sub.try_get_one::<bool>(id).ok().flatten() == Some(&true);
+        let long_running = flag("watch") || flag("stdin_stream");
+        let interactive = flag("interactive")
+            || (name == "edit" && sub.try_get_one::<String>("ops").ok().flatten().is_none());
+        if name == "serve" || long_running || interactive {
+            return false;
+        }
+        let reads_stdin = sub.ids().any(|id| {
+            sub.try_get_raw(id.as_str())
+                .ok()
+                .flatten()
+                .is_some_and(|mut values| values.any(|value| value == "-"))
+        });
+        if reads_stdin && std::io::stdin().is_terminal() {
+            return false;
+        }
+        current = sub;
     }
+    true
 }
@@ -0,0 +1,228 @@
+//! 批量转换的映射规则
+//!
+//! 规则文件（任意支持的格式）是一个规则列表，每条规则把匹配 glob 的输入文件映射到输出路径：
+//!
+//! ```yaml
+//! - configs/*.yaml -> dist/{stem}.json
+//! - from: legacy/**/*.toml
+//!   to: dist/legacy/{dir}/{stem}.yaml
+//!   format: yaml      # 可选，默认从输出路径的扩展名推断
+//! ```
+//!
+//! 输出路径中可以使用的占位符：
+//! - `{stem}`：输入文件名去掉扩展名
+//! - `{name}`：输入文件名
+//! - `{ext}`：输入文件的扩展名
+//! - `{dir}`：输入文件所在目录相对于 glob 中不含通配符的前缀的路径
+//!
+//! glob 中 `*` 不跨越 `/`，`**` 匹配任意层目录；
+//! 规则中的路径都相对于规则文件所在的目录
+
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::log;
+use crate::t;
+use crate::walk;
+use globset::{Glob, GlobBuilder, GlobMatcher};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// 输出路径中可用的占位符
+const PLACEHOLDERS: &[&str] = &["stem", "name", "ext", "dir"];
+
+/// 规则文件中的一条规则
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawRule {
+    /// `输入 glob -> 输出模板` 的简写
+    Short(String),
+    /// 完整写法
+    Full {
+        from: String,
+        to: String,
+        #[serde(default)]
+        format: Option<String>,
+    },
+}
+
+/// 一条映射规则
+#[derive(Debug)]
+pub struct Rule {
+    /// 输入 glob（相对于规则文件所在目录）
+    pub from: String,
+    /// 输出路径模板
+    pub to: String,
+    /// 指定的目标格式
+    pub format: Option<Format>,
+}
+
+/// 一次转换：输入文件、输出文件和目标格式
+#[derive(Debug, PartialEq)]
+pub struct Job {
+    pub input: String,
+    pub output: String,
+    pub format: Format,
+}
+
+/// 解析规则列表
+pub fn parse_rules(value: Value) -> Result<Vec<Rule>> {
+    let raw: Vec<RawRule> = serde_json::from_value(value).map_err(|e| Error::Parse {
+        format: "规则文件",
+        source: e.to_string(),
+    })?;
+    raw.into_iter()
+        .enumerate()
+        .map(|(i, rule)| {
+            let invalid = |message: String| Error::Parse {
+                format: "规则文件",
+                source: t!("第 {} 条规则: {}", i + 1, message),
+            };
+            let (from, to, format) = match rule {
+                RawRule::Short(text) => {
+                    let (from, to) = text
+                        .split_once("->")
+                        .ok_or_else(|| invalid(t!("简写规则的形式为 '输入 -> 输出'")))?;
+                    (from.trim().to_string(), to.trim().to_string(), None)
+                }
+                RawRule::Full { from, to, format } => (from, to, format),
+            };
+            let format = format
+                .map(|name| {
+                    <Format as clap::ValueEnum>::from_str(&name, true)
+                        .map_err(|_| invalid(t!("未知的格式: {}", name)))
+                })
+                .transpose()?;
+            check_template(&to).map_err(invalid)?;
+            glob(&from).map_err(|e| invalid(e.to_string()))?;
+            Ok(Rule { from, to, format })
+        })
+        .collect()
+}
+
+/// 检查输出模板中的占位符
+fn check_template(template: &str) -> std::result::Result<(), String> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..]
+            .find('}')
+            .ok_or_else(|| t!("占位符缺少 '}}': {}", template))?;
+        let name = &rest[start + 1..start + end];
+        if !PLACEHOLDERS.contains(&name) {
+            return Err(t!(
+                "未知的占位符 {{{}}}，可用的有 {}",
+                name,
+                PLACEHOLDERS.join(", ")
+            ));
+        }
+        rest = &rest[start + end + 1..];
+    }
+    Ok(())
+}
+
+/// 按规则找出所有要做的转换，`root` 是规则文件所在的目录
+///
+/// 没有匹配任何文件的规则会给出警告；两个转换写到同一个输出文件时报错
+pub fn plan(rules: &[Rule], root: &Path) -> Result<Vec<Job>> {
+    let mut jobs = Vec::new();
+    let mut outputs: HashMap<String, String> = HashMap::new();
+    for rule in rules {
+        let matcher = glob(&rule.from)
+            .map_err(|e| Error::Convert {
+                message: e.to_string(),
+            })?
+            .compile_matcher();
+        let prefix = literal_prefix(&rule.from);
+        let matched = matching_files(root, &prefix, &matcher)?;
+        if matched.is_empty() {
+            log!(Normal, "警告: 规则 '{}' 没有匹配任何文件", rule.from);
+        }
+        for relative in matched {
+            let output = normalize(&root.join(expand(&rule.to, &relative, &prefix)));
+            let format = match rule.format {
+                Some(format) => format,
+                None => Format::from_path(&output).ok_or_else(|| Error::UnknownFormat {
+                    path: output.clone(),
+                })?,
+            };
+            let input = normalize(&root.join(&relative));
+            if let Some(other) = outputs.insert(output.clone(), input.clone()) {
+                return Err(Error::Convert {
+                    message: t!("{} 和 {} 都会写入 {}", other, input, output),
+                });
+            }
+            jobs.push(Job {
+                input,
+                output,
+                format,
+            });
+        }
+    }
+    Ok(jobs)
+}
+
+/// 编译 glob，`*` 不跨越 `/`
+fn glob(pattern: &str) -> std::result::Result<Glob, globset::Error> {
+    GlobBuilder::new(pattern).literal_separator(true).build()
+}
+
+/// glob 中第一个含通配符的段之前的目录
+fn literal_prefix(glob: &str) -> PathBuf {
+    let mut segments: Vec<&str> = glob.split('/').collect();
+    // 最后一段是文件名部分，即使不含通配符也不算目录
+    segments.pop();
+    segments
+        .into_iter()
+        .take_while(|segment| !segment.contains(['*', '?', '[', '{']))
+        .collect()
+}
+
+/// `root/prefix` 下与 glob 匹配的文件，返回相对于 `root` 的路径（以 `/` 分隔）
+fn matching_files(root: &Path, prefix: &Path, matcher: &GlobMatcher) -> Result<Vec<String>> {
+    let base = root.join(prefix);
+    if !base.is_dir() {
+        return Ok(Vec::new());
+    }
+    let files = walk::collect_files(&[base.to_string_lossy().into_owned()], true)?;
+    Ok(files
+        .into_iter()
+        .filter_map(|file| {
+            let relative = Path::new(&file).strip_prefix(root).ok()?;
+            let relative = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            matcher.is_match(&relative).then_some(relative)
+        })
+        .collect())
+}
+
+/// 用输入文件填充输出模板
+fn expand(template: &str, relative: &str, prefix: &Path) -> String {
+    let path = Path::new(relative);
+    let text = |s: Option<&std::ffi::OsStr>| {
+        s.map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let dir = path
+        .parent()
+        .and_then(|parent| parent.strip_prefix(prefix).ok())
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    template
+        .replace("{stem}", &text(path.file_stem()))
+        .replace("{name}", &text(path.file_name()))
+        .replace("{ext}", &text(path.extension()))
+        .replace("{dir}", &dir)
+}
+
+/// 去掉路径中多余的 `.` 和重复的分隔符
+fn normalize(path: &Path) -> String {
+    let normalized: PathBuf = path
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+    normalized.to_string_lossy().into_owned()
+}
@@ -0,0 +1,309 @@
+//! 合并引擎
+//!
+//! 把多个配置按顺序叠加为一个，供 merge、模板变量等需要分层覆盖的功能共用：
+//! - 对象逐键递归合并
+//! - 其他值（标量、数组）由后面的整体覆盖前面的
+//! - 用 `--merge-key` 指定合并键的对象数组按键逐个元素合并（见 [`MergeKey`]）
+//! - 最后应用命令行上的 `--set path=value`
+
+use crate::error::{Error, Result};
+use crate::flatten;
+use crate::path::{self, Path, Pattern, Segment};
+use crate::t;
+use serde_json::{Map, Value};
+
+/// 一次覆盖：同一路径上前后两层的值不同
+#[derive(Debug)]
+pub struct Conflict {
+    /// 发生覆盖的路径
+    pub path: Path,
+    /// 被覆盖的值
+    pub old: Value,
+    /// 覆盖后的值
+    pub new: Value,
+}
+
+/// 覆盖层数组元素中的指令键：`$patch: delete` 表示删除合并键相同的元素
+pub const PATCH_DIRECTIVE: &str = "$patch";
+
+/// 一条 `--merge-key [PATH=]KEY` 规则
+///
+/// 对象数组不再整体覆盖，而是按 `KEY` 的值配对：相同的元素递归合并，
+/// 新的元素和没有 `KEY` 的元素追加到末尾，带 `$patch: delete` 的元素删除对应的元素。
+/// 例如 `--merge-key '**.containers=name'` 按名称合并 Kubernetes 的容器列表，
+/// 不受元素顺序影响。没有路径时适用于有元素带 `KEY` 的对象数组。
+/// 指定了合并键时，`$patch` 指令不会出现在合并结果中，包括整体覆盖的数组
+#[derive(Clone, Debug)]
+pub struct MergeKey {
+    pattern: Option<Pattern>,
+    key: String,
+}
+
+impl MergeKey {
+    /// 解析 `[PATH=]KEY`
+    pub fn parse(input: &str) -> Result<Self> {
+        let (pattern, key) = match input.rsplit_once('=') {
+            Some((path, key)) => (Some(Pattern::parse(path)?), key),
+            None => (None, input),
+        };
+        if key.is_empty() {
+            return Err(Error::InvalidPath {
+                path: input.to_string(),
+                message: t!("缺少合并键"),
+            });
+        }
+        Ok(MergeKey {
+            pattern,
+            key: key.to_string(),
+        })
+    }
+
+    /// 按 `root` 展开规则里的负数下标，保持规则顺序
+    fn resolve(keys: &[MergeKey], root: &Value) -> Vec<MergeKey> {
+        keys.iter()
+            .flat_map(|rule| match &rule.pattern {
+                Some(pattern) => pattern
+                    .resolve(root)
+                    .into_iter()
+                    .map(|pattern| MergeKey {
+                        pattern: Some(pattern),
+                        key: rule.key.clone(),
+                    })
+                    .collect(),
+                None => vec![rule.clone()],
+            })
+            .collect()
+    }
+}
+
+/// 把 `overlay` 合并到 `base` 上，返回所有值被改写的路径
+///
+/// 两边都是对象时不算冲突，只比较下面的键；值相同的覆盖也不算冲突
+pub fn merge(base: &mut Value, overlay: Value) -> Vec<Conflict> {
+    merge_with(base, overlay, &[])
+}
+
+/// 同 [`merge`]，对象数组按 `keys` 中的合并键逐个元素合并；被删除的元素记为覆盖为 null
+pub fn merge_with(base: &mut Value, overlay: Value, keys: &[MergeKey]) -> Vec<Conflict> {
+    let mut conflicts = Vec::new();
+    let keys = MergeKey::resolve(keys, base);
+    merge_at(base, overlay, &Path::default(), &keys, &mut conflicts);
+    conflicts
+}
+
+fn merge_at(
+    base: &mut Value,
+    overlay: Value,
+    path: &Path,
+    keys: &[MergeKey],
+    conflicts: &mut Vec<Conflict>,
+) {
+    match (&mut *base, overlay) {
+        (Value::Object(map), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                let child = path.join(Segment::Key(key.clone()));
+                match map.get_mut(&key) {
+                    Some(existing) => merge_at(existing, value, &child, keys, conflicts),
+                    None => {
+                        map.insert(key, value);
+                    }
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(overlay)) => {
+            match merge_key(path, keys, items, &overlay) {
+                Some(key) => merge_elements(items, overlay, key, path, keys, conflicts),
+                None if !keys.is_empty() => {
+                    let overlay = strip_directives(overlay);
+                    overwrite(base, Value::Array(overlay), path, conflicts)
+                }
+                None => overwrite(base, Value::Array(overlay), path, conflicts),
+            }
+        }
+        (_, overlay) => overwrite(base, overlay, path, conflicts),
+    }
+}
+
+/// 用 `overlay` 整体替换 `base`
+fn overwrite(base: &mut Value, overlay: Value, path: &Path, conflicts: &mut Vec<Conflict>) {
+    if *base != overlay {
+        let old = std::mem::replace(base, overlay);
+        conflicts.push(Conflict {
+            path: path.clone(),
+            old,
+            new: base.clone(),
+        });
+    }
+}
+
+/// 按合并键 `key` 把 `overlay` 中的元素逐个合并到 `base` 中
+fn merge_elements(
+    base: &mut Vec<Value>,
+    overlay: Vec<Value>,
+    key: &str,
+    path: &Path,
+    keys: &[MergeKey],
+    conflicts: &mut Vec<Conflict>,
+) {
+    for mut element in overlay {
+        let delete = take_directive(&mut element);
+        // 没有合并键的元素不与任何元素配对
+        let index = element.get(key).and_then(|value| {
+            base.iter()
+                .position(|existing| existing.get(key) == Some(value))
+        });
+        match (index, delete) {
+            (Some(index), true) => {
+                let old = base.remove(index);
+                conflicts.push(Conflict {
+                    path: path.join(Segment::Index(index as i64)),
+                    old,
+                    new: Value::Null,
+                });
+            }
+            (Some(index), false) => {
+                let child = path.join(Segment::Index(index as i64));
+                merge_at(&mut base[index], element, &child, keys, conflicts);
+            }
+            (None, true) => {}
+            (None, false) => base.push(element),
+        }
+    }
+}
+
+/// 去掉元素中的 `$patch` 指令，返回是否是删除指令
+fn take_directive(element: &mut Value) -> bool {
+    element
+        .as_object_mut()
+        .and_then(|map| map.shift_remove(PATCH_DIRECTIVE))
+        .is_some_and(|directive| directive == "delete")
+}
+
+/// 整体覆盖的数组中没有可以删除的元素：去掉删除指令的元素和其他元素中的指令
+fn strip_directives(overlay: Vec<Value>) -> Vec<Value> {
+    overlay
+        .into_iter()
+        .filter_map(|mut element| (!take_directive(&mut element)).then_some(element))
+        .collect()
+}
+
+/// 数组适用的合并键：最后一条匹配的规则；没有路径的规则要求有元素带这个键
+fn merge_key<'a>(
+    path: &Path,
+    keys: &'a [MergeKey],
+    base: &[Value],
+    overlay: &[Value],
+) -> Option<&'a str> {
+    let rule = keys.iter().rev().find(|rule| {
+        rule.pattern
+            .as_ref()
+            .is_none_or(|pattern| pattern.matches(path))
+    })?;
+    (rule.pattern.is_some()
+        || base
+            .iter()
+            .chain(overlay)
+            .any(|element| element.get(&rule.key).is_some()))
+    .then_some(rule.key.as_str())
+}
+
+/// 命令行上的一个 `--set path=value` 覆盖
+#[derive(Clone, Debug)]
+pub struct Assignment {
+    /// 要写入的路径
+    pub path: Path,
+    /// 写入的值
+    pub value: Value,
+}
+
+impl Assignment {
+    /// 解析 `path=value`
+    ///
+    /// 值按 YAML 标量解释（`3` 是数字、`true` 是布尔、`null` 是空值），
+    /// 其他写法（包括看起来像对象或数组的）一律作为字符串
+    pub fn parse(input: &str) -> Result<Self> {
+        let (path, text) = input.split_once('=').ok_or_else(|| Error::InvalidPath {
+            path: input.to_string(),
+            message: t!("应为 path=value 的形式"),
+        })?;
+        Ok(Assignment {
+            path: Path::parse(path)?,
+            value: flatten::parse_scalar(text),
+        })
+    }
+}
+
+/// 按顺序合并所有层，再依次应用 `--set` 覆盖，得到最终的值
+pub fn resolve(layers: Vec<Value>, assignments: &[Assignment], keys: &[MergeKey]) -> Result<Value> {
+    let mut result = Value::Object(Map::new());
+    for layer in layers {
+        merge_with(&mut result, layer, keys);
+    }
+    for assignment in assignments {
+        path::insert(&mut result, &assignment.path, assignment.value.clone())?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn merges_arrays_by_key() {
+        let mut base = json!({"containers": [
+            {"name": "app", "image": "app:1"},
+            {"name": "proxy", "image": "proxy:1"},
+            {"name": "debug"}
+        ]});
+        let patch = json!({"containers": [
+            {"name": "proxy", "image": "proxy:2"},
+            {"name": "debug", "$patch": "delete"},
+            {"name": "sidecar"}
+        ]});
+        let keys = [MergeKey::parse("**.containers=name").unwrap()];
+        let conflicts = merge_with(&mut base, patch.clone(), &keys);
+        assert_eq!(
+            base,
+            json!({"containers": [
+                {"name": "app", "image": "app:1"},
+                {"name": "proxy", "image": "proxy:2"},
+                {"name": "sidecar"}
+            ]})
+        );
+        let paths: Vec<String> = conflicts.iter().map(|c| c.path.to_string()).collect();
+        assert_eq!(paths, ["containers[1].image", "containers[2]"]);
+
+        // 没有合并键时整体覆盖
+        let mut base = json!({"containers": [{"name": "app"}]});
+        merge(&mut base, patch.clone());
+        assert_eq!(base, patch);
+    }
+
+    #[test]
+    fn keyless_elements_are_appended_and_directives_never_leak() {
+        let keys = [MergeKey::parse("**.containers=name").unwrap()];
+        let mut base = json!({"containers": [{"name": "app"}, {"image": "base"}]});
+        let patch = json!({"containers": [
+            {"name": "app", "image": "app:2"},
+            {"image": "extra"},
+            {"image": "gone", "$patch": "delete"}
+        ]});
+        merge_with(&mut base, patch, &keys);
+        assert_eq!(
+            base,
+            json!({"containers": [
+                {"name": "app", "image": "app:2"},
+                {"image": "base"},
+                {"image": "extra"}
+            ]})
+        );
+
+        // 规则不适用的数组整体覆盖，指令同样去掉
+        let mut base = json!({"ports": [1]});
+        let patch = json!({"ports": [{"port": 2, "$patch": "replace"}, {"$patch": "delete"}]});
+        merge_with(&mut base, patch, &keys);
+        assert_eq!(base, json!({"ports": [{"port": 2}]}));
+    }
+}
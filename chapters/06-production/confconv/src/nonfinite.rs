@@ -0,0 +1,368 @@
+//! 无穷大和 NaN
+//!
+//! JSON 不能表示无穷大和 NaN，默认与 serde_yml 一样把 YAML 的 `.inf`、`.nan` 和 TOML 的
+//! `inf`、`nan` 读成 null。指定 `--json-allow-nan` 时，解析器把它们读成只有一个键的标记对象
+//! （与 TOML 日期时间的做法相同），序列化时再还原：
+//! - YAML 和 TOML 输出各自的原生写法（`.inf`、`inf` 等）
+//! - JSON 按模式输出：`null`、字符串 `"NaN"`/`"Infinity"`/`"-Infinity"`，
+//!   或者直接写出 `NaN`、`Infinity`、`-Infinity`（JavaScript、Python 等宽松的解析器接受这种写法）
+//!
+//! 同时 JSON 输入也接受这三个字面量
+
+use crate::dialect::{self, NanPolicy};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use serde_json::{Map, Number, Value};
+use std::borrow::Cow;
+use std::fmt;
+
+/// 标记对象唯一的键，值为 `inf`、`-inf` 或 `nan`
+pub const NONFINITE_KEY: &str = "$__confconv_nonfinite";
+
+/// JSON 中三个非有限值的字面量及对应的浮点数
+const LITERALS: [(&str, f64); 3] = [
+    ("-Infinity", f64::NEG_INFINITY),
+    ("Infinity", f64::INFINITY),
+    ("NaN", f64::NAN),
+];
+
+/// 是否指定了 `--json-allow-nan`
+pub fn enabled() -> bool {
+    dialect::current().json_nan.is_some()
+}
+
+/// 浮点数对应的值：有限值是数字，非有限值在启用时是标记对象，否则是 null
+pub fn float(value: f64) -> Value {
+    match Number::from_f64(value) {
+        Some(number) => Value::Number(number),
+        None if enabled() => marker(value),
+        None => Value::Null,
+    }
+}
+
+/// 非有限浮点数的标记对象
+pub fn marker(value: f64) -> Value {
+    serde_json::json!({ NONFINITE_KEY: marker_text(value) })
+}
+
+/// 标记对象中的文本
+pub(crate) fn marker_text(value: f64) -> &'static str {
+    if value.is_nan() {
+        "nan"
+    } else if value > 0.0 {
+        "inf"
+    } else {
+        "-inf"
+    }
+}
+
+/// 如果值是标记对象，返回它表示的浮点数
+pub fn get(value: &Value) -> Option<f64> {
+    match value.as_object() {
+        Some(map) if map.len() == 1 => match map.get(NONFINITE_KEY)?.as_str()? {
+            "inf" => Some(f64::INFINITY),
+            "-inf" => Some(f64::NEG_INFINITY),
+            "nan" => Some(f64::NAN),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// 与 `serde_json::Value` 相同的反序列化，只是把非有限浮点数读成标记对象
+pub struct Lossless(pub Value);
+
+impl<'de> Deserialize<'de> for Lossless {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(LosslessVisitor).map(Lossless)
+    }
+}
+
+struct LosslessVisitor;
+
+impl<'de> Visitor<'de> for LosslessVisitor {
+    type Value = Value;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any valid JSON value")
+    }
+
+    fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<Value, E> {
+        Value::deserialize(de::value::I128Deserializer::new(value))
+    }
+
+    fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<Value, E> {
+        Value::deserialize(de::value::U128Deserializer::new(value))
+    }
+
+    fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+        Ok(Number::from_f64(value).map_or_else(|| marker(value), Value::Number))
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_none<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Value, D::Error> {
+        Lossless::deserialize(deserializer).map(|value| value.0)
+    }
+
+    fn visit_unit<E>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(Lossless(item)) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Value, A::Error> {
+        let mut map = Map::new();
+        while let Some((key, Lossless(value))) = access.next_entry::<String, Lossless>()? {
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// 与 `serde_json::Value` 相同的序列化，只是把标记对象写成浮点数，用于 YAML 输出
+pub struct Native<'a>(pub &'a Value);
+
+impl Serialize for Native<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if let Some(float) = get(self.0) {
+            return serializer.serialize_f64(float);
+        }
+        match self.0 {
+            Value::Array(items) => {
+                let mut seq = serializer.serialize_seq(Some(items.len()))?;
+                for item in items {
+                    seq.serialize_element(&Native(item))?;
+                }
+                seq.end()
+            }
+            Value::Object(map) => {
+                let mut out = serializer.serialize_map(Some(map.len()))?;
+                for (key, value) in map {
+                    out.serialize_entry(key, &Native(value))?;
+                }
+                out.end()
+            }
+            other => other.serialize(serializer),
+        }
+    }
+}
+
+/// 把 JSON 文本中字符串以外的 `NaN`、`Infinity`、`-Infinity` 换成标记对象，没有时不复制
+pub fn read_json_literals(text: &str) -> Cow<'_, str> {
+    let bytes = text.as_bytes();
+    let mut out = String::new();
+    let (mut copied, mut i) = (0, 0);
+    let mut in_string = false;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        if in_string {
+            match byte {
+                b'\\' => i += 1,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            i += 1;
+            continue;
+        }
+        if byte == b'"' {
+            in_string = true;
+            i += 1;
+            continue;
+        }
+        let boundary = |at: usize| bytes.get(at).is_none_or(|b| !b.is_ascii_alphanumeric());
+        let literal = LITERALS.iter().find(|(literal, _)| {
+            text[i..].starts_with(literal)
+                && (i == 0 || boundary(i - 1))
+                && boundary(i + literal.len())
+        });
+        match literal {
+            Some((literal, value)) => {
+                out.push_str(&text[copied..i]);
+                out.push_str(&marker(*value).to_string());
+                i += literal.len();
+                copied = i;
+            }
+            None => i += 1,
+        }
+    }
+    if copied == 0 {
+        return Cow::Borrowed(text);
+    }
+    out.push_str(&text[copied..]);
+    Cow::Owned(out)
+}
+
+/// 按模式把含有标记对象的值序列化为 JSON
+///
+/// `indent` 为美化输出的缩进空格数，None 表示紧凑输出
+pub fn to_json(value: &Value, policy: NanPolicy, indent: Option<u8>) -> serde_json::Result<String> {
+    // literal 模式先写成占位字符串，序列化后再去掉引号
+    const PLACEHOLDER: &str = "$__confconv_nonfinite:";
+    fn replace(value: &mut Value, policy: NanPolicy) {
+        if let Some(float) = get(value) {
+            let literal = if float.is_nan() {
+                "NaN"
+            } else if float > 0.0 {
+                "Infinity"
+            } else {
+                "-Infinity"
+            };
+            *value = match policy {
+                NanPolicy::Null => Value::Null,
+                NanPolicy::String => Value::String(literal.to_string()),
+                NanPolicy::Literal => Value::String(format!("{}{}", PLACEHOLDER, literal)),
+            };
+            return;
+        }
+        match value {
+            Value::Object(map) => map.values_mut().for_each(|v| replace(v, policy)),
+            Value::Array(items) => items.iter_mut().for_each(|v| replace(v, policy)),
+            _ => {}
+        }
+    }
+
+    let mut value = value.clone();
+    replace(&mut value, policy);
+    let mut text = crate::codec::to_json(&value, indent)?;
+    if policy == NanPolicy::Literal {
+        for (literal, _) in LITERALS {
+            text = text.replace(&format!("\"{}{}\"", PLACEHOLDER, literal), literal);
+        }
+    }
+    Ok(text)
+}
+
+/// TOML 值和标记对象之间的转换（toml 的两个版本各有一个 `Value` 类型）
+macro_rules! toml_conversions {
+    ($(#[$doc:meta])* $module:ident, $value:ident) => {
+        $(#[$doc])*
+        pub mod $module {
+            use super::{get, marker_text, NONFINITE_KEY};
+            use ::$value::{Table, Value};
+
+            /// 把非有限浮点数换成标记对象，之后再转换为 JSON 值
+            pub fn mark(value: &mut Value) {
+                match value {
+                    Value::Float(float) if !float.is_finite() => {
+                        let mut table = Table::new();
+                        let text = marker_text(*float).to_string();
+                        table.insert(NONFINITE_KEY.to_string(), Value::String(text));
+                        *value = Value::Table(table);
+                    }
+                    Value::Table(table) => table.iter_mut().for_each(|(_, child)| mark(child)),
+                    Value::Array(items) => items.iter_mut().for_each(mark),
+                    _ => {}
+                }
+            }
+
+            /// 把标记对象还原为浮点数
+            pub fn restore(value: &mut Value) {
+                match value {
+                    Value::Table(table) => {
+                        let float = match table.get(NONFINITE_KEY) {
+                            Some(Value::String(text)) if table.len() == 1 => {
+                                get(&serde_json::json!({ NONFINITE_KEY: text }))
+                            }
+                            _ => None,
+                        };
+                        match float {
+                            Some(float) => *value = Value::Float(float),
+                            None => table.iter_mut().for_each(|(_, child)| restore(child)),
+                        }
+                    }
+                    Value::Array(items) => items.iter_mut().for_each(restore),
+                    _ => {}
+                }
+            }
+        }
+    };
+}
+
+toml_conversions!(
+    /// TOML 1.0 值的转换
+    toml10,
+    toml
+);
+toml_conversions!(
+    /// TOML 1.1 值的转换
+    toml11,
+    toml11
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn reads_literals_outside_strings() {
+        let text = read_json_literals(
+            r#"{"a": NaN, "b": [-Infinity, Infinity], "c": "NaN \" NaN", "NaN": 1}"#,
+        );
+        let value: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(value["a"], marker(f64::NAN));
+        assert_eq!(
+            value["b"],
+            json!([marker(f64::NEG_INFINITY), marker(f64::INFINITY)])
+        );
+        assert_eq!(value["c"], json!("NaN \" NaN"));
+        assert!(matches!(read_json_literals("[1, 2]"), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn writes_json_by_policy() {
+        let value = json!({"a": marker(f64::INFINITY), "b": [marker(f64::NAN)], "c": 1.5});
+        assert_eq!(
+            to_json(&value, NanPolicy::Null, None).unwrap(),
+            r#"{"a":null,"b":[null],"c":1.5}"#
+        );
+        assert_eq!(
+            to_json(&value, NanPolicy::String, None).unwrap(),
+            r#"{"a":"Infinity","b":["NaN"],"c":1.5}"#
+        );
+        assert_eq!(
+            to_json(&value, NanPolicy::Literal, None).unwrap(),
+            r#"{"a":Infinity,"b":[NaN],"c":1.5}"#
+        );
+    }
+
+    #[test]
+    fn yaml_round_trip_keeps_nonfinite_values() {
+        let Lossless(value) = serde_yml::from_str("a: .inf\nb: -.inf\nc: .nan\nd: 1\n").unwrap();
+        assert_eq!(value["b"], marker(f64::NEG_INFINITY));
+        assert_eq!(value["d"], json!(1));
+        assert_eq!(
+            serde_yml::to_string(&Native(&value)).unwrap(),
+            "a: .inf\nb: -.inf\nc: .nan\nd: 1\n"
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! 批量编辑操作
+//!
+//! 操作文件是一个操作列表（任意支持的格式），按顺序应用：
+//!
+//! ```yaml
+//! - op: set
+//!   path: server.port
+//!   value: 8080
+//! - op: unset
+//!   path: legacy
+//! - op: move
+//!   from: db.url
+//!   to: database.url
+//! - op: rename
+//!   path: server.host
+//!   to: hostname
+//! ```
+
+use crate::error::{Error, Result};
+use crate::path::{self, Path};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// 单个编辑操作
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase", deny_unknown_fields)]
+pub enum Operation {
+    /// 在路径上写入值，缺少的父级自动创建
+    Set { path: String, value: Value },
+    /// 删除路径上的值
+    Unset { path: String },
+    /// 把子树移动到新路径，目标已存在时需要 `force: true`
+    Move {
+        from: String,
+        to: String,
+        #[serde(default)]
+        force: bool,
+    },
+    /// 原地重命名最后一段的键，保持它在父对象中的位置
+    Rename { path: String, to: String },
+}
+
+impl Operation {
+    /// 把操作应用到值上
+    pub fn apply(&self, value: &mut Value) -> Result<()> {
+        match self {
+            Operation::Set { path, value: new } => {
+                path::insert(value, &Path::parse(path)?, new.clone())?;
+            }
+            Operation::Unset { path } => {
+                let path = Path::parse(path)?;
+                path::remove(value, &path).ok_or_else(|| Error::PathNotFound {
+                    path: path.to_string(),
+                })?;
+            }
+            Operation::Move { from, to, force } => {
+                path::relocate(value, &Path::parse(from)?, &Path::parse(to)?, *force)?;
+            }
+            Operation::Rename { path, to } => {
+                path::rename(value, &Path::parse(path)?, to)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 解析操作列表
+pub fn parse_operations(value: Value) -> Result<Vec<Operation>> {
+    serde_json::from_value(value).map_err(|e| Error::Parse {
+        format: "操作文件",
+        source: e.to_string(),
+    })
+}
+
+/// 按顺序应用所有操作
+///
+/// 在副本上执行，任何一个操作失败都不会修改原值，保证要么全部生效要么都不生效
+pub fn apply_all(value: &mut Value, operations: &[Operation]) -> Result<()> {
+    let mut working = value.clone();
+    for (i, operation) in operations.iter().enumerate() {
+        operation
+            .apply(&mut working)
+            .map_err(|e| Error::Operation {
+                index: i + 1,
+                source: Box::new(e),
+            })?;
+    }
+    *value = working;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn failing_operation_leaves_value_unchanged() {
+        let operations = parse_operations(json!([
+            {"op": "set", "path": "server.port", "value": 8080},
+            {"op": "rename", "path": "server.host", "to": "hostname"},
+            {"op": "unset", "path": "missing"},
+            {"op": "set", "path": "never", "value": true}
+        ]))
+        .unwrap();
+        let original = json!({"server": {"host": "a", "port": 80}});
+        let mut value = original.clone();
+        let error = apply_all(&mut value, &operations).unwrap_err();
+        assert!(matches!(error, Error::Operation { index: 3, .. }));
+        assert_eq!(value, original);
+
+        apply_all(&mut value, &operations[..2]).unwrap();
+        assert_eq!(value, json!({"server": {"hostname": "a", "port": 8080}}));
+    }
+}
@@ -0,0 +1,106 @@
+//! 标准输出
+//!
+//! 转换结果以原始字节写出，二进制格式（如插件提供的 MessagePack）也能通过管道传递。
+//! 标准输出是终端时拒绝直接输出二进制数据，可以用 `--force` 强制输出，
+//! 或用 `--hex`/`--base64` 以可读的形式显示
+
+use crate::error::{Error, Result};
+use crate::t;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal, Write};
+
+/// 结果在标准输出上的显示形式
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Encoding {
+    /// 原样输出
+    #[default]
+    Raw,
+    /// 十六进制转储（偏移、十六进制字节和可打印字符）
+    Hex,
+    /// base64 编码
+    Base64,
+}
+
+/// 写到标准输出时的选项
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdoutOptions {
+    /// 显示形式
+    pub encoding: Encoding,
+    /// 标准输出是终端时也输出二进制数据
+    pub force: bool,
+}
+
+/// 按选项把结果写到标准输出
+pub fn write_stdout(bytes: &[u8], options: &StdoutOptions) -> Result<()> {
+    let encoded;
+    let bytes = match options.encoding {
+        Encoding::Raw => {
+            if !options.force && is_binary(bytes) && io::stdout().is_terminal() {
+                return Err(Error::Convert {
+                    message: t!("输出是二进制数据，不会写到终端；使用 --force 强制输出，或用 --hex/--base64 显示"),
+                });
+            }
+            bytes
+        }
+        Encoding::Hex => {
+            encoded = hexdump(bytes);
+            encoded.as_bytes()
+        }
+        Encoding::Base64 => {
+            encoded = format!("{}\n", STANDARD.encode(bytes));
+            encoded.as_bytes()
+        }
+    };
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(bytes)
+        .and_then(|()| stdout.flush())
+        .map_err(|e| Error::FileWrite {
+            path: "stdout".to_string(),
+            source: e,
+        })
+}
+
+/// 是否是二进制数据：不是 UTF-8 文本，或含有除制表、换行、回车和转义以外的控制字符
+pub fn is_binary(bytes: &[u8]) -> bool {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => text
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\t' | '\n' | '\r' | '\x1b')),
+        Err(_) => true,
+    }
+}
+
+/// 与 `hexdump -C` 相同的十六进制转储
+fn hexdump(bytes: &[u8]) -> String {
+    let mut output = String::new();
+    for (i, chunk) in bytes.chunks(16).enumerate() {
+        let _ = write!(output, "{:08x} ", i * 16);
+        for j in 0..16 {
+            if j % 8 == 0 {
+                output.push(' ');
+            }
+            match chunk.get(j) {
+                Some(byte) => {
+                    let _ = write!(output, "{:02x} ", byte);
+                }
+                None => output.push_str("   "),
+            }
+        }
+        let printable: String = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        let _ = writeln!(output, " |{}|", printable);
+    }
+    let _ = writeln!(output, "{:08x}", bytes.len());
+    output
+}
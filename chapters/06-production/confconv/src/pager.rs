@@ -0,0 +1,119 @@
+//! 分页器
+//!
+//! 与 git 一样，标准输出是终端时把输出交给分页器，长输出不会一下子滚出屏幕：
+//! 分页器取自 `CONFCONV_PAGER` 或 `PAGER`，都没有设置时使用 `less`。
+//! 没有设置 `LESS` 时按 `FRX` 运行 less：输出不足一屏时直接显示并退出，保留颜色，退出后不清屏。
+//! 分页器为空或为 `cat` 时不使用分页器，全局参数 `--no-pager` 也可以关闭它
+//!
+//! 实现方式是把进程的标准输出（标准错误也是终端时包括标准错误）换成通往分页器的管道，
+//! 所以各个命令不需要做任何修改；只支持 Unix
+
+use crate::color::Stream;
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// 标准输出、标准错误是否被换成了通往分页器的管道
+static STDOUT: AtomicBool = AtomicBool::new(false);
+static STDERR: AtomicBool = AtomicBool::new(false);
+
+/// 输出流是否连接到终端：被换成分页器管道的流仍然算作终端，着色和宽度按终端处理
+pub fn is_terminal(stream: Stream) -> bool {
+    match stream {
+        Stream::Stdout => STDOUT.load(Ordering::Relaxed) || io::stdout().is_terminal(),
+        Stream::Stderr => STDERR.load(Ordering::Relaxed) || io::stderr().is_terminal(),
+    }
+}
+
+/// 运行中的分页器，调用 [`Pager::finish`] 等待它退出
+pub struct Pager {
+    #[cfg(unix)]
+    child: std::process::Child,
+    /// 原来的标准输出和标准错误，结束时恢复
+    #[cfg(unix)]
+    saved: Vec<(i32, i32)>,
+}
+
+/// 本次运行使用的分页器命令，不使用分页器时为 None
+fn command() -> Option<String> {
+    let command = env::var("CONFCONV_PAGER")
+        .or_else(|_| env::var("PAGER"))
+        .unwrap_or_else(|_| "less".to_string());
+    let command = command.trim();
+    (!command.is_empty() && command != "cat").then(|| command.to_string())
+}
+
+impl Pager {
+    /// 标准输出是终端时启动分页器；启动失败时直接输出到终端
+    #[cfg(unix)]
+    pub fn start() -> Option<Pager> {
+        use crate::process;
+        use std::os::fd::AsRawFd;
+        use std::process::Stdio;
+
+        if !io::stdout().is_terminal() {
+            return None;
+        }
+        let mut command = process::shell(&command()?);
+        if env::var_os("LESS").is_none() {
+            command.env("LESS", "FRX");
+        }
+        let mut child = command.stdin(Stdio::piped()).spawn().ok()?;
+        let pipe = child.stdin.take()?;
+
+        let mut targets = vec![libc::STDOUT_FILENO];
+        if io::stderr().is_terminal() {
+            targets.push(libc::STDERR_FILENO);
+        }
+        let mut saved = Vec::new();
+        for fd in targets {
+            // SAFETY: 只复制和替换本进程的标准输出、标准错误，管道在替换完成前一直有效
+            unsafe {
+                let original = libc::dup(fd);
+                if original < 0 || libc::dup2(pipe.as_raw_fd(), fd) < 0 {
+                    break;
+                }
+                saved.push((fd, original));
+            }
+            let redirected = if fd == libc::STDOUT_FILENO {
+                &STDOUT
+            } else {
+                &STDERR
+            };
+            redirected.store(true, Ordering::Relaxed);
+        }
+        // 用户在分页器中提前退出时像 git 一样直接结束，而不是报告写入失败
+        // SAFETY: 恢复 SIGPIPE 的默认处理不涉及任何内存
+        unsafe {
+            libc::signal(libc::SIGPIPE, libc::SIG_DFL);
+        }
+        Some(Pager { child, saved })
+    }
+
+    #[cfg(not(unix))]
+    pub fn start() -> Option<Pager> {
+        None
+    }
+
+    /// 恢复标准输出和标准错误，等待用户退出分页器
+    pub fn finish(self) {
+        #[cfg(unix)]
+        {
+            use std::io::Write;
+
+            let Pager { mut child, saved } = self;
+            let _ = io::stdout().flush();
+            for (fd, original) in saved {
+                // SAFETY: original 是 start 时复制的描述符，恢复后关闭它；
+                // 替换掉的是管道的最后一个写端，分页器随之读到输入结束
+                unsafe {
+                    libc::dup2(original, fd);
+                    libc::close(original);
+                }
+            }
+            STDOUT.store(false, Ordering::Relaxed);
+            STDERR.store(false, Ordering::Relaxed);
+            let _ = child.wait();
+        }
+    }
+}
@@ -0,0 +1,879 @@
+//! 路径引擎
+//!
+//! 用路径表达式定位配置中的值，供 get/set/unset/move 等按路径操作的命令共用
+//!
+//! ## 语法
+//! - `server.tls.cert`：点号分隔的键
+//! - `servers[0].host`：数组下标
+//! - `users[-1]`：负数下标，从末尾数起
+//! - `tags[+]`：追加到数组末尾（只能用于写入，且只能是最后一段）
+//! - `"a.b".c`：包含特殊字符的键用双引号括起来，支持 `\"` 和 `\\` 转义
+
+use crate::error::{Error, Result};
+use crate::i18n::tr;
+use crate::t;
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// 路径中的一段
+#[derive(Clone, Debug, PartialEq)]
+pub enum Segment {
+    /// 对象的键
+    Key(String),
+    /// 数组下标，负数表示从末尾数起
+    Index(i64),
+    /// 追加到数组末尾
+    Append,
+}
+
+/// 解析后的路径
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<Segment>,
+}
+
+impl Path {
+    /// 解析路径表达式，空字符串表示根
+    pub fn parse(input: &str) -> Result<Self> {
+        Parser::new(input).parse()
+    }
+
+    /// 是否为根路径
+    pub fn is_root(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// 路径的各段
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// 在末尾追加一段，返回新路径
+    pub fn join(&self, segment: Segment) -> Path {
+        let mut segments = self.segments.clone();
+        segments.push(segment);
+        Path { segments }
+    }
+
+    /// 去掉最后一段的路径，根路径的父路径是它自己
+    pub fn parent(&self) -> Path {
+        let mut segments = self.segments.clone();
+        segments.pop();
+        Path { segments }
+    }
+
+    /// `self` 是否为 `other` 本身或它的祖先
+    pub fn is_prefix_of(&self, other: &Path) -> bool {
+        other.segments.starts_with(&self.segments)
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                Segment::Key(key) => {
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+                    if is_plain_key(key) {
+                        write!(f, "{}", key)?;
+                    } else {
+                        let escaped = key.replace('\\', "\\\\").replace('"', "\\\"");
+                        write!(f, "\"{}\"", escaped)?;
+                    }
+                }
+                Segment::Index(index) => write!(f, "[{}]", index)?,
+                Segment::Append => write!(f, "[+]")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 不需要引号的键
+fn is_plain_key(key: &str) -> bool {
+    !key.is_empty() && !key.contains(['.', '[', ']', '"', '\\'])
+}
+
+/// 路径通配模式，用于按路径筛选
+///
+/// 语法与路径表达式相同，另外支持：
+/// - 段内的 `*`（任意字符）和 `?`（单个字符），如 `metadata.*`、`items[*].name`
+/// - 单独一段 `**`，匹配任意多段（包括零段），如 `**.password`
+///
+/// 负数下标（`items[-1]`）需要知道数组的长度，匹配前先用 [`Pattern::resolve`] 按值换算
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    parts: Vec<PatternPart>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PatternPart {
+    /// `**`
+    Any,
+    /// 段内通配，下标段保留方括号，如 `[0]`、`[*]`
+    Glob(String),
+    /// 换算负数下标时展开的具体的段，不含通配
+    Literal(String),
+}
+
+impl PatternPart {
+    /// 负数下标段 `[-n]` 的下标
+    fn negative_index(&self) -> Option<i64> {
+        match self {
+            PatternPart::Glob(glob) => glob
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+                .and_then(|index| index.trim().parse().ok())
+                .filter(|index: &i64| *index < 0),
+            _ => None,
+        }
+    }
+}
+
+impl Pattern {
+    /// 解析通配模式，空字符串表示根
+    pub fn parse(input: &str) -> Result<Self> {
+        let error = |message: &str| Error::InvalidPath {
+            path: input.to_string(),
+            message: tr(message).to_string(),
+        };
+
+        let mut parts = Vec::new();
+        let mut chars = input.chars().peekable();
+        // 上一个字符是 `.`，后面必须是一个键
+        let mut after_dot = false;
+        while let Some(c) = chars.next() {
+            if after_dot && matches!(c, '.' | '[') {
+                return Err(error("路径中存在空的段"));
+            }
+            after_dot = c == '.';
+            let text = match c {
+                '[' => {
+                    let mut text = String::from("[");
+                    loop {
+                        match chars.next() {
+                            Some(']') => break,
+                            Some(c) => text.push(c),
+                            None => return Err(error("下标缺少结束的 ']'")),
+                        }
+                    }
+                    text.push(']');
+                    text
+                }
+                '"' => {
+                    let mut text = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('"') => break,
+                            Some('\\') => text.extend(chars.next()),
+                            Some(c) => text.push(c),
+                            None => return Err(error("带引号的键缺少结束的 '\"'")),
+                        }
+                    }
+                    text
+                }
+                '.' if parts.is_empty() => return Err(error("路径不能以 '.' 开头")),
+                '.' => continue,
+                c => {
+                    let mut text = String::from(c);
+                    while let Some(&c) = chars.peek() {
+                        if c == '.' || c == '[' {
+                            break;
+                        }
+                        text.push(c);
+                        chars.next();
+                    }
+                    text
+                }
+            };
+            parts.push(if text == "**" {
+                PatternPart::Any
+            } else {
+                PatternPart::Glob(text)
+            });
+        }
+        if after_dot {
+            return Err(error("路径中存在空的段"));
+        }
+
+        Ok(Pattern { parts })
+    }
+
+    /// 按 `root` 中数组的实际长度换算负数下标
+    ///
+    /// 负数下标之前的部分展开为 `root` 中的具体路径，每条路径得到一个模式，
+    /// 所以同一个模式在不同长度的数组中换算为各自的下标；没有负数下标的模式原样返回
+    pub fn resolve(&self, root: &Value) -> Vec<Pattern> {
+        let mut patterns = Vec::new();
+        expand(&mut Vec::new(), &self.parts, root, &mut patterns);
+        patterns
+    }
+
+    /// `path` 与模式完全匹配
+    pub fn matches(&self, path: &Path) -> bool {
+        match_parts(&self.parts, &segment_texts(path), false)
+    }
+
+    /// `path` 本身或它的某个祖先与模式完全匹配
+    pub fn covers(&self, path: &Path) -> bool {
+        let texts = segment_texts(path);
+        (0..=texts.len()).any(|n| match_parts(&self.parts, &texts[..n], false))
+    }
+
+    /// `path` 的某个后代（或它本身）可能与模式匹配
+    pub fn may_match_below(&self, path: &Path) -> bool {
+        match_parts(&self.parts, &segment_texts(path), true)
+    }
+}
+
+/// 对每个模式调用 [`Pattern::resolve`]
+pub fn resolve_patterns(patterns: &[Pattern], root: &Value) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .flat_map(|pattern| pattern.resolve(root))
+        .collect()
+}
+
+/// 展开负数下标之前的部分：`done` 是已经展开的段，`rest` 是剩下的模式
+fn expand(
+    done: &mut Vec<PatternPart>,
+    rest: &[PatternPart],
+    value: &Value,
+    out: &mut Vec<Pattern>,
+) {
+    if rest.iter().all(|part| part.negative_index().is_none()) {
+        let pattern = Pattern {
+            parts: done.iter().chain(rest).cloned().collect(),
+        };
+        if !out.contains(&pattern) {
+            out.push(pattern);
+        }
+        return;
+    }
+    let Some((part, tail)) = rest.split_first() else {
+        return;
+    };
+    let mut descend = |text: String, child: &Value, rest: &[PatternPart]| {
+        done.push(PatternPart::Literal(text));
+        expand(done, rest, child, out);
+        done.pop();
+    };
+    if let Some(index) = part.negative_index() {
+        if let Some(items) = value.as_array() {
+            if let Some(i) = resolve_index(index, items.len()) {
+                descend(format!("[{}]", i), &items[i], tail);
+            }
+        }
+        return;
+    }
+    for (text, child) in children(value) {
+        match part {
+            // `**` 匹配零段的情况在下面单独处理
+            PatternPart::Any => descend(text, child, rest),
+            PatternPart::Glob(glob) if wildcard(glob, &text) => descend(text, child, tail),
+            PatternPart::Literal(literal) if *literal == text => descend(text, child, tail),
+            _ => {}
+        }
+    }
+    if matches!(part, PatternPart::Any) {
+        expand(done, tail, value, out);
+    }
+}
+
+/// 对象和数组的子值及其匹配文本
+fn children(value: &Value) -> Vec<(String, &Value)> {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, child)| (key.clone(), child))
+            .collect(),
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(i, child)| (format!("[{}]", i), child))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// 路径各段的匹配文本：键为原文，下标为 `[n]`
+fn segment_texts(path: &Path) -> Vec<String> {
+    path.segments
+        .iter()
+        .map(|segment| match segment {
+            Segment::Key(key) => key.clone(),
+            Segment::Index(index) => format!("[{}]", index),
+            Segment::Append => "[+]".to_string(),
+        })
+        .collect()
+}
+
+/// 按段匹配，`partial` 为真时路径用完而模式还有剩余也算匹配
+fn match_parts(parts: &[PatternPart], texts: &[String], partial: bool) -> bool {
+    match parts.split_first() {
+        None => texts.is_empty(),
+        Some((PatternPart::Any, rest)) => {
+            match_parts(rest, texts, partial)
+                || (!texts.is_empty() && match_parts(parts, &texts[1..], partial))
+        }
+        Some((PatternPart::Glob(glob), rest)) => match texts.split_first() {
+            None => partial,
+            Some((text, remaining)) => {
+                wildcard(glob, text) && match_parts(rest, remaining, partial)
+            }
+        },
+        Some((PatternPart::Literal(literal), rest)) => match texts.split_first() {
+            None => partial,
+            Some((text, remaining)) => literal == text && match_parts(rest, remaining, partial),
+        },
+    }
+}
+
+/// 段内通配：`*` 匹配任意个字符，`?` 匹配一个字符
+fn wildcard(glob: &str, text: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut g, mut t) = (0, 0);
+    let mut backtrack = None;
+    while t < text.len() {
+        match glob.get(g) {
+            Some('*') => {
+                backtrack = Some((g, t));
+                g += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                g += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((bg, bt)) => {
+                    g = bg + 1;
+                    t = bt + 1;
+                    backtrack = Some((bg, bt + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+/// 路径表达式解析器
+struct Parser<'a> {
+    input: &'a str,
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser {
+            input,
+            chars: input.char_indices().peekable(),
+        }
+    }
+
+    fn parse(mut self) -> Result<Path> {
+        let mut segments = Vec::new();
+
+        while let Some(&(pos, c)) = self.chars.peek() {
+            match c {
+                '[' => {
+                    self.chars.next();
+                    segments.push(self.index()?);
+                }
+                '.' if segments.is_empty() => return Err(self.error("路径不能以 '.' 开头")),
+                '.' => {
+                    self.chars.next();
+                    segments.push(Segment::Key(self.key()?));
+                }
+                _ if segments.is_empty() => segments.push(Segment::Key(self.key()?)),
+                _ => return Err(self.error(&t!("位置 {} 处应为 '.' 或 '['", pos))),
+            }
+        }
+
+        if let Some(i) = segments[..segments.len().saturating_sub(1)]
+            .iter()
+            .position(|s| *s == Segment::Append)
+        {
+            return Err(self.error(&t!("[+] 只能出现在最后一段（第 {} 段）", i + 1)));
+        }
+
+        Ok(Path { segments })
+    }
+
+    /// 解析一个键（普通键或带引号的键）
+    fn key(&mut self) -> Result<String> {
+        if let Some(&(_, '"')) = self.chars.peek() {
+            self.chars.next();
+            let mut key = String::new();
+            loop {
+                match self.chars.next() {
+                    Some((_, '"')) => return Ok(key),
+                    Some((_, '\\')) => match self.chars.next() {
+                        Some((_, c)) => key.push(c),
+                        None => return Err(self.error("转义符 '\\' 后缺少字符")),
+                    },
+                    Some((_, c)) => key.push(c),
+                    None => return Err(self.error("带引号的键缺少结束的 '\"'")),
+                }
+            }
+        }
+
+        let mut key = String::new();
+        while let Some(&(_, c)) = self.chars.peek() {
+            if c == '.' || c == '[' {
+                break;
+            }
+            if c == ']' || c == '"' {
+                return Err(self.error(&t!("键中出现了 '{}'，请用双引号括起来", c)));
+            }
+            key.push(c);
+            self.chars.next();
+        }
+        if key.is_empty() {
+            return Err(self.error("路径中存在空的段"));
+        }
+        Ok(key)
+    }
+
+    /// 解析 `[` 之后的下标，直到 `]`
+    fn index(&mut self) -> Result<Segment> {
+        let mut text = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, ']')) => break,
+                Some((_, c)) => text.push(c),
+                None => return Err(self.error("下标缺少结束的 ']'")),
+            }
+        }
+        match text.trim() {
+            "+" => Ok(Segment::Append),
+            n => n
+                .parse()
+                .map(Segment::Index)
+                .map_err(|_| self.error(&t!("无效的数组下标 '{}'", text))),
+        }
+    }
+
+    fn error(&self, message: &str) -> Error {
+        Error::InvalidPath {
+            path: self.input.to_string(),
+            message: tr(message).to_string(),
+        }
+    }
+}
+
+/// 把可能为负数的下标换算为实际位置
+pub(crate) fn resolve_index(index: i64, len: usize) -> Option<usize> {
+    let resolved = if index < 0 {
+        len.checked_sub(index.unsigned_abs() as usize)?
+    } else {
+        index as usize
+    };
+    (resolved < len).then_some(resolved)
+}
+
+/// 读取路径上的值
+pub fn get<'a>(value: &'a Value, path: &Path) -> Option<&'a Value> {
+    path.segments
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            Segment::Key(key) => current.as_object()?.get(key),
+            Segment::Index(index) => {
+                let items = current.as_array()?;
+                items.get(resolve_index(*index, items.len())?)
+            }
+            Segment::Append => None,
+        })
+}
+
+/// 取得路径上的值的可变引用
+fn get_mut<'a>(value: &'a mut Value, segments: &[Segment]) -> Option<&'a mut Value> {
+    segments
+        .iter()
+        .try_fold(value, |current, segment| match segment {
+            Segment::Key(key) => current.as_object_mut()?.get_mut(key),
+            Segment::Index(index) => {
+                let items = current.as_array_mut()?;
+                let i = resolve_index(*index, items.len())?;
+                items.get_mut(i)
+            }
+            Segment::Append => None,
+        })
+}
+
+/// 删除路径上的值并返回它
+pub fn remove(value: &mut Value, path: &Path) -> Option<Value> {
+    let (last, parents) = path.segments.split_last()?;
+    let parent = get_mut(value, parents)?;
+    match last {
+        Segment::Key(key) => parent.as_object_mut()?.shift_remove(key),
+        Segment::Index(index) => {
+            let items = parent.as_array_mut()?;
+            let i = resolve_index(*index, items.len())?;
+            Some(items.remove(i))
+        }
+        Segment::Append => None,
+    }
+}
+
+/// 在路径上写入值，缺少的父级会自动创建
+///
+/// 下一段是下标或 `[+]` 时创建数组，否则创建对象。
+/// 返回被替换掉的旧值；父级类型不匹配或下标越界时报错
+pub fn insert(value: &mut Value, path: &Path, new: Value) -> Result<Option<Value>> {
+    let Some((last, parents)) = path.segments.split_last() else {
+        return Ok(Some(std::mem::replace(value, new)));
+    };
+
+    let mut current = value;
+    for (depth, segment) in parents.iter().enumerate() {
+        let next = &path.segments[depth + 1];
+        let empty = || match next {
+            Segment::Key(_) => Value::Object(Map::new()),
+            Segment::Index(_) | Segment::Append => Value::Array(Vec::new()),
+        };
+        current = match segment {
+            Segment::Key(key) => as_object_mut(current, path, depth)?
+                .entry(key.clone())
+                .or_insert_with(empty),
+            Segment::Index(index) => {
+                let items = as_array_mut(current, path, depth)?;
+                let i = resolve_index(*index, items.len())
+                    .ok_or_else(|| out_of_range(path, depth + 1))?;
+                &mut items[i]
+            }
+            Segment::Append => unreachable!("解析时已保证 [+] 只出现在最后一段"),
+        };
+    }
+
+    let depth = parents.len();
+    match last {
+        Segment::Key(key) => Ok(as_object_mut(current, path, depth)?.insert(key.clone(), new)),
+        Segment::Index(index) => {
+            let items = as_array_mut(current, path, depth)?;
+            let i =
+                resolve_index(*index, items.len()).ok_or_else(|| out_of_range(path, depth + 1))?;
+            Ok(Some(std::mem::replace(&mut items[i], new)))
+        }
+        Segment::Append => {
+            as_array_mut(current, path, depth)?.push(new);
+            Ok(None)
+        }
+    }
+}
+
+/// 把 `from` 上的子树移动到 `to`，缺少的父级会自动创建
+///
+/// 目标路径已存在时报错，除非指定 `force`。下标都按移动前的数组解释：
+/// 在同一个数组中移动元素是调整顺序（`a[0]` → `a[2]` 让第一个元素排到第三个），不会覆盖其他元素；
+/// 目标在同一个数组后面元素的子路径下时，按移除后的位置调整下标
+pub fn relocate(value: &mut Value, from: &Path, to: &Path, force: bool) -> Result<()> {
+    if from.is_root() || to.is_root() {
+        return Err(Error::InvalidPath {
+            path: String::new(),
+            message: t!("不能移动根节点"),
+        });
+    }
+    if from.is_prefix_of(to) {
+        return Err(Error::InvalidPath {
+            path: to.to_string(),
+            message: t!("不能移动到 '{}' 自身或它的子路径下", from),
+        });
+    }
+    let not_found = || Error::PathNotFound {
+        path: from.to_string(),
+    };
+    // 删除 from 之后插入的路径
+    let mut target = to.clone();
+    if let Some((depth, i, j)) = same_array(value, from, to)? {
+        if to.segments.len() == depth + 1 {
+            let items = get_mut(value, &from.segments[..depth])
+                .and_then(Value::as_array_mut)
+                .ok_or_else(not_found)?;
+            let item = items.remove(i);
+            items.insert(j, item);
+            return Ok(());
+        }
+        if i == j {
+            return Err(Error::InvalidPath {
+                path: to.to_string(),
+                message: t!("不能移动到 '{}' 自身或它的子路径下", from),
+            });
+        }
+        target.segments[depth] = Segment::Index(if j > i { j - 1 } else { j } as i64);
+    }
+    if !force && get(value, to).is_some() {
+        return Err(Error::PathExists {
+            path: to.to_string(),
+        });
+    }
+    let subtree = remove(value, from).ok_or_else(not_found)?;
+    insert(value, &target, subtree)?;
+    Ok(())
+}
+
+/// `from` 是数组元素、`to` 经过同一个数组时，返回数组的深度和按移动前解析的两个下标
+fn same_array(value: &Value, from: &Path, to: &Path) -> Result<Option<(usize, usize, usize)>> {
+    let Some((Segment::Index(i), parents)) = from.segments.split_last() else {
+        return Ok(None);
+    };
+    let depth = parents.len();
+    let Some(Segment::Index(j)) = to.segments.get(depth) else {
+        return Ok(None);
+    };
+    if to.segments[..depth] != *parents {
+        return Ok(None);
+    }
+    let Some(items) = get(
+        value,
+        &Path {
+            segments: parents.to_vec(),
+        },
+    )
+    .and_then(Value::as_array) else {
+        return Ok(None);
+    };
+    let i = resolve_index(*i, items.len()).ok_or_else(|| Error::PathNotFound {
+        path: from.to_string(),
+    })?;
+    let j = resolve_index(*j, items.len()).ok_or_else(|| out_of_range(to, depth + 1))?;
+    Ok(Some((depth, i, j)))
+}
+
+/// 原地重命名路径最后一段的键，保持它在父对象中的位置
+pub fn rename(value: &mut Value, path: &Path, new_key: &str) -> Result<()> {
+    let not_found = || Error::PathNotFound {
+        path: path.to_string(),
+    };
+    let Some((Segment::Key(old_key), parents)) = path.segments.split_last() else {
+        return Err(Error::InvalidPath {
+            path: path.to_string(),
+            message: t!("只能重命名对象的键"),
+        });
+    };
+    let map = get_mut(value, parents)
+        .and_then(Value::as_object_mut)
+        .ok_or_else(not_found)?;
+    if !map.contains_key(old_key) {
+        return Err(not_found());
+    }
+    if old_key != new_key && map.contains_key(new_key) {
+        let mut target = path.clone();
+        if let Some(last) = target.segments.last_mut() {
+            *last = Segment::Key(new_key.to_string());
+        }
+        return Err(Error::PathExists {
+            path: target.to_string(),
+        });
+    }
+    *map = std::mem::take(map)
+        .into_iter()
+        .map(|(key, child)| {
+            if key == *old_key {
+                (new_key.to_string(), child)
+            } else {
+                (key, child)
+            }
+        })
+        .collect();
+    Ok(())
+}
+
+/// 前 `depth` 段组成的路径，用于错误提示
+fn prefix(path: &Path, depth: usize) -> String {
+    Path {
+        segments: path.segments[..depth].to_vec(),
+    }
+    .to_string()
+}
+
+/// 取得对象的可变引用，不是对象时报告是哪一段父级路径出了问题
+fn as_object_mut<'a>(
+    value: &'a mut Value,
+    path: &Path,
+    depth: usize,
+) -> Result<&'a mut Map<String, Value>> {
+    value
+        .as_object_mut()
+        .ok_or_else(|| type_mismatch(path, depth, "对象"))
+}
+
+/// 取得数组的可变引用，不是数组时报告是哪一段父级路径出了问题
+fn as_array_mut<'a>(value: &'a mut Value, path: &Path, depth: usize) -> Result<&'a mut Vec<Value>> {
+    value
+        .as_array_mut()
+        .ok_or_else(|| type_mismatch(path, depth, "数组"))
+}
+
+fn type_mismatch(path: &Path, depth: usize, expected: &str) -> Error {
+    Error::InvalidPath {
+        path: path.to_string(),
+        message: match depth {
+            0 => t!("根节点不是{}", tr(expected)),
+            _ => t!("'{}' 不是{}", prefix(path, depth), tr(expected)),
+        },
+    }
+}
+
+fn out_of_range(path: &Path, depth: usize) -> Error {
+    Error::InvalidPath {
+        path: path.to_string(),
+        message: t!("'{}' 下标越界", prefix(path, depth)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn segments(input: &str) -> Vec<Segment> {
+        Path::parse(input).unwrap().segments
+    }
+
+    #[test]
+    fn parses_keys_indices_and_append() {
+        assert_eq!(segments(""), vec![]);
+        assert_eq!(
+            segments("servers[0].host"),
+            vec![
+                Segment::Key("servers".into()),
+                Segment::Index(0),
+                Segment::Key("host".into()),
+            ]
+        );
+        assert_eq!(
+            segments("users[-1]"),
+            vec![Segment::Key("users".into()), Segment::Index(-1)]
+        );
+        assert_eq!(
+            segments("tags[+]"),
+            vec![Segment::Key("tags".into()), Segment::Append]
+        );
+        assert_eq!(
+            segments("[1][2]"),
+            vec![Segment::Index(1), Segment::Index(2)]
+        );
+        assert_eq!(
+            segments(r#""a.b"."c\"d""#),
+            vec![Segment::Key("a.b".into()), Segment::Key("c\"d".into())]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_paths() {
+        for input in [
+            ".a", "a.", "a..b", "a[", "a[x]", "a[+].b", "a]b", "\"a", "a[0]b",
+        ] {
+            assert!(Path::parse(input).is_err(), "{} 应该解析失败", input);
+        }
+    }
+
+    #[test]
+    fn patterns_reject_empty_segments_and_resolve_negative_indices() {
+        for input in ["a..b", "a.", "a.[0]"] {
+            assert!(Pattern::parse(input).is_err(), "{} 应该解析失败", input);
+        }
+
+        let value = json!({"groups": [{"items": [1, 2]}, {"items": [1, 2, 3]}]});
+        let patterns = Pattern::parse("groups[*].items[-1]")
+            .unwrap()
+            .resolve(&value);
+        let matches = |p: &str| {
+            let path = Path::parse(p).unwrap();
+            patterns.iter().any(|pattern| pattern.matches(&path))
+        };
+        assert!(matches("groups[0].items[1]") && matches("groups[1].items[2]"));
+        assert!(!matches("groups[0].items[2]") && !matches("groups[1].items[1]"));
+
+        let patterns = Pattern::parse("**[-1]").unwrap().resolve(&value);
+        assert!(patterns
+            .iter()
+            .any(|p| p.matches(&Path::parse("groups[1]").unwrap())));
+        assert!(patterns
+            .iter()
+            .any(|p| p.matches(&Path::parse("groups[1].items[2]").unwrap())));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        for input in [
+            "a.b",
+            "servers[0].host",
+            "users[-1]",
+            "tags[+]",
+            r#""a.b".c"#,
+        ] {
+            let path = Path::parse(input).unwrap();
+            assert_eq!(Path::parse(&path.to_string()).unwrap(), path);
+        }
+    }
+
+    #[test]
+    fn get_resolves_negative_indices() {
+        let value = json!({"users": [{"name": "a"}, {"name": "b"}]});
+        let get = |p: &str| get(&value, &Path::parse(p).unwrap()).cloned();
+        assert_eq!(get("users[0].name"), Some(json!("a")));
+        assert_eq!(get("users[-1].name"), Some(json!("b")));
+        assert_eq!(get("users[2]"), None);
+        assert_eq!(get("users[-3]"), None);
+        assert_eq!(get("users.name"), None);
+    }
+
+    #[test]
+    fn insert_creates_parents_and_appends() {
+        let mut value = json!({});
+        insert(&mut value, &Path::parse("a.tags[+]").unwrap(), json!("x")).unwrap();
+        insert(&mut value, &Path::parse("a.tags[+]").unwrap(), json!("y")).unwrap();
+        insert(&mut value, &Path::parse("a.tags[-1]").unwrap(), json!("z")).unwrap();
+        assert_eq!(value, json!({"a": {"tags": ["x", "z"]}}));
+
+        assert!(insert(&mut value, &Path::parse("a.tags[5]").unwrap(), json!(1)).is_err());
+        assert!(insert(&mut value, &Path::parse("a.tags.x").unwrap(), json!(1)).is_err());
+    }
+
+    #[test]
+    fn remove_handles_keys_and_indices() {
+        let mut value = json!({"a": [1, 2, 3], "b": 1});
+        assert_eq!(
+            remove(&mut value, &Path::parse("a[-1]").unwrap()),
+            Some(json!(3))
+        );
+        assert_eq!(
+            remove(&mut value, &Path::parse("b").unwrap()),
+            Some(json!(1))
+        );
+        assert_eq!(remove(&mut value, &Path::parse("c").unwrap()), None);
+        assert_eq!(value, json!({"a": [1, 2]}));
+    }
+
+    #[test]
+    fn relocate_within_one_array_uses_original_indices() {
+        let moved = |from: &str, to: &str, force: bool| {
+            let mut value = json!({"a": [1, 2, 3], "b": [{"x": 0}, {"x": 1}, {"x": 2}]});
+            relocate(
+                &mut value,
+                &Path::parse(from).unwrap(),
+                &Path::parse(to).unwrap(),
+                force,
+            )
+            .map(|_| value)
+        };
+        assert_eq!(moved("a[0]", "a[1]", false).unwrap()["a"], json!([2, 1, 3]));
+        assert_eq!(moved("a[0]", "a[-1]", true).unwrap()["a"], json!([2, 3, 1]));
+        assert_eq!(moved("a[2]", "a[0]", false).unwrap()["a"], json!([3, 1, 2]));
+        assert_eq!(
+            moved("b[0]", "b[2].y", false).unwrap()["b"],
+            json!([{"x": 1}, {"x": 2, "y": {"x": 0}}])
+        );
+        assert!(matches!(
+            moved("b[0]", "b[2].x", false),
+            Err(Error::PathExists { .. })
+        ));
+        assert!(moved("b[-3]", "b[0].y", false).is_err());
+        assert!(moved("a[0]", "a[3]", false).is_err());
+    }
+}
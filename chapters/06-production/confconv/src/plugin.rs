@@ -0,0 +1,128 @@
+//! 外部格式插件
+//!
+//! `PATH` 中名为 `confconv-format-<name>` 的可执行文件会被识别为格式插件，
+//! `<name>` 即格式名，也是按扩展名推断格式时使用的扩展名（如 `confconv-format-ucl` 对应 `.ucl`）。
+//!
+//! 协议：
+//! - `confconv-format-<name> parse`：从标准输入读取该格式的内容，向标准输出写出等价的 JSON
+//! - `confconv-format-<name> serialize`：从标准输入读取 JSON，向标准输出写出该格式的内容
+//!
+//! 格式的内容可以是二进制数据（如 MessagePack），JSON 则总是 UTF-8 文本
+//!
+//! 以非零状态退出表示失败，标准错误的内容会作为错误信息显示
+
+use crate::error::{Error, Result};
+use crate::process;
+use crate::t;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+/// 插件可执行文件名的前缀
+pub const PREFIX: &str = "confconv-format-";
+
+/// 内置格式的名称，同名的插件会被忽略
+const BUILTIN: &[&str] = &["json", "yaml", "yml", "toml", "ini", "cfg"];
+
+/// 一个已发现的格式插件
+#[derive(Debug, PartialEq)]
+pub struct Plugin {
+    /// 格式名
+    pub name: &'static str,
+    /// 可执行文件路径
+    pub path: PathBuf,
+}
+
+impl Plugin {
+    /// 调用插件把该格式的内容（可以是二进制数据）解析为中间表示
+    pub fn parse(&self, input: &[u8]) -> Result<Value> {
+        let output = self.run("parse", input)?;
+        serde_json::from_slice(&output).map_err(|e| Error::Parse {
+            format: self.name,
+            source: t!("插件输出的不是合法的 JSON: {}", e),
+        })
+    }
+
+    /// 调用插件把中间表示序列化为该格式的内容
+    pub fn serialize(&self, value: &Value) -> Result<Vec<u8>> {
+        self.run("serialize", value.to_string().as_bytes())
+    }
+
+    /// 以 `action` 为参数运行插件，把 `input` 写入标准输入，返回标准输出
+    fn run(&self, action: &str, input: &[u8]) -> Result<Vec<u8>> {
+        let mut command = Command::new(&self.path);
+        command.arg(action);
+        process::pipe(
+            command,
+            &format!("{} {}", self.path.display(), action),
+            input,
+        )
+    }
+}
+
+/// 在 `PATH` 中查找格式插件，结果按名称排序；同名的插件以 `PATH` 中靠前的为准
+pub fn discover() -> &'static [Plugin] {
+    static PLUGINS: OnceLock<Vec<Plugin>> = OnceLock::new();
+    PLUGINS.get_or_init(|| {
+        let mut found = BTreeMap::new();
+        let dirs = env::var_os("PATH")
+            .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+            .unwrap_or_default();
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                let path = entry.path();
+                let Some(name) = plugin_name(&path) else {
+                    continue;
+                };
+                if is_executable(&path) {
+                    found.entry(name).or_insert(path);
+                }
+            }
+        }
+        found
+            .into_iter()
+            .map(|(name, path)| Plugin {
+                // 插件在整个进程中只发现一次，名称需要与内置格式一样是 'static 的
+                name: Box::leak(name.into_boxed_str()),
+                path,
+            })
+            .collect()
+    })
+}
+
+/// 从可执行文件名中取出格式名，不是插件或与内置格式同名时返回 None
+///
+/// 格式名只能包含小写字母、数字、`-` 和 `_`，Windows 上忽略 `.exe` 等扩展名
+fn plugin_name(path: &Path) -> Option<String> {
+    let file_name = if cfg!(windows) {
+        path.file_stem()?
+    } else {
+        path.file_name()?
+    };
+    let name = file_name.to_str()?.strip_prefix(PREFIX)?;
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    (valid && !BUILTIN.contains(&name)).then(|| name.to_string())
+}
+
+/// 是否为可执行的普通文件
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+/// 是否为可执行的普通文件
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok_and(|m| m.is_file())
+}
@@ -0,0 +1,56 @@
+//! 外部命令
+//!
+//! 格式插件和 `--filter` 都通过标准输入输出与外部程序交换文本
+
+use crate::error::{Error, Result};
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// 通过系统 shell 执行一行命令（Unix 上为 `sh -c`，Windows 上为 `cmd /C`）
+pub fn shell(line: &str) -> Command {
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    command.arg(line);
+    command
+}
+
+/// 运行命令，把 `input` 写入标准输入，返回标准输出的原始字节
+///
+/// 以非零状态退出时，标准错误的内容（为空时为退出状态）作为错误信息；
+/// `display` 是错误信息中显示的命令
+pub fn pipe(mut command: Command, display: &str, input: &[u8]) -> Result<Vec<u8>> {
+    let error = |message: String| Error::ExternalCommand {
+        command: display.to_string(),
+        message,
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| error(e.to_string()))?;
+    // 在单独的线程中写入，避免子进程先写满输出管道时双方互相等待
+    let mut stdin = child.stdin.take().expect("已设置为管道");
+    let input = input.to_vec();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output().map_err(|e| error(e.to_string()))?;
+    // 子进程可能不读完输入就退出，这时写入失败不影响结果
+    let _ = writer.join();
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(error(match stderr.trim() {
+            "" => output.status.to_string(),
+            message => message.to_string(),
+        }));
+    }
+    Ok(output.stdout)
+}
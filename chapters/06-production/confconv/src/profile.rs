@@ -0,0 +1,172 @@
+//! 配置档
+//!
+//! 配置文件中的 `[profile.<名称>]` 段把一组参数打包起来，用全局参数 `--profile <名称>` 选用，
+//! 同一个仓库可以在 CI 中使用严格的设置，在本地使用宽松的设置：
+//!
+//! ```toml
+//! [profile.ci]
+//! sort = "lexical"
+//! indent = 4
+//! deny = ["warnings"]
+//!
+//! [profile.dev]
+//! allow = ["key-retyped"]
+//! ```
+//!
+//! 键是长参数名（不带 `--`），值可以是字符串、数字、布尔值或数组：
+//! - `true` 表示指定这个开关；`--sort` 这种取值可省略的参数需要写出取值
+//! - `false` 表示不指定
+//! - 数组用于可以多次指定的参数
+//!
+//! 配置档中的值只是默认值，命令行中显式指定的参数优先。
+//! 没有指定 `--profile` 时使用名为 `default` 的配置档（如果有）。
+//! 配置档可以包含多个命令的参数，当前命令没有的参数会被忽略，任何命令都没有的参数是错误
+
+use crate::error::{Error, Result};
+use crate::t;
+use clap::{Arg, ArgAction, Command};
+use std::collections::BTreeMap;
+
+/// 一个配置档：长参数名到值
+pub type Profile = BTreeMap<String, toml::Value>;
+
+/// 把配置档中的参数设为 `path` 所指子命令（如 `["kv", "export"]`）及全局参数的默认值
+///
+/// `source` 是配置文件的路径，用于错误信息
+pub fn apply(
+    mut command: Command,
+    path: &[&str],
+    name: &str,
+    profile: &Profile,
+    source: &str,
+) -> Result<Command> {
+    let invalid = |message: String| Error::Config {
+        path: source.to_string(),
+        message,
+    };
+    for (key, value) in profile {
+        let global = command
+            .get_arguments()
+            .find(|arg| arg.is_global_set() && arg.get_long() == Some(key.as_str()));
+        let local = subcommand(&command, path).and_then(|sub| {
+            sub.get_arguments()
+                .find(|arg| arg.get_long() == Some(key.as_str()))
+        });
+        let Some(arg) = global.or(local) else {
+            if !defined_anywhere(&command, key) {
+                return Err(invalid(t!(
+                    "配置档 '{}' 中的 '{}' 不是任何命令的参数",
+                    name,
+                    key
+                )));
+            }
+            continue;
+        };
+        let Some(values) = defaults(arg, value) else {
+            return Err(invalid(t!(
+                "配置档 '{}' 中 '{}' 的值 {} 无效",
+                name,
+                key,
+                value
+            )));
+        };
+        if values.is_empty() {
+            continue;
+        }
+        let id = arg.get_id().clone();
+        let set = move |arg: Arg| arg.default_values(values);
+        command = if global.is_some() {
+            command.mut_arg(id, set)
+        } else {
+            mut_subcommand(command, path, &|sub: Command| {
+                sub.mut_arg(id.clone(), set.clone())
+            })
+        };
+    }
+    Ok(command)
+}
+
+/// 按路径找到子命令
+fn subcommand<'a>(command: &'a Command, path: &[&str]) -> Option<&'a Command> {
+    path.iter()
+        .try_fold(command, |command, name| command.find_subcommand(name))
+}
+
+/// 修改路径所指的子命令
+fn mut_subcommand(command: Command, path: &[&str], f: &dyn Fn(Command) -> Command) -> Command {
+    match path.split_first() {
+        None => f(command),
+        Some((name, rest)) => command.mut_subcommand(name, |sub| mut_subcommand(sub, rest, f)),
+    }
+}
+
+/// 是否有任何命令接受这个长参数
+fn defined_anywhere(command: &Command, long: &str) -> bool {
+    command
+        .get_arguments()
+        .any(|arg| arg.get_long() == Some(long))
+        || command
+            .get_subcommands()
+            .any(|sub| defined_anywhere(sub, long))
+}
+
+/// 配置档中的值对应的默认值，空表示不设置，None 表示值的类型不适用于这个参数
+fn defaults(arg: &Arg, value: &toml::Value) -> Option<Vec<&'static str>> {
+    let scalar = |value: &toml::Value| match value {
+        toml::Value::String(text) => Some(text.clone()),
+        toml::Value::Integer(number) => Some(number.to_string()),
+        toml::Value::Float(number) => Some(number.to_string()),
+        _ => None,
+    };
+    let values = match value {
+        toml::Value::Boolean(false) => Vec::new(),
+        toml::Value::Boolean(true) => match arg.get_action() {
+            ArgAction::SetTrue => vec!["true".to_string()],
+            ArgAction::Count => vec!["1".to_string()],
+            _ => return None,
+        },
+        toml::Value::Array(items) if matches!(arg.get_action(), ArgAction::Append) => {
+            items.iter().map(scalar).collect::<Option<_>>()?
+        }
+        value if arg.get_action().takes_values() => vec![scalar(value)?],
+        _ => return None,
+    };
+    // 默认值需要 'static 的字符串；配置档只在启动时应用一次
+    Some(values.into_iter().map(|value| &*value.leak()).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::CommandFactory;
+
+    fn parse(profile: &str, args: &[&str]) -> Result<clap::ArgMatches> {
+        let profile: Profile = toml::from_str(profile).unwrap();
+        let path = [args[1]];
+        let command = apply(Cli::command(), &path, "ci", &profile, "config.toml")?;
+        Ok(command.try_get_matches_from(args).unwrap())
+    }
+
+    #[test]
+    fn profile_values_are_defaults() {
+        let profile = "indent = 4\nsort = \"lexical\"\ndeny = [\"warnings\"]\nstamp = true";
+        let matches = parse(profile, &["confconv", "format", "a.json"]).unwrap();
+        let (_, format) = matches.subcommand().unwrap();
+        assert_eq!(format.get_one::<u8>("indent"), Some(&4));
+        assert_eq!(format.get_raw("sort").unwrap().next().unwrap(), "lexical");
+        let deny: Vec<&String> = format.get_many("deny").unwrap().collect();
+        assert_eq!(deny, ["warnings"]);
+
+        let matches = parse(profile, &["confconv", "format", "a.json", "--indent", "2"]).unwrap();
+        let (_, format) = matches.subcommand().unwrap();
+        assert_eq!(format.get_one::<u8>("indent"), Some(&2));
+    }
+
+    #[test]
+    fn unknown_keys_are_errors() {
+        assert!(parse("no-such-flag = true", &["confconv", "format", "a.json"]).is_err());
+        assert!(parse("indent = true", &["confconv", "format", "a.json"]).is_err());
+        assert!(parse("sort = true", &["confconv", "format", "a.json"]).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! 对象存储输入输出
+//!
+//! 输入和 `-o` 可以是 `s3://bucket/key` 或 `gs://bucket/key`，不需要先下载到临时文件。
+//! 凭据按各云平台的标准方式查找：
+//! - S3：`AWS_ACCESS_KEY_ID` 等环境变量、Web Identity 令牌、EC2/ECS 实例元数据
+//! - GCS：`GOOGLE_APPLICATION_CREDENTIALS`、gcloud 应用默认凭据、GCE 实例元数据
+//!
+//! 需要启用 `cloud` 功能
+
+use crate::error::{Error, Result};
+
+/// 支持的 URI 前缀
+const SCHEMES: &[&str] = &["s3://", "gs://"];
+
+/// 是否是对象存储的 URI
+pub fn is_remote(path: &str) -> bool {
+    SCHEMES.iter().any(|scheme| path.starts_with(scheme))
+}
+
+/// 读取对象的全部内容
+#[cfg(feature = "cloud")]
+pub fn read(uri: &str) -> Result<Vec<u8>> {
+    let (store, path) = open(uri).map_err(|e| read_error(uri, e))?;
+    runtime(uri)?
+        .block_on(async {
+            let result = store.get(&path).await?;
+            result.bytes().await
+        })
+        .map(|bytes| bytes.to_vec())
+        .map_err(|e| read_error(uri, e))
+}
+
+/// 写入（或覆盖）对象
+#[cfg(feature = "cloud")]
+pub fn write(uri: &str, content: &[u8]) -> Result<()> {
+    let (store, path) = open(uri).map_err(|e| write_error(uri, e))?;
+    let payload = object_store::PutPayload::from(content.to_vec());
+    runtime(uri)?
+        .block_on(store.put(&path, payload))
+        .map(|_| ())
+        .map_err(|e| write_error(uri, e))
+}
+
+/// 按 URI 创建对应平台的客户端，返回客户端和对象路径
+#[cfg(feature = "cloud")]
+fn open(
+    uri: &str,
+) -> object_store::Result<(Box<dyn object_store::ObjectStore>, object_store::path::Path)> {
+    use object_store::aws::AmazonS3Builder;
+    use object_store::gcp::GoogleCloudStorageBuilder;
+
+    let (scheme, rest) = uri.split_once("://").unwrap_or_default();
+    let key = rest.split_once('/').map_or("", |(_, key)| key);
+    let store: Box<dyn object_store::ObjectStore> = match scheme {
+        "s3" => Box::new(AmazonS3Builder::from_env().with_url(uri).build()?),
+        _ => Box::new(
+            GoogleCloudStorageBuilder::from_env()
+                .with_url(uri)
+                .build()?,
+        ),
+    };
+    Ok((store, object_store::path::Path::from(key)))
+}
+
+/// 执行单个请求用的运行时
+#[cfg(feature = "cloud")]
+fn runtime(uri: &str) -> Result<tokio::runtime::Runtime> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| Error::FileRead {
+            path: uri.to_string(),
+            source: e,
+        })
+}
+
+#[cfg(feature = "cloud")]
+fn read_error(uri: &str, error: object_store::Error) -> Error {
+    Error::FileRead {
+        path: uri.to_string(),
+        source: std::io::Error::other(error.to_string()),
+    }
+}
+
+#[cfg(feature = "cloud")]
+fn write_error(uri: &str, error: object_store::Error) -> Error {
+    Error::FileWrite {
+        path: uri.to_string(),
+        source: std::io::Error::other(error.to_string()),
+    }
+}
+
+/// 未启用 `cloud` 功能时无法访问对象存储
+#[cfg(not(feature = "cloud"))]
+pub fn read(uri: &str) -> Result<Vec<u8>> {
+    Err(Error::FileRead {
+        path: uri.to_string(),
+        source: std::io::Error::other(disabled()),
+    })
+}
+
+/// 未启用 `cloud` 功能时无法访问对象存储
+#[cfg(not(feature = "cloud"))]
+pub fn write(uri: &str, _content: &[u8]) -> Result<()> {
+    Err(Error::FileWrite {
+        path: uri.to_string(),
+        source: std::io::Error::other(disabled()),
+    })
+}
+
+#[cfg(not(feature = "cloud"))]
+fn disabled() -> String {
+    crate::t!("编译时未启用 cloud 功能")
+}
@@ -0,0 +1,392 @@
+//! 报告输出
+//!
+//! diff 和 lint 默认每个问题输出一行文本。`--report-format html` 改为输出一个独立的 HTML 文件
+//! （样式内嵌，不引用外部资源），可以作为 CI 的产物交给不看终端的评审者：
+//! - diff：差异按路径组成可折叠的树，新增、删除、修改分别用绿、红、黄色标出
+//! - lint：每个文件一个可折叠的段落，列出问题的级别、规则、位置和说明
+//!
+//! validate、lint 和 test 还支持 `--report-format junit`，输出 JUnit 风格的 XML，
+//! Jenkins、GitLab 等 CI 系统可以直接在测试页面中显示每个文件（test 是每条断言）的结果：
+//! 有错误的文件是失败的测试用例，警告写在用例的 `<system-out>` 中。
+//!
+//! `-f/--format` 已经用于指定输入的格式，所以报告的格式用单独的参数
+
+use crate::diagnostic::{self, Diagnostic, Severity};
+use crate::diff::Change;
+use crate::i18n::tr;
+use crate::path::{Path, Segment};
+use crate::t;
+use clap::builder::{PossibleValuesParser, TypedValueParser};
+use clap::ValueEnum;
+use serde_json::Value;
+use std::fmt::Write;
+
+/// 报告的格式
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    /// 每个问题一行文本
+    #[default]
+    #[value(help = tr("每个问题一行文本"))]
+    Text,
+    /// 独立的 HTML 文件
+    #[value(help = tr("独立的 HTML 文件（可折叠的树，差异着色）"))]
+    Html,
+    /// JUnit 风格的 XML
+    #[value(help = tr("JUnit 风格的 XML，供 CI 在测试页面中显示"))]
+    Junit,
+}
+
+/// 只接受 `formats` 的命令行参数解析器，帮助中也只列出这些格式
+pub fn parser(formats: &'static [ReportFormat]) -> impl TypedValueParser<Value = ReportFormat> {
+    PossibleValuesParser::new(formats.iter().filter_map(ValueEnum::to_possible_value))
+        .map(|name| ReportFormat::from_str(&name, false).expect("只列出了已有的格式"))
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2em;color:#24292f}\
+h1{font-size:1.4em}.summary{color:#57606a}\
+details{margin-left:1.2em}summary{cursor:pointer;font-family:monospace}\
+.count{color:#57606a;font-size:.85em;margin-left:.5em}\
+.change{font-family:monospace;white-space:pre-wrap;margin-left:1.2em;padding:1px 4px}\
+.added{background:#e6ffec;color:#116329}.removed{background:#ffebe9;color:#82071e}\
+.changed{background:#fff8c5;color:#6f4e00}\
+table{border-collapse:collapse;margin:.5em 0 1em 1.2em}\
+td,th{border:1px solid #d0d7de;padding:2px 8px;text-align:left;vertical-align:top}\
+.error{color:#cf222e;font-weight:bold}.warning{color:#9a6700;font-weight:bold}.note{color:#0969da}";
+
+/// 转义 HTML 和 XML 中的特殊字符
+pub fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// 完整的 HTML 文件
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>{STYLE}</style>\n</head>\n<body>\n<h1>{title}</h1>\n{body}</body>\n</html>\n",
+        title = escape(title),
+    )
+}
+
+/// 差异树的一个节点
+#[derive(Default)]
+struct Node<'c, 'v> {
+    /// 子节点，按首次出现的顺序
+    children: Vec<(String, Node<'c, 'v>)>,
+    /// 正好位于这个路径的差异
+    changes: Vec<&'c Change<'v>>,
+}
+
+impl<'c, 'v> Node<'c, 'v> {
+    fn insert(&mut self, segments: &[Segment], change: &'c Change<'v>) {
+        let Some((first, rest)) = segments.split_first() else {
+            self.changes.push(change);
+            return;
+        };
+        let label = match first {
+            Segment::Key(key) => key.clone(),
+            Segment::Index(index) => format!("[{}]", index),
+            Segment::Append => "[+]".to_string(),
+        };
+        let position = match self.children.iter().position(|(name, _)| *name == label) {
+            Some(position) => position,
+            None => {
+                self.children.push((label, Node::default()));
+                self.children.len() - 1
+            }
+        };
+        self.children[position].1.insert(rest, change);
+    }
+
+    /// 子树中的差异数
+    fn count(&self) -> usize {
+        self.changes.len()
+            + self
+                .children
+                .iter()
+                .map(|(_, node)| node.count())
+                .sum::<usize>()
+    }
+
+    fn render(&self, out: &mut String) {
+        for change in &self.changes {
+            let compact = |value: &Value| escape(&serde_json::to_string(value).unwrap_or_default());
+            let (class, text) = match change {
+                Change::Added { value, .. } => ("added", format!("+ {}", compact(value))),
+                Change::Removed { value, .. } => ("removed", format!("- {}", compact(value))),
+                Change::Changed { old, new, .. } => {
+                    ("changed", format!("~ {} → {}", compact(old), compact(new)))
+                }
+            };
+            let _ = writeln!(out, "<div class=\"change {}\">{}</div>", class, text);
+        }
+        for (label, node) in &self.children {
+            let _ = writeln!(
+                out,
+                "<details open><summary>{}<span class=\"count\">{}</span></summary>",
+                escape(label),
+                node.count()
+            );
+            node.render(out);
+            out.push_str("</details>\n");
+        }
+    }
+}
+
+/// diff 的 HTML 报告，`left` 和 `right` 是两边的文件名
+pub fn diff_html(left: &str, right: &str, changes: &[Change]) -> String {
+    let mut root = Node::default();
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+    for change in changes {
+        let path: &Path = match change {
+            Change::Added { path, .. } => {
+                added += 1;
+                path
+            }
+            Change::Removed { path, .. } => {
+                removed += 1;
+                path
+            }
+            Change::Changed { path, .. } => {
+                changed += 1;
+                path
+            }
+        };
+        root.insert(path.segments(), change);
+    }
+
+    let mut body = format!(
+        "<p class=\"summary\">{} → {}</p>\n<p class=\"summary\">{}</p>\n",
+        escape(left),
+        escape(right),
+        escape(&match changes.len() {
+            0 => t!("没有差异"),
+            total => t!(
+                "{} 处差异：{} 处新增，{} 处删除，{} 处修改",
+                total,
+                added,
+                removed,
+                changed
+            ),
+        })
+    );
+    root.render(&mut body);
+    page(&t!("confconv 比较报告"), &body)
+}
+
+/// lint 的 HTML 报告，`files` 是每个文件和它的问题
+pub fn lint_html(files: &[(String, Vec<Diagnostic>)]) -> String {
+    let count = |severity: Severity, diagnostics: &[Diagnostic]| {
+        diagnostics
+            .iter()
+            .filter(|d| d.severity == severity)
+            .count()
+    };
+    let all: Vec<&Diagnostic> = files.iter().flat_map(|(_, d)| d).collect();
+    let errors = all.iter().filter(|d| d.severity == Severity::Error).count();
+    let warnings = all
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .count();
+    let mut body = format!(
+        "<p class=\"summary\">{}</p>\n",
+        escape(&t!(
+            "检查了 {} 个文件：{} 个错误，{} 个警告",
+            files.len(),
+            errors,
+            warnings
+        ))
+    );
+    for (file, diagnostics) in files {
+        let summary = match diagnostics.len() {
+            0 => t!("没有问题"),
+            _ => t!(
+                "{} 个错误，{} 个警告",
+                count(Severity::Error, diagnostics),
+                count(Severity::Warning, diagnostics)
+            ),
+        };
+        // 没有问题的文件默认折叠
+        let open = if diagnostics.is_empty() { "" } else { " open" };
+        let _ = writeln!(
+            body,
+            "<details{}><summary>{}<span class=\"count\">{}</span></summary>",
+            open,
+            escape(file),
+            escape(&summary)
+        );
+        if !diagnostics.is_empty() {
+            let _ = writeln!(
+                body,
+                "<table>\n<tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>",
+                escape(tr("级别")),
+                escape(tr("规则")),
+                escape(tr("位置")),
+                escape(tr("说明"))
+            );
+            for diagnostic in diagnostics {
+                let (class, level) = match diagnostic.severity {
+                    Severity::Error => ("error", tr("错误")),
+                    Severity::Warning => ("warning", tr("警告")),
+                    Severity::Note => ("note", tr("提示")),
+                };
+                let location = diagnostic
+                    .location
+                    .map(|(line, column)| format!("{}:{}", line, column))
+                    .unwrap_or_default();
+                let _ = writeln!(
+                    body,
+                    "<tr><td class=\"{}\">{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    class,
+                    escape(level),
+                    escape(diagnostic.code),
+                    location,
+                    escape(&diagnostic.message)
+                );
+            }
+            body.push_str("</table>\n");
+        }
+        body.push_str("</details>\n");
+    }
+    page(&t!("confconv 检查报告"), &body)
+}
+
+/// JUnit 报告中的一个测试用例
+pub struct Case {
+    /// 用例名（文件名或断言）
+    pub name: String,
+    /// 失败的原因，为空表示通过
+    pub failures: Vec<String>,
+    /// 不影响结果的输出（警告等）
+    pub output: Vec<String>,
+}
+
+/// JUnit 风格的 XML 报告，`suite` 是测试套件名，同时用作每个用例的 classname
+pub fn junit(suite: &str, cases: &[Case]) -> String {
+    let failures = cases
+        .iter()
+        .filter(|case| !case.failures.is_empty())
+        .count();
+    let suite = escape(suite);
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"{suite}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\">",
+        cases.len()
+    );
+    let _ = writeln!(
+        out,
+        "  <testsuite name=\"{suite}\" tests=\"{}\" failures=\"{failures}\" errors=\"0\" skipped=\"0\">",
+        cases.len()
+    );
+    for case in cases {
+        let _ = write!(
+            out,
+            "    <testcase classname=\"{suite}\" name=\"{}\"",
+            escape(&case.name)
+        );
+        if case.failures.is_empty() && case.output.is_empty() {
+            out.push_str("/>\n");
+            continue;
+        }
+        out.push_str(">\n");
+        if let Some(first) = case.failures.first() {
+            let message = match case.failures.len() {
+                1 => first.clone(),
+                count => t!("{} 个问题，第一个: {}", count, first),
+            };
+            let _ = writeln!(
+                out,
+                "      <failure message=\"{}\">{}</failure>",
+                escape(&message),
+                escape(&case.failures.join("\n"))
+            );
+        }
+        if !case.output.is_empty() {
+            let _ = writeln!(
+                out,
+                "      <system-out>{}</system-out>",
+                escape(&case.output.join("\n"))
+            );
+        }
+        out.push_str("    </testcase>\n");
+    }
+    out.push_str("  </testsuite>\n</testsuites>\n");
+    out
+}
+
+/// lint 的 JUnit 报告，每个文件一个用例，错误级别的问题使用例失败
+pub fn lint_junit(files: &[(String, Vec<Diagnostic>)]) -> String {
+    let cases: Vec<Case> = files
+        .iter()
+        .map(|(file, diagnostics)| {
+            let mut case = Case {
+                name: file.clone(),
+                failures: Vec::new(),
+                output: Vec::new(),
+            };
+            for diagnostic in diagnostics {
+                let line = diagnostic::plain(file, diagnostic);
+                match diagnostic.severity {
+                    Severity::Error => case.failures.push(line),
+                    _ => case.output.push(line),
+                }
+            }
+            case
+        })
+        .collect();
+    junit("confconv.lint", &cases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diff::{self, Options};
+    use serde_json::json;
+
+    #[test]
+    fn diff_report_nests_changes_by_path() {
+        let old = json!({"server": {"port": 80, "host": "a<b"}});
+        let new = json!({"server": {"port": 81}, "debug": true});
+        let changes = diff::diff(&old, &new, &Options::default());
+        let html = diff_html("old.yaml", "new.yaml", &changes);
+        assert!(html.contains("<summary>server<span class=\"count\">2</span></summary>"));
+        assert!(html.contains("<div class=\"change changed\">~ 80 → 81</div>"));
+        assert!(html.contains("<div class=\"change removed\">- &quot;a&lt;b&quot;</div>"));
+        assert!(html.contains("<div class=\"change added\">+ true</div>"));
+    }
+
+    #[test]
+    fn junit_marks_cases_with_failures() {
+        let cases = [
+            Case {
+                name: "a.yaml".to_string(),
+                failures: Vec::new(),
+                output: Vec::new(),
+            },
+            Case {
+                name: "b&c.json".to_string(),
+                failures: vec!["bad <x>".to_string()],
+                output: vec!["note".to_string()],
+            },
+        ];
+        let xml = junit("confconv.validate", &cases);
+        assert!(xml.contains("<testsuite name=\"confconv.validate\" tests=\"2\" failures=\"1\""));
+        assert!(xml.contains("<testcase classname=\"confconv.validate\" name=\"a.yaml\"/>"));
+        assert!(xml.contains(
+            "<testcase classname=\"confconv.validate\" name=\"b&amp;c.json\">\n      \
+             <failure message=\"bad &lt;x&gt;\">bad &lt;x&gt;</failure>\n      \
+             <system-out>note</system-out>\n    </testcase>"
+        ));
+    }
+}
@@ -0,0 +1,194 @@
+//! 输出规则文件
+//!
+//! 合规要求（哪些路径不能以明文出现在输出中）集中写在一个规则文件里，便于评审。
+//! 规则文件可以是任何支持的格式，每条规则把一个路径模式（支持通配）对应到一种处理方式：
+//!
+//! ```yaml
+//! # 计算摘要时加在值前面的盐，可以省略
+//! salt: team-2024
+//! rules:
+//!   - path: "**.password"
+//!     action: redact    # 替换为 ***
+//!   - path: "users[*].email"
+//!     action: hash      # 替换为 sha256:<十六进制摘要>，相同的值得到相同的摘要
+//!   - path: db.dsn
+//!     action: encrypt   # 替换为加密命令输出的密文
+//! ```
+//!
+//! 用 `--rules FILE` 指定，或者在配置文件的 `[transform]` 段设置 `rules`，convert 和 format
+//! 每次都会自动应用。规则在脱敏（`--redact`）之后执行，一个值只按第一条匹配的规则处理。
+//! `encrypt` 把值（字符串是文本本身，其他值是 JSON 文本）写入用户配置中 `[transform]` 段的
+//! `encrypt` 命令的标准输入，用它的输出作为密文，例如 `age -r age1... -a`；
+//! 项目配置不能设置这个命令
+
+use crate::codec;
+use crate::error::{Error, Result};
+use crate::path::{Path, Pattern};
+use crate::process;
+use crate::t;
+use crate::transform::{self, Transform, REDACTED};
+use serde::Deserialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::path::PathBuf;
+
+/// 对匹配的值的处理方式
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Action {
+    /// 替换为 [`REDACTED`]
+    Redact,
+    /// 替换为 `sha256:` 加上十六进制摘要
+    Hash,
+    /// 替换为加密命令输出的密文
+    Encrypt,
+}
+
+/// 一条规则
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Rule {
+    path: String,
+    action: Action,
+}
+
+/// 规则文件的内容
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RulesFile {
+    #[serde(default)]
+    salt: String,
+    rules: Vec<Rule>,
+}
+
+/// 读取后的规则
+pub struct Rules {
+    rules: Vec<(Pattern, Action)>,
+    salt: String,
+    /// 加密命令
+    encrypt: Option<String>,
+}
+
+impl Rules {
+    /// 读取规则文件，`encrypt` 是加密命令；有 `encrypt` 规则而没有加密命令时报错
+    pub fn load(file: &std::path::Path, encrypt: Option<&str>) -> Result<Self> {
+        let source = file.display().to_string();
+        let invalid = |message: String| Error::Config {
+            path: source.clone(),
+            message,
+        };
+        let (value, _) = codec::read_value(&source, None)?;
+        let parsed: RulesFile =
+            serde_json::from_value(value).map_err(|e| invalid(e.to_string()))?;
+        let rules = parsed
+            .rules
+            .into_iter()
+            .map(|rule| Ok((Pattern::parse(&rule.path)?, rule.action)))
+            .collect::<Result<Vec<_>>>()
+            .map_err(|e| invalid(e.to_string()))?;
+        if encrypt.is_none() && rules.iter().any(|(_, action)| *action == Action::Encrypt) {
+            return Err(invalid(t!(
+                "有 encrypt 规则，但用户配置的 [transform] 段没有设置加密命令 encrypt"
+            )));
+        }
+        Ok(Rules {
+            rules,
+            salt: parsed.salt,
+            encrypt: encrypt.map(str::to_string),
+        })
+    }
+
+    /// 第一条与路径匹配的规则，`rules` 中的负数下标已经换算过
+    fn action(rules: &[(Pattern, Action)], path: &Path) -> Option<Action> {
+        rules
+            .iter()
+            .find(|(pattern, _)| pattern.matches(path))
+            .map(|(_, action)| *action)
+    }
+
+    /// 按规则处理一个值
+    fn protect(&self, value: &mut Value, action: Action) -> Result<()> {
+        let text = match &*value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        *value = Value::String(match action {
+            Action::Redact => REDACTED.to_string(),
+            Action::Hash => {
+                let digest = Sha256::digest(format!("{}{}", self.salt, text));
+                let hex: String = digest.iter().map(|b| format!("{:02x}", b)).collect();
+                format!("sha256:{}", hex)
+            }
+            Action::Encrypt => {
+                let command = self.encrypt.as_deref().unwrap_or_default();
+                let output = process::pipe(process::shell(command), command, text.as_bytes())?;
+                String::from_utf8_lossy(&output).trim_end().to_string()
+            }
+        });
+        Ok(())
+    }
+}
+
+impl Transform for Rules {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        let rules: Vec<(Pattern, Action)> = self
+            .rules
+            .iter()
+            .flat_map(|(pattern, action)| {
+                pattern
+                    .resolve(value)
+                    .into_iter()
+                    .map(|pattern| (pattern, *action))
+            })
+            .collect();
+        let patterns: Vec<Pattern> = rules.iter().map(|(p, _)| p.clone()).collect();
+        transform::for_each_match(value, &Path::default(), &patterns, &mut |value, path| {
+            match Self::action(&rules, path) {
+                Some(action) => self.protect(value, action),
+                None => Ok(()),
+            }
+        })
+    }
+}
+
+/// 执行时才读取的规则文件，见 [`Rules`]
+pub struct RulesStep {
+    /// 规则文件
+    pub file: PathBuf,
+    /// 加密命令
+    pub encrypt: Option<String>,
+}
+
+impl Transform for RulesStep {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        Rules::load(&self.file, self.encrypt.as_deref())?.apply(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn applies_first_matching_rule() {
+        let rules = Rules {
+            rules: vec![
+                (Pattern::parse("db.password").unwrap(), Action::Redact),
+                (Pattern::parse("**.password").unwrap(), Action::Hash),
+            ],
+            salt: String::new(),
+            encrypt: None,
+        };
+        let mut value = json!({"db": {"password": "x"}, "api": {"password": "x"}, "user": "a"});
+        rules.apply(&mut value).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "db": {"password": "***"},
+                "api": {"password": "sha256:2d711642b726b04401627ca9fbac32f5c8530fb1903cc4db02258717921a4881"},
+                "user": "a"
+            })
+        );
+    }
+}
@@ -0,0 +1,522 @@
+//! JSON Schema
+//!
+//! - 从样例配置推断 JSON Schema
+//! - 按 JSON Schema 生成示例配置
+//! - 按 JSON Schema 的 `default` 补全配置，或删去等于默认值的键
+
+use crate::i18n::tr;
+use crate::path::{Path, Segment};
+use clap::ValueEnum;
+use serde_json::{json, Map, Value};
+
+/// 推断的严格程度
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum Level {
+    /// 只给出宽泛的类型（整数也记为 number），不限制必填和取值
+    #[value(help = tr("只给出宽泛的类型（整数也记为 number），不限制必填和取值"))]
+    Loose,
+    /// 区分整数，在所有样例中都出现的键记为必填
+    #[value(help = tr("区分整数，在所有样例中都出现的键记为必填"))]
+    #[default]
+    Normal,
+    /// 所有出现过的键都必填，禁止额外的键，
+    /// 取值很少的字符串生成 enum，数字生成观察到的 minimum/maximum
+    #[value(help = tr("所有出现过的键都必填，禁止额外的键，取值很少的字符串生成 enum，数字生成观察到的 minimum/maximum"))]
+    Strict,
+}
+
+/// 字符串取值不超过这个数量时（strict）生成 enum
+const MAX_ENUM_VALUES: usize = 5;
+
+/// 从一组样例推断 Schema
+pub fn infer(samples: &[Value], level: Level) -> Value {
+    let mut shape = Shape::default();
+    for sample in samples {
+        shape.observe(sample);
+    }
+    let mut schema = Map::new();
+    schema.insert(
+        "$schema".to_string(),
+        json!("https://json-schema.org/draft/2020-12/schema"),
+    );
+    if let Value::Object(body) = shape.to_schema(level) {
+        schema.extend(body);
+    }
+    Value::Object(schema)
+}
+
+/// 某个位置上观察到的所有值的汇总
+#[derive(Default)]
+struct Shape {
+    /// 观察到的类型，按首次出现的顺序
+    types: Vec<&'static str>,
+    /// 作为对象出现的次数
+    objects: usize,
+    /// 对象的键，以及每个键出现的次数
+    properties: Vec<(String, usize, Shape)>,
+    /// 数组元素
+    items: Option<Box<Shape>>,
+    /// 出现过的字符串，超过上限后不再记录
+    strings: Vec<String>,
+    /// 字符串取值超过上限
+    many_strings: bool,
+    /// 观察到的数字范围
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+}
+
+impl Shape {
+    fn observe(&mut self, value: &Value) {
+        let kind = type_name(value);
+        if !self.types.contains(&kind) {
+            self.types.push(kind);
+        }
+        match value {
+            Value::Object(map) => {
+                self.objects += 1;
+                for (key, child) in map {
+                    match self.properties.iter_mut().find(|(k, _, _)| k == key) {
+                        Some((_, count, shape)) => {
+                            *count += 1;
+                            shape.observe(child);
+                        }
+                        None => {
+                            let mut shape = Shape::default();
+                            shape.observe(child);
+                            self.properties.push((key.clone(), 1, shape));
+                        }
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let shape = self.items.get_or_insert_with(Default::default);
+                for item in items {
+                    shape.observe(item);
+                }
+            }
+            Value::String(s) => {
+                if !self.many_strings && !self.strings.contains(s) {
+                    if self.strings.len() < MAX_ENUM_VALUES {
+                        self.strings.push(s.clone());
+                    } else {
+                        self.many_strings = true;
+                    }
+                }
+            }
+            Value::Number(n) => {
+                if let Some(n) = n.as_f64() {
+                    self.minimum = Some(self.minimum.map_or(n, |m| m.min(n)));
+                    self.maximum = Some(self.maximum.map_or(n, |m| m.max(n)));
+                }
+            }
+            Value::Bool(_) | Value::Null => {}
+        }
+    }
+
+    fn to_schema(&self, level: Level) -> Value {
+        let mut schema = Map::new();
+
+        let mut types: Vec<&str> = Vec::new();
+        for &kind in &self.types {
+            // 同时出现整数和小数，或 loose 模式下，整数并入 number
+            let kind =
+                if kind == "integer" && (level == Level::Loose || self.types.contains(&"number")) {
+                    "number"
+                } else {
+                    kind
+                };
+            if !types.contains(&kind) {
+                types.push(kind);
+            }
+        }
+        match types.as_slice() {
+            [] => {}
+            [kind] => {
+                schema.insert("type".to_string(), json!(kind));
+            }
+            kinds => {
+                schema.insert("type".to_string(), json!(kinds));
+            }
+        }
+
+        if self.objects > 0 {
+            let properties: Map<String, Value> = self
+                .properties
+                .iter()
+                .map(|(key, _, shape)| (key.clone(), shape.to_schema(level)))
+                .collect();
+            let required: Vec<&str> = self
+                .properties
+                .iter()
+                .filter(|(_, count, _)| match level {
+                    Level::Loose => false,
+                    Level::Normal => *count == self.objects,
+                    Level::Strict => true,
+                })
+                .map(|(key, _, _)| key.as_str())
+                .collect();
+            schema.insert("properties".to_string(), Value::Object(properties));
+            if !required.is_empty() {
+                schema.insert("required".to_string(), json!(required));
+            }
+            if level == Level::Strict {
+                schema.insert("additionalProperties".to_string(), json!(false));
+            }
+        }
+
+        if let Some(items) = &self.items {
+            if !items.types.is_empty() {
+                schema.insert("items".to_string(), items.to_schema(level));
+            }
+        }
+
+        if level == Level::Strict {
+            // 只有全是字符串时才生成 enum，混合类型的位置不限制取值
+            if types == ["string"] && !self.many_strings {
+                schema.insert("enum".to_string(), json!(self.strings));
+            }
+            if let (Some(min), Some(max)) = (self.minimum, self.maximum) {
+                schema.insert("minimum".to_string(), number(min));
+                schema.insert("maximum".to_string(), number(max));
+            }
+        }
+
+        Value::Object(schema)
+    }
+}
+
+/// `$ref` 展开的最大深度，防止递归定义无限展开
+const MAX_REF_DEPTH: usize = 16;
+
+/// 按 Schema 生成示例值
+///
+/// 依次取 `default`、`examples` 的第一项、`const`、`enum` 的第一项，
+/// 都没有时按类型（和 `format`）生成占位值；对象包含所有列出的属性
+pub fn example(schema: &Value) -> Value {
+    Example { root: schema }.generate(schema, 0)
+}
+
+struct Example<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Example<'a> {
+    fn generate(&self, schema: &'a Value, depth: usize) -> Value {
+        let Value::Object(node) = schema else {
+            return Value::Null;
+        };
+
+        if let Some(reference) = node.get("$ref").and_then(Value::as_str) {
+            return match self.resolve(reference) {
+                Some(target) if depth < MAX_REF_DEPTH => self.generate(target, depth + 1),
+                _ => Value::Null,
+            };
+        }
+
+        if let Some(value) = node
+            .get("default")
+            .or_else(|| node.get("examples").and_then(|e| e.get(0)))
+            .or_else(|| node.get("example"))
+            .or_else(|| node.get("const"))
+            .or_else(|| node.get("enum").and_then(|e| e.get(0)))
+        {
+            return value.clone();
+        }
+
+        if let Some(Value::Array(parts)) = node.get("allOf") {
+            let mut merged = Map::new();
+            for part in parts {
+                if let Value::Object(map) = self.generate(part, depth) {
+                    merged.extend(map);
+                }
+            }
+            if let Value::Object(own) = self.object(node, depth) {
+                merged.extend(own);
+            }
+            return Value::Object(merged);
+        }
+        for key in ["oneOf", "anyOf"] {
+            if let Some(first) = node.get(key).and_then(|v| v.get(0)) {
+                return self.generate(first, depth);
+            }
+        }
+
+        let kind = match node.get("type") {
+            Some(Value::String(kind)) => kind.as_str(),
+            // 多个类型时优先取非 null 的
+            Some(Value::Array(kinds)) => kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|k| *k != "null")
+                .unwrap_or("null"),
+            _ if node.contains_key("properties") => "object",
+            _ if node.contains_key("items") => "array",
+            _ => "null",
+        };
+
+        match kind {
+            "object" => self.object(node, depth),
+            "array" => match node.get("items") {
+                Some(items) => {
+                    let count = node
+                        .get("minItems")
+                        .and_then(Value::as_u64)
+                        .unwrap_or(1)
+                        .max(1);
+                    let item = self.generate(items, depth);
+                    Value::Array(vec![item; count.min(16) as usize])
+                }
+                None => json!([]),
+            },
+            "string" => json!(placeholder(node.get("format").and_then(Value::as_str))),
+            "integer" => json!(node.get("minimum").and_then(Value::as_i64).unwrap_or(0)),
+            "number" => node.get("minimum").cloned().unwrap_or(json!(0.0)),
+            "boolean" => json!(false),
+            _ => Value::Null,
+        }
+    }
+
+    /// 生成对象的所有属性
+    fn object(&self, node: &'a Map<String, Value>, depth: usize) -> Value {
+        let mut map = Map::new();
+        if let Some(Value::Object(properties)) = node.get("properties") {
+            for (key, schema) in properties {
+                map.insert(key.clone(), self.generate(schema, depth));
+            }
+        }
+        Value::Object(map)
+    }
+
+    /// 解析文档内部的引用，如 `#/$defs/server`
+    fn resolve(&self, reference: &str) -> Option<&'a Value> {
+        let pointer = reference.strip_prefix('#')?;
+        self.root.pointer(pointer)
+    }
+}
+
+/// 用 Schema 中的 `default` 补全缺少的键，返回补上的键
+///
+/// 缺少的键有 `default` 时取 `default`；没有 `default` 但属性本身是对象、
+/// 且其中有带默认值的键时补上一个只含这些键的对象。已有的对象和数组元素按
+/// `properties`、`items` 和 `allOf` 逐层补全；`oneOf`、`anyOf` 无法确定适用的分支，不处理
+pub fn apply_defaults(value: &mut Value, schema: &Value) -> Vec<Path> {
+    let mut filled = Vec::new();
+    Defaults { root: schema }.apply(value, schema, &Path::default(), 0, &mut filled);
+    filled
+}
+
+/// 删去值等于 Schema 中 `default` 的键，返回删去的键，与 [`apply_defaults`] 互逆
+///
+/// 对象中的键全部删去后，对象本身也删去；数组元素不删，只逐个处理其中的对象
+pub fn prune_defaults(value: &mut Value, schema: &Value) -> Vec<Path> {
+    let mut pruned = Vec::new();
+    Defaults { root: schema }.prune(value, schema, &Path::default(), 0, &mut pruned);
+    pruned
+}
+
+struct Defaults<'a> {
+    root: &'a Value,
+}
+
+impl<'a> Defaults<'a> {
+    fn apply(
+        &self,
+        value: &mut Value,
+        schema: &'a Value,
+        path: &Path,
+        depth: usize,
+        filled: &mut Vec<Path>,
+    ) {
+        let Some(node) = self.resolve(schema, depth) else {
+            return;
+        };
+        if let Some(Value::Array(parts)) = node.get("allOf") {
+            for part in parts {
+                self.apply(value, part, path, depth + 1, filled);
+            }
+        }
+        match value {
+            Value::Object(map) => {
+                let Some(Value::Object(properties)) = node.get("properties") else {
+                    return;
+                };
+                for (key, property) in properties {
+                    let path = path.join(Segment::Key(key.clone()));
+                    if let Some(child) = map.get_mut(key) {
+                        self.apply(child, property, &path, depth + 1, filled);
+                        continue;
+                    }
+                    let default = self.default(property, depth + 1);
+                    let mut child = default.cloned().unwrap_or_else(|| json!({}));
+                    let before = filled.len();
+                    self.apply(&mut child, property, &path, depth + 1, filled);
+                    // 没有默认值的对象只在其中补上了键时才加入；补上的键只记录最外层的
+                    if default.is_none() && filled.len() == before {
+                        continue;
+                    }
+                    filled.truncate(before);
+                    filled.push(path);
+                    map.insert(key.clone(), child);
+                }
+            }
+            Value::Array(items) => {
+                let Some(schema) = node.get("items") else {
+                    return;
+                };
+                for (index, item) in items.iter_mut().enumerate() {
+                    let path = path.join(Segment::Index(index as i64));
+                    self.apply(item, schema, &path, depth + 1, filled);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn prune(
+        &self,
+        value: &mut Value,
+        schema: &'a Value,
+        path: &Path,
+        depth: usize,
+        pruned: &mut Vec<Path>,
+    ) {
+        let Some(node) = self.resolve(schema, depth) else {
+            return;
+        };
+        if let Some(Value::Array(parts)) = node.get("allOf") {
+            for part in parts {
+                self.prune(value, part, path, depth + 1, pruned);
+            }
+        }
+        match value {
+            Value::Object(map) => {
+                let Some(Value::Object(properties)) = node.get("properties") else {
+                    return;
+                };
+                for (key, property) in properties {
+                    let Some(child) = map.get_mut(key) else {
+                        continue;
+                    };
+                    let path = path.join(Segment::Key(key.clone()));
+                    if self.default(property, depth + 1) == Some(&*child) {
+                        map.shift_remove(key);
+                        pruned.push(path);
+                        continue;
+                    }
+                    let before = pruned.len();
+                    self.prune(child, property, &path, depth + 1, pruned);
+                    if pruned.len() > before && child.as_object().is_some_and(Map::is_empty) {
+                        map.shift_remove(key);
+                        pruned.truncate(before);
+                        pruned.push(path);
+                    }
+                }
+            }
+            Value::Array(items) => {
+                let Some(schema) = node.get("items") else {
+                    return;
+                };
+                for (index, item) in items.iter_mut().enumerate() {
+                    let path = path.join(Segment::Index(index as i64));
+                    self.prune(item, schema, &path, depth + 1, pruned);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 展开 `$ref`，得到对象形式的 Schema
+    fn resolve(&self, schema: &'a Value, depth: usize) -> Option<&'a Map<String, Value>> {
+        let node = schema.as_object()?;
+        match node.get("$ref").and_then(Value::as_str) {
+            Some(reference) if depth < MAX_REF_DEPTH => {
+                let target = self.root.pointer(reference.strip_prefix('#')?)?;
+                self.resolve(target, depth + 1)
+            }
+            Some(_) => None,
+            None => Some(node),
+        }
+    }
+
+    /// 属性的默认值，属性本身的 `default` 优先于引用目标的
+    fn default(&self, schema: &'a Value, depth: usize) -> Option<&'a Value> {
+        schema
+            .get("default")
+            .or_else(|| self.resolve(schema, depth)?.get("default"))
+    }
+}
+
+/// 按 `format` 生成字符串占位值
+fn placeholder(format: Option<&str>) -> &'static str {
+    match format {
+        Some("email") => "user@example.com",
+        Some("uri" | "url" | "iri") => "https://example.com",
+        Some("hostname") => "example.com",
+        Some("ipv4") => "192.0.2.1",
+        Some("ipv6") => "2001:db8::1",
+        Some("date-time") => "2024-01-01T00:00:00Z",
+        Some("date") => "2024-01-01",
+        Some("time") => "00:00:00",
+        Some("duration") => "PT1H",
+        Some("uuid") => "00000000-0000-0000-0000-000000000000",
+        _ => "string",
+    }
+}
+
+/// JSON Schema 中的类型名
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// 整数值的边界输出为整数
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 1e15 {
+        json!(n as i64)
+    } else {
+        json!(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_and_prunes_defaults() {
+        let schema = json!({
+            "properties": {
+                "name": {"type": "string"},
+                "port": {"default": 80},
+                "tls": {"properties": {"enabled": {"default": false}}},
+                "log": {"properties": {"file": {"type": "string"}}},
+                "servers": {"items": {"$ref": "#/$defs/server"}}
+            },
+            "$defs": {"server": {"properties": {"weight": {"default": 1}}}}
+        });
+        let mut value = json!({"port": 8080, "servers": [{"weight": 2}, {}]});
+        let filled = apply_defaults(&mut value, &schema);
+        assert_eq!(
+            value,
+            json!({
+                "port": 8080,
+                "servers": [{"weight": 2}, {"weight": 1}],
+                "tls": {"enabled": false}
+            })
+        );
+        let filled: Vec<String> = filled.iter().map(Path::to_string).collect();
+        assert_eq!(filled, ["tls", "servers[1].weight"]);
+
+        let pruned = prune_defaults(&mut value, &schema);
+        assert_eq!(value, json!({"port": 8080, "servers": [{"weight": 2}, {}]}));
+        let pruned: Vec<String> = pruned.iter().map(Path::to_string).collect();
+        assert_eq!(pruned, ["tls", "servers[1].weight"]);
+    }
+}
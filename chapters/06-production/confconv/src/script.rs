@@ -0,0 +1,94 @@
+//! Rhai 变换脚本
+//!
+//! 脚本中的变量 `value` 是整个文档（对象映射为 Rhai 的 map，数组映射为 array），
+//! 执行结束时 `value` 的值就是变换结果，适合写条件性的改写：
+//!
+//! ```rhai
+//! if value.tls?.enabled == true {
+//!     value.port = value.tls.port;
+//!     value.remove("tls");
+//! }
+//! ```
+//!
+//! Rhai 的 map 按键排序，变换后已有的键恢复原来的顺序，新增的键排在后面。
+//! `print` 和 `debug` 的输出写到标准错误；执行的操作数有上限，避免死循环
+
+use crate::error::{Error, Result};
+use serde_json::Value;
+use std::path::Path;
+
+/// 单次执行最多的操作数
+#[cfg(feature = "script")]
+const MAX_OPERATIONS: u64 = 100_000_000;
+
+/// 用 `script` 处的 Rhai 脚本变换 `value`
+#[cfg(feature = "script")]
+pub fn transform(script: &Path, value: &mut Value) -> Result<()> {
+    use crate::t;
+    use crate::verbosity::{self, Verbosity};
+    use rhai::{Dynamic, Engine, Scope};
+
+    let error = |message: String| Error::Script {
+        script: script.display().to_string(),
+        message,
+    };
+
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.on_print(|text| eprintln!("{}", text));
+    // 脚本中的 debug() 只在 -vv 时输出
+    engine.on_debug(|text, _, position| {
+        if verbosity::enabled(Verbosity::Debug) {
+            eprintln!("[{}] {}", position, text);
+        }
+    });
+    let ast = engine
+        .compile_file(script.to_path_buf())
+        .map_err(|e| error(e.to_string()))?;
+
+    let mut scope = Scope::new();
+    let input = rhai::serde::to_dynamic(&*value).map_err(|e| error(e.to_string()))?;
+    scope.push("value", input);
+    engine
+        .run_ast_with_scope(&mut scope, &ast)
+        .map_err(|e| error(e.to_string()))?;
+    let output = scope
+        .get_value::<Dynamic>("value")
+        .ok_or_else(|| error(t!("脚本删除了变量 value")))?;
+    let mut result: Value = rhai::serde::from_dynamic(&output).map_err(|e| error(e.to_string()))?;
+    restore_order(&mut result, value);
+    *value = result;
+    Ok(())
+}
+
+/// 未启用 `script` 功能时无法运行脚本
+#[cfg(not(feature = "script"))]
+pub fn transform(script: &Path, _value: &mut Value) -> Result<()> {
+    Err(Error::Script {
+        script: script.display().to_string(),
+        message: crate::t!("编译时未启用 script 功能"),
+    })
+}
+
+/// 按 `original` 中键的顺序重排 `value` 中的对象，`original` 中没有的键排在后面
+#[cfg_attr(not(feature = "script"), allow(dead_code))]
+fn restore_order(value: &mut Value, original: &Value) {
+    match (value, original) {
+        (Value::Object(map), Value::Object(original)) => {
+            let mut rest = std::mem::take(map);
+            for (key, original) in original {
+                if let Some(mut child) = rest.shift_remove(key) {
+                    restore_order(&mut child, original);
+                    map.insert(key.clone(), child);
+                }
+            }
+            map.extend(rest);
+        }
+        (Value::Array(items), Value::Array(original)) => {
+            for (item, original) in items.iter_mut().zip(original) {
+                restore_order(item, original);
+            }
+        }
+        _ => {}
+    }
+}
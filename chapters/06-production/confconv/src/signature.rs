@@ -0,0 +1,154 @@
+//! 分离签名
+//!
+//! 对配置的规范形式（键排序后的紧凑 JSON）做 Ed25519 签名，
+//! 因此格式转换、重新排版、调整键顺序之后签名仍然有效。
+//!
+//! 密钥使用 PKCS#8 PEM 格式，可以用 OpenSSL 生成：
+//!
+//! ```text
+//! openssl genpkey -algorithm ed25519 -out key.pem
+//! openssl pkey -in key.pem -pubout -out key.pub.pem
+//! ```
+//!
+//! 签名文件是几行文本：
+//!
+//! ```text
+//! confconv-signature: 1
+//! algorithm: ed25519
+//! public-key: <32 字节公钥的十六进制>
+//! signature: <64 字节签名的十六进制>
+//! ```
+
+use crate::error::{Error, Result};
+use crate::i18n::tr;
+use crate::t;
+use crate::transform::{self, SortMode};
+use ed25519_dalek::pkcs8::{DecodePrivateKey, DecodePublicKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde_json::Value;
+
+/// 签名文件第一行
+const HEADER: &str = "confconv-signature: 1";
+
+/// 值的规范形式：所有对象的键按字典序排列，紧凑 JSON
+pub fn canonical(value: &Value) -> String {
+    let mut value = value.clone();
+    transform::order_keys(&mut value, &[], SortMode::Lexical);
+    value.to_string()
+}
+
+/// 用 PEM 私钥签名，返回签名文件的内容
+pub fn sign(value: &Value, private_key_pem: &str) -> Result<String> {
+    let key = SigningKey::from_pkcs8_pem(private_key_pem).map_err(|e| Error::Signature {
+        message: t!("无法读取私钥: {}", e),
+    })?;
+    let signature = key.sign(canonical(value).as_bytes());
+    Ok(format!(
+        "{}\nalgorithm: ed25519\npublic-key: {}\nsignature: {}\n",
+        HEADER,
+        to_hex(key.verifying_key().as_bytes()),
+        to_hex(&signature.to_bytes()),
+    ))
+}
+
+/// 用可信的公钥校验签名
+///
+/// 签名文件中的公钥必须就是 `public_key_pem`：任何人都能改动内容后用自己的密钥重新签名，
+/// 只用签名文件自带的公钥校验什么也证明不了
+pub fn verify(value: &Value, signature_file: &str, public_key_pem: &str) -> Result<()> {
+    let (embedded, signature) = parse_file(signature_file)?;
+    let trusted =
+        VerifyingKey::from_public_key_pem(public_key_pem).map_err(|e| Error::Signature {
+            message: t!("无法读取公钥: {}", e),
+        })?;
+    if trusted != embedded {
+        return Err(Error::Signature {
+            message: t!("签名不是由指定的公钥生成的"),
+        });
+    }
+    embedded
+        .verify(canonical(value).as_bytes(), &signature)
+        .map_err(|_| Error::Signature {
+            message: t!("签名与内容不匹配，内容可能已被修改"),
+        })
+}
+
+/// 解析签名文件，返回其中的公钥和签名
+fn parse_file(text: &str) -> Result<(VerifyingKey, Signature)> {
+    let invalid = |message: &str| Error::Signature {
+        message: t!("签名文件格式错误: {}", tr(message)),
+    };
+
+    let mut lines = text.lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return Err(invalid("缺少文件头"));
+    }
+    let (mut public_key, mut signature) = (None, None);
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim() {
+            "algorithm" if value != "ed25519" => {
+                return Err(invalid(&t!("不支持的算法 '{}'", value)));
+            }
+            "public-key" => public_key = Some(value),
+            "signature" => signature = Some(value),
+            _ => {}
+        }
+    }
+
+    let public_key: [u8; 32] = public_key
+        .and_then(from_hex)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("公钥缺失或无效"))?;
+    let signature: [u8; 64] = signature
+        .and_then(from_hex)
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("签名缺失或无效"))?;
+
+    let public_key = VerifyingKey::from_bytes(&public_key).map_err(|_| invalid("公钥无效"))?;
+    Ok((public_key, Signature::from_bytes(&signature)))
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::pkcs8::spki::der::pem::LineEnding;
+    use ed25519_dalek::pkcs8::{EncodePrivateKey, EncodePublicKey};
+    use serde_json::json;
+
+    #[test]
+    fn only_the_trusted_key_is_accepted() {
+        let key = |seed| SigningKey::from_bytes(&[seed; 32]);
+        let private_pem = |seed| key(seed).to_pkcs8_pem(LineEnding::LF).unwrap();
+        let trusted = key(1)
+            .verifying_key()
+            .to_public_key_pem(LineEnding::LF)
+            .unwrap();
+        let value = json!({"port": 8080});
+
+        let signed = sign(&value, &private_pem(1)).unwrap();
+        assert!(verify(&value, &signed, &trusted).is_ok());
+        assert!(verify(&json!({"port": 80}), &signed, &trusted).is_err());
+
+        // 改动内容后用别的密钥重新签名，签名本身有效，但不是可信的公钥
+        let forged = sign(&json!({"port": 80}), &private_pem(2)).unwrap();
+        assert!(verify(&json!({"port": 80}), &forged, &trusted).is_err());
+    }
+}
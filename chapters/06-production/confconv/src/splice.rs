@@ -0,0 +1,715 @@
+//! 按路径只改写文档的一部分
+//!
+//! `format --only PATH` 只重新格式化路径上的子树，文件的其余部分（包括注释和空行）逐字节保持不变，
+//! 用于在多人共用的大文件中尽量减小差异。做法是在原文中找到子树所占的区间，把它换成格式化后的文本：
+//! - JSON：扫描原文找到值的起止位置，格式化后的文本按值所在行的缩进对齐
+//! - YAML：从 libyml 的事件流中取得节点的起点，节点之后的下一个事件是终点；
+//!   区间末尾的空行和注释行留在原处
+//! - TOML：路径必须是用 `[表头]`（或 `[[表头]]` 的一个元素）写出的表，区间从表头到下一个
+//!   不属于这个表的表头，它的子表必须写在一起
+//!
+//! 子树中的注释和锚点与完整的 `format` 一样不保留。拼接后重新解析整个文档，内容与预期不同时
+//! （例如子树中的锚点在别处被引用，或者子树位于 YAML 的流式集合中）报错而不改动文件。
+//!
+//! `lint --fix` 用 [`rewrite_objects`] 只重新生成被修正的对象，被替换的区间中有注释、锚点
+//! 或 YAML 流式集合时报错，不会悄悄丢掉它们
+
+use crate::codec::{self, OutputOptions};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::path::{self, Path, Segment};
+use crate::t;
+use serde_json::Value;
+use serde_yml::libyml::parser::{Event, Parser};
+use std::borrow::Cow;
+use std::ops::Range;
+
+/// 把 `input` 中路径 `path` 上的子树换成 `value` 中同一路径上的值，按 `style` 格式化
+///
+/// `value` 是 `input` 解析并变换后的结果，子树以外的变换不生效
+pub fn format_at(
+    input: &str,
+    format: Format,
+    value: &Value,
+    path: &Path,
+    style: &OutputOptions,
+) -> Result<String> {
+    splice_at(input, format, value, path, style).map(|(output, _)| output)
+}
+
+/// 同 [`format_at`]，同时返回原文中被替换的区间
+fn splice_at(
+    input: &str,
+    format: Format,
+    value: &Value,
+    path: &Path,
+    style: &OutputOptions,
+) -> Result<(String, Range<usize>)> {
+    let not_found = || Error::PathNotFound {
+        path: path.to_string(),
+    };
+    let original = codec::parse(input, format)?;
+    let segments = absolute(&original, path).ok_or_else(not_found)?;
+    let subtree = path::get(value, path).ok_or_else(not_found)?;
+
+    let (range, replacement) = match format {
+        Format::Json => {
+            let range = json_span(input, &segments).ok_or_else(not_found)?;
+            let text = codec::serialize_with(subtree, format, style)?;
+            let indent = line_indent(input, range.start);
+            (range, indented(text.trim_end(), &indent, false))
+        }
+        Format::Yaml => yaml_splice(input, &segments, subtree, style)?.ok_or_else(not_found)?,
+        Format::Toml => toml_splice(input, &segments, subtree, path, style)?,
+        _ => {
+            return Err(Error::Convert {
+                message: t!("--only 只支持 JSON、YAML 和 TOML"),
+            })
+        }
+    };
+
+    let output = format!(
+        "{}{}{}",
+        &input[..range.start],
+        replacement,
+        &input[range.end..]
+    );
+    let mut expected = original;
+    path::insert(&mut expected, path, subtree.clone())?;
+    match codec::parse(&output, format) {
+        Ok(parsed) if parsed == expected => Ok((output, range)),
+        _ => Err(Error::Convert {
+            message: t!(
+                "无法只格式化 {}：拼接后文档的内容发生了变化（子树中的锚点可能在别处被引用，或者它位于流式集合中）",
+                path
+            ),
+        }),
+    }
+}
+
+/// 把 `input` 中 `paths` 上的对象换成 `value` 中同一路径上的对象，其余部分保持不变
+///
+/// `value` 与 `input` 的区别只能在这些对象中（对象本身的路径不变）；已经包含在其他路径中的路径不再单独处理。
+/// 路径是根、或者无法只替换这个对象时（例如 TOML 的内联表）重新生成整个文档。
+/// 被替换的区间中有注释或 YAML 锚点、别名、流式集合时报错
+pub fn rewrite_objects(
+    input: &str,
+    format: Format,
+    value: &Value,
+    paths: &[Path],
+    style: &OutputOptions,
+) -> Result<String> {
+    let mut outermost: Vec<&Path> = Vec::new();
+    for path in paths {
+        if !paths
+            .iter()
+            .any(|other| other != path && other.is_prefix_of(path))
+            && !outermost.contains(&path)
+        {
+            outermost.push(path);
+        }
+    }
+    let lossy = |path: &Path| Error::Convert {
+        message: t!(
+            "{} 中有注释、锚点或流式集合，修正后会丢失，没有修改文件",
+            if path.is_root() {
+                ".".to_string()
+            } else {
+                path.to_string()
+            }
+        ),
+    };
+    let whole = |input: &str| -> Result<String> {
+        if !preserves(input, format, 0..input.len()) {
+            return Err(lossy(&Path::default()));
+        }
+        codec::serialize_with(value, format, style)
+    };
+
+    let spliceable = matches!(format, Format::Json | Format::Yaml | Format::Toml);
+    if !spliceable || outermost.iter().any(|path| path.is_root()) {
+        return whole(input);
+    }
+    let mut output = input.to_string();
+    for path in outermost {
+        match splice_at(&output, format, value, path, style) {
+            Ok((spliced, range)) => {
+                if !preserves(&output, format, range) {
+                    return Err(lossy(path));
+                }
+                output = spliced;
+            }
+            Err(_) => return whole(input),
+        }
+    }
+    Ok(output)
+}
+
+/// 原文的区间中是否没有会在重新生成时丢失的注释、YAML 锚点、别名和流式集合
+fn preserves(text: &str, format: Format, range: Range<usize>) -> bool {
+    match format {
+        Format::Json => true,
+        Format::Yaml => yaml_preserves(text, range),
+        Format::Toml => !toml_has_comment(&text[range]),
+        Format::Ini => !text[range]
+            .lines()
+            .any(|line| line.trim_start().starts_with([';', '#'])),
+        _ => !format.capabilities().comments,
+    }
+}
+
+/// YAML 区间中没有注释、锚点、别名和流式集合；标量中的 `#` 不是注释
+fn yaml_preserves(text: &str, range: Range<usize>) -> bool {
+    let mut parser = Parser::new(Cow::Borrowed(text.as_bytes()));
+    let mut scalars = Vec::new();
+    loop {
+        let Ok((event, mark)) = parser.parse_next_event() else {
+            return false;
+        };
+        let at = mark.index() as usize;
+        let lossy = match &event {
+            Event::StreamEnd => break,
+            Event::Alias(_) => true,
+            Event::Scalar(scalar) => {
+                let end = at + scalar.repr.map_or(0, <[u8]>::len);
+                scalars.push(at..end);
+                scalar.anchor.is_some()
+            }
+            // 流式集合会被写成块状
+            Event::SequenceStart(start) => start.anchor.is_some() || text[at..].starts_with('['),
+            Event::MappingStart(start) => start.anchor.is_some() || text[at..].starts_with('{'),
+            _ => false,
+        };
+        if lossy && range.contains(&at) {
+            return false;
+        }
+    }
+    // `#` 在行首或空白之后才是注释
+    !text[range.clone()].char_indices().any(|(i, c)| {
+        let at = range.start + i;
+        c == '#'
+            && text[..at]
+                .chars()
+                .next_back()
+                .is_none_or(char::is_whitespace)
+            && !scalars.iter().any(|scalar| scalar.contains(&at))
+    })
+}
+
+/// TOML 文本中是否有注释（字符串外的 `#`）
+fn toml_has_comment(text: &str) -> bool {
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        rest = &rest[c.len_utf8()..];
+        match c {
+            '#' => return true,
+            '"' | '\'' => {
+                let triple = if c == '"' { "\"\"" } else { "''" };
+                if let Some(after) = rest.strip_prefix(triple) {
+                    let close = if c == '"' { "\"\"\"" } else { "'''" };
+                    rest = after.find(close).map_or("", |i| &after[i + 3..]);
+                    continue;
+                }
+                let mut chars = rest.char_indices();
+                let mut end = rest.len();
+                while let Some((i, ch)) = chars.next() {
+                    match ch {
+                        '\\' if c == '"' => {
+                            chars.next();
+                        }
+                        '\n' => {
+                            end = i;
+                            break;
+                        }
+                        _ if ch == c => {
+                            end = i + 1;
+                            break;
+                        }
+                        _ => {}
+                    }
+                }
+                rest = &rest[end..];
+            }
+            _ => {}
+        }
+    }
+    false
+}
+
+/// 把路径中的负数下标换算为实际位置，路径不存在时返回 None
+fn absolute(value: &Value, path: &Path) -> Option<Vec<Segment>> {
+    let mut current = value;
+    let mut segments = Vec::new();
+    for segment in path.segments() {
+        let segment = match segment {
+            Segment::Key(key) => {
+                current = current.as_object()?.get(key)?;
+                Segment::Key(key.clone())
+            }
+            Segment::Index(index) => {
+                let items = current.as_array()?;
+                let i = path::resolve_index(*index, items.len())?;
+                current = &items[i];
+                Segment::Index(i as i64)
+            }
+            Segment::Append => return None,
+        };
+        segments.push(segment);
+    }
+    Some(segments)
+}
+
+/// `text` 的第二行起每行加上 `indent`；`all` 为真时第一行也加
+fn indented(text: &str, indent: &str, all: bool) -> String {
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| {
+            if (i > 0 || all) && !line.is_empty() {
+                format!("{}{}", indent, line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// 位置 `at` 所在行开头的空白
+fn line_indent(text: &str, at: usize) -> String {
+    let start = text[..at].rfind('\n').map_or(0, |i| i + 1);
+    text[start..at]
+        .chars()
+        .take_while(|c| c.is_whitespace())
+        .collect()
+}
+
+/// 把区间末尾的空白、空行和注释行（`#` 开头）移出区间，但不早于 `start`
+fn trim_end(text: &str, start: usize, mut end: usize) -> usize {
+    loop {
+        end = start.max(text[..end].trim_end().len());
+        let line_start = text[..end].rfind('\n').map_or(0, |i| i + 1).max(start);
+        if line_start == end || !text[line_start..end].trim_start().starts_with('#') {
+            return end;
+        }
+        end = line_start;
+    }
+}
+
+/// JSON 中路径上的值在原文中的区间
+fn json_span(text: &str, segments: &[Segment]) -> Option<Range<usize>> {
+    let mut scanner = JsonScanner {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    scanner.skip_whitespace();
+    let mut start = scanner.pos;
+    for segment in segments {
+        scanner.pos = start;
+        let mut found = None;
+        match (scanner.bytes.get(start), segment) {
+            (Some(b'{'), Segment::Key(key)) => {
+                scanner.pos += 1;
+                loop {
+                    scanner.skip_whitespace();
+                    if scanner.bytes.get(scanner.pos) != Some(&b'"') {
+                        break;
+                    }
+                    let key_start = scanner.pos;
+                    scanner.skip_value();
+                    let name: String =
+                        serde_json::from_slice(&scanner.bytes[key_start..scanner.pos]).ok()?;
+                    scanner.skip_whitespace();
+                    scanner.pos += 1; // ':'
+                    scanner.skip_whitespace();
+                    // 重复的键以最后一个为准，与解析时相同
+                    if name == *key {
+                        found = Some(scanner.pos);
+                    }
+                    scanner.skip_value();
+                    scanner.skip_whitespace();
+                    if scanner.bytes.get(scanner.pos) != Some(&b',') {
+                        break;
+                    }
+                    scanner.pos += 1;
+                }
+            }
+            (Some(b'['), Segment::Index(index)) => {
+                scanner.pos += 1;
+                for i in 0..=*index {
+                    scanner.skip_whitespace();
+                    if i == *index {
+                        found = Some(scanner.pos);
+                        break;
+                    }
+                    scanner.skip_value();
+                    scanner.skip_whitespace();
+                    scanner.pos += 1; // ','
+                }
+            }
+            _ => {}
+        }
+        start = found?;
+    }
+    scanner.pos = start;
+    scanner.skip_value();
+    Some(start..scanner.pos)
+}
+
+/// 跳过 JSON 值的简单扫描器，只用于已经通过语法检查的文本
+struct JsonScanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl JsonScanner<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .bytes
+            .get(self.pos)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.pos += 1;
+        }
+    }
+
+    /// 跳过从当前位置开始的一个值
+    fn skip_value(&mut self) {
+        let mut depth = 0usize;
+        let mut in_string = false;
+        while let Some(&byte) = self.bytes.get(self.pos) {
+            if in_string {
+                match byte {
+                    b'\\' => self.pos += 1,
+                    b'"' => in_string = false,
+                    _ => {}
+                }
+                self.pos += 1;
+                if !in_string && depth == 0 {
+                    return;
+                }
+                continue;
+            }
+            match byte {
+                b'"' => in_string = true,
+                b'{' | b'[' => depth += 1,
+                b'}' | b']' if depth == 0 => return,
+                b'}' | b']' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        self.pos += 1;
+                        return;
+                    }
+                }
+                b',' if depth == 0 => return,
+                b if b.is_ascii_whitespace() && depth == 0 => return,
+                _ => {}
+            }
+            self.pos += 1;
+        }
+    }
+}
+
+/// YAML 中要替换的区间和替换后的文本，路径不存在时返回 None
+fn yaml_splice(
+    text: &str,
+    segments: &[Segment],
+    subtree: &Value,
+    style: &OutputOptions,
+) -> Result<Option<(Range<usize>, String)>> {
+    let Some(node) = yaml_node(text, segments) else {
+        return Ok(None);
+    };
+    let formatted = codec::serialize_with(subtree, Format::Yaml, style)?;
+    let formatted = formatted.trim_end();
+    let end = trim_end(text, node.start, node.end);
+    let result = match node.key {
+        // 映射的值：从冒号之后开始替换，集合换行后按键的列缩进（序列与键对齐，与 serde_yml 相同）
+        Some((key_end, column)) => {
+            let colon = key_end + text[key_end..].len() - text[key_end..].trim_start().len();
+            let start = match text[colon..].starts_with(':') {
+                true => colon + 1,
+                false => node.start,
+            };
+            let replacement = match subtree {
+                Value::Object(map) if !map.is_empty() => {
+                    format!("\n{}", indented(formatted, &" ".repeat(column + 2), true))
+                }
+                Value::Array(items) if !items.is_empty() => {
+                    format!("\n{}", indented(formatted, &" ".repeat(column), true))
+                }
+                _ => format!(" {}", indented(formatted, &" ".repeat(column), false)),
+            };
+            (start..end, replacement)
+        }
+        // 序列的元素：从节点开始替换，之后的行按节点的列缩进
+        None => (
+            node.start..end,
+            indented(formatted, &" ".repeat(node.column), false),
+        ),
+    };
+    Ok(Some(result))
+}
+
+/// YAML 节点在原文中的位置
+struct YamlNode {
+    /// 节点第一个事件的位置
+    start: usize,
+    /// 节点之后下一个事件的位置
+    end: usize,
+    /// 节点所在的列
+    column: usize,
+    /// 节点是映射的值时，键的结束位置和键所在的列
+    key: Option<(usize, usize)>,
+}
+
+/// 从事件流中找到路径上的节点
+fn yaml_node(text: &str, segments: &[Segment]) -> Option<YamlNode> {
+    enum Next {
+        Key,
+        Value(String, usize, usize),
+        Index(i64),
+    }
+    let mut parser = Parser::new(Cow::Borrowed(text.as_bytes()));
+    // 每层集合的下一个位置，以及从根到当前集合的路径
+    let mut stack: Vec<Next> = Vec::new();
+    let mut path: Vec<Segment> = Vec::new();
+    let mut found: Option<YamlNode> = None;
+    // 找到节点后记录它所在的集合深度，回到这个深度时节点结束
+    let mut depth = None;
+    loop {
+        let (event, mark) = parser.parse_next_event().ok()?;
+        let index = mark.index() as usize;
+        if let Some(node) = found.as_mut().filter(|_| depth == Some(stack.len())) {
+            node.end = index;
+            return found;
+        }
+        let is_key = matches!(stack.last(), Some(Next::Key));
+        if !is_key && found.is_none() {
+            if let Event::Scalar(_)
+            | Event::SequenceStart(_)
+            | Event::MappingStart(_)
+            | Event::Alias(_) = event
+            {
+                let mut here = path.clone();
+                let mut key = None;
+                match stack.last() {
+                    Some(Next::Value(name, key_end, column)) => {
+                        here.push(Segment::Key(name.clone()));
+                        key = Some((*key_end, *column));
+                    }
+                    Some(Next::Index(i)) => here.push(Segment::Index(*i)),
+                    _ => {}
+                }
+                if here == segments && !here.is_empty() {
+                    found = Some(YamlNode {
+                        start: index,
+                        end: text.len(),
+                        column: mark.column() as usize,
+                        key,
+                    });
+                    depth = Some(stack.len());
+                }
+            }
+        }
+        match event {
+            Event::StreamEnd => return found,
+            Event::SequenceStart(_) | Event::MappingStart(_) => {
+                match stack.last() {
+                    Some(Next::Value(name, ..)) => path.push(Segment::Key(name.clone())),
+                    Some(Next::Index(i)) => path.push(Segment::Index(*i)),
+                    _ => {}
+                }
+                stack.push(match event {
+                    Event::SequenceStart(_) => Next::Index(0),
+                    _ => Next::Key,
+                });
+                continue;
+            }
+            Event::SequenceEnd | Event::MappingEnd => {
+                stack.pop();
+                if !stack.is_empty() {
+                    path.pop();
+                }
+            }
+            Event::Scalar(scalar) if is_key => {
+                let name = String::from_utf8_lossy(&scalar.value).into_owned();
+                let key_end = index + scalar.repr.map_or(0, <[u8]>::len);
+                if let Some(next) = stack.last_mut() {
+                    *next = Next::Value(name, key_end, mark.column() as usize);
+                }
+                continue;
+            }
+            Event::Scalar(_) | Event::Alias(_) => {}
+            _ => continue,
+        }
+        // 读完一个值，移动到下一个位置
+        match stack.last_mut() {
+            Some(next @ Next::Value(..)) => *next = Next::Key,
+            Some(Next::Index(i)) => *i += 1,
+            _ => {}
+        }
+    }
+}
+
+/// TOML 中要替换的区间和替换后的文本
+fn toml_splice(
+    text: &str,
+    segments: &[Segment],
+    subtree: &Value,
+    path: &Path,
+    style: &OutputOptions,
+) -> Result<(Range<usize>, String)> {
+    let unsupported = || Error::Convert {
+        message: t!("TOML 中 --only 只能指定用 [表头] 写出的表: {}", path),
+    };
+    let document = toml_edit::ImDocument::parse(text).map_err(|e| Error::Parse {
+        format: "TOML",
+        source: e.to_string(),
+    })?;
+    let mut headers = Vec::new();
+    toml_headers(document.as_table(), &mut Vec::new(), &mut headers);
+
+    let start = headers
+        .iter()
+        .find(|(header, _)| header == segments)
+        .map(|(_, start)| *start)
+        .ok_or_else(unsupported)?;
+    let inside = |header: &Vec<Segment>| header.starts_with(segments);
+    let end = headers
+        .iter()
+        .filter(|(header, at)| *at > start && !inside(header))
+        .map(|(_, at)| *at)
+        .min()
+        .unwrap_or(text.len());
+    if headers
+        .iter()
+        .any(|(header, at)| inside(header) && (*at < start || *at >= end))
+    {
+        return Err(Error::Convert {
+            message: t!("{} 的子表没有写在一起，无法只格式化这个表", path),
+        });
+    }
+
+    // 把子树放回它的路径下再序列化，表头和 --toml-arrays 的路径都保持完整
+    let mut wrapped = subtree.clone();
+    for segment in segments.iter().rev() {
+        wrapped = match segment {
+            Segment::Key(key) => serde_json::json!({ key.as_str(): wrapped }),
+            _ => Value::Array(vec![wrapped]),
+        };
+    }
+    let formatted = codec::serialize_with(&wrapped, Format::Toml, style)?;
+    Ok((
+        start..trim_end(text, start, end),
+        formatted.trim_end().to_string(),
+    ))
+}
+
+/// 收集所有表头的路径和所在行的开始位置
+fn toml_headers(
+    table: &toml_edit::Table,
+    path: &mut Vec<Segment>,
+    headers: &mut Vec<(Vec<Segment>, usize)>,
+) {
+    for (key, item) in table.iter() {
+        path.push(Segment::Key(key.to_string()));
+        match item {
+            toml_edit::Item::Table(child) => {
+                if let Some(span) = child.span().filter(|_| !child.is_implicit()) {
+                    headers.push((path.clone(), span.start));
+                }
+                toml_headers(child, path, headers);
+            }
+            toml_edit::Item::ArrayOfTables(array) => {
+                for (i, child) in array.iter().enumerate() {
+                    path.push(Segment::Index(i as i64));
+                    if let Some(span) = child.span() {
+                        headers.push((path.clone(), span.start));
+                    }
+                    toml_headers(child, path, headers);
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn only(input: &str, format: Format, path: &str) -> String {
+        let value = codec::parse(input, format).unwrap();
+        let style = OutputOptions {
+            pretty: true,
+            indent: Some(2),
+            ..OutputOptions::default()
+        };
+        format_at(input, format, &value, &Path::parse(path).unwrap(), &style).unwrap()
+    }
+
+    #[test]
+    fn formats_only_the_subtree() {
+        let json = "{\"a\":   1,\n  \"b\": {\"x\":1,   \"y\":[1,2]}, \"c\": 2}";
+        assert_eq!(
+            only(json, Format::Json, "b"),
+            "{\"a\":   1,\n  \"b\": {\n    \"x\": 1,\n    \"y\": [\n      1,\n      2\n    ]\n  }, \"c\": 2}"
+        );
+
+        let yaml = "# top\na:   1\nb: {x: 1,   z: [1, 2]}\n# keep\nc:    2\nd:\n  -   {m: 1}\n";
+        assert_eq!(
+            only(yaml, Format::Yaml, "b"),
+            "# top\na:   1\nb:\n  x: 1\n  z:\n  - 1\n  - 2\n# keep\nc:    2\nd:\n  -   {m: 1}\n"
+        );
+        assert_eq!(
+            only(yaml, Format::Yaml, "d[0]"),
+            "# top\na:   1\nb: {x: 1,   z: [1, 2]}\n# keep\nc:    2\nd:\n  -   m: 1\n"
+        );
+
+        let toml = "a   =   1\n\n[b]\nx   = 1\n\n[b.c]\ny =   2\n\n# keep\n[d]\nz   = 3\n";
+        assert_eq!(
+            only(toml, Format::Toml, "b"),
+            "a   =   1\n\n[b]\nx = 1\n\n[b.c]\ny = 2\n\n# keep\n[d]\nz   = 3\n"
+        );
+    }
+
+    #[test]
+    fn rewrites_only_fixed_objects_and_refuses_lossy_ones() {
+        let style = OutputOptions {
+            pretty: true,
+            indent: Some(2),
+            ..OutputOptions::default()
+        };
+        let rewrite = |input: &str, format, fixed: &str, path: &str| {
+            let value: Value = serde_json::from_str(fixed).unwrap();
+            let paths = [Path::parse(path).unwrap()];
+            rewrite_objects(input, format, &value, &paths, &style)
+        };
+
+        let yaml = "# top\nbase: &b {x: 1}\nuse: *b\nsvc:\n  port: 1\n  host: \"a # b\"\n";
+        let fixed = r#"{"base": {"x": 1}, "use": {"x": 1}, "svc": {"host": "a # b", "port": 1}}"#;
+        assert_eq!(
+            rewrite(yaml, Format::Yaml, fixed, "svc").unwrap(),
+            "# top\nbase: &b {x: 1}\nuse: *b\nsvc:\n  host: 'a # b'\n  port: 1\n"
+        );
+        assert!(rewrite(yaml, Format::Yaml, fixed, "").is_err());
+        let commented = "svc:\n  z: 1  # last\n  y: 2\n";
+        assert!(rewrite(
+            commented,
+            Format::Yaml,
+            r#"{"svc": {"y": 2, "z": 1}}"#,
+            "svc"
+        )
+        .is_err());
+
+        let toml = "[a]\nz = \"#\"\ny = 1\n\n[b]\n# keep\nz = 1\ny = 2\n";
+        let fixed = r##"{"a": {"y": 1, "z": "#"}, "b": {"y": 2, "z": 1}}"##;
+        assert_eq!(
+            rewrite(toml, Format::Toml, fixed, "a").unwrap(),
+            "[a]\ny = 1\nz = \"#\"\n\n[b]\n# keep\nz = 1\ny = 2\n"
+        );
+        assert!(rewrite(toml, Format::Toml, fixed, "b").is_err());
+    }
+}
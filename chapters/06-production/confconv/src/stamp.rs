@@ -0,0 +1,62 @@
+//! 完整性标记
+//!
+//! 在生成的文件末尾追加一行 `# confconv: sha256=<摘要>` 注释，
+//! 摘要覆盖标记之前的全部内容，之后任何手工修改都能被 `verify` 发现。
+//! JSON 没有注释语法，不支持标记
+
+use crate::format::Format;
+use sha2::{Digest, Sha256};
+
+/// 标记行的前缀
+pub const PREFIX: &str = "# confconv: sha256=";
+
+/// 格式是否支持标记（需要有注释语法）
+pub fn supports(format: Format) -> bool {
+    format.capabilities().comments
+}
+
+/// 在内容末尾追加标记行
+pub fn apply(content: &str) -> String {
+    let mut stamped = content.to_string();
+    if !stamped.is_empty() && !stamped.ends_with('\n') {
+        stamped.push('\n');
+    }
+    let digest = sha256_hex(stamped.as_bytes());
+    stamped.push_str(PREFIX);
+    stamped.push_str(&digest);
+    stamped.push('\n');
+    stamped
+}
+
+/// 校验结果
+#[derive(Debug, PartialEq)]
+pub enum Status {
+    /// 内容与标记一致
+    Valid,
+    /// 文件末尾没有标记
+    Missing,
+    /// 内容在生成后被修改过
+    Modified,
+}
+
+/// 检查内容末尾的标记
+pub fn check(content: &str) -> Status {
+    let body = content.strip_suffix('\n').unwrap_or(content);
+    let (before, last) = match body.rfind('\n') {
+        Some(i) => (&content[..i + 1], &body[i + 1..]),
+        None => ("", body),
+    };
+    match last.strip_prefix(PREFIX) {
+        Some(expected) if expected.trim() == sha256_hex(before.as_bytes()) => Status::Valid,
+        Some(_) => Status::Modified,
+        None => Status::Missing,
+    }
+}
+
+/// SHA-256 摘要的十六进制文本
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,196 @@
+//! 终端宽度
+//!
+//! 输出到终端时按终端宽度排版：错误信息和诊断信息折行，差异中过长的值截断，
+//! 帮助信息按终端宽度展开。宽度依次取自：
+//! - 全局参数 `--term-width`（0 表示不折行也不截断），指定后即使不是终端也按这个宽度排版，方便脚本得到固定的输出
+//! - 环境变量 `COLUMNS`
+//! - 终端本身的大小
+//!
+//! 中日韩文字按两列计算
+
+use crate::color::Stream;
+use crate::pager;
+use std::env;
+use std::sync::OnceLock;
+
+/// 截断时用来代替被省略部分的字符
+const ELLIPSIS: char = '…';
+
+static WIDTH: OnceLock<Option<usize>> = OnceLock::new();
+
+/// 设置 `--term-width`，只有第一次调用有效
+pub fn init(width: Option<usize>) {
+    let _ = WIDTH.set(width);
+}
+
+/// 输出到 `stream` 时可用的宽度，None 表示不限制（不是终端，或者 `--term-width 0`）
+pub fn width(stream: Stream) -> Option<usize> {
+    if let Some(&Some(width)) = WIDTH.get() {
+        return Some(width).filter(|&width| width > 0);
+    }
+    if pager::is_terminal(stream) {
+        detect()
+    } else {
+        None
+    }
+}
+
+/// 检测终端宽度：先看 `COLUMNS`，再询问终端
+pub fn detect() -> Option<usize> {
+    env::var("COLUMNS")
+        .ok()
+        .and_then(|columns| columns.trim().parse().ok())
+        .filter(|&columns: &usize| columns > 0)
+        .or_else(terminal_width)
+}
+
+#[cfg(unix)]
+fn terminal_width() -> Option<usize> {
+    [libc::STDOUT_FILENO, libc::STDERR_FILENO, libc::STDIN_FILENO]
+        .into_iter()
+        .find_map(|fd| {
+            let mut size = libc::winsize {
+                ws_row: 0,
+                ws_col: 0,
+                ws_xpixel: 0,
+                ws_ypixel: 0,
+            };
+            // SAFETY: TIOCGWINSZ 只把终端大小写入传入的 winsize
+            let result = unsafe { libc::ioctl(fd, libc::TIOCGWINSZ, &mut size) };
+            (result == 0 && size.ws_col > 0).then_some(size.ws_col as usize)
+        })
+}
+
+#[cfg(not(unix))]
+fn terminal_width() -> Option<usize> {
+    None
+}
+
+/// 输出到标准错误，按终端宽度折行
+pub fn eprint_wrapped(text: &str) {
+    match width(Stream::Stderr) {
+        Some(width) => eprintln!("{}", wrap(text, width)),
+        None => eprintln!("{}", text),
+    }
+}
+
+/// 字符占用的列数
+fn char_width(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0x303E
+        | 0x3041..=0x33FF
+        | 0x3400..=0x4DBF
+        | 0x4E00..=0x9FFF
+        | 0xA000..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFE30..=0xFE4F
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6
+        | 0x20000..=0x3FFFD => 2,
+        _ => 1,
+    }
+}
+
+/// 文本占用的列数，着色用的转义序列不占位置
+pub fn display_width(text: &str) -> usize {
+    let mut width = 0;
+    let mut escape = false;
+    for c in text.chars() {
+        match c {
+            '\x1b' => escape = true,
+            'm' if escape => escape = false,
+            _ if escape => {}
+            _ => width += char_width(c),
+        }
+    }
+    width
+}
+
+/// 截断到不超过 `width` 列，被截掉时以 `…` 结尾
+pub fn truncate(text: &str, width: usize) -> String {
+    if display_width(text) <= width {
+        return text.to_string();
+    }
+    let mut output = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        if used + char_width(c) + 1 > width {
+            break;
+        }
+        used += char_width(c);
+        output.push(c);
+    }
+    output.push(ELLIPSIS);
+    output
+}
+
+/// 按 `width` 列折行，后续行缩进两格；在空格处或中日韩文字之间断开，
+/// 没有断点的长单词（路径、URL 等）不拆开
+pub fn wrap(text: &str, width: usize) -> String {
+    const INDENT: &str = "  ";
+    let mut output = String::new();
+    for (i, line) in text.lines().enumerate() {
+        if i > 0 {
+            output.push('\n');
+        }
+        let mut used = 0;
+        for (j, word) in words(line).into_iter().enumerate() {
+            let word_width = display_width(word);
+            if j > 0 && used + word_width > width {
+                output.truncate(output.trim_end_matches(' ').len());
+                output.push('\n');
+                output.push_str(INDENT);
+                used = INDENT.len();
+                let word = word.trim_start_matches(' ');
+                output.push_str(word);
+                used += display_width(word);
+            } else {
+                output.push_str(word);
+                used += word_width;
+            }
+        }
+    }
+    output
+}
+
+/// 拆成可以在其间折行的片段：空格跟随前一个单词，每个中日韩文字单独一段
+fn words(line: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        let end = i + c.len_utf8();
+        let next = chars.peek().map(|&(_, next)| next);
+        let boundary = match next {
+            None => true,
+            Some(next) => (c == ' ' && next != ' ') || char_width(c) == 2 || char_width(next) == 2,
+        };
+        if boundary {
+            words.push(&line[start..end]);
+            start = end;
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wraps_at_spaces_and_between_wide_characters() {
+        assert_eq!(wrap("aaa bbb ccc", 8), "aaa bbb\n  ccc");
+        assert_eq!(wrap("错误: 无法读取文件", 10), "错误: 无法\n  读取文件");
+        assert_eq!(wrap("short", 80), "short");
+    }
+
+    #[test]
+    fn truncates_by_display_width() {
+        assert_eq!(truncate("abcdef", 4), "abc…");
+        assert_eq!(truncate("中文字符", 5), "中文…");
+        assert_eq!(truncate("abc", 3), "abc");
+        assert_eq!(display_width("\x1b[1;33m警告\x1b[0m"), 4);
+    }
+}
@@ -0,0 +1,195 @@
+//! TOML 中对象数组的写法
+//!
+//! 元素都是表的数组在 TOML 中有两种写法：`[[servers]]` 段，或者内联的 `servers = [{ ... }, { ... }]`。
+//! 默认全部写成段，`--toml-arrays [PATH=]STYLE` 可以整体或按路径选择（可多次指定，后指定的优先）：
+//! - `tables`：写成 `[[...]]` 段
+//! - `inline`：写成内联数组
+//! - `auto`：整行不超过 80 列时写成内联数组，否则写成段
+//!
+//! ```text
+//! confconv convert app.json -t toml --toml-arrays auto --toml-arrays 'servers=tables'
+//! ```
+//!
+//! 内联表中的数组只能是内联的；使用 TOML 1.1 语法的输出不做调整
+
+use crate::i18n::tr;
+use crate::path::{Path, Pattern, Segment};
+use toml_edit::{ArrayOfTables, DocumentMut, Item, Table, Value};
+
+/// `auto` 写成内联数组的最大行宽
+const AUTO_WIDTH: usize = 80;
+
+/// 对象数组的写法
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArrayStyle {
+    /// `[[...]]` 段
+    Tables,
+    /// 内联数组
+    Inline,
+    /// 按行宽选择
+    Auto,
+}
+
+/// 一条 `--toml-arrays` 规则，没有路径时适用于所有数组
+#[derive(Clone, Debug)]
+pub struct ArrayRule {
+    pattern: Option<Pattern>,
+    style: ArrayStyle,
+}
+
+impl ArrayRule {
+    /// 解析 `[PATH=]STYLE`，供 clap 使用
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let (pattern, style) = match input.rsplit_once('=') {
+            Some((path, style)) => (
+                Some(Pattern::parse(path).map_err(|e| e.to_string())?),
+                style,
+            ),
+            None => (None, input),
+        };
+        let style = match style {
+            "tables" => ArrayStyle::Tables,
+            "inline" => ArrayStyle::Inline,
+            "auto" => ArrayStyle::Auto,
+            _ => return Err(tr("写法应为 tables、inline 或 auto").to_string()),
+        };
+        Ok(ArrayRule { pattern, style })
+    }
+
+    /// 按 `root` 展开规则里的负数下标，保持规则顺序
+    pub fn resolve(rules: &[ArrayRule], root: &serde_json::Value) -> Vec<ArrayRule> {
+        rules
+            .iter()
+            .flat_map(|rule| match &rule.pattern {
+                Some(pattern) => pattern
+                    .resolve(root)
+                    .into_iter()
+                    .map(|pattern| ArrayRule {
+                        pattern: Some(pattern),
+                        style: rule.style,
+                    })
+                    .collect(),
+                None => vec![rule.clone()],
+            })
+            .collect()
+    }
+}
+
+/// 按规则调整 TOML 文本中对象数组的写法；没有规则或无法解析时原样返回
+pub fn apply(text: &str, rules: &[ArrayRule]) -> String {
+    if rules.is_empty() {
+        return text.to_string();
+    }
+    let Ok(mut document) = text.parse::<DocumentMut>() else {
+        return text.to_string();
+    };
+    layout(document.as_table_mut(), &Path::default(), rules);
+    document.to_string()
+}
+
+/// 路径适用的写法：最后一条匹配的规则
+fn style_for(path: &Path, rules: &[ArrayRule]) -> ArrayStyle {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| {
+            rule.pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.matches(path))
+        })
+        .map_or(ArrayStyle::Tables, |rule| rule.style)
+}
+
+fn layout(table: &mut Table, path: &Path, rules: &[ArrayRule]) {
+    let keys: Vec<String> = table.iter().map(|(key, _)| key.to_string()).collect();
+    for key in keys {
+        let path = path.join(Segment::Key(key.clone()));
+        let Some(item) = table.get_mut(&key) else {
+            continue;
+        };
+        let style = style_for(&path, rules);
+        match item {
+            Item::ArrayOfTables(tables) => {
+                let inline = tables.clone().into_array();
+                if style == ArrayStyle::Inline || (style == ArrayStyle::Auto && fits(&key, &inline))
+                {
+                    *item = Item::Value(Value::Array(inline));
+                    // 原来的键是表头的一部分，没有 `=` 两边的空格
+                    if let Some(mut key) = table.key_mut(&key) {
+                        key.leaf_decor_mut().set_suffix(" ");
+                    }
+                    continue;
+                }
+                for (index, sub) in tables.iter_mut().enumerate() {
+                    layout(sub, &path.join(Segment::Index(index as i64)), rules);
+                }
+            }
+            Item::Value(Value::Array(array))
+                if !array.is_empty() && array.iter().all(Value::is_inline_table) =>
+            {
+                if style == ArrayStyle::Inline || (style == ArrayStyle::Auto && fits(&key, array)) {
+                    continue;
+                }
+                let mut tables = ArrayOfTables::new();
+                for value in array.iter() {
+                    if let Value::InlineTable(inline) = value {
+                        tables.push(inline.clone().into_table());
+                    }
+                }
+                for (index, sub) in tables.iter_mut().enumerate() {
+                    layout(sub, &path.join(Segment::Index(index as i64)), rules);
+                }
+                *item = Item::ArrayOfTables(tables);
+                if let Some(mut key) = table.key_mut(&key) {
+                    key.leaf_decor_mut().set_suffix("");
+                }
+            }
+            Item::Table(sub) => layout(sub, &path, rules),
+            _ => {}
+        }
+    }
+}
+
+/// `key = [...]` 写成一行是否不超过 [`AUTO_WIDTH`]
+fn fits(key: &str, array: &toml_edit::Array) -> bool {
+    let mut array = array.clone();
+    array.fmt();
+    let line = format!("{} = {}", key, array.to_string().trim());
+    !line.contains('\n') && line.chars().count() <= AUTO_WIDTH
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(specs: &[&str]) -> Vec<ArrayRule> {
+        specs
+            .iter()
+            .map(|spec| ArrayRule::parse(spec).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn switches_between_tables_and_inline_arrays() {
+        let tables = "[[a]]\nx = 1\n\n[[a]]\nx = 2\n";
+        assert_eq!(
+            apply(tables, &rules(&["inline"])).trim(),
+            "a = [{ x = 1 }, { x = 2 }]"
+        );
+        assert_eq!(
+            apply(tables, &rules(&["auto"])).trim(),
+            "a = [{ x = 1 }, { x = 2 }]"
+        );
+        assert_eq!(apply(tables, &rules(&["inline", "a=tables"])), tables);
+
+        let inline = "a = [{ x = 1 }, { x = 2 }]\n";
+        assert_eq!(apply(inline, &rules(&["tables"])).trim(), tables.trim());
+        let long = format!("a = [{{ x = \"{}\" }}]\n", "y".repeat(80));
+        assert!(apply(&long, &rules(&["auto"])).starts_with("[[a]]"));
+    }
+
+    #[test]
+    fn rejects_unknown_styles() {
+        assert!(ArrayRule::parse("a=sections").is_err());
+    }
+}
@@ -0,0 +1,1087 @@
+//! 值变换
+//!
+//! 在解析之后、序列化之前对中间表示做的调整，例如调整键的顺序。
+//! 每种调整都实现 [`Transform`]，由 [`Pipeline`] 按顺序执行；
+//! 命令行参数对应的 [`Transforms`] 负责按固定顺序组装流水线
+
+use crate::codec;
+use crate::date;
+use crate::error::{Error, Result};
+use crate::i18n::tr;
+use crate::path::{self, Path, Pattern, Segment};
+use crate::process;
+use crate::rules::RulesStep;
+use crate::script;
+use crate::t;
+use crate::units;
+use crate::vault;
+use crate::wasm;
+use base64::engine::general_purpose::{STANDARD, URL_SAFE};
+use base64::Engine;
+use clap::ValueEnum;
+use serde_json::{Map, Value};
+use std::cmp::Ordering;
+use std::path::PathBuf;
+
+/// 排序方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum SortMode {
+    /// 按字符逐个比较（item10 排在 item2 前面）
+    #[value(help = tr("按字符逐个比较（item10 排在 item2 前面）"))]
+    #[default]
+    Lexical,
+    /// 自然排序：数字部分按数值比较（item2 排在 item10 前面）
+    #[value(help = tr("自然排序：数字部分按数值比较（item2 排在 item10 前面）"))]
+    Natural,
+}
+
+impl SortMode {
+    /// 按当前方式比较两个字符串
+    pub fn compare(self, a: &str, b: &str) -> Ordering {
+        match self {
+            SortMode::Lexical => a.cmp(b),
+            SortMode::Natural => natural_cmp(a, b),
+        }
+    }
+}
+
+/// 一个值变换步骤
+///
+/// 实现这个 trait 的类型都可以加入 [`Pipeline`]；新增变换只需要实现它，
+/// 再在 [`Transforms::pipeline`] 中排好位置，不需要改动命令代码
+pub trait Transform {
+    /// 原地变换整个文档
+    fn apply(&self, value: &mut Value) -> Result<()>;
+}
+
+/// 按加入顺序依次执行的一组变换
+#[derive(Default)]
+pub struct Pipeline {
+    steps: Vec<Box<dyn Transform>>,
+}
+
+impl Pipeline {
+    /// 空的流水线
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在末尾加入一个变换
+    pub fn push(&mut self, step: impl Transform + 'static) -> &mut Self {
+        self.steps.push(Box::new(step));
+        self
+    }
+
+    /// 是否没有任何变换
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+}
+
+impl Transform for Pipeline {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        self.steps.iter().try_for_each(|step| step.apply(value))
+    }
+}
+
+/// 命令行指定的一组值变换
+#[derive(Debug, Default)]
+pub struct Transforms {
+    /// 只保留这些路径（及其子树）
+    pub select: Vec<Pattern>,
+    /// 删除这些路径
+    pub exclude: Vec<Pattern>,
+    /// 重命名键
+    pub rename: Vec<Rename>,
+    /// 转换这些路径上标量的类型
+    pub coerce: Vec<Coerce>,
+    /// 把这些路径上的值替换为 [`REDACTED`]
+    pub redact: Vec<Pattern>,
+    /// 输出规则文件，在脱敏之后执行，见 [`rules`](crate::rules)
+    pub rules: Option<PathBuf>,
+    /// `encrypt` 规则使用的加密命令
+    pub encrypt: Option<String>,
+    /// 优先排在前面的键，指定后其余的键也会排序
+    pub key_order: Option<Vec<String>>,
+    /// 对所有对象的键排序
+    pub sort: Option<SortMode>,
+    /// 排序文件，按其中路径的先后排列键（在 `key_order` 和 `sort` 之后）
+    pub order_file: Option<PathBuf>,
+    /// 对元素全是标量的数组排序
+    pub sort_arrays: bool,
+    /// 解码这些路径上的 base64 字符串，内容是文档时展开为结构
+    pub decode_base64: Vec<Pattern>,
+    /// 把这些路径上的值编码为 base64 字符串（最后执行）
+    pub encode_base64: Vec<Pattern>,
+    /// 展开这些路径（及其子树）中内嵌 JSON/YAML 文档的字符串
+    pub parse_embedded: Vec<Pattern>,
+    /// 把这些路径上的结构重新序列化为 JSON 字符串
+    pub embed: Vec<Pattern>,
+    /// 把 `vault:路径#字段` 引用替换为 Vault 中的值
+    pub resolve_vault: bool,
+    /// 把日期时间字符串和 TOML 日期时间规范化为 RFC 3339
+    pub normalize_dates: bool,
+    /// 规范化日期时间时转换到 UTC
+    pub utc: bool,
+    /// 这些路径上的时长换算为秒
+    pub durations: Vec<Pattern>,
+    /// 这些路径上的大小换算为字节
+    pub sizes: Vec<Pattern>,
+    /// 时长和大小反过来换算为带单位的字符串
+    pub humanize: bool,
+    /// 依次运行的 WASM 变换模块
+    pub wasm: Vec<PathBuf>,
+    /// 依次运行的 Rhai 变换脚本（在 WASM 模块之后）
+    pub scripts: Vec<PathBuf>,
+    /// 依次通过这些 shell 命令过滤（在脚本之后）
+    pub filters: Vec<String>,
+}
+
+impl Transforms {
+    /// 按固定顺序组装流水线：
+    ///
+    /// 1. 解码 base64、展开内嵌文档
+    /// 2. 筛选（select、exclude）和重命名
+    /// 3. 解析 Vault 引用，规范化日期，换算时长和大小，转换类型
+    /// 4. WASM 模块、Rhai 脚本、外部命令
+    /// 5. 脱敏和规则文件（在外部变换之后，复制出来的敏感值也会被替换）
+    /// 6. 调整键和数组的顺序（排序文件在 `key_order` 和 `sort` 之后），最后重新内嵌和编码 base64
+    pub fn pipeline(&self) -> Pipeline {
+        let mut pipeline = Pipeline::new();
+        if !self.decode_base64.is_empty() {
+            pipeline.push(DecodeBase64(self.decode_base64.clone()));
+        }
+        if !self.parse_embedded.is_empty() {
+            pipeline.push(ParseEmbedded(self.parse_embedded.clone()));
+        }
+        if !self.select.is_empty() {
+            pipeline.push(Select(self.select.clone()));
+        }
+        if !self.exclude.is_empty() {
+            pipeline.push(Exclude(self.exclude.clone()));
+        }
+        for rename in &self.rename {
+            pipeline.push(rename.clone());
+        }
+        if self.resolve_vault {
+            pipeline.push(ResolveVault);
+        }
+        if self.normalize_dates {
+            pipeline.push(NormalizeDates { utc: self.utc });
+        }
+        if !self.durations.is_empty() {
+            pipeline.push(Durations {
+                paths: self.durations.clone(),
+                humanize: self.humanize,
+            });
+        }
+        if !self.sizes.is_empty() {
+            pipeline.push(Sizes {
+                paths: self.sizes.clone(),
+                humanize: self.humanize,
+            });
+        }
+        for coerce in &self.coerce {
+            pipeline.push(coerce.clone());
+        }
+        for module in &self.wasm {
+            pipeline.push(Wasm(module.clone()));
+        }
+        for path in &self.scripts {
+            pipeline.push(Script(path.clone()));
+        }
+        for line in &self.filters {
+            pipeline.push(Filter(line.clone()));
+        }
+        if !self.redact.is_empty() {
+            pipeline.push(Redact(self.redact.clone()));
+        }
+        if let Some(file) = &self.rules {
+            pipeline.push(RulesStep {
+                file: file.clone(),
+                encrypt: self.encrypt.clone(),
+            });
+        }
+        let mode = self.sort.unwrap_or_default();
+        if self.key_order.is_some() || self.sort.is_some() {
+            pipeline.push(OrderKeys {
+                priority: self.key_order.clone().unwrap_or_default(),
+                mode,
+            });
+        }
+        if let Some(file) = &self.order_file {
+            pipeline.push(OrderFile(file.clone()));
+        }
+        if self.sort_arrays {
+            pipeline.push(SortArrays(mode));
+        }
+        if !self.embed.is_empty() {
+            pipeline.push(Embed(self.embed.clone()));
+        }
+        if !self.encode_base64.is_empty() {
+            pipeline.push(EncodeBase64(self.encode_base64.clone()));
+        }
+        pipeline
+    }
+
+    /// 依次应用所有变换
+    pub fn apply(&self, value: &mut Value) -> Result<()> {
+        self.pipeline().apply(value)
+    }
+}
+
+/// 脱敏后的占位值
+pub const REDACTED: &str = "***";
+
+/// 只保留与任一模式匹配的路径（及其子树），其余的键和元素都删除
+pub struct Select(pub Vec<Pattern>);
+
+impl Transform for Select {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        let patterns = path::resolve_patterns(&self.0, value);
+        if !select(value, &Path::default(), &patterns) {
+            *value = match value {
+                Value::Array(_) => Value::Array(Vec::new()),
+                _ => Value::Object(Map::new()),
+            };
+        }
+        Ok(())
+    }
+}
+
+/// 删除与任一模式匹配的路径
+pub struct Exclude(pub Vec<Pattern>);
+
+impl Transform for Exclude {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        let patterns = path::resolve_patterns(&self.0, value);
+        exclude(value, &Path::default(), &patterns);
+        Ok(())
+    }
+}
+
+/// 把路径最后一段的键改名，`--rename PATH=KEY`
+#[derive(Clone, Debug)]
+pub struct Rename {
+    /// 要改名的键的完整路径
+    pub path: Path,
+    /// 新的键名
+    pub to: String,
+}
+
+impl Rename {
+    /// 解析 `PATH=KEY`
+    pub fn parse(input: &str) -> Result<Self> {
+        let (path, to) = input.rsplit_once('=').ok_or_else(|| Error::InvalidPath {
+            path: input.to_string(),
+            message: t!("应为 path=key 的形式"),
+        })?;
+        if to.is_empty() {
+            return Err(Error::InvalidPath {
+                path: input.to_string(),
+                message: t!("缺少新的键名"),
+            });
+        }
+        Ok(Rename {
+            path: Path::parse(path)?,
+            to: to.to_string(),
+        })
+    }
+}
+
+impl Transform for Rename {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        path::rename(value, &self.path, &self.to)
+    }
+}
+
+/// 标量类型
+#[derive(Clone, Copy, Debug, PartialEq, ValueEnum)]
+pub enum ScalarType {
+    /// 字符串
+    #[value(help = tr("字符串"))]
+    String,
+    /// 数字（整数保持为整数）
+    #[value(help = tr("数字（整数保持为整数）"))]
+    Number,
+    /// 整数（小数部分为 0 的数字也可以）
+    #[value(help = tr("整数（小数部分为 0 的数字也可以）"))]
+    Integer,
+    /// 布尔值（true/false、yes/no、on/off、1/0）
+    #[value(help = tr("布尔值（true/false、yes/no、on/off、1/0）"))]
+    Boolean,
+}
+
+impl ScalarType {
+    /// 类型的名称，用于错误信息
+    fn name(self) -> &'static str {
+        match self {
+            ScalarType::String => "字符串",
+            ScalarType::Number => "数字",
+            ScalarType::Integer => "整数",
+            ScalarType::Boolean => "布尔值",
+        }
+    }
+}
+
+/// 把路径上的标量转换为指定类型，`--coerce PATH=TYPE`
+#[derive(Clone, Debug)]
+pub struct Coerce {
+    /// 要转换的路径
+    pub pattern: Pattern,
+    /// 目标类型
+    pub to: ScalarType,
+}
+
+impl Coerce {
+    /// 解析 `PATH=TYPE`
+    pub fn parse(input: &str) -> Result<Self> {
+        let (path, to) = input.rsplit_once('=').ok_or_else(|| Error::InvalidPath {
+            path: input.to_string(),
+            message: t!("应为 path=type 的形式"),
+        })?;
+        let to = ScalarType::from_str(to, true).map_err(|_| Error::InvalidPath {
+            path: input.to_string(),
+            message: t!(
+                "未知的类型: {}（可用 string、number、integer、boolean）",
+                to
+            ),
+        })?;
+        Ok(Coerce {
+            pattern: Pattern::parse(path)?,
+            to,
+        })
+    }
+}
+
+impl Transform for Coerce {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        let to = self.to;
+        for_each_match(
+            value,
+            &Path::default(),
+            std::slice::from_ref(&self.pattern),
+            &mut |v, path| coerce(v, path, to),
+        )
+    }
+}
+
+/// 把路径上的值替换为 [`REDACTED`]，整个子树一起替换
+pub struct Redact(pub Vec<Pattern>);
+
+impl Transform for Redact {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        for_each_match(value, &Path::default(), &self.0, &mut |v, _| {
+            *v = Value::String(REDACTED.to_string());
+            Ok(())
+        })
+    }
+}
+
+/// 解码路径上的 base64 字符串，内容是文档时展开为结构
+pub struct DecodeBase64(pub Vec<Pattern>);
+
+impl Transform for DecodeBase64 {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        for_each_match(value, &Path::default(), &self.0, &mut decode_base64)
+    }
+}
+
+/// 把路径上的值编码为 base64 字符串
+pub struct EncodeBase64(pub Vec<Pattern>);
+
+impl Transform for EncodeBase64 {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        for_each_match(value, &Path::default(), &self.0, &mut encode_base64)
+    }
+}
+
+/// 展开路径（及其子树）中内嵌 JSON/YAML 文档的字符串
+pub struct ParseEmbedded(pub Vec<Pattern>);
+
+impl Transform for ParseEmbedded {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        for_each_match(value, &Path::default(), &self.0, &mut |v, _| {
+            parse_embedded(v);
+            Ok(())
+        })
+    }
+}
+
+/// 把路径上的结构重新序列化为 JSON 字符串
+pub struct Embed(pub Vec<Pattern>);
+
+impl Transform for Embed {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        for_each_match(value, &Path::default(), &self.0, &mut embed)
+    }
+}
+
+/// 把 `vault:路径#字段` 引用替换为 Vault 中的值
+pub struct ResolveVault;
+
+impl Transform for ResolveVault {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        vault::resolve(value)
+    }
+}
+
+/// 把日期时间规范化为 RFC 3339
+pub struct NormalizeDates {
+    /// 是否转换到 UTC
+    pub utc: bool,
+}
+
+impl Transform for NormalizeDates {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        normalize_dates(value, self.utc);
+        Ok(())
+    }
+}
+
+/// 把路径上的时长换算为秒（或反过来换算为带单位的写法）
+pub struct Durations {
+    /// 时长所在的路径
+    pub paths: Vec<Pattern>,
+    /// 换算为带单位的写法
+    pub humanize: bool,
+}
+
+impl Transform for Durations {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        let humanize = self.humanize;
+        for_each_match(value, &Path::default(), &self.paths, &mut |v, path| {
+            convert_unit(v, path, "时长", units::parse_duration, |seconds| {
+                if humanize {
+                    units::humanize_duration(seconds).map(Value::String)
+                } else {
+                    Some(number(seconds))
+                }
+            })
+        })
+    }
+}
+
+/// 把路径上的大小换算为字节（或反过来换算为带单位的写法）
+pub struct Sizes {
+    /// 大小所在的路径
+    pub paths: Vec<Pattern>,
+    /// 换算为带单位的写法
+    pub humanize: bool,
+}
+
+impl Transform for Sizes {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        let humanize = self.humanize;
+        for_each_match(value, &Path::default(), &self.paths, &mut |v, path| {
+            convert_unit(
+                v,
+                path,
+                "大小",
+                |text| units::parse_size(text).map(|b| b as f64),
+                |bytes| {
+                    // 负数不是大小，不能让 `as u64` 把它变成 0
+                    let bytes = bytes.round();
+                    if !(0.0..u64::MAX as f64).contains(&bytes) {
+                        return None;
+                    }
+                    Some(if humanize {
+                        Value::String(units::humanize_size(bytes as u64))
+                    } else {
+                        Value::from(bytes as u64)
+                    })
+                },
+            )
+        })
+    }
+}
+
+/// 运行一个 WASM 变换模块
+pub struct Wasm(pub PathBuf);
+
+impl Transform for Wasm {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        wasm::transform(&self.0, value)
+    }
+}
+
+/// 运行一个 Rhai 变换脚本
+pub struct Script(pub PathBuf);
+
+impl Transform for Script {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        script::transform(&self.0, value)
+    }
+}
+
+/// 通过一条 shell 命令过滤
+pub struct Filter(pub String);
+
+impl Transform for Filter {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        filter(value, &self.0)
+    }
+}
+
+/// 按优先级调整所有对象的键顺序，见 [`order_keys`]
+pub struct OrderKeys {
+    /// 按列出的顺序排在最前的键
+    pub priority: Vec<String>,
+    /// 其余键的排序方式
+    pub mode: SortMode,
+}
+
+impl Transform for OrderKeys {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        order_keys(value, &self.priority, self.mode);
+        Ok(())
+    }
+}
+
+/// 按排序文件调整键的顺序，见 [`read_order_file`] 和 [`order_paths`]
+pub struct OrderFile(pub PathBuf);
+
+impl Transform for OrderFile {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        let order = read_order_file(&self.0)?;
+        let order = path::resolve_patterns(&order, value);
+        order_paths(value, &Path::default(), &order);
+        Ok(())
+    }
+}
+
+/// 对元素全是标量的数组排序，见 [`sort_arrays`]
+pub struct SortArrays(pub SortMode);
+
+impl Transform for SortArrays {
+    fn apply(&self, value: &mut Value) -> Result<()> {
+        sort_arrays(value, self.0);
+        Ok(())
+    }
+}
+
+/// 对与任一模式匹配的值调用 `f`，匹配的值本身不再向下查找
+///
+/// 模式中的负数下标按 `value` 换算（见 [`Pattern::resolve`]）
+pub(crate) fn for_each_match(
+    value: &mut Value,
+    path: &Path,
+    patterns: &[Pattern],
+    f: &mut dyn FnMut(&mut Value, &Path) -> Result<()>,
+) -> Result<()> {
+    let patterns = path::resolve_patterns(patterns, value);
+    visit_matches(value, path, &patterns, f)
+}
+
+fn visit_matches(
+    value: &mut Value,
+    path: &Path,
+    patterns: &[Pattern],
+    f: &mut dyn FnMut(&mut Value, &Path) -> Result<()>,
+) -> Result<()> {
+    if patterns.iter().any(|p| p.matches(path)) {
+        return f(value, path);
+    }
+    if !patterns.iter().any(|p| p.may_match_below(path)) {
+        return Ok(());
+    }
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                visit_matches(child, &path.join(Segment::Key(key.clone())), patterns, f)?;
+            }
+        }
+        Value::Array(items) => {
+            for (i, child) in items.iter_mut().enumerate() {
+                visit_matches(child, &path.join(Segment::Index(i as i64)), patterns, f)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 只保留与任一模式匹配的子树，返回 `value` 中是否还有要保留的内容
+fn select(value: &mut Value, path: &Path, patterns: &[Pattern]) -> bool {
+    if patterns.iter().any(|p| p.matches(path)) {
+        return true;
+    }
+    if !patterns.iter().any(|p| p.may_match_below(path)) {
+        return false;
+    }
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, child| select(child, &path.join(Segment::Key(key.clone())), patterns));
+            !map.is_empty()
+        }
+        Value::Array(items) => {
+            let mut index = 0;
+            items.retain_mut(|child| {
+                let keep = select(child, &path.join(Segment::Index(index)), patterns);
+                index += 1;
+                keep
+            });
+            !items.is_empty()
+        }
+        _ => false,
+    }
+}
+
+/// 删除与任一模式匹配的键和元素
+fn exclude(value: &mut Value, path: &Path, patterns: &[Pattern]) {
+    if !patterns.iter().any(|p| p.may_match_below(path)) {
+        return;
+    }
+    let visit = |child: &mut Value, child_path: Path| {
+        if patterns.iter().any(|p| p.matches(&child_path)) {
+            return false;
+        }
+        exclude(child, &child_path, patterns);
+        true
+    };
+    match value {
+        Value::Object(map) => {
+            map.retain(|key, child| visit(child, path.join(Segment::Key(key.clone()))));
+        }
+        Value::Array(items) => {
+            let mut index = 0;
+            items.retain_mut(|child| {
+                let keep = visit(child, path.join(Segment::Index(index)));
+                index += 1;
+                keep
+            });
+        }
+        _ => {}
+    }
+}
+
+/// 把标量转换为 `to` 类型，对象、数组和无法转换的值报错
+fn coerce(value: &mut Value, path: &Path, to: ScalarType) -> Result<()> {
+    let integral = |x: f64| (x.is_finite() && x.fract() == 0.0).then(|| number(x));
+    let converted = match (to, &*value) {
+        (_, Value::Object(_) | Value::Array(_) | Value::Null) => None,
+        (ScalarType::String, Value::String(_))
+        | (ScalarType::Number, Value::Number(_))
+        | (ScalarType::Boolean, Value::Bool(_)) => return Ok(()),
+        (ScalarType::String, other) => Some(Value::String(other.to_string())),
+        (ScalarType::Number, Value::String(s)) => s
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|x| x.is_finite())
+            .map(number),
+        (ScalarType::Integer, Value::Number(n)) if n.is_i64() || n.is_u64() => return Ok(()),
+        (ScalarType::Integer, Value::Number(n)) => n.as_f64().and_then(integral),
+        (ScalarType::Integer, Value::String(s)) => match s.trim().parse::<i64>() {
+            Ok(n) => Some(Value::from(n)),
+            Err(_) => s.trim().parse::<f64>().ok().and_then(integral),
+        },
+        (ScalarType::Boolean, Value::String(s)) => match s.trim().to_ascii_lowercase().as_str() {
+            "true" | "yes" | "on" | "1" => Some(Value::Bool(true)),
+            "false" | "no" | "off" | "0" => Some(Value::Bool(false)),
+            _ => None,
+        },
+        (ScalarType::Boolean, Value::Number(n)) => match n.as_f64() {
+            Some(0.0) => Some(Value::Bool(false)),
+            Some(1.0) => Some(Value::Bool(true)),
+            _ => None,
+        },
+        _ => None,
+    };
+    *value = converted.ok_or_else(|| Error::Convert {
+        message: t!("{} 无法转换为{}: {}", path, tr(to.name()), value),
+    })?;
+    Ok(())
+}
+
+/// 把值以 JSON 写入 shell 命令的标准输入，用命令输出的 JSON 替换它
+///
+/// 命令输出多个（或零个）值时（如 `jq '.[]'`）收集为数组
+fn filter(value: &mut Value, line: &str) -> Result<()> {
+    let output = process::pipe(process::shell(line), line, value.to_string().as_bytes())?;
+    let mut values = serde_json::Deserializer::from_slice(&output)
+        .into_iter::<Value>()
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| Error::ExternalCommand {
+            command: line.to_string(),
+            message: t!("输出的不是合法的 JSON: {}", e),
+        })?;
+    *value = match values.len() {
+        1 => values.remove(0),
+        _ => Value::Array(values),
+    };
+    Ok(())
+}
+
+/// 解码 base64 字符串：内容能解析为对象或数组时展开为结构，否则保留为解码后的文本
+fn decode_base64(value: &mut Value, path: &Path) -> Result<()> {
+    let Value::String(encoded) = value else {
+        return Err(Error::Convert {
+            message: t!("{} 不是字符串，无法按 base64 解码", path),
+        });
+    };
+    let compact: String = encoded.chars().filter(|c| !c.is_whitespace()).collect();
+    let bytes = STANDARD
+        .decode(&compact)
+        .or_else(|_| URL_SAFE.decode(&compact))
+        .map_err(|e| Error::Convert {
+            message: t!("{} 不是有效的 base64: {}", path, e),
+        })?;
+    let text = String::from_utf8(bytes).map_err(|_| Error::Convert {
+        message: t!("{} 解码后不是 UTF-8 文本", path),
+    })?;
+    *value = match codec::parse_any(&text) {
+        Ok((nested @ (Value::Object(_) | Value::Array(_)), _)) => nested,
+        _ => Value::String(text),
+    };
+    Ok(())
+}
+
+/// 展开子树中所有内嵌文档的字符串，展开后的结构里如果还有内嵌文档也会继续展开
+///
+/// 只展开看起来是文档的字符串：以 `{` 或 `[` 开头，或者是多行文本，
+/// 并且能解析为对象或数组；`a: 1` 这样的单行文本保持为字符串
+fn parse_embedded(value: &mut Value) {
+    match value {
+        Value::String(s) => {
+            let trimmed = s.trim_start();
+            if !(trimmed.starts_with(['{', '[']) || s.trim().contains('\n')) {
+                return;
+            }
+            if let Ok((nested @ (Value::Object(_) | Value::Array(_)), _)) = codec::parse_any(s) {
+                *value = nested;
+                parse_embedded(value);
+            }
+        }
+        Value::Object(map) => map.values_mut().for_each(parse_embedded),
+        Value::Array(items) => items.iter_mut().for_each(parse_embedded),
+        _ => {}
+    }
+}
+
+/// 规范化所有日期时间，TOML 日期时间对象也会变成字符串
+fn normalize_dates(value: &mut Value, utc: bool) {
+    if let Some(text) = date::toml_datetime(value) {
+        let text = date::normalize(text, utc).unwrap_or_else(|| text.to_string());
+        *value = Value::String(text);
+        return;
+    }
+    match value {
+        Value::String(s) => {
+            if let Some(normalized) = date::normalize(s, utc) {
+                *s = normalized;
+            }
+        }
+        Value::Object(map) => map.values_mut().for_each(|v| normalize_dates(v, utc)),
+        Value::Array(items) => items.iter_mut().for_each(|v| normalize_dates(v, utc)),
+        _ => {}
+    }
+}
+
+/// 换算带单位的值：字符串按 `parse` 解析，数字视为已换算的值，再用 `output` 生成结果，
+/// `output` 返回 None（结果无法表示）时报错
+fn convert_unit(
+    value: &mut Value,
+    path: &Path,
+    kind: &str,
+    parse: impl Fn(&str) -> Option<f64>,
+    output: impl Fn(f64) -> Option<Value>,
+) -> Result<()> {
+    let amount = match value {
+        Value::String(text) => parse(text),
+        Value::Number(n) => n.as_f64(),
+        _ => None,
+    };
+    let amount = amount.ok_or_else(|| Error::Convert {
+        message: t!("{} 不是可识别的{}: {}", path, tr(kind), value),
+    })?;
+    *value = output(amount).ok_or_else(|| Error::Convert {
+        message: t!("{} 的{}超出可以表示的范围: {}", path, tr(kind), value),
+    })?;
+    Ok(())
+}
+
+/// 整数值保持为整数，其余为浮点数
+fn number(amount: f64) -> Value {
+    if amount.fract() == 0.0 && amount.abs() < i64::MAX as f64 {
+        Value::from(amount as i64)
+    } else {
+        Value::from(amount)
+    }
+}
+
+/// 把结构序列化为紧凑 JSON 字符串，已经是字符串的值保持不变
+fn embed(value: &mut Value, _path: &Path) -> Result<()> {
+    if !value.is_string() {
+        *value = Value::String(value.to_string());
+    }
+    Ok(())
+}
+
+/// 编码为 base64：字符串直接编码，其他值先序列化为紧凑 JSON
+fn encode_base64(value: &mut Value, _path: &Path) -> Result<()> {
+    let text = match &mut *value {
+        Value::String(s) => std::mem::take(s),
+        other => other.to_string(),
+    };
+    *value = Value::String(STANDARD.encode(text));
+    Ok(())
+}
+
+/// 按优先级调整所有对象的键顺序
+///
+/// `priority` 中列出的键按列出的顺序排在最前，其余的键按 `mode` 排序排在后面，
+/// 与 package.json、Cargo.toml 常见的字段顺序约定一致
+pub fn order_keys(value: &mut Value, priority: &[String], mode: SortMode) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = std::mem::take(map).into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| {
+                let rank = |key: &String| priority.iter().position(|p| p == key);
+                match (rank(a), rank(b)) {
+                    (Some(x), Some(y)) => x.cmp(&y),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => mode.compare(a, b),
+                }
+            });
+            *map = entries
+                .into_iter()
+                .map(|(key, mut child)| {
+                    order_keys(&mut child, priority, mode);
+                    (key, child)
+                })
+                .collect::<Map<_, _>>();
+        }
+        Value::Array(items) => {
+            for item in items {
+                order_keys(item, priority, mode);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 读取排序文件：每行一个路径（支持通配，如 `servers[*].name`），空行和 `#` 开头的行忽略
+pub fn read_order_file(file: &std::path::Path) -> Result<Vec<Pattern>> {
+    let text = std::fs::read_to_string(file).map_err(|e| Error::FileRead {
+        path: file.display().to_string(),
+        source: e,
+    })?;
+    text.lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            Pattern::parse(line).map_err(|e| Error::Config {
+                path: file.display().to_string(),
+                message: t!("第 {} 行: {}", number, e),
+            })
+        })
+        .collect()
+}
+
+/// 按 `order` 中路径的先后排列每个对象的键
+///
+/// 对象中的键排到第一个与它的完整路径匹配的模式的位置；没有列出的键排在列出的键之后，
+/// 保持原来的相对顺序（同时指定了 `--sort` 时就是排序后的顺序）
+pub fn order_paths(value: &mut Value, path: &Path, order: &[Pattern]) {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(Path, String, Value)> = std::mem::take(map)
+                .into_iter()
+                .map(|(key, child)| (path.join(Segment::Key(key.clone())), key, child))
+                .collect();
+            entries.sort_by_cached_key(|(path, _, _)| {
+                order
+                    .iter()
+                    .position(|pattern| pattern.matches(path))
+                    .unwrap_or(usize::MAX)
+            });
+            *map = entries
+                .into_iter()
+                .map(|(path, key, mut child)| {
+                    order_paths(&mut child, &path, order);
+                    (key, child)
+                })
+                .collect();
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter_mut().enumerate() {
+                order_paths(item, &path.join(Segment::Index(i as i64)), order);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// 对元素全是标量的数组排序，包含对象或数组的数组保持原顺序
+///
+/// 数字按数值排在最前，字符串按 `mode` 排序，其他标量（布尔、null）保持相对顺序排在最后
+pub fn sort_arrays(value: &mut Value, mode: SortMode) {
+    match value {
+        Value::Object(map) => {
+            for child in map.values_mut() {
+                sort_arrays(child, mode);
+            }
+        }
+        Value::Array(items) => {
+            if items.iter().any(|v| v.is_array() || v.is_object()) {
+                for item in items {
+                    sort_arrays(item, mode);
+                }
+                return;
+            }
+            items.sort_by(|a, b| match (a, b) {
+                (Value::Number(x), Value::Number(y)) => {
+                    let (x, y) = (x.as_f64().unwrap_or(0.0), y.as_f64().unwrap_or(0.0));
+                    x.partial_cmp(&y).unwrap_or(Ordering::Equal)
+                }
+                (Value::String(x), Value::String(y)) => mode.compare(x, y),
+                (Value::Number(_), _) => Ordering::Less,
+                (_, Value::Number(_)) => Ordering::Greater,
+                (Value::String(_), _) => Ordering::Less,
+                (_, Value::String(_)) => Ordering::Greater,
+                _ => Ordering::Equal,
+            });
+        }
+        _ => {}
+    }
+}
+
+/// 自然排序比较：连续的数字按数值比较，其余字符逐个比较
+///
+/// 数值相同但写法不同（如 `07` 和 `7`）时再按原字符串比较，保证排序稳定
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a, b);
+    loop {
+        match (x.chars().next(), y.chars().next()) {
+            (None, None) => return a.cmp(b),
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(cx), Some(cy)) if cx.is_ascii_digit() && cy.is_ascii_digit() => {
+                let (dx, rest_x) = split_digits(x);
+                let (dy, rest_y) = split_digits(y);
+                let (nx, ny) = (dx.trim_start_matches('0'), dy.trim_start_matches('0'));
+                let ord = nx.len().cmp(&ny.len()).then_with(|| nx.cmp(ny));
+                if ord != Ordering::Equal {
+                    return ord;
+                }
+                (x, y) = (rest_x, rest_y);
+            }
+            (Some(cx), Some(cy)) => {
+                if cx != cy {
+                    return cx.cmp(&cy);
+                }
+                (x, y) = (&x[cx.len_utf8()..], &y[cy.len_utf8()..]);
+            }
+        }
+    }
+}
+
+/// 拆出开头的连续数字
+fn split_digits(s: &str) -> (&str, &str) {
+    let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    s.split_at(end)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn keys_keep_input_order_unless_sorted() {
+        let input = "zone: b\nname: a\nmeta:\n  version: 1\n  arch: x86\n";
+        let mut value = crate::codec::parse(input, crate::format::Format::Yaml).unwrap();
+        Transforms::default().apply(&mut value).unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{"zone":"b","name":"a","meta":{"version":1,"arch":"x86"}}"#
+        );
+
+        let sorted = Transforms {
+            sort: Some(SortMode::Lexical),
+            ..Transforms::default()
+        };
+        sorted.apply(&mut value).unwrap();
+        assert_eq!(
+            value.to_string(),
+            r#"{"meta":{"arch":"x86","version":1},"name":"a","zone":"b"}"#
+        );
+    }
+
+    #[test]
+    fn pipeline_runs_steps_in_order() {
+        let mut value = json!({
+            "server": {"addr": "localhost", "port": "8080"},
+            "items": [{"name": "a", "secret": "x"}, {"name": "b", "secret": "y"}],
+            "other": 1
+        });
+        let mut pipeline = Pipeline::new();
+        pipeline
+            .push(Select(vec![
+                Pattern::parse("server").unwrap(),
+                Pattern::parse("items").unwrap(),
+            ]))
+            .push(Exclude(vec![Pattern::parse("items.*.secret").unwrap()]))
+            .push(Rename::parse("server.addr=address").unwrap())
+            .push(Coerce::parse("server.port=integer").unwrap());
+        pipeline.apply(&mut value).unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "server": {"address": "localhost", "port": 8080},
+                "items": [{"name": "a"}, {"name": "b"}]
+            })
+        );
+        assert!(Coerce::parse("server.address=number")
+            .unwrap()
+            .apply(&mut value)
+            .is_err());
+    }
+
+    #[test]
+    fn orders_keys_by_listed_paths() {
+        let mut value = json!({
+            "z": 1,
+            "server": {"port": 80, "tls": true, "host": "a"},
+            "items": [{"b": 1, "name": "x"}],
+            "a": 2
+        });
+        let order: Vec<Pattern> = ["server", "server.host", "items[*].name", "a"]
+            .into_iter()
+            .map(|p| Pattern::parse(p).unwrap())
+            .collect();
+        order_paths(&mut value, &Path::default(), &order);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            r#"{"server":{"host":"a","port":80,"tls":true},"a":2,"z":1,"items":[{"name":"x","b":1}]}"#
+        );
+    }
+
+    #[test]
+    fn humanized_units_never_lose_the_value() {
+        let pattern = |text| vec![Pattern::parse(text).unwrap()];
+        let durations = Durations {
+            paths: pattern("t[*]"),
+            humanize: true,
+        };
+        let mut value = json!({"t": ["250us", "0.0001", 1.5e-9, "90m", 0]});
+        durations.apply(&mut value).unwrap();
+        assert_eq!(
+            value,
+            json!({"t": ["250us", "100us", "2ns", "1h30m", "0s"]})
+        );
+        assert!(durations.apply(&mut json!({"t": [1e-12]})).is_err());
+
+        let sizes = Sizes {
+            paths: pattern("s"),
+            humanize: true,
+        };
+        let mut value = json!({"s": 1536});
+        sizes.apply(&mut value).unwrap();
+        assert_eq!(value, json!({"s": "1536B"}));
+        assert!(sizes.apply(&mut json!({"s": -5})).is_err());
+    }
+}
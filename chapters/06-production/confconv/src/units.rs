@@ -0,0 +1,134 @@
+//! 时长和字节数的单位换算
+//!
+//! 配置里常混用 `30s`、`1.5h`、`512Mi`、`2GB` 和纯数字，
+//! 统一换算为秒和字节后才好比较；也可以反过来换算为带单位的写法
+
+/// 时长单位及对应的秒数，按从大到小排列
+const DURATION_UNITS: &[(&str, f64)] = &[
+    ("w", 604_800.0),
+    ("d", 86_400.0),
+    ("h", 3_600.0),
+    ("m", 60.0),
+    ("s", 1.0),
+    ("ms", 1e-3),
+    ("us", 1e-6),
+    ("µs", 1e-6),
+    ("ns", 1e-9),
+];
+
+/// 字节单位及对应的字节数：K/KB 等为十进制，Ki/KiB 等为二进制（不区分大小写）
+const SIZE_UNITS: &[(&str, u64)] = &[
+    ("b", 1),
+    ("k", 1_000),
+    ("kb", 1_000),
+    ("ki", 1 << 10),
+    ("kib", 1 << 10),
+    ("m", 1_000_000),
+    ("mb", 1_000_000),
+    ("mi", 1 << 20),
+    ("mib", 1 << 20),
+    ("g", 1_000_000_000),
+    ("gb", 1_000_000_000),
+    ("gi", 1 << 30),
+    ("gib", 1 << 30),
+    ("t", 1_000_000_000_000),
+    ("tb", 1_000_000_000_000),
+    ("ti", 1 << 40),
+    ("tib", 1 << 40),
+    ("p", 1_000_000_000_000_000),
+    ("pb", 1_000_000_000_000_000),
+    ("pi", 1 << 50),
+    ("pib", 1 << 50),
+];
+
+/// 解析时长为秒数
+///
+/// 支持 `90`、`30s`、`1.5h`、`1h30m`、`250ms` 这样的写法，纯数字视为秒
+pub fn parse_duration(text: &str) -> Option<f64> {
+    let text = text.trim();
+    if let Ok(seconds) = text.parse::<f64>() {
+        return seconds.is_finite().then_some(seconds);
+    }
+    let mut rest = text;
+    let mut total = 0.0;
+    while !rest.is_empty() {
+        let (number, after) = split_number(rest)?;
+        let unit_len = after
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(after.len());
+        let (unit, next) = after.split_at(unit_len);
+        let (_, factor) = DURATION_UNITS.iter().find(|(u, _)| *u == unit.trim())?;
+        total += number * factor;
+        rest = next.trim_start();
+    }
+    (!text.is_empty()).then_some(total)
+}
+
+/// 解析字节数，支持 `512Mi`、`2GB`、`1.5 KiB` 和纯数字
+pub fn parse_size(text: &str) -> Option<u64> {
+    let (number, unit) = split_number(text.trim())?;
+    let factor = match unit.trim() {
+        "" => 1,
+        unit => {
+            let unit = unit.to_ascii_lowercase();
+            SIZE_UNITS.iter().find(|(u, _)| *u == unit)?.1
+        }
+    };
+    let bytes = (number * factor as f64).round();
+    (bytes >= 0.0 && bytes < u64::MAX as f64).then_some(bytes as u64)
+}
+
+/// 把秒数写成带单位的时长，如 5400 → `1h30m`，不足一秒的部分用 `ms`、`us`、`ns` 表示
+///
+/// 精确到纳秒；不是 0 但不足半纳秒、无法这样表示时返回 None
+pub fn humanize_duration(seconds: f64) -> Option<String> {
+    if seconds == 0.0 {
+        return Some("0s".to_string());
+    }
+    let sign = if seconds < 0.0 { "-" } else { "" };
+    let mut nanos = (seconds.abs() * 1e9).round() as u128;
+    if nanos == 0 {
+        return None;
+    }
+    let mut parts = String::new();
+    for (unit, factor) in [
+        ("d", 86_400_000_000_000),
+        ("h", 3_600_000_000_000),
+        ("m", 60_000_000_000),
+        ("s", 1_000_000_000),
+        ("ms", 1_000_000),
+        ("us", 1_000),
+        ("ns", 1),
+    ] {
+        if nanos >= factor {
+            parts.push_str(&format!("{}{}", nanos / factor, unit));
+            nanos %= factor;
+        }
+    }
+    Some(format!("{}{}", sign, parts))
+}
+
+/// 把字节数写成能整除、且数值最小的单位，如 `512Mi`、`2G`，都不能整除时写作 `1536B`
+pub fn humanize_size(bytes: u64) -> String {
+    let binary = ["Ki", "Mi", "Gi", "Ti", "Pi"]
+        .iter()
+        .zip((1..=5).map(|n| 1u64 << (10 * n)));
+    let decimal = ["K", "M", "G", "T", "P"]
+        .iter()
+        .zip((1..=5).map(|n| 1000u64.pow(n)));
+    binary
+        .chain(decimal)
+        .filter(|(_, factor)| bytes > 0 && bytes.is_multiple_of(*factor))
+        .max_by_key(|(_, factor)| *factor)
+        .map(|(unit, factor)| format!("{}{}", bytes / factor, unit))
+        .unwrap_or_else(|| format!("{}B", bytes))
+}
+
+/// 拆出开头的数字部分（可以带小数点），返回数值和剩余文本
+fn split_number(text: &str) -> Option<(f64, &str)> {
+    let end = text
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(text.len());
+    let number = text[..end].parse().ok()?;
+    Some((number, &text[end..]))
+}
@@ -0,0 +1,556 @@
+//! 与格式无关的中间值模型
+//!
+//! 各命令目前都在 `serde_json::Value` 上工作，JSON 表示不了的内容要么在解析时丢失，
+//! 要么用标记对象变通（TOML 日期时间、`--json-allow-nan` 的无穷大和 NaN），
+//! 注释则单独保存在 [`Comments`] 中。[`ConfValue`] 把这些都作为一等的值：
+//! - 对象保持键的顺序，每个键带一个注释位置
+//! - 日期时间、无穷大和 NaN、超出 64 位整数或双精度浮点数精度的数字（保留原文）
+//! - YAML 的自定义标签（`!Ref name`）
+//!
+//! [`parse`] 和 [`serialize`] 建立在 [`codec`] 的解析和序列化之上，只补上 JSON 值表示不了的部分。
+//! 需要 `serde_json::Value` 的代码通过 `From<serde_json::Value>` 和 [`ConfValue::to_json`] 转换，命令可以逐步迁移到这个模型。
+//! 目前 convert 在这个模型上读写：没有指定值变换时，超出精度的数字、自定义标签和注释原样带到输出
+
+use crate::codec::{self, OutputOptions};
+use crate::comments::{self, Comments};
+use crate::custom_tags;
+use crate::date::{self, TOML_DATETIME_KEY};
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::lossy;
+use crate::nonfinite;
+use crate::path::{Path, Segment};
+use indexmap::IndexMap;
+use serde_json::Value;
+
+/// 对象：保持键的顺序
+pub type Map = IndexMap<String, Entry>;
+
+/// 对象中的一项
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    /// 键对应的值
+    pub value: ConfValue,
+    /// 键上方和行末的注释（多行用换行连接，不含 `#`）
+    pub comment: Option<String>,
+}
+
+impl Entry {
+    /// 没有注释的一项
+    pub fn new(value: ConfValue) -> Self {
+        Entry {
+            value,
+            comment: None,
+        }
+    }
+}
+
+/// 数字
+#[derive(Clone, Debug, PartialEq)]
+pub enum Number {
+    /// 有符号整数
+    Int(i64),
+    /// 超出 i64 范围的无符号整数
+    UInt(u64),
+    /// 浮点数，包括无穷大和 NaN
+    Float(f64),
+    /// 超出 64 位整数范围或双精度浮点数精度的数字，保留原文
+    Big(String),
+}
+
+/// 带自定义标签的值
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tagged {
+    /// 标签，不含开头的 `!`
+    pub tag: String,
+    /// 被标记的值
+    pub value: ConfValue,
+}
+
+/// 中间值
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConfValue {
+    /// 空值
+    Null,
+    /// 布尔值
+    Bool(bool),
+    /// 数字
+    Number(Number),
+    /// 字符串
+    String(String),
+    /// 日期时间（TOML 的日期时间类型），保留原文
+    DateTime(String),
+    /// 数组
+    Array(Vec<ConfValue>),
+    /// 对象
+    Map(Map),
+    /// 带自定义标签的值（YAML 的 `!Ref name`）
+    Tagged(Box<Tagged>),
+}
+
+impl From<Value> for ConfValue {
+    /// 从现有的中间表示转换，日期时间和无穷大、NaN 的标记对象以及标签对象还原为对应的值
+    fn from(value: Value) -> Self {
+        if let Some(text) = date::toml_datetime(&value) {
+            return ConfValue::DateTime(text.to_string());
+        }
+        if let Some(float) = nonfinite::get(&value) {
+            return ConfValue::Number(Number::Float(float));
+        }
+        if let Some((tag, inner)) = custom_tags::get(&value) {
+            return ConfValue::Tagged(Box::new(Tagged {
+                tag: tag[1..].to_string(),
+                value: inner.clone().into(),
+            }));
+        }
+        match value {
+            Value::Null => ConfValue::Null,
+            Value::Bool(b) => ConfValue::Bool(b),
+            Value::Number(n) => ConfValue::Number(match (n.as_i64(), n.as_u64()) {
+                (Some(i), _) => Number::Int(i),
+                (None, Some(u)) => Number::UInt(u),
+                _ => Number::Float(n.as_f64().unwrap_or(f64::NAN)),
+            }),
+            Value::String(s) => ConfValue::String(s),
+            Value::Array(items) => ConfValue::Array(items.into_iter().map(Into::into).collect()),
+            Value::Object(map) => ConfValue::Map(
+                map.into_iter()
+                    .map(|(key, child)| (key, Entry::new(child.into())))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl ConfValue {
+    /// 转换为现有代码使用的 `serde_json::Value`
+    ///
+    /// 日期时间写成 TOML 日期时间的标记对象，无穷大和 NaN 按 `--json-allow-nan` 处理，
+    /// 超出范围的数字转换为浮点数，标签写成标签对象（见 [`crate::custom_tags`]）；
+    /// 注释丢弃（可以用 [`ConfValue::comments`] 取出）
+    pub fn to_json(&self) -> Value {
+        match self {
+            ConfValue::Null => Value::Null,
+            ConfValue::Bool(b) => Value::Bool(*b),
+            ConfValue::Number(Number::Int(i)) => Value::from(*i),
+            ConfValue::Number(Number::UInt(u)) => Value::from(*u),
+            ConfValue::Number(Number::Float(x)) => nonfinite::float(*x),
+            ConfValue::Number(Number::Big(text)) => {
+                text.parse().map(nonfinite::float).unwrap_or(Value::Null)
+            }
+            ConfValue::String(s) => Value::String(s.clone()),
+            ConfValue::DateTime(text) => serde_json::json!({ TOML_DATETIME_KEY: text }),
+            ConfValue::Array(items) => Value::Array(items.iter().map(ConfValue::to_json).collect()),
+            ConfValue::Map(map) => Value::Object(
+                map.iter()
+                    .map(|(key, entry)| (key.clone(), entry.value.to_json()))
+                    .collect(),
+            ),
+            ConfValue::Tagged(tagged) => {
+                serde_json::json!({ format!("!{}", tagged.tag): tagged.value.to_json() })
+            }
+        }
+    }
+
+    /// 所有键上的注释，按出现顺序
+    pub fn comments(&self) -> Comments {
+        let mut comments = Vec::new();
+        collect_comments(self, &Path::default(), &mut comments);
+        comments
+    }
+
+    /// 把注释放到对应键的注释位置上，找不到的键忽略
+    pub fn attach_comments(&mut self, comments: Comments) {
+        for (path, text) in comments {
+            let Some((Segment::Key(key), parents)) = path.segments().split_last() else {
+                continue;
+            };
+            let entry = self.get_mut(parents).and_then(|parent| match parent {
+                ConfValue::Map(map) => map.get_mut(key),
+                _ => None,
+            });
+            if let Some(entry) = entry {
+                entry.comment = Some(text);
+            }
+        }
+    }
+
+    /// 把 `_comment_<键>` 字段（只限同一对象中确实有对应键的）移到对应键的注释位置上，
+    /// 与 [`comments::take`] 的规则相同
+    pub fn take_comment_fields(&mut self) {
+        match self {
+            ConfValue::Map(map) => {
+                let fields: Vec<String> = map
+                    .iter()
+                    .filter(|(field, entry)| {
+                        matches!(entry.value, ConfValue::String(_))
+                            && field
+                                .strip_prefix(comments::PREFIX)
+                                .is_some_and(|key| map.contains_key(key))
+                    })
+                    .map(|(field, _)| field.clone())
+                    .collect();
+                for field in fields {
+                    if let Some(Entry {
+                        value: ConfValue::String(text),
+                        ..
+                    }) = map.shift_remove(&field)
+                    {
+                        if let Some(entry) = map.get_mut(&field[comments::PREFIX.len()..]) {
+                            entry.comment = Some(text);
+                        }
+                    }
+                }
+                map.values_mut()
+                    .for_each(|entry| entry.value.take_comment_fields());
+            }
+            ConfValue::Array(items) => items.iter_mut().for_each(ConfValue::take_comment_fields),
+            ConfValue::Tagged(tagged) => tagged.value.take_comment_fields(),
+            _ => {}
+        }
+    }
+
+    /// 去掉所有注释
+    pub fn clear_comments(&mut self) {
+        match self {
+            ConfValue::Map(map) => map.values_mut().for_each(|entry| {
+                entry.comment = None;
+                entry.value.clear_comments();
+            }),
+            ConfValue::Array(items) => items.iter_mut().for_each(ConfValue::clear_comments),
+            ConfValue::Tagged(tagged) => tagged.value.clear_comments(),
+            _ => {}
+        }
+    }
+
+    /// 按路径段取出子值，标签透明
+    pub fn get_mut(&mut self, segments: &[Segment]) -> Option<&mut ConfValue> {
+        let Some((first, rest)) = segments.split_first() else {
+            return Some(self);
+        };
+        let child = match (self, first) {
+            (ConfValue::Tagged(tagged), _) => return tagged.value.get_mut(segments),
+            (ConfValue::Map(map), Segment::Key(key)) => &mut map.get_mut(key)?.value,
+            (ConfValue::Array(items), Segment::Index(index)) => {
+                let index = if *index < 0 {
+                    items.len().checked_sub(index.unsigned_abs() as usize)?
+                } else {
+                    *index as usize
+                };
+                items.get_mut(index)?
+            }
+            _ => return None,
+        };
+        child.get_mut(rest)
+    }
+
+    /// 子树中是否有超出范围的数字
+    fn has_big_numbers(&self) -> bool {
+        match self {
+            ConfValue::Number(Number::Big(_)) => true,
+            ConfValue::Array(items) => items.iter().any(ConfValue::has_big_numbers),
+            ConfValue::Map(map) => map.values().any(|entry| entry.value.has_big_numbers()),
+            ConfValue::Tagged(tagged) => tagged.value.has_big_numbers(),
+            _ => false,
+        }
+    }
+}
+
+fn collect_comments(value: &ConfValue, path: &Path, comments: &mut Comments) {
+    match value {
+        ConfValue::Map(map) => {
+            for (key, entry) in map {
+                let child = path.join(Segment::Key(key.clone()));
+                if let Some(text) = &entry.comment {
+                    comments.push((child.clone(), text.clone()));
+                }
+                collect_comments(&entry.value, &child, comments);
+            }
+        }
+        ConfValue::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_comments(item, &path.join(Segment::Index(i as i64)), comments);
+            }
+        }
+        ConfValue::Tagged(tagged) => collect_comments(&tagged.value, path, comments),
+        _ => {}
+    }
+}
+
+/// 把文本按格式解析为 [`ConfValue`]
+///
+/// 在 [`codec::parse`] 的结果上构建，方言和 `--custom-tags` 等选项与其他命令一致：
+/// - JSON：超出范围的数字保留原文
+/// - YAML、TOML：保留注释；标签对象、日期时间和无穷大、NaN 的标记对象还原为对应的值
+/// - 插件格式：经过 `serde_json::Value` 转换
+pub fn parse(input: &str, format: Format) -> Result<ConfValue> {
+    let json = codec::parse(input, format)?;
+    let mut value = match format {
+        Format::Json => JsonReader { input, pos: 0 }.value(),
+        _ => json.into(),
+    };
+    if comments::supports(format) {
+        let mut plain = Value::Null;
+        value.attach_comments(comments::collect(input, format, &mut plain));
+    }
+    Ok(value)
+}
+
+/// 按格式序列化 [`ConfValue`]，注释写成目标格式的注释（或 `_comment_` 字段）
+///
+/// - JSON：超出范围的数字按原文写出
+/// - 其他格式经过 `serde_json::Value`，见 [`ConfValue::to_json`]
+pub fn serialize(value: &ConfValue, format: Format, style: &OutputOptions) -> Result<String> {
+    String::from_utf8(serialize_bytes(value, format, style)?).map_err(|_| Error::Convert {
+        message: crate::t!(
+            "{} 的输出是二进制数据，只能用 convert 命令输出",
+            format.name()
+        ),
+    })
+}
+
+/// 与 [`serialize`] 相同，但插件格式的输出可以是二进制数据
+pub fn serialize_bytes(
+    value: &ConfValue,
+    format: Format,
+    style: &OutputOptions,
+) -> Result<Vec<u8>> {
+    let comments = value.comments();
+    if matches!(format, Format::Json) && value.has_big_numbers() {
+        let mut json = with_placeholders(value);
+        comments::embed(&mut json, &comments);
+        let mut text = codec::serialize_with(&json, format, style)?;
+        restore_placeholders(value, &mut text);
+        return Ok(text.into_bytes());
+    }
+    comments::serialize_bytes(&value.to_json(), format, style, &comments)
+}
+
+/// JSON 输出时超出范围的数字先写成带前缀的字符串，序列化后再换回原文
+const BIG_PLACEHOLDER: &str = "$__confconv_number:";
+
+fn with_placeholders(value: &ConfValue) -> Value {
+    match value {
+        ConfValue::Number(Number::Big(text)) => {
+            Value::String(format!("{}{}", BIG_PLACEHOLDER, text))
+        }
+        ConfValue::Array(items) => Value::Array(items.iter().map(with_placeholders).collect()),
+        ConfValue::Map(map) => Value::Object(
+            map.iter()
+                .map(|(key, entry)| (key.clone(), with_placeholders(&entry.value)))
+                .collect(),
+        ),
+        ConfValue::Tagged(tagged) => {
+            serde_json::json!({ format!("!{}", tagged.tag): with_placeholders(&tagged.value) })
+        }
+        other => other.to_json(),
+    }
+}
+
+fn restore_placeholders(value: &ConfValue, text: &mut String) {
+    match value {
+        ConfValue::Number(Number::Big(number)) => {
+            *text = text.replace(&format!("\"{}{}\"", BIG_PLACEHOLDER, number), number);
+        }
+        ConfValue::Array(items) => items
+            .iter()
+            .for_each(|item| restore_placeholders(item, text)),
+        ConfValue::Map(map) => map
+            .values()
+            .for_each(|entry| restore_placeholders(&entry.value, text)),
+        ConfValue::Tagged(tagged) => restore_placeholders(&tagged.value, text),
+        _ => {}
+    }
+}
+
+/// 读取已经通过语法检查的 JSON 文本，数字保留原文
+///
+/// 语法错误已经由 [`codec::parse`] 报告，这里遇到意外的输入时尽量读下去
+struct JsonReader<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl JsonReader<'_> {
+    fn value(&mut self) -> ConfValue {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let literals = [
+            ("true", ConfValue::Bool(true)),
+            ("false", ConfValue::Bool(false)),
+            ("null", ConfValue::Null),
+            ("NaN", ConfValue::Number(Number::Float(f64::NAN))),
+            ("Infinity", ConfValue::Number(Number::Float(f64::INFINITY))),
+            (
+                "-Infinity",
+                ConfValue::Number(Number::Float(f64::NEG_INFINITY)),
+            ),
+        ];
+        for (literal, value) in literals {
+            if rest.starts_with(literal) {
+                self.pos += literal.len();
+                return value;
+            }
+        }
+        match rest.as_bytes().first() {
+            Some(b'{') => self.object(),
+            Some(b'[') => self.array(),
+            Some(b'"') => ConfValue::String(self.string()),
+            _ => self.number(),
+        }
+    }
+
+    fn object(&mut self) -> ConfValue {
+        let mut map = Map::new();
+        self.pos += 1;
+        loop {
+            self.skip_whitespace();
+            match self.input.as_bytes().get(self.pos) {
+                Some(b'"') => {
+                    let key = self.string();
+                    self.skip_whitespace();
+                    self.pos += 1; // ':'
+                    let value = self.value();
+                    map.insert(key, Entry::new(value));
+                }
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => break,
+            }
+        }
+        ConfValue::Map(map)
+    }
+
+    fn array(&mut self) -> ConfValue {
+        let mut items = Vec::new();
+        self.pos += 1;
+        loop {
+            self.skip_whitespace();
+            match self.input.as_bytes().get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    break;
+                }
+                None => break,
+                _ => items.push(self.value()),
+            }
+        }
+        ConfValue::Array(items)
+    }
+
+    /// 读取字符串（包括两边的引号），转义交给 serde_json 处理
+    fn string(&mut self) -> String {
+        let start = self.pos;
+        let mut escaped = false;
+        for (i, c) in self.input[start + 1..].char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => {
+                    self.pos = start + 1 + i + 1;
+                    return serde_json::from_str(&self.input[start..self.pos]).unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+        self.pos = self.input.len();
+        String::new()
+    }
+
+    fn number(&mut self) -> ConfValue {
+        let start = self.pos;
+        let rest = &self.input[start..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-')))
+            .unwrap_or(rest.len());
+        self.pos += len.max(1);
+        let text = &rest[..len];
+        if let Ok(i) = text.parse() {
+            return ConfValue::Number(Number::Int(i));
+        }
+        if let Ok(u) = text.parse() {
+            return ConfValue::Number(Number::UInt(u));
+        }
+        if lossy::loses_precision(text) {
+            return ConfValue::Number(Number::Big(text.to_string()));
+        }
+        ConfValue::Number(Number::Float(text.parse().unwrap_or(f64::NAN)))
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_what_json_values_lose() {
+        let json = parse(
+            r#"{"id": 123456789012345678901234567890, "ratio": 0.5}"#,
+            Format::Json,
+        )
+        .unwrap();
+        let ConfValue::Map(map) = &json else {
+            panic!("应为对象");
+        };
+        assert_eq!(
+            map["id"].value,
+            ConfValue::Number(Number::Big("123456789012345678901234567890".into()))
+        );
+        assert_eq!(
+            serialize(&json, Format::Json, &OutputOptions::default()).unwrap(),
+            r#"{"id":123456789012345678901234567890,"ratio":0.5}"#
+        );
+
+        let yaml = parse("# 名称\nname: Bucket\n", Format::Yaml).unwrap();
+        let ConfValue::Map(map) = &yaml else {
+            panic!("应为对象");
+        };
+        assert_eq!(map["name"].comment.as_deref(), Some("名称"));
+
+        let toml = parse("at = 2024-01-02T03:04:05Z\n", Format::Toml).unwrap();
+        let ConfValue::Map(map) = &toml else {
+            panic!("应为对象");
+        };
+        assert_eq!(
+            map["at"].value,
+            ConfValue::DateTime("2024-01-02T03:04:05Z".into())
+        );
+    }
+
+    #[test]
+    fn comment_fields_move_into_comment_slots() {
+        let mut value = parse(
+            r#"{"_comment_port": "端口", "port": 80, "_comment_gone": "x", "id": 123456789012345678901234567890}"#,
+            Format::Json,
+        )
+        .unwrap();
+        value.take_comment_fields();
+        assert_eq!(
+            value.comments(),
+            vec![(Path::parse("port").unwrap(), "端口".to_string())]
+        );
+        assert_eq!(
+            serialize(&value, Format::Yaml, &OutputOptions::default()).unwrap(),
+            "# 端口\nport: 80\n_comment_gone: x\nid: 1.2345678901234568e29\n"
+        );
+        value.clear_comments();
+        assert!(value.comments().is_empty());
+
+        let json = serde_json::json!({"name": {"!Ref": "Bucket"}});
+        let tagged = ConfValue::from(json.clone());
+        let ConfValue::Map(map) = &tagged else {
+            panic!("应为对象");
+        };
+        assert!(matches!(&map["name"].value, ConfValue::Tagged(t) if t.tag == "Ref"));
+        assert_eq!(tagged.to_json(), json);
+    }
+}
@@ -0,0 +1,175 @@
+//! HashiCorp Vault 密钥引用
+//!
+//! `--resolve-vault` 把整个值为 `vault:路径#字段` 的字符串替换为 Vault 中的值，
+//! 例如 `vault:secret/data/app#password`。路径是 API 路径（KV v2 引擎的路径含 `data/`），
+//! 省略 `#字段` 时替换为整个密钥对象。同一路径只请求一次。
+//!
+//! 连接方式与 vault 命令行一致：
+//! - `VAULT_ADDR`：服务地址，默认 `https://127.0.0.1:8200`
+//! - `VAULT_TOKEN`：令牌，未设置时读取 `~/.vault-token`
+//! - `VAULT_NAMESPACE`：企业版的命名空间（可选）
+//!
+//! 需要启用 `vault` 功能
+
+use crate::error::{Error, Result};
+use serde_json::Value;
+
+/// 引用的前缀
+const PREFIX: &str = "vault:";
+
+/// 解析字符串中的引用，返回路径和字段
+#[cfg_attr(not(feature = "vault"), allow(dead_code))]
+fn reference(text: &str) -> Option<(&str, Option<&str>)> {
+    let rest = text.strip_prefix(PREFIX)?;
+    Some(match rest.split_once('#') {
+        Some((path, field)) => (path, Some(field)),
+        None => (rest, None),
+    })
+}
+
+/// 替换 `value` 中所有的 Vault 引用
+#[cfg(feature = "vault")]
+pub fn resolve(value: &mut Value) -> Result<()> {
+    let client = Client::from_env()?;
+    let mut cache = std::collections::HashMap::new();
+    replace(value, &mut |text| {
+        let Some((path, field)) = reference(text) else {
+            return Ok(None);
+        };
+        let error = |message: String| Error::Secret {
+            reference: text.to_string(),
+            message,
+        };
+        if !cache.contains_key(path) {
+            let secret = client.read(path).map_err(error)?;
+            cache.insert(path.to_string(), secret);
+        }
+        let secret = &cache[path];
+        match field {
+            None => Ok(Some(secret.clone())),
+            Some(field) => secret
+                .get(field)
+                .cloned()
+                .map(Some)
+                .ok_or_else(|| error(crate::t!("密钥中没有字段 {}", field))),
+        }
+    })
+}
+
+/// 对每个字符串调用 `f`，返回 `Some` 时替换该字符串
+#[cfg(feature = "vault")]
+fn replace(value: &mut Value, f: &mut dyn FnMut(&str) -> Result<Option<Value>>) -> Result<()> {
+    match value {
+        Value::String(text) => {
+            if let Some(new) = f(text)? {
+                *value = new;
+            }
+        }
+        Value::Object(map) => {
+            for child in map.values_mut() {
+                replace(child, f)?;
+            }
+        }
+        Value::Array(items) => {
+            for child in items {
+                replace(child, f)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// 读取密钥的客户端
+#[cfg(feature = "vault")]
+struct Client {
+    agent: ureq::Agent,
+    addr: String,
+    token: String,
+    namespace: Option<String>,
+}
+
+#[cfg(feature = "vault")]
+impl Client {
+    /// 按 vault 命令行的环境变量创建客户端
+    fn from_env() -> Result<Self> {
+        use std::env;
+
+        let token = match env::var("VAULT_TOKEN") {
+            Ok(token) => token,
+            Err(_) => env::var_os("HOME")
+                .map(|home| std::path::PathBuf::from(home).join(".vault-token"))
+                .and_then(|path| std::fs::read_to_string(path).ok())
+                .map(|token| token.trim().to_string())
+                .ok_or_else(|| Error::Secret {
+                    reference: PREFIX.to_string(),
+                    message: crate::t!("未设置 VAULT_TOKEN，也没有 ~/.vault-token"),
+                })?,
+        };
+        let agent = ureq::Agent::config_builder()
+            .timeout_global(Some(std::time::Duration::from_secs(30)))
+            .http_status_as_error(false)
+            .build()
+            .into();
+        Ok(Client {
+            agent,
+            addr: env::var("VAULT_ADDR")
+                .unwrap_or_else(|_| "https://127.0.0.1:8200".to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            token,
+            namespace: env::var("VAULT_NAMESPACE").ok(),
+        })
+    }
+
+    /// 读取 `path` 处的密钥，KV v2 引擎的响应会去掉外层的版本信息
+    fn read(&self, path: &str) -> std::result::Result<Value, String> {
+        let url = format!("{}/v1/{}", self.addr, path.trim_start_matches('/'));
+        let mut request = self.agent.get(&url).header("X-Vault-Token", &self.token);
+        if let Some(namespace) = &self.namespace {
+            request = request.header("X-Vault-Namespace", namespace);
+        }
+        let mut response = request.call().map_err(|e| e.to_string())?;
+        let status = response.status();
+        let body = response
+            .body_mut()
+            .read_to_string()
+            .map_err(|e| e.to_string())?;
+        let body: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+        if !status.is_success() {
+            // Vault 的错误响应是 {"errors": [...]}，读取不存在的路径时为空数组
+            let errors = body
+                .get("errors")
+                .and_then(Value::as_array)
+                .map(|errors| {
+                    errors
+                        .iter()
+                        .filter_map(Value::as_str)
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                })
+                .unwrap_or_default();
+            return Err(if errors.is_empty() {
+                status.to_string()
+            } else {
+                errors
+            });
+        }
+        let data = body
+            .get("data")
+            .ok_or_else(|| crate::t!("响应中没有 data"))?;
+        match (data.get("data"), data.get("metadata")) {
+            (Some(inner @ Value::Object(_)), Some(Value::Object(_))) => Ok(inner.clone()),
+            _ => Ok(data.clone()),
+        }
+    }
+}
+
+/// 未启用 `vault` 功能时无法解析引用
+#[cfg(not(feature = "vault"))]
+pub fn resolve(_value: &mut Value) -> Result<()> {
+    Err(Error::Secret {
+        reference: PREFIX.to_string(),
+        message: crate::t!("编译时未启用 vault 功能"),
+    })
+}
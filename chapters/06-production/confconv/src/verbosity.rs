@@ -0,0 +1,77 @@
+//! 输出的详细程度
+//!
+//! 由全局参数 `-q` 和重复的 `-v` 决定，所有输出都写到标准错误，不影响命令输出的数据：
+//! - `-q`：只输出错误，警告、提示和进度信息都不输出
+//! - 默认：再加上警告和提示
+//! - `-v`：处理过程（读取了哪个文件、写入了哪里等）
+//! - `-vv`：调试信息（配置文件、方言、读取的字节数、执行的钩子等）
+//! - `-vvv`：跟踪信息（解析和变换后的中间表示）
+//!
+//! 命令模块通过参数接收 `verbose`、`quiet`，编解码等底层模块用 [`log!`](crate::log) 按当前级别输出
+
+use std::sync::OnceLock;
+
+/// 详细程度，越往后输出越多
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// 只输出错误
+    Quiet,
+    /// 错误、警告和提示
+    #[default]
+    Normal,
+    /// 处理过程
+    Info,
+    /// 调试信息
+    Debug,
+    /// 跟踪信息
+    Trace,
+}
+
+impl Verbosity {
+    /// 由 `-v` 的次数和 `-q` 确定
+    pub fn new(verbose: u8, quiet: bool) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Info,
+            (false, 2) => Verbosity::Debug,
+            (false, _) => Verbosity::Trace,
+        }
+    }
+}
+
+static VERBOSITY: OnceLock<Verbosity> = OnceLock::new();
+
+/// 设置本次运行的详细程度，只有第一次调用有效
+pub fn init(verbosity: Verbosity) {
+    let _ = VERBOSITY.set(verbosity);
+}
+
+/// 当前的详细程度，没有调用过 [`init`] 时为默认值
+pub fn current() -> Verbosity {
+    *VERBOSITY.get_or_init(Verbosity::default)
+}
+
+/// 是否输出这个级别的信息
+pub fn enabled(level: Verbosity) -> bool {
+    current() >= level
+}
+
+/// 是否指定了 `-q`
+pub fn quiet() -> bool {
+    current() == Verbosity::Quiet
+}
+
+/// 按当前详细程度输出翻译后的信息到标准错误
+///
+/// ```ignore
+/// log!(Debug, "读取: {}", path);
+/// ```
+#[macro_export]
+macro_rules! log {
+    ($level:ident, $($arg:tt)+) => {
+        if $crate::verbosity::enabled($crate::verbosity::Verbosity::$level) {
+            eprintln!("{}", $crate::t!($($arg)+));
+        }
+    };
+}
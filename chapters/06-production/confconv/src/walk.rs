@@ -0,0 +1,94 @@
+//! 目录遍历
+//!
+//! 命令行参数可以是文件也可以是目录：目录会被递归展开为其中
+//! 能识别格式的配置文件，默认像 ripgrep 一样遵守 .gitignore
+
+use crate::error::{Error, Result};
+use crate::format::Format;
+use crate::t;
+use globset::{GlobBuilder, GlobMatcher};
+use ignore::WalkBuilder;
+use std::io;
+use std::path::Path;
+
+/// 配置文件中按文件选用设置的模式
+///
+/// 相对于当前目录的 glob，`*` 不跨越 `/`；不含 `/` 时只匹配文件名，如 `*.toml`
+#[derive(Clone, Debug)]
+pub struct FilePattern {
+    matcher: GlobMatcher,
+    whole_path: bool,
+}
+
+impl FilePattern {
+    /// 编译模式
+    pub fn new(pattern: &str) -> std::result::Result<Self, globset::Error> {
+        let matcher = GlobBuilder::new(pattern)
+            .literal_separator(true)
+            .build()?
+            .compile_matcher();
+        Ok(FilePattern {
+            matcher,
+            whole_path: pattern.contains('/'),
+        })
+    }
+
+    /// 命令行中给出的（或遍历目录得到的）文件路径是否匹配
+    pub fn matches(&self, path: &str) -> bool {
+        let path = Path::new(path);
+        match path.file_name() {
+            Some(name) if !self.whole_path => self.matcher.is_match(name),
+            _ => self.matcher.is_match(path),
+        }
+    }
+}
+
+/// 把输入路径展开为文件列表
+///
+/// - 显式给出的文件原样保留（即使扩展名无法识别，交给后续命令报错）
+/// - 目录递归遍历，只收集扩展名可识别的文件
+/// - `respect_gitignore` 为 true 时跳过 .gitignore / .ignore 忽略的路径
+pub fn collect_files(paths: &[String], respect_gitignore: bool) -> Result<Vec<String>> {
+    let mut files = Vec::new();
+
+    for path in paths {
+        if path == "-" || !Path::new(path).is_dir() {
+            files.push(path.clone());
+            continue;
+        }
+
+        let walker = WalkBuilder::new(path)
+            .standard_filters(respect_gitignore)
+            // 隐藏文件（如 .eslintrc.json）也是常见的配置文件
+            .hidden(false)
+            .filter_entry(|entry| entry.file_name() != ".git")
+            .sort_by_file_name(|a, b| a.cmp(b))
+            .build();
+
+        for entry in walker {
+            let entry = entry.map_err(|e| Error::FileRead {
+                path: path.clone(),
+                source: io::Error::other(e.to_string()),
+            })?;
+            let is_file = entry.file_type().is_some_and(|t| t.is_file());
+            let entry_path = entry.path().to_string_lossy().into_owned();
+            if is_file && Format::from_extension(&entry_path).is_some() {
+                files.push(entry_path);
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+/// 同 [`collect_files`]，但一个文件也没有找到时报错，用于处理文件的命令
+pub fn require_files(paths: &[String], respect_gitignore: bool) -> Result<Vec<String>> {
+    let files = collect_files(paths, respect_gitignore)?;
+    if files.is_empty() {
+        return Err(Error::FileRead {
+            path: paths.join(" "),
+            source: io::Error::new(io::ErrorKind::NotFound, t!("没有扩展名可以识别的配置文件")),
+        });
+    }
+    Ok(files)
+}
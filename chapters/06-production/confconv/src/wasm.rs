@@ -0,0 +1,100 @@
+//! WASM 变换插件
+//!
+//! 组织可以把自定义的规范化逻辑编译为 WebAssembly 模块，在转换流程中对中间表示做变换。
+//! 模块在沙箱中运行：不提供任何导入（无法访问文件、网络和环境变量），
+//! 执行的指令数和内存都有上限。
+//!
+//! 模块需要导出：
+//! - `memory`：线性内存
+//! - `alloc(len: i32) -> i32`：分配 `len` 字节，返回起始地址
+//! - `transform(ptr: i32, len: i32) -> i64`：输入是 `ptr` 处 `len` 字节的 JSON 文本，
+//!   返回值的高 32 位是输出 JSON 的地址、低 32 位是长度
+//!
+//! 模块执行 `unreachable` 等陷入时视为失败
+
+use crate::error::{Error, Result};
+use serde_json::Value;
+use std::path::Path;
+
+/// 单次变换最多消耗的燃料（大致相当于执行的指令数）
+#[cfg(feature = "wasm")]
+const MAX_FUEL: u64 = 100_000_000;
+
+/// 模块线性内存的上限
+#[cfg(feature = "wasm")]
+const MAX_MEMORY: usize = 256 * 1024 * 1024;
+
+/// 用 `module` 处的 WASM 模块变换 `value`
+#[cfg(feature = "wasm")]
+pub fn transform(module: &Path, value: &mut Value) -> Result<()> {
+    use crate::t;
+    use wasmi::{Config, Engine, Linker, Module, Store, StoreLimits, StoreLimitsBuilder};
+
+    let error = |message: String| Error::Wasm {
+        module: module.display().to_string(),
+        message,
+    };
+
+    let bytes = std::fs::read(module).map_err(|e| Error::FileRead {
+        path: module.display().to_string(),
+        source: e,
+    })?;
+    let mut config = Config::default();
+    config.consume_fuel(true);
+    let engine = Engine::new(&config);
+    let compiled = Module::new(&engine, &bytes).map_err(|e| error(e.to_string()))?;
+
+    let limits = StoreLimitsBuilder::new()
+        .memory_size(MAX_MEMORY)
+        .instances(1)
+        .build();
+    let mut store = Store::new(&engine, limits);
+    store.limiter(|limits: &mut StoreLimits| limits);
+    store.set_fuel(MAX_FUEL).map_err(|e| error(e.to_string()))?;
+
+    // 不定义任何导入，需要导入的模块会在实例化时失败
+    let linker = Linker::<StoreLimits>::new(&engine);
+    let instance = linker
+        .instantiate(&mut store, &compiled)
+        .and_then(|pre| pre.start(&mut store))
+        .map_err(|e| error(e.to_string()))?;
+    let memory = instance
+        .get_memory(&store, "memory")
+        .ok_or_else(|| error(t!("模块没有导出 memory")))?;
+    let alloc = instance
+        .get_typed_func::<i32, i32>(&store, "alloc")
+        .map_err(|e| error(e.to_string()))?;
+    let run = instance
+        .get_typed_func::<(i32, i32), i64>(&store, "transform")
+        .map_err(|e| error(e.to_string()))?;
+
+    let input = value.to_string();
+    let len = i32::try_from(input.len()).map_err(|_| error(t!("输入过大")))?;
+    let ptr = alloc
+        .call(&mut store, len)
+        .map_err(|e| error(e.to_string()))?;
+    memory
+        .write(&mut store, ptr as u32 as usize, input.as_bytes())
+        .map_err(|e| error(e.to_string()))?;
+    let packed = run
+        .call(&mut store, (ptr, len))
+        .map_err(|e| error(e.to_string()))? as u64;
+
+    let (ptr, len) = ((packed >> 32) as usize, (packed & 0xffff_ffff) as usize);
+    let output = memory
+        .data(&store)
+        .get(ptr..ptr + len)
+        .ok_or_else(|| error(t!("返回的输出超出了模块内存")))?;
+    *value =
+        serde_json::from_slice(output).map_err(|e| error(t!("输出的不是合法的 JSON: {}", e)))?;
+    Ok(())
+}
+
+/// 未启用 `wasm` 功能时无法运行模块
+#[cfg(not(feature = "wasm"))]
+pub fn transform(module: &Path, _value: &mut Value) -> Result<()> {
+    Err(Error::Wasm {
+        module: module.display().to_string(),
+        message: crate::t!("编译时未启用 wasm 功能"),
+    })
+}
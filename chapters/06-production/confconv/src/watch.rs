@@ -0,0 +1,100 @@
+//! 监视模式
+//!
+//! 每隔一小段时间检查输入文件的修改时间，有变化时重新执行。
+//! 执行失败只报告错误并继续监视，按 Ctrl-C 退出
+
+use crate::error::{Error, Result};
+use crate::process;
+use crate::t;
+use crate::term;
+use crate::verbosity;
+use std::fs;
+use std::io::{self, Write};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// 检查修改时间的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(300);
+
+/// 发现修改后再等待的时间，让编辑器把文件写完
+const SETTLE_DELAY: Duration = Duration::from_millis(100);
+
+/// 监视选项
+#[derive(Debug, Default)]
+pub struct WatchOptions {
+    /// 每次执行成功后运行的 shell 命令
+    pub exec: Option<String>,
+    /// 每次执行前清屏
+    pub clear: bool,
+}
+
+/// 先执行一次 `job`，之后每当 `files` 中的文件被修改就重新执行，不会返回（除非参数有误）
+pub fn run(
+    files: &[String],
+    options: &WatchOptions,
+    mut job: impl FnMut() -> Result<()>,
+) -> Result<()> {
+    if files.iter().any(|file| file == "-") {
+        return Err(Error::Convert {
+            message: t!("监视模式不能从标准输入读取"),
+        });
+    }
+
+    let mut stamps = modified_times(files);
+    loop {
+        if options.clear {
+            // 清屏并把光标移到左上角
+            print!("\x1b[2J\x1b[H");
+            // 标准输出按行缓冲，不刷新的话清屏会落在本次输出之后
+            let _ = io::stdout().flush();
+        }
+        match job() {
+            Ok(()) => {
+                if let Some(command) = &options.exec {
+                    if let Err(e) = exec(command) {
+                        term::eprint_wrapped(&t!("错误: {}", e));
+                    }
+                }
+            }
+            Err(e) => term::eprint_wrapped(&t!("错误: {}", e)),
+        }
+        if !verbosity::quiet() {
+            eprintln!("{}", t!("等待文件修改…（按 Ctrl-C 退出）"));
+        }
+
+        loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = modified_times(files);
+            if current != stamps {
+                thread::sleep(SETTLE_DELAY);
+                stamps = modified_times(files);
+                break;
+            }
+        }
+    }
+}
+
+/// 各文件的修改时间，文件不存在（如正在被替换）时为 None
+fn modified_times(files: &[String]) -> Vec<Option<SystemTime>> {
+    files
+        .iter()
+        .map(|file| fs::metadata(file).and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// 运行 `--exec` 指定的命令并等待它结束
+fn exec(command: &str) -> Result<()> {
+    let status = process::shell(command)
+        .status()
+        .map_err(|e| Error::ExternalCommand {
+            command: command.to_string(),
+            message: e.to_string(),
+        })?;
+    if !status.success() {
+        return Err(Error::ExternalCommand {
+            command: command.to_string(),
+            message: status.to_string(),
+        });
+    }
+    Ok(())
+}
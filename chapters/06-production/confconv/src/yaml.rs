@@ -0,0 +1,288 @@
+//! 按行宽排版的 YAML 输出
+//!
+//! serde_yml 的输出没有行宽控制：长字符串永远写在一行里，数组永远展开为块。
+//! 这里自己负责结构排版，标量的引号规则仍然交给 serde_yml，保证输出合法：
+//! - 放得下的纯标量数组写成流式序列 `[a, b, c]`，放不下时展开为块
+//! - 超出行宽的长字符串使用折叠块 `>-` 按单词换行
+//! - 多行字符串保持 serde_yml 的字面块 `|`
+//!
+//! 不限行宽时排版与 serde_yml 相同，用于只改变引号写法的情况。
+//! `--custom-tags keep` 时标签对象写成标签（`!Ref name`），带标签的数组总是展开为块
+
+use crate::codec::Quote;
+use crate::custom_tags;
+use crate::error::{Error, Result};
+use crate::nonfinite;
+use serde_json::{Map, Value};
+
+/// 折叠块内容的最小可用宽度，避免缩进很深时每行只剩一个单词
+const MIN_FOLD_WIDTH: usize = 20;
+
+/// 排版方式
+#[derive(Clone, Copy, Debug)]
+pub struct Layout {
+    /// 行宽，不指定时不折叠长字符串，数组总是展开为块
+    pub width: Option<usize>,
+    /// 字符串值的引号
+    pub quote: Quote,
+    /// 把标签对象还原为标签
+    pub tags: bool,
+}
+
+/// 按给定排版方式把值序列化为 YAML
+pub fn to_string(value: &Value, layout: &Layout) -> Result<String> {
+    let mut out = String::new();
+    if let Some((tag, inner)) = tagged(value, layout) {
+        out.push_str(tag);
+        write_value(&mut out, inner, 0, tag.chars().count(), layout)?;
+        return Ok(out);
+    }
+    match value {
+        Value::Object(map) if !map.is_empty() => write_mapping(&mut out, map, 0, false, layout)?,
+        Value::Array(items) if !items.is_empty() => {
+            write_sequence(&mut out, items, 0, false, layout)?
+        }
+        _ => {
+            out.push_str(&value_scalar(value, 0, layout)?);
+            out.push('\n');
+        }
+    }
+    Ok(out)
+}
+
+/// 写出映射，`inline_first` 表示第一个键接在 `- ` 后面，不需要缩进
+fn write_mapping(
+    out: &mut String,
+    map: &Map<String, Value>,
+    indent: usize,
+    inline_first: bool,
+    layout: &Layout,
+) -> Result<()> {
+    for (i, (key, value)) in map.iter().enumerate() {
+        if i > 0 || !inline_first {
+            out.push_str(&" ".repeat(indent));
+        }
+        let key = key_text(key)?;
+        out.push_str(&key);
+        out.push(':');
+        write_value(out, value, indent, indent + key.chars().count() + 1, layout)?;
+    }
+    Ok(())
+}
+
+/// 写出序列，`inline_first` 表示第一项接在上一级的 `- ` 后面
+fn write_sequence(
+    out: &mut String,
+    items: &[Value],
+    indent: usize,
+    inline_first: bool,
+    layout: &Layout,
+) -> Result<()> {
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 || !inline_first {
+            out.push_str(&" ".repeat(indent));
+        }
+        out.push_str("- ");
+        let column = indent + 2;
+        if let Some((tag, inner)) = tagged(item, layout) {
+            out.push_str(tag);
+            write_value(out, inner, column, column + tag.chars().count(), layout)?;
+            continue;
+        }
+        match item {
+            Value::Object(map) if !map.is_empty() && nonfinite::get(item).is_none() => {
+                write_mapping(out, map, column, true, layout)?
+            }
+            Value::Array(nested) if !nested.is_empty() => match flow(nested, column, layout)? {
+                Some(flow) => {
+                    out.push_str(&flow);
+                    out.push('\n');
+                }
+                None => write_sequence(out, nested, column, true, layout)?,
+            },
+            _ => {
+                write_scalar(out, item, indent, column, layout)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 写出键后面的值，`column` 是冒号之后的列位置
+fn write_value(
+    out: &mut String,
+    value: &Value,
+    indent: usize,
+    column: usize,
+    layout: &Layout,
+) -> Result<()> {
+    if let Some((tag, inner)) = tagged(value, layout) {
+        out.push(' ');
+        out.push_str(tag);
+        return write_value(out, inner, indent, column + 1 + tag.chars().count(), layout);
+    }
+    match value {
+        Value::Object(map) if !map.is_empty() && nonfinite::get(value).is_none() => {
+            out.push('\n');
+            write_mapping(out, map, indent + 2, false, layout)
+        }
+        Value::Array(items) if !items.is_empty() => match flow(items, column + 1, layout)? {
+            Some(flow) => {
+                out.push(' ');
+                out.push_str(&flow);
+                out.push('\n');
+                Ok(())
+            }
+            None => {
+                // 与 serde_yml 一致：键下面的序列不额外缩进
+                out.push('\n');
+                write_sequence(out, items, indent, false, layout)
+            }
+        },
+        _ => {
+            out.push(' ');
+            write_scalar(out, value, indent, column + 1, layout)
+        }
+    }
+}
+
+/// 写出标量，放不下时尝试折叠
+fn write_scalar(
+    out: &mut String,
+    value: &Value,
+    indent: usize,
+    column: usize,
+    layout: &Layout,
+) -> Result<()> {
+    let text = value_scalar(value, indent, layout)?;
+    let fits = |width| column + text.chars().count() <= width;
+    if let (Some(width), Value::String(s), Quote::Auto) = (layout.width, value, layout.quote) {
+        if !fits(width) {
+            if let Some(lines) = fold(s, indent + 2, width) {
+                out.push_str(">-\n");
+                for line in lines {
+                    out.push_str(&" ".repeat(indent + 2));
+                    out.push_str(&line);
+                    out.push('\n');
+                }
+                return Ok(());
+            }
+        }
+    }
+    out.push_str(&text);
+    out.push('\n');
+    Ok(())
+}
+
+/// 用 serde_yml 生成单个标量的文本（处理引号和转义）
+///
+/// 多行字符串会生成字面块，块内容需要相对所属的键再缩进一层
+fn scalar(value: &Value, indent: usize) -> Result<String> {
+    let text = serde_yml::to_string(&nonfinite::Native(value)).map_err(|e| Error::Convert {
+        message: e.to_string(),
+    })?;
+    let text = text.strip_suffix('\n').unwrap_or(&text);
+    let pad = " ".repeat(indent);
+    Ok(text
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            if i == 0 || line.is_empty() {
+                line.to_string()
+            } else {
+                format!("{}{}", pad, line)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+/// 值的文本：指定了双引号时字符串都写成双引号形式（与 JSON 字符串的写法相同）
+fn value_scalar(value: &Value, indent: usize, layout: &Layout) -> Result<String> {
+    match (value, layout.quote) {
+        (Value::String(s), Quote::Double) => serde_json::to_string(s).map_err(|e| Error::Convert {
+            message: e.to_string(),
+        }),
+        _ => scalar(value, indent),
+    }
+}
+
+/// 需要写成标签时，返回标签和被标记的值
+fn tagged<'a>(value: &'a Value, layout: &Layout) -> Option<(&'a str, &'a Value)> {
+    custom_tags::get(value).filter(|_| layout.tags)
+}
+
+/// 键的文本：多行的键无法使用块写法，改用双引号
+fn key_text(key: &str) -> Result<String> {
+    let value = Value::String(key.to_string());
+    if key.contains('\n') {
+        return serde_json::to_string(&value).map_err(|e| Error::Convert {
+            message: e.to_string(),
+        });
+    }
+    scalar(&value, 0)
+}
+
+/// 生成流式序列 `[a, b]`，只有全部元素都是标量且放得下时才返回
+fn flow(items: &[Value], column: usize, layout: &Layout) -> Result<Option<String>> {
+    let Some(width) = layout.width else {
+        return Ok(None);
+    };
+    let mut parts = Vec::with_capacity(items.len());
+    for item in items {
+        if tagged(item, layout).is_some() {
+            return Ok(None);
+        }
+        let part = match item {
+            Value::Object(_) if nonfinite::get(item).is_some() => scalar(item, 0)?,
+            Value::Array(_) | Value::Object(_) => return Ok(None),
+            Value::String(s) => {
+                let text = value_scalar(item, 0, layout)?;
+                // 流式上下文里 , [ ] { } 有特殊含义，多行字符串也不能用块写法
+                if text.contains([',', '[', ']', '{', '}', '\n']) {
+                    serde_json::to_string(s).map_err(|e| Error::Convert {
+                        message: e.to_string(),
+                    })?
+                } else {
+                    text
+                }
+            }
+            _ => scalar(item, 0)?,
+        };
+        parts.push(part);
+    }
+    let flow = format!("[{}]", parts.join(", "));
+    Ok((column + flow.chars().count() <= width).then_some(flow))
+}
+
+/// 把长字符串按单词折叠为多行，返回 None 表示不适合折叠
+///
+/// 折叠块会把换行还原为单个空格，所以只处理单词之间只有一个空格、
+/// 首尾没有空白、不含换行和控制字符的字符串
+fn fold(s: &str, indent: usize, width: usize) -> Option<Vec<String>> {
+    if s.is_empty()
+        || s.contains("  ")
+        || s.starts_with(' ')
+        || s.ends_with(' ')
+        || s.chars().any(char::is_control)
+        || !s.contains(' ')
+    {
+        return None;
+    }
+
+    let available = width.saturating_sub(indent).max(MIN_FOLD_WIDTH);
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in s.split(' ') {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > available {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    lines.push(line);
+
+    (lines.len() > 1).then_some(lines)
+}
@@ -0,0 +1,348 @@
+//! YAML 1.1 解析
+//!
+//! serde_yml 按 YAML 1.2 解释没有引号的标量，一些旧工具（如部分基于 snakeyaml 的 Java 程序）
+//! 写出的配置依赖 1.1 的规则。`--yaml-spec 1.1` 时直接读取 libyml 的事件，按 1.1 的类型规则解释纯标量：
+//! - 空值：`~`、`null`、空
+//! - 布尔：`y`/`n`、`yes`/`no`、`on`/`off`、`true`/`false`（全小写、首字母大写或全大写）
+//! - 整数：`0b` 二进制、`0` 开头的八进制、`0x` 十六进制、`_` 分隔、六十进制（`1:30` 为 90）
+//! - 浮点数：必须带小数点，可以有 `_` 分隔和六十进制，以及 `.inf`、`.nan`
+//! - 合并键 `<<`：把一个或多个映射的键并入当前映射，显式写出的键优先
+//!
+//! 映射的键也按这些规则解释后再转为字符串，所以 `on:` 的键是 `"true"`
+
+use crate::custom_tags::{self, CustomTags};
+use crate::error::{Error, Result};
+use crate::nonfinite;
+use crate::t;
+use regex::Regex;
+use serde_json::{Map, Value};
+use serde_yml::libyml::parser::{Anchor, Event, Parser, Scalar, ScalarStyle};
+use serde_yml::libyml::tag::Tag;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// 别名展开后值的最大数量，防止 "billion laughs" 式的放大
+const MAX_NODES: usize = 10_000_000;
+
+/// 按 YAML 1.1 解析单个文档
+pub fn from_str(input: &str) -> Result<Value> {
+    let mut loader = Loader {
+        parser: Parser::new(Cow::Borrowed(input.as_bytes())),
+        anchors: BTreeMap::new(),
+        nodes: 0,
+        line: 1,
+    };
+    let mut document = None;
+    loop {
+        match loader.next()? {
+            Event::StreamStart | Event::DocumentEnd => {}
+            Event::StreamEnd => break,
+            Event::DocumentStart if document.is_some() => {
+                return Err(parse_error(t!("不支持含有多个文档的 YAML")));
+            }
+            Event::DocumentStart => {
+                let event = loader.next()?;
+                document = Some(loader.node(event)?);
+            }
+            _ => return Err(parse_error(t!("意外的 YAML 事件"))),
+        }
+    }
+    Ok(document.unwrap_or(Value::Null))
+}
+
+struct Loader<'input> {
+    parser: Parser<'input>,
+    /// 锚点和它标记的值，以及值的数量
+    anchors: BTreeMap<Anchor, (Value, usize)>,
+    /// 已生成的值的数量（别名按展开后计算）
+    nodes: usize,
+    /// 最近读取的事件所在的行
+    line: usize,
+}
+
+impl<'input> Loader<'input> {
+    fn next(&mut self) -> Result<Event<'input>> {
+        let (event, mark) = self
+            .parser
+            .parse_next_event()
+            .map_err(|e| parse_error(e.to_string()))?;
+        self.line = mark.line() as usize + 1;
+        Ok(event)
+    }
+
+    fn count(&mut self, nodes: usize) -> Result<()> {
+        self.nodes += nodes;
+        if self.nodes > MAX_NODES {
+            return Err(Error::LimitExceeded {
+                limit: "值的数量",
+                max: MAX_NODES,
+            });
+        }
+        Ok(())
+    }
+
+    /// 从已读取的第一个事件开始读取一个节点
+    fn node(&mut self, event: Event<'input>) -> Result<Value> {
+        let (start, line) = (self.nodes, self.line);
+        let (value, anchor, tag) = match event {
+            Event::Scalar(scalar) => {
+                self.count(1)?;
+                let value = resolve_scalar(&scalar)?;
+                (value, scalar.anchor, scalar.tag)
+            }
+            Event::SequenceStart(start) => {
+                self.count(1)?;
+                let mut items = Vec::new();
+                loop {
+                    match self.next()? {
+                        Event::SequenceEnd => break,
+                        event => items.push(self.node(event)?),
+                    }
+                }
+                (Value::Array(items), start.anchor, start.tag)
+            }
+            Event::MappingStart(start) => {
+                self.count(1)?;
+                (self.mapping()?, start.anchor, start.tag)
+            }
+            Event::Alias(anchor) => {
+                let (value, nodes) = self
+                    .anchors
+                    .get(&anchor)
+                    .cloned()
+                    .ok_or_else(|| parse_error(t!("别名引用了未定义的锚点")))?;
+                self.count(nodes)?;
+                return Ok(value);
+            }
+            _ => return Err(parse_error(t!("意外的 YAML 事件"))),
+        };
+        // 自定义标签按 --custom-tags 处理
+        let value = match tag.filter(custom_tags::is_custom) {
+            Some(tag) if custom_tags::mode() == CustomTags::Error => {
+                let tag = String::from_utf8_lossy(&tag).into_owned();
+                return Err(custom_tags::unsupported(&tag, line));
+            }
+            Some(tag) => custom_tags::wrap(&tag, value),
+            None => value,
+        };
+        if let Some(anchor) = anchor {
+            self.anchors
+                .insert(anchor, (value.clone(), self.nodes - start));
+        }
+        Ok(value)
+    }
+
+    /// 读取映射的内容，直到映射结束
+    fn mapping(&mut self) -> Result<Value> {
+        let mut entries = Vec::new();
+        let mut merges = Vec::new();
+        loop {
+            let event = self.next()?;
+            if let Event::MappingEnd = event {
+                break;
+            }
+            let merge = matches!(
+                &event,
+                Event::Scalar(s) if s.style == ScalarStyle::Plain && s.tag.is_none() && &*s.value == b"<<"
+            );
+            let key = self.node(event)?;
+            let event = self.next()?;
+            let value = self.node(event)?;
+            if merge {
+                match value {
+                    Value::Array(sources) => merges.extend(sources),
+                    source => merges.push(source),
+                }
+            } else {
+                entries.push((key_text(key), value));
+            }
+        }
+
+        // 先放入合并来的键（靠前的来源优先），再写入显式的键
+        let mut map = Map::new();
+        for source in merges {
+            let Value::Object(source) = source else {
+                return Err(parse_error(t!("合并键 << 的值必须是映射或映射的列表")));
+            };
+            for (key, value) in source {
+                map.entry(key).or_insert(value);
+            }
+        }
+        for (key, value) in entries {
+            map.insert(key, value);
+        }
+        Ok(Value::Object(map))
+    }
+}
+
+/// 映射的键转为字符串
+fn key_text(key: Value) -> String {
+    match key {
+        Value::String(text) => text,
+        other => other.to_string(),
+    }
+}
+
+/// 按标签和引号风格解释标量：加引号或块标量是字符串，纯标量按 1.1 的规则解释
+fn resolve_scalar(scalar: &Scalar) -> Result<Value> {
+    let text = std::str::from_utf8(&scalar.value)
+        .map_err(|e| parse_error(t!("不是有效的 UTF-8: {}", e)))?;
+    let tag = scalar.tag.as_ref();
+    if tag.is_some_and(|tag| *tag == *"tag:yaml.org,2002:str") {
+        return Ok(Value::String(text.to_string()));
+    }
+    let typed = [Tag::NULL, Tag::BOOL, Tag::INT, Tag::FLOAT];
+    if tag.is_some_and(|tag| typed.iter().any(|name| *tag == **name)) {
+        let value = resolve(text);
+        if value.is_string() {
+            return Err(parse_error(t!("无法按标签解释标量: {}", text)));
+        }
+        return Ok(value);
+    }
+    if scalar.style != ScalarStyle::Plain {
+        return Ok(Value::String(text.to_string()));
+    }
+    Ok(resolve(text))
+}
+
+/// 按 YAML 1.1 的类型规则解释纯标量的文本
+pub(crate) fn resolve(text: &str) -> Value {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    let patterns = PATTERNS.get_or_init(Patterns::new);
+
+    if matches!(text, "" | "~" | "null" | "Null" | "NULL") {
+        return Value::Null;
+    }
+    match text {
+        "y" | "Y" | "yes" | "Yes" | "YES" | "true" | "True" | "TRUE" | "on" | "On" | "ON" => {
+            return Value::Bool(true)
+        }
+        "n" | "N" | "no" | "No" | "NO" | "false" | "False" | "FALSE" | "off" | "Off" | "OFF" => {
+            return Value::Bool(false)
+        }
+        _ => {}
+    }
+
+    let (negative, digits) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let plain = digits.replace('_', "");
+    let integer = if patterns.binary.is_match(text) {
+        i128::from_str_radix(&plain[2..], 2).ok()
+    } else if patterns.hex.is_match(text) {
+        i128::from_str_radix(&plain[2..], 16).ok()
+    } else if patterns.octal.is_match(text) {
+        i128::from_str_radix(&plain[1..], 8).ok()
+    } else if patterns.decimal.is_match(text) {
+        plain.parse::<i128>().ok()
+    } else if patterns.sexagesimal_int.is_match(text) {
+        plain.split(':').try_fold(0i128, |acc, part| {
+            Some(acc.checked_mul(60)? + part.parse::<i128>().ok()?)
+        })
+    } else {
+        None
+    };
+    if let Some(integer) = integer {
+        let integer = if negative { -integer } else { integer };
+        if let Ok(n) = i64::try_from(integer) {
+            return Value::from(n);
+        }
+        if let Ok(n) = u64::try_from(integer) {
+            return Value::from(n);
+        }
+        return Value::String(text.to_string());
+    }
+
+    let float = if patterns.float.is_match(text) {
+        plain.parse::<f64>().ok()
+    } else if patterns.sexagesimal_float.is_match(text) {
+        plain.split(':').try_fold(0f64, |acc, part| {
+            Some(acc * 60.0 + part.parse::<f64>().ok()?)
+        })
+    } else if patterns.infinity.is_match(text) {
+        Some(f64::INFINITY)
+    } else if patterns.nan.is_match(text) {
+        Some(f64::NAN)
+    } else {
+        None
+    };
+    match float {
+        Some(float) => {
+            let float = if negative { -float } else { float };
+            // JSON 不能表示无穷大和 NaN，与 serde_yml 一样转为 null（指定 --json-allow-nan 时保留）
+            nonfinite::float(float)
+        }
+        None => Value::String(text.to_string()),
+    }
+}
+
+/// YAML 1.1 类型规则中的正则表达式
+struct Patterns {
+    binary: Regex,
+    octal: Regex,
+    decimal: Regex,
+    hex: Regex,
+    sexagesimal_int: Regex,
+    float: Regex,
+    sexagesimal_float: Regex,
+    infinity: Regex,
+    nan: Regex,
+}
+
+impl Patterns {
+    fn new() -> Self {
+        let regex = |pattern: &str| Regex::new(pattern).expect("正则表达式是常量");
+        Patterns {
+            binary: regex(r"^[-+]?0b[01_]+$"),
+            octal: regex(r"^[-+]?0[0-7_]+$"),
+            decimal: regex(r"^[-+]?(0|[1-9][0-9_]*)$"),
+            hex: regex(r"^[-+]?0x[0-9a-fA-F_]+$"),
+            sexagesimal_int: regex(r"^[-+]?[1-9][0-9_]*(:[0-5]?[0-9])+$"),
+            float: regex(r"^[-+]?([0-9][0-9_]*\.[0-9_]*|\.[0-9_]+)([eE][-+][0-9]+)?$"),
+            sexagesimal_float: regex(r"^[-+]?[0-9][0-9_]*(:[0-5]?[0-9])+\.[0-9_]*$"),
+            infinity: regex(r"^[-+]?\.(inf|Inf|INF)$"),
+            nan: regex(r"^\.(nan|NaN|NAN)$"),
+        }
+    }
+}
+
+fn parse_error(source: String) -> Error {
+    Error::Parse {
+        format: "YAML",
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn resolves_yaml_11_scalars() {
+        let value = from_str(
+            "a: yes\nb: Off\nc: 0777\nd: 0b101\ne: 0x1F\nf: 1_000\ng: 1:30\nh: 1.5e+3\ni: '0777'\nj: 1e3\nk: ~\n",
+        )
+        .unwrap();
+        assert_eq!(
+            value,
+            json!({
+                "a": true, "b": false, "c": 511, "d": 5, "e": 31, "f": 1000,
+                "g": 90, "h": 1500.0, "i": "0777", "j": "1e3", "k": null
+            })
+        );
+    }
+
+    #[test]
+    fn merges_keys_and_expands_aliases() {
+        let value = from_str(
+            "base: &base {a: 1, b: 2}\nextra: &extra {c: 3}\nitem:\n  <<: [*base, *extra]\n  b: 20\non: x\n",
+        )
+        .unwrap();
+        assert_eq!(value["item"], json!({"a": 1, "b": 20, "c": 3}));
+        assert_eq!(value["true"], json!("x"));
+    }
+}
@@ -0,0 +1,123 @@
+//! YAML 的 `!!timestamp` 和 `!!binary` 标签
+//!
+//! serde_yml 读取时丢掉这两个标准标签，只留下标量的文本。`--yaml-tags` 决定怎样处理：
+//! - `string`（默认）：保持为字符串
+//! - `parse`：`!!timestamp` 读成日期时间（与 TOML 日期时间相同的标记对象，写成 TOML 时是原生的
+//!   日期时间）；`!!binary` 按 base64 解码检查，写出时是去掉换行的标准 base64 字符串，
+//!   因为 JSON 和 TOML 都没有二进制类型
+//! - `error`：出现这两个标签时报错，用于要求配置中不使用它们
+//!
+//! 标签的位置从事件流中读取（见 [`cycle::visit_leaves`]），映射的键上的标签不处理
+
+use crate::cycle::{self, Leaf};
+use crate::date::{self, TOML_DATETIME_KEY};
+use crate::error::{Error, Result};
+use crate::i18n::tr;
+use crate::path::{self, Path};
+use crate::t;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use clap::ValueEnum;
+use serde_json::Value;
+
+const TIMESTAMP: &str = "tag:yaml.org,2002:timestamp";
+const BINARY: &str = "tag:yaml.org,2002:binary";
+
+/// `!!timestamp` 和 `!!binary` 的处理方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, ValueEnum)]
+pub enum YamlTags {
+    /// 读成日期时间和二进制数据
+    #[value(help = tr("读成日期时间和二进制数据（写出时二进制数据是 base64 字符串）"))]
+    Parse,
+    /// 保持为字符串
+    #[default]
+    #[value(help = tr("保持为字符串"))]
+    String,
+    /// 出现这两个标签时报错
+    #[value(help = tr("出现这两个标签时报错"))]
+    Error,
+}
+
+/// 带标签的标量
+struct Tagged {
+    path: Path,
+    binary: bool,
+    line: usize,
+}
+
+/// 按 `mode` 处理 `text` 中带 `!!timestamp` 和 `!!binary` 标签的标量，`value` 是 `text` 解析的结果
+pub fn apply(text: &str, value: &mut Value, mode: YamlTags) -> Result<()> {
+    if mode == YamlTags::String || !text.contains("!!") && !text.contains("tag:yaml.org") {
+        return Ok(());
+    }
+    let mut tagged = Vec::new();
+    cycle::visit_leaves(text, |_, leaf, path, (line, _)| {
+        if let Leaf::Scalar {
+            scalar,
+            is_key: false,
+        } = leaf
+        {
+            match scalar.tag.as_ref() {
+                Some(tag) if *tag == TIMESTAMP || *tag == BINARY => tagged.push(Tagged {
+                    path: path.clone(),
+                    binary: *tag == BINARY,
+                    line,
+                }),
+                _ => {}
+            }
+        }
+        None::<()>
+    });
+
+    for Tagged { path, binary, line } in tagged {
+        let tag = if binary { "!!binary" } else { "!!timestamp" };
+        let invalid = |message: String| Error::Parse {
+            format: "YAML",
+            source: t!("{}（第 {} 行）: {}", path, line, message),
+        };
+        if mode == YamlTags::Error {
+            return Err(invalid(t!("不允许使用 {} 标签（--yaml-tags error）", tag)));
+        }
+        let Some(text) = path::get(value, &path).and_then(Value::as_str) else {
+            continue;
+        };
+        let parsed = if binary {
+            let compact: String = text.split_whitespace().collect();
+            let bytes = STANDARD
+                .decode(&compact)
+                .map_err(|e| invalid(t!("不是有效的 base64: {}", e)))?;
+            Value::String(STANDARD.encode(bytes))
+        } else {
+            let normalized = date::normalize(text, false)
+                .ok_or_else(|| invalid(t!("不是有效的时间戳: {}", text)))?;
+            serde_json::json!({ TOML_DATETIME_KEY: normalized })
+        };
+        path::insert(value, &path, parsed)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_or_rejects_tagged_scalars() {
+        let text = "a: !!timestamp 2024-01-02 03:04:05Z\nb:\n  - !!binary |\n    aGVs\n    bG8=\nc: !!str 1\n";
+        let parsed = |mode| {
+            let mut value: Value = serde_yml::from_str(text).unwrap();
+            apply(text, &mut value, mode).map(|_| value)
+        };
+        assert_eq!(
+            parsed(YamlTags::Parse).unwrap(),
+            serde_json::json!({
+                "a": { TOML_DATETIME_KEY: "2024-01-02T03:04:05Z" },
+                "b": ["aGVsbG8="],
+                "c": "1",
+            })
+        );
+        assert_eq!(parsed(YamlTags::String).unwrap()["b"][0], "aGVs\nbG8=\n");
+        let error = parsed(YamlTags::Error).unwrap_err().to_string();
+        assert!(error.contains("a（第 1 行）"), "{}", error);
+    }
+}
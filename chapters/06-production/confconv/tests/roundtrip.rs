@@ -0,0 +1,100 @@
+//! 格式之间的往返转换测试
+//!
+//! 随机生成值，经过 源格式 → 目标格式 → 解析 的转换后应当与原值相同
+
+use confconv::codec;
+use confconv::format::Format;
+use proptest::prelude::*;
+use serde_json::{Map, Number, Value};
+
+const FORMATS: [Format; 4] = [Format::Json, Format::Yaml, Format::Toml, Format::Ini];
+
+/// 有数字、布尔值和数组的格式
+const TYPED_FORMATS: [Format; 3] = [Format::Json, Format::Yaml, Format::Toml];
+
+/// 标量：`allow_null` 为假时不生成 null（TOML 没有 null）
+fn scalar(allow_null: bool) -> BoxedStrategy<Value> {
+    let values = prop_oneof![
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(|n| Value::Number(n.into())),
+        any::<f64>()
+            .prop_filter("只有有限的小数能表示", |f| f.is_finite())
+            .prop_map(|f| Value::Number(Number::from_f64(f).expect("已过滤"))),
+        "\\PC{0,16}".prop_map(Value::String),
+        "[a-z0-9 #:\\-'\"\\[\\]{},.&*!|>%@`]{0,12}".prop_map(Value::String),
+    ];
+    if allow_null {
+        prop_oneof![1 => Just(Value::Null), 9 => values].boxed()
+    } else {
+        values.boxed()
+    }
+}
+
+/// 任意嵌套的值
+fn value(allow_null: bool) -> impl Strategy<Value = Value> {
+    scalar(allow_null).prop_recursive(4, 48, 6, move |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..6).prop_map(Value::Array),
+            prop::collection::vec(("\\PC{0,8}", inner), 0..6)
+                .prop_map(|entries| Value::Object(entries.into_iter().collect::<Map<_, _>>())),
+        ]
+    })
+}
+
+/// 有类型的格式都能表示的值：顶层是对象，不含 null
+fn typed() -> impl Strategy<Value = Value> {
+    prop::collection::vec(("\\PC{0,8}", value(false)), 0..6)
+        .prop_map(|entries| Value::Object(entries.into_iter().collect::<Map<_, _>>()))
+}
+
+/// 所有格式（包括 INI）都能表示的值：嵌套的对象，值都是字符串，
+/// 键不含 INI 的分隔符和段名中的 `.`
+fn portable() -> impl Strategy<Value = Value> {
+    let object = |inner: BoxedStrategy<Value>| {
+        prop::collection::vec(("[A-Za-z0-9_-]{1,8}", inner), 0..5)
+            .prop_map(|entries| Value::Object(entries.into_iter().collect::<Map<_, _>>()))
+    };
+    let leaf = "[A-Za-z0-9 ._:/@#;=-]{0,12}".prop_map(Value::String);
+    object(
+        leaf.prop_recursive(3, 24, 5, move |inner| object(inner.boxed()))
+            .boxed(),
+    )
+}
+
+fn roundtrip(value: &Value, format: Format) -> Value {
+    let text = codec::serialize(value, format, true)
+        .unwrap_or_else(|e| panic!("{} 序列化失败: {}", format.name(), e));
+    codec::parse(&text, format)
+        .unwrap_or_else(|e| panic!("{} 解析失败: {}\n{}", format.name(), e, text))
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(256))]
+
+    #[test]
+    fn json_yaml_roundtrip(value in value(true)) {
+        for format in [Format::Json, Format::Yaml] {
+            prop_assert_eq!(&roundtrip(&value, format), &value);
+        }
+    }
+
+    #[test]
+    fn typed_pairs_roundtrip(value in typed()) {
+        for from in TYPED_FORMATS {
+            let source = roundtrip(&value, from);
+            for to in TYPED_FORMATS {
+                prop_assert_eq!(&roundtrip(&source, to), &value, "{} -> {}", from.name(), to.name());
+            }
+        }
+    }
+
+    #[test]
+    fn all_pairs_roundtrip(value in portable()) {
+        for from in FORMATS {
+            let source = roundtrip(&value, from);
+            for to in FORMATS {
+                prop_assert_eq!(&roundtrip(&source, to), &value, "{} -> {}", from.name(), to.name());
+            }
+        }
+    }
+}
@@ -0,0 +1,68 @@
+//! 修改历史和 undo 命令的测试
+//!
+//! 历史记录在进程级的状态中初始化，状态目录由环境变量决定，
+//! 所以在单独的进程中运行命令，不影响其他测试
+
+// 状态目录由 XDG_STATE_HOME 决定，只有 Linux 上可以这样指向临时目录
+#![cfg(target_os = "linux")]
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// 在 `root` 下的状态目录和配置中运行 confconv
+fn confconv(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_confconv"))
+        .args(args)
+        .current_dir(root)
+        .env("XDG_STATE_HOME", root.join("state"))
+        .env("CONFCONV_CONFIG", root.join("config.toml"))
+        .env_remove("CONFCONV_PROFILE")
+        .output()
+        .expect("无法运行 confconv")
+}
+
+fn mode(path: &Path) -> u32 {
+    fs::metadata(path).unwrap().permissions().mode() & 0o777
+}
+
+#[test]
+fn undo_restores_recorded_versions_and_prunes_old_ones() {
+    let root = tempfile::tempdir().unwrap();
+    let root = root.path();
+    fs::write(root.join("config.toml"), "[history]\nkeep = 2\n").unwrap();
+    fs::write(root.join("app.yaml"), "v: 0\n").unwrap();
+    for i in 1..=3 {
+        let ops = format!("- op: set\n  path: v\n  value: {}\n", i);
+        fs::write(root.join("ops.yaml"), ops).unwrap();
+        let output = confconv(root, &["edit", "app.yaml", "--ops", "ops.yaml", "-w"]);
+        assert!(output.status.success(), "{:?}", output);
+    }
+
+    // 记录中有修改前的内容，只有所有者可以访问
+    let history = root.join("state/confconv/history");
+    assert_eq!(mode(&history), 0o700);
+    for dir in fs::read_dir(&history).unwrap() {
+        let dir = dir.unwrap().path();
+        assert_eq!(mode(&dir), 0o700);
+        let files: Vec<_> = fs::read_dir(&dir)
+            .unwrap()
+            .map(|f| f.unwrap().path())
+            .collect();
+        // 第一次修改的记录已经按 keep 删除
+        assert_eq!(files.len(), 4);
+        assert!(files.iter().all(|file| mode(file) == 0o600));
+    }
+
+    let undo = |args: &[&str]| confconv(root, &[&["undo", "app.yaml"], args].concat());
+    let read = || fs::read_to_string(root.join("app.yaml")).unwrap();
+    assert!(undo(&[]).status.success());
+    assert_eq!(read(), "v: 2\n");
+    // 记录之后又被改过的文件需要 --force
+    fs::write(root.join("app.yaml"), "v: 9\n").unwrap();
+    assert!(!undo(&[]).status.success());
+    assert!(undo(&["--force"]).status.success());
+    assert_eq!(read(), "v: 1\n");
+    assert!(!undo(&[]).status.success());
+}